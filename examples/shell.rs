@@ -49,7 +49,7 @@ fn handle_cli(executor: &mut QueryExecutor) -> bool {
         let result = executor.prepare(command.to_string());
         println!("{}", result);
     }
-    return false;
+    false
 }
 
 //SELECT * FROM Namen INNER JOIN Orders ON Namen.Id = Orders.Id
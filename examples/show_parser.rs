@@ -1,6 +1,6 @@
-use rustql::executor::{QueryExecutor, QueryResult};
+use rustql::executor::QueryExecutor;
 use rustql::parser::*;
-use rustql::planner::{CompiledQuery, Planner};
+use rustql::planner::Planner;
 use std::io;
 use std::io::Write;
 
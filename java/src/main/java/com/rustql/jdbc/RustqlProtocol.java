@@ -7,19 +7,28 @@ import java.io.DataInputStream;
 import java.io.DataOutputStream;
 import java.io.EOFException;
 import java.io.IOException;
+import java.math.BigDecimal;
+import java.math.BigInteger;
 import java.net.InetSocketAddress;
 import java.net.Socket;
 import java.nio.charset.StandardCharsets;
 import java.sql.Date;
 import java.sql.SQLException;
+import java.sql.Time;
+import java.sql.Timestamp;
 import java.sql.Types;
+import java.time.Instant;
 import java.time.LocalDate;
+import java.time.LocalDateTime;
+import java.time.LocalTime;
+import java.time.ZoneOffset;
 import java.util.ArrayList;
 import java.util.List;
+import java.util.UUID;
 
 final class RustqlProtocol {
     private static final byte[] MAGIC = new byte[]{'R', 'S', 'Q', 'L'};
-    private static final byte VERSION = 2;
+    private static final byte VERSION = 5;
 
     static final int TYPE_NULL = 0;
     static final int TYPE_INTEGER = 1;
@@ -27,6 +36,14 @@ final class RustqlProtocol {
     static final int TYPE_VARCHAR = 3;
     static final int TYPE_DATE = 4;
     static final int TYPE_BOOLEAN = 5;
+    static final int TYPE_DECIMAL = 6;
+    static final int TYPE_BLOB = 7;
+    static final int TYPE_TIMESTAMP = 8;
+    static final int TYPE_TIMESTAMPTZ = 9;
+    static final int TYPE_TIME = 10;
+    static final int TYPE_TEXT = 11;
+    static final int TYPE_UUID = 12;
+    static final int TYPE_JSON = 13;
 
     private RustqlProtocol() {
     }
@@ -131,7 +148,23 @@ final class RustqlProtocol {
             }
         }
 
-        return new QueryResponse(status, message, columns, rows);
+        int generatedKeyRows = in.readInt();
+        if (generatedKeyRows < 0) {
+            throw new SQLException("Invalid generated key row count");
+        }
+        List<List<GeneratedKey>> generatedKeys = new ArrayList<>();
+        for (int r = 0; r < generatedKeyRows; r++) {
+            int keyCount = in.readUnsignedShort();
+            List<GeneratedKey> keys = new ArrayList<>(keyCount);
+            for (int i = 0; i < keyCount; i++) {
+                String column = readStringU16(in);
+                int value = in.readInt();
+                keys.add(new GeneratedKey(column, value));
+            }
+            generatedKeys.add(keys);
+        }
+
+        return new QueryResponse(status, message, columns, rows, generatedKeys);
     }
 
     private static String readString(DataInputStream in) throws IOException {
@@ -155,17 +188,31 @@ final class RustqlProtocol {
         return new String(bytes, StandardCharsets.UTF_8);
     }
 
-    private static Object[] decodeRow(byte[] row, List<ColumnMeta> columns) throws SQLException {
+    static Object[] decodeRow(byte[] row, List<ColumnMeta> columns) throws SQLException {
         Object[] result = new Object[columns.size()];
-        int offset = 0;
+        // rows start with a null bitmap, one bit per column
+        int offset = (columns.size() + 7) / 8;
+        if (offset > row.length) {
+            throw new SQLException("Row shorter than its null bitmap");
+        }
 
         for (int i = 0; i < columns.size(); i++) {
             ColumnMeta c = columns.get(i);
-            int size = rustFieldSize(c.typeTag, c.typeArg);
-            if (offset + size > row.length) {
+            int size;
+            if (c.typeTag == TYPE_BLOB || c.typeTag == TYPE_TEXT || c.typeTag == TYPE_JSON) {
+                // blob, text and json cells are sent as [4] length + content
+                if (offset + 4 > row.length) {
+                    throw new SQLException("Row shorter than expected for column " + c.name);
+                }
+                size = 4 + readInt(row, offset);
+            } else {
+                size = rustFieldSize(c.typeTag, c.typeArg);
+            }
+            if (size < 0 || offset + size > row.length) {
                 throw new SQLException("Row shorter than expected for column " + c.name);
             }
-            result[i] = decodeCell(row, offset, c.typeTag, c.typeArg);
+            boolean isNull = (row[i / 8] & (1 << (i % 8))) != 0;
+            result[i] = isNull ? null : decodeCell(row, offset, c.typeTag, c.typeArg);
             offset += size;
         }
 
@@ -177,25 +224,71 @@ final class RustqlProtocol {
             case TYPE_NULL -> 1;
             case TYPE_INTEGER -> 5;
             case TYPE_STRING -> 256;
-            case TYPE_VARCHAR -> typeArg + 1;
+            case TYPE_VARCHAR -> varcharWidth(typeArg);
             case TYPE_DATE -> 5;
             case TYPE_BOOLEAN -> 1;
+            case TYPE_DECIMAL -> decimalWidth(decimalPrecision(typeArg)) + 1;
+            case TYPE_TIMESTAMP, TYPE_TIME -> 9;
+            case TYPE_UUID -> 17;
+            case TYPE_TIMESTAMPTZ -> 11;
             default -> throw new SQLException("Unknown RustQL type tag: " + typeTag);
         };
     }
 
-    private static Object decodeCell(byte[] row, int offset, int typeTag, int typeArg) throws SQLException {
+    static int decimalPrecision(int typeArg) {
+        return typeArg >>> 16;
+    }
+
+    static int decimalScale(int typeArg) {
+        return typeArg & 0xFFFF;
+    }
+
+    private static int decimalWidth(int precision) {
+        if (precision <= 2) {
+            return 1;
+        } else if (precision <= 4) {
+            return 2;
+        } else if (precision <= 9) {
+            return 4;
+        } else if (precision <= 18) {
+            return 8;
+        }
+        return 16;
+    }
+
+    static Object decodeCell(byte[] row, int offset, int typeTag, int typeArg) throws SQLException {
         return switch (typeTag) {
             case TYPE_NULL -> null;
             case TYPE_INTEGER -> decodeInteger(row, offset);
             case TYPE_STRING -> decodeString(row, offset, 256);
-            case TYPE_VARCHAR -> decodeString(row, offset, typeArg + 1);
+            case TYPE_VARCHAR -> decodeString(row, offset, varcharWidth(typeArg));
             case TYPE_DATE -> decodeDate(row, offset);
             case TYPE_BOOLEAN -> (row[offset] & 1) != 0;
+            case TYPE_DECIMAL -> decodeDecimal(row, offset, decimalPrecision(typeArg), decimalScale(typeArg));
+            case TYPE_BLOB -> decodeBlob(row, offset);
+            case TYPE_TEXT, TYPE_JSON -> new String(decodeBlob(row, offset), StandardCharsets.UTF_8);
+            case TYPE_TIMESTAMP -> decodeTimestamp(row, offset, false);
+            case TYPE_TIMESTAMPTZ -> decodeTimestamp(row, offset, true);
+            case TYPE_TIME -> decodeTime(row, offset);
+            case TYPE_UUID -> decodeUuid(row, offset).toString();
             default -> throw new SQLException("Unknown RustQL type tag: " + typeTag);
         };
     }
 
+    private static int readInt(byte[] row, int offset) {
+        return ((row[offset] & 0xFF) << 24)
+                | ((row[offset + 1] & 0xFF) << 16)
+                | ((row[offset + 2] & 0xFF) << 8)
+                | (row[offset + 3] & 0xFF);
+    }
+
+    private static byte[] decodeBlob(byte[] row, int offset) {
+        int len = readInt(row, offset);
+        byte[] content = new byte[len];
+        System.arraycopy(row, offset + 4, content, 0, len);
+        return content;
+    }
+
     private static Integer decodeInteger(byte[] row, int offset) {
         int b1 = row[offset + 1] & 0xFF;
         int b2 = row[offset + 2] & 0xFF;
@@ -204,6 +297,11 @@ final class RustqlProtocol {
         return (b1 << 24) | (b2 << 16) | (b3 << 8) | b4;
     }
 
+    static int varcharWidth(int maxChars) {
+        // every character may take up to four UTF-8 bytes, plus the flag byte
+        return maxChars * 4 + 1;
+    }
+
     private static String decodeString(byte[] row, int offset, int len) {
         int payload = Math.max(0, len - 1);
         int end = offset;
@@ -213,6 +311,43 @@ final class RustqlProtocol {
         return new String(row, offset, end - offset, StandardCharsets.UTF_8);
     }
 
+    private static BigDecimal decodeDecimal(byte[] row, int offset, int precision, int scale) {
+        // stored as big-endian two's complement with the sign bit flipped
+        int width = decimalWidth(precision);
+        byte[] unscaled = new byte[width];
+        System.arraycopy(row, offset, unscaled, 0, width);
+        unscaled[0] ^= (byte) 0x80;
+        return new BigDecimal(new BigInteger(unscaled), scale);
+    }
+
+    private static long readLong(byte[] row, int offset) {
+        long value = 0;
+        for (int i = 0; i < 8; i++) {
+            value = (value << 8) | (row[offset + i] & 0xFF);
+        }
+        return value;
+    }
+
+    private static Timestamp decodeTimestamp(byte[] row, int offset, boolean withTimeZone) {
+        // microseconds since epoch, big-endian with the sign bit flipped
+        long micros = readLong(row, offset) ^ Long.MIN_VALUE;
+        long seconds = Math.floorDiv(micros, 1_000_000L);
+        int nanos = (int) Math.floorMod(micros, 1_000_000L) * 1000;
+        if (withTimeZone) {
+            return Timestamp.from(Instant.ofEpochSecond(seconds, nanos));
+        }
+        return Timestamp.valueOf(LocalDateTime.ofEpochSecond(seconds, nanos, ZoneOffset.UTC));
+    }
+
+    private static Time decodeTime(byte[] row, int offset) {
+        long micros = readLong(row, offset);
+        return Time.valueOf(LocalTime.ofNanoOfDay(micros * 1000));
+    }
+
+    private static UUID decodeUuid(byte[] row, int offset) {
+        return new UUID(readLong(row, offset), readLong(row, offset + 8));
+    }
+
     private static Date decodeDate(byte[] row, int offset) {
         int year = ((row[offset] & 0xFF) << 8) | (row[offset + 1] & 0xFF);
         int month = row[offset + 2] & 0xFF;
@@ -227,6 +362,12 @@ final class RustqlProtocol {
             case TYPE_STRING, TYPE_VARCHAR -> Types.VARCHAR;
             case TYPE_DATE -> Types.DATE;
             case TYPE_BOOLEAN -> Types.BOOLEAN;
+            case TYPE_DECIMAL -> Types.DECIMAL;
+            case TYPE_BLOB -> Types.LONGVARBINARY;
+            case TYPE_TEXT, TYPE_JSON -> Types.LONGVARCHAR;
+            case TYPE_TIMESTAMP, TYPE_TIMESTAMPTZ -> Types.TIMESTAMP;
+            case TYPE_TIME -> Types.TIME;
+            case TYPE_UUID -> Types.CHAR;
             default -> throw new SQLException("Unknown RustQL type tag: " + rustType);
         };
     }
@@ -243,17 +384,30 @@ final class RustqlProtocol {
         }
     }
 
+    static final class GeneratedKey {
+        final String column;
+        final int value;
+
+        GeneratedKey(String column, int value) {
+            this.column = column;
+            this.value = value;
+        }
+    }
+
     static final class QueryResponse {
         final int status;
         final String message;
         final List<ColumnMeta> columns;
         final List<Object[]> rows;
+        // per inserted row that drew values from sequences, its keys
+        final List<List<GeneratedKey>> generatedKeys;
 
-        QueryResponse(int status, String message, List<ColumnMeta> columns, List<Object[]> rows) {
+        QueryResponse(int status, String message, List<ColumnMeta> columns, List<Object[]> rows, List<List<GeneratedKey>> generatedKeys) {
             this.status = status;
             this.message = message;
             this.columns = columns;
             this.rows = rows;
+            this.generatedKeys = generatedKeys;
         }
     }
 }
@@ -7,6 +7,7 @@ import java.sql.SQLException;
 import java.sql.SQLFeatureNotSupportedException;
 import java.sql.SQLWarning;
 import java.sql.Statement;
+import java.util.ArrayList;
 import java.util.List;
 import java.util.concurrent.locks.LockSupport;
 
@@ -21,6 +22,7 @@ final class RustqlStatement implements Statement {
     private final RustqlConnection connection;
     private boolean closed;
     private ResultSet lastResultSet;
+    private List<List<RustqlProtocol.GeneratedKey>> lastGeneratedKeys = List.of();
     private int updateCount = -1;
     private int fetchSize = DEFAULT_FETCH_SIZE;
 
@@ -31,9 +33,11 @@ final class RustqlStatement implements Statement {
     @Override
     public ResultSet executeQuery(String sql) throws SQLException {
         ensureOpen();
+        this.lastGeneratedKeys = List.of();
         RustqlProtocol.QueryResponse response = executeWithRetry(sql);
 
         this.lastResultSet = toCachedRowSet(response.columns, response.rows);
+        this.lastGeneratedKeys = response.generatedKeys;
         this.updateCount = -1;
         return this.lastResultSet;
     }
@@ -90,8 +94,13 @@ final class RustqlStatement implements Statement {
             metaData.setColumnLabel(idx, c.name);
             metaData.setColumnType(idx, jdbcType);
             metaData.setNullable(idx, ResultSetMetaData.columnNullableUnknown);
-            metaData.setPrecision(idx, 0);
-            metaData.setScale(idx, 0);
+            if (c.typeTag == RustqlProtocol.TYPE_DECIMAL) {
+                metaData.setPrecision(idx, RustqlProtocol.decimalPrecision(c.typeArg));
+                metaData.setScale(idx, RustqlProtocol.decimalScale(c.typeArg));
+            } else {
+                metaData.setPrecision(idx, 0);
+                metaData.setScale(idx, 0);
+            }
         }
 
         rowSet.setMetaData((RowSetMetaDataImpl) metaData);
@@ -101,7 +110,11 @@ final class RustqlStatement implements Statement {
             rowSet.last();
             rowSet.moveToInsertRow();
             for (int i = 0; i < row.length; i++) {
-                rowSet.updateObject(i + 1, row[i]);
+                if (row[i] == null) {
+                    rowSet.updateNull(i + 1);
+                } else {
+                    rowSet.updateObject(i + 1, row[i]);
+                }
             }
             rowSet.insertRow();
         }
@@ -244,7 +257,30 @@ final class RustqlStatement implements Statement {
 
     @Override
     public ResultSet getGeneratedKeys() throws SQLException {
-        throw new SQLFeatureNotSupportedException();
+        ensureOpen();
+        // one row per row the last INSERT inserted with values drawn from sequences; a column
+        // a row did not draw from is NULL in it
+        List<String> names = new ArrayList<>();
+        for (List<RustqlProtocol.GeneratedKey> keys : lastGeneratedKeys) {
+            for (RustqlProtocol.GeneratedKey key : keys) {
+                if (!names.contains(key.column)) {
+                    names.add(key.column);
+                }
+            }
+        }
+        List<RustqlProtocol.ColumnMeta> columns = new ArrayList<>(names.size());
+        for (String name : names) {
+            columns.add(new RustqlProtocol.ColumnMeta(name, RustqlProtocol.TYPE_INTEGER, 0));
+        }
+        List<Object[]> rows = new ArrayList<>(lastGeneratedKeys.size());
+        for (List<RustqlProtocol.GeneratedKey> keys : lastGeneratedKeys) {
+            Object[] row = new Object[names.size()];
+            for (RustqlProtocol.GeneratedKey key : keys) {
+                row[names.indexOf(key.column)] = key.value;
+            }
+            rows.add(row);
+        }
+        return toCachedRowSet(columns, rows);
     }
 
     @Override
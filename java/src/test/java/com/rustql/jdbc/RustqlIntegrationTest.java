@@ -106,6 +106,40 @@ class RustqlIntegrationTest {
         }
     }
 
+    @Test
+    void insertReportsGeneratedKeys() throws SQLException {
+        String table = "it_auto_" + System.nanoTime();
+
+        try (Connection connection = DriverManager.getConnection(jdbcUrl());
+             Statement statement = connection.createStatement()) {
+            statement.execute("CREATE TABLE " + table + " (id Integer PRIMARY KEY AUTOINCREMENT, name Varchar(25))");
+            for (int expected = 1; expected <= 2; expected++) {
+                statement.executeUpdate("INSERT INTO " + table + " (name) VALUES ('Ada')", Statement.RETURN_GENERATED_KEYS);
+                try (ResultSet keys = statement.getGeneratedKeys()) {
+                    assertTrue(keys.next());
+                    assertEquals("id", keys.getMetaData().getColumnName(1));
+                    assertEquals(expected, keys.getInt(1));
+                    assertFalse(keys.next());
+                }
+            }
+
+            statement.executeUpdate("INSERT INTO " + table + " (name) VALUES ('Grace'), ('Linus')", Statement.RETURN_GENERATED_KEYS);
+            try (ResultSet keys = statement.getGeneratedKeys()) {
+                assertEquals(1, keys.getMetaData().getColumnCount());
+                for (int expected = 3; expected <= 4; expected++) {
+                    assertTrue(keys.next());
+                    assertEquals(expected, keys.getInt(1));
+                }
+                assertFalse(keys.next());
+            }
+
+            statement.executeQuery("SELECT * FROM " + table);
+            try (ResultSet keys = statement.getGeneratedKeys()) {
+                assertFalse(keys.next());
+            }
+        }
+    }
+
     @Test
     void invalidQueryReturnsSQLException() throws SQLException {
         try (Connection connection = DriverManager.getConnection(jdbcUrl());
@@ -2,10 +2,13 @@ package com.rustql.jdbc;
 
 import org.junit.jupiter.api.Test;
 
+import java.nio.charset.StandardCharsets;
 import java.sql.SQLException;
 import java.sql.Types;
+import java.util.List;
 
 import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertNull;
 import static org.junit.jupiter.api.Assertions.assertThrows;
 
 class RustqlProtocolTest {
@@ -18,6 +21,48 @@ class RustqlProtocolTest {
         assertEquals(Types.VARCHAR, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_VARCHAR));
         assertEquals(Types.DATE, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_DATE));
         assertEquals(Types.BOOLEAN, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_BOOLEAN));
+        assertEquals(Types.DECIMAL, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_DECIMAL));
+        assertEquals(Types.LONGVARBINARY, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_BLOB));
+        assertEquals(Types.LONGVARCHAR, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_TEXT));
+        assertEquals(Types.TIMESTAMP, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_TIMESTAMP));
+        assertEquals(Types.TIMESTAMP, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_TIMESTAMPTZ));
+        assertEquals(Types.TIME, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_TIME));
+        assertEquals(Types.CHAR, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_UUID));
+        assertEquals(Types.LONGVARCHAR, RustqlProtocol.toJdbcType(RustqlProtocol.TYPE_JSON));
+    }
+
+    @Test
+    void decodesUtf8VarcharCells() throws SQLException {
+        byte[] encoded = "Jürgen 東京".getBytes(StandardCharsets.UTF_8);
+        byte[] row = new byte[2 + RustqlProtocol.varcharWidth(9)];
+        System.arraycopy(encoded, 0, row, 2, encoded.length);
+        assertEquals("Jürgen 東京", RustqlProtocol.decodeCell(row, 2, RustqlProtocol.TYPE_VARCHAR, 9));
+        assertEquals(37, RustqlProtocol.varcharWidth(9));
+    }
+
+    @Test
+    void decodesUuidCells() throws SQLException {
+        byte[] row = new byte[17];
+        for (int i = 0; i < 16; i++) {
+            row[i] = (byte) (0xF0 + i);
+        }
+        assertEquals(
+                "f0f1f2f3-f4f5-f6f7-f8f9-fafbfcfdfeff",
+                RustqlProtocol.decodeCell(row, 0, RustqlProtocol.TYPE_UUID, 0));
+    }
+
+    @Test
+    void decodesNullCellsFromBitmap() throws SQLException {
+        List<RustqlProtocol.ColumnMeta> columns = List.of(
+                new RustqlProtocol.ColumnMeta("id", RustqlProtocol.TYPE_INTEGER, 0),
+                new RustqlProtocol.ColumnMeta("qty", RustqlProtocol.TYPE_INTEGER, 0),
+                new RustqlProtocol.ColumnMeta("note", RustqlProtocol.TYPE_TEXT, 0));
+        // bitmap, id = 7, NULL qty, NULL note of length 0
+        byte[] row = new byte[]{0b110, 0, 0, 0, 0, 7, 1, 0, 0, 0, 0, 0, 0, 0, 0};
+        Object[] decoded = RustqlProtocol.decodeRow(row, columns);
+        assertEquals(7, decoded[0]);
+        assertNull(decoded[1]);
+        assertNull(decoded[2]);
     }
 
     @Test
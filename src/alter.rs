@@ -0,0 +1,355 @@
+use crate::btree::Btree;
+use crate::dataframe::RowSource;
+use crate::debug::Status;
+use crate::executor::{QueryExecutor, QueryResult, MASTER_TABLE_NAME, MASTER_TYPE_TABLE};
+use crate::pager::{Position, Row};
+use crate::pager_proxy::{PageManager, PagerProxy};
+use crate::parser::{Lexer, ParsedAlterTableAction, ParsedQuery, ParsedValue, Parser};
+use crate::planner::{CompiledAlterTableQuery, CompiledQuery, Planner, SqlConditionOpCode};
+use crate::schema::{Field, TableSchema};
+use crate::serializer::Serializer;
+use std::collections::HashSet;
+
+impl QueryExecutor {
+    /// Rewrites a table for ALTER TABLE. The stored CREATE TABLE statement is rebuilt from
+    /// its column definitions, every row is converted and inserted into a new B-tree, and the
+    /// old pages are released. Nothing is written before all converted rows pass the table's
+    /// constraints. The dropped index tables are recreated by `run_query_internal`.
+    pub(crate) fn alter_table(
+        &mut self,
+        q: CompiledAlterTableQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        let old_table = self.schema.tables[q.table_id].clone();
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+            self.lock_table_if_needed(&old_table.name)?;
+        }
+
+        let create_sql = self.altered_create_table_sql(&old_table.name, &q.action)?;
+        let CompiledQuery::CreateTable(create) = self.compile_query(&create_sql)? else {
+            return Err(QueryResult::err(Status::InternalExceptionCompilerError));
+        };
+        let mut table = create.schema;
+        table.btree_order = self.btree_node_width;
+        let mut altered_schema = self.schema.clone();
+        altered_schema
+            .foreign_keys
+            .retain(|fk| fk.child_table != old_table.name);
+        for foreign_key in &create.foreign_keys {
+            altered_schema.foreign_keys.push(Planner::resolve_foreign_key(
+                &self.schema,
+                &table,
+                &create.unique_columns,
+                foreign_key,
+            )?);
+        }
+        altered_schema.tables[q.table_id] = table.clone();
+        Planner::check_views_survive_alter(&altered_schema, &old_table.name, &q.action)?;
+
+        let mut old_rows = vec![];
+        let mut source = self
+            .create_scan_source(q.table_id, SqlConditionOpCode::SelectFTS, None)
+            .map_err(QueryResult::err)?;
+        source.reset().map_err(QueryResult::err)?;
+        while let Some(row) = source.next().map_err(QueryResult::err)? {
+            old_rows.push(row);
+        }
+
+        // payloads of converted values; released again if the rows are rejected
+        let mut written_payloads = vec![];
+        let checked = self
+            .convert_rows(&q.action, &old_table, &table, &old_rows, &mut written_payloads)
+            .and_then(|rows| {
+                let current_schema = std::mem::replace(&mut self.schema, altered_schema);
+                let checked = self.check_altered_rows(&q, &rows);
+                self.schema = current_schema;
+                checked.map(|_| rows)
+            });
+        let rows = match checked {
+            Ok(rows) => rows,
+            Err(error) => {
+                for field in &written_payloads {
+                    PageManager::release_payload_field(self.pager_accessor.clone(), field)
+                        .map_err(QueryResult::err)?;
+                }
+                return Err(error);
+            }
+        };
+
+        for index_name in &q.dropped_indexes {
+            let index_table_id = Planner::find_table_id(&self.schema, index_name)?;
+            let index_table = self.schema.tables[index_table_id].clone();
+            self.release_table_pages(&index_table)?;
+            self.delete_master_row(index_name)?;
+        }
+        self.release_table_pages(&old_table)?;
+
+        let root_page =
+            PagerProxy::create_empty_node_on_new_page(&table, self.pager_accessor.clone())
+                .map_err(QueryResult::err)?
+                .position
+                .page();
+        table.root = Position::new(root_page, 0);
+        let page_capacity = table.max_nodes_per_page().map_err(QueryResult::err)?;
+        table.free_list = vec![(root_page, page_capacity.saturating_sub(1))];
+        let free_list_encoded = Self::encode_free_list_top_10(&table);
+        let mut btree = Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+            .map_err(QueryResult::err)?;
+        for row in &rows {
+            let (key, row) = Serializer::split_key_and_row(row, &table).map_err(QueryResult::err)?;
+            btree.insert(key, row).map_err(QueryResult::err)?;
+        }
+
+        self.release_master_sql(&old_table.name)?;
+        let sql_value = self.master_sql_value(&create_sql)?;
+        let update_master_row = format!(
+            "UPDATE {} SET type = '{}', sql = '{}', rootpage = {}, free_list = '{}' WHERE name = '{}'",
+            MASTER_TABLE_NAME,
+            MASTER_TYPE_TABLE,
+            sql_value.replace("'", "''"),
+            root_page,
+            free_list_encoded.replace("'", "''"),
+            old_table.name.replace("'", "''")
+        );
+        self.run_query_internal(&update_master_row, true)?;
+
+        match &q.action {
+            ParsedAlterTableAction::DropColumn(column) => {
+                let field_idx = Self::field_position(&old_table, column)?;
+                if old_table.identity[field_idx].is_some() {
+                    let sequence_name = Planner::identity_sequence_name(&old_table.name, column);
+                    self.drop_sequence(&sequence_name, true)?;
+                }
+                if old_table.fields[field_idx].field_type.is_payload_backed() {
+                    for row in &old_rows {
+                        let field = Serializer::get_field_on_row(row, field_idx, &old_table)
+                            .map_err(QueryResult::err)?;
+                        PageManager::release_payload_field(self.pager_accessor.clone(), &field)
+                            .map_err(QueryResult::err)?;
+                    }
+                }
+            }
+            ParsedAlterTableAction::RenameColumn { from, to } => {
+                let field_idx = Self::field_position(&old_table, from)?;
+                if old_table.identity[field_idx].is_some() {
+                    self.rename_sequence(
+                        &Planner::identity_sequence_name(&old_table.name, from),
+                        &Planner::identity_sequence_name(&old_table.name, to),
+                    )?;
+                }
+            }
+            _ => {}
+        }
+
+        self.reload_schema()?;
+        let table_id = Planner::find_table_id(&self.schema, &old_table.name)?;
+        self.rebuild_indices_for_table_id(table_id)?;
+        if !allow_modification_to_system_table {
+            self.last_write_table_id = Some(table_id);
+        }
+        Ok(QueryResult::went_fine())
+    }
+
+    /// The CREATE TABLE statement of `table_name` after `action`, rebuilt from the column
+    /// definitions and constraints of the stored one.
+    fn altered_create_table_sql(
+        &self,
+        table_name: &str,
+        action: &ParsedAlterTableAction,
+    ) -> Result<String, QueryResult> {
+        let stored_sql = self.stored_sql(table_name)?;
+        let Ok(ParsedQuery::CreateTable(create)) = Parser::new(stored_sql).parse_query() else {
+            return Err(QueryResult::err(Status::InternalExceptionCompilerError));
+        };
+        let column_position = |column: &str| {
+            create
+                .table_fields
+                .iter()
+                .position(|f| f == column)
+                .ok_or_else(|| QueryResult::err(Status::InternalExceptionCompilerError))
+        };
+        let mut definitions = create.column_definitions.clone();
+        let mut constraints = create.table_constraints.clone();
+        match action {
+            ParsedAlterTableAction::AddColumn { definition, .. } => {
+                definitions.push(definition.clone());
+            }
+            ParsedAlterTableAction::DropColumn(column) => {
+                definitions.remove(column_position(column)?);
+                // a FOREIGN KEY clause of the column goes with it
+                constraints.retain(|constraint| {
+                    let mut lexer = Lexer::new(constraint.clone());
+                    let tokens: Vec<String> = std::iter::from_fn(|| lexer.next_token())
+                        .take(4)
+                        .collect();
+                    !(tokens[0].to_uppercase() == "FOREIGN" && tokens.get(3) == Some(column))
+                });
+            }
+            ParsedAlterTableAction::RenameColumn { from, to } => {
+                for text in definitions.iter_mut().chain(constraints.iter_mut()) {
+                    *text = Parser::rename_column_in(text, table_name, from, to);
+                }
+            }
+            ParsedAlterTableAction::AlterColumnType {
+                column,
+                column_type,
+            } => {
+                let position = column_position(column)?;
+                definitions[position] =
+                    Parser::with_column_type(&definitions[position], column_type)
+                        .map_err(QueryResult::user_input_wrong)?;
+            }
+        }
+        definitions.extend(constraints);
+        Ok(format!(
+            "CREATE TABLE {} ({}){}",
+            table_name,
+            definitions.join(", "),
+            if create.with_rowid { " WITH ROWID" } else { "" }
+        ))
+    }
+
+    /// Converts full rows of `old_table` to full rows of `table`. Kept columns keep their
+    /// bytes unless their type was widened, an added column takes its DEFAULT or NULL.
+    fn convert_rows(
+        &self,
+        action: &ParsedAlterTableAction,
+        old_table: &TableSchema,
+        table: &TableSchema,
+        old_rows: &[Row],
+        written_payloads: &mut Vec<Vec<u8>>,
+    ) -> Result<Vec<Row>, QueryResult> {
+        let sources: Vec<Option<usize>> = table
+            .fields
+            .iter()
+            .map(|field| {
+                let name = match action {
+                    ParsedAlterTableAction::RenameColumn { from, to } if *to == field.name => from,
+                    _ => &field.name,
+                };
+                old_table.fields.iter().position(|f| &f.name == name)
+            })
+            .collect();
+
+        let mut rows = Vec::with_capacity(old_rows.len());
+        for old_row in old_rows {
+            let mut row = vec![];
+            for (field_idx, field) in table.fields.iter().enumerate() {
+                let value = match sources[field_idx] {
+                    Some(old_idx) => {
+                        let old_type = &old_table.fields[old_idx].field_type;
+                        let bytes = Serializer::get_field_on_row(old_row, old_idx, old_table)
+                            .map_err(QueryResult::err)?;
+                        if *old_type == field.field_type {
+                            bytes
+                        } else if Serializer::is_null(&bytes, old_type).map_err(QueryResult::err)? {
+                            Serializer::null_field(&field.field_type).map_err(QueryResult::err)?
+                        } else {
+                            let value =
+                                Serializer::format_field(&bytes, old_type).map_err(QueryResult::err)?;
+                            let value = ParsedValue::Literal(value);
+                            self.compile_altered_value(&value, field, old_table, written_payloads)?
+                        }
+                    }
+                    None => match &table.column_defaults[field_idx] {
+                        Some(ParsedValue::NextVal(sequence_name)) => {
+                            let value = self.next_sequence_value(sequence_name)?;
+                            Serializer::parse_int(&value.to_string())
+                                .map_err(QueryResult::err)?
+                                .to_vec()
+                        }
+                        Some(default) => {
+                            self.compile_altered_value(default, field, old_table, written_payloads)?
+                        }
+                        None if table.not_null[field_idx] => {
+                            return Err(Planner::not_null_violation(table, field_idx));
+                        }
+                        None => Serializer::null_field(&field.field_type).map_err(QueryResult::err)?,
+                    },
+                };
+                row.extend(value);
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Compiles a value for `field`; payload-backed values are written to payload pages owned
+    /// by the altered table.
+    fn compile_altered_value(
+        &self,
+        value: &ParsedValue,
+        field: &Field,
+        old_table: &TableSchema,
+        written_payloads: &mut Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>, QueryResult> {
+        let compiled = Planner::compile_parsed_value(value, field)?;
+        if !field.field_type.is_payload_backed() {
+            return Ok(compiled);
+        }
+        let payload = PageManager::write_payload_field(
+            self.pager_accessor.clone(),
+            &compiled,
+            old_table.root.page(),
+        )
+        .map_err(QueryResult::err)?;
+        written_payloads.push(payload.clone());
+        Ok(payload)
+    }
+
+    /// Checks converted rows against the altered table, which `self.schema` holds meanwhile.
+    /// Only the added or retyped column can break a UNIQUE or FOREIGN KEY constraint; rows the
+    /// other columns already broke, e.g. while `foreign_keys` was off, are left as they are.
+    fn check_altered_rows(&self, q: &CompiledAlterTableQuery, rows: &[Row]) -> Result<(), QueryResult> {
+        for row in rows {
+            self.check_row_constraints(q.table_id, row)?;
+        }
+        let column = match &q.action {
+            ParsedAlterTableAction::AddColumn { column, .. }
+            | ParsedAlterTableAction::AlterColumnType { column, .. } => column,
+            _ => return Ok(()),
+        };
+        let table = &self.schema.tables[q.table_id];
+        if q
+            .created_indexes
+            .iter()
+            .any(|idx| idx.unique && idx.column_name == *column)
+        {
+            let field_idx = Self::field_position(table, column)?;
+            let field = &table.fields[field_idx];
+            let mut seen = HashSet::new();
+            for row in rows {
+                let value =
+                    Serializer::get_field_on_row(row, field_idx, table).map_err(QueryResult::err)?;
+                if !Serializer::is_null(&value, &field.field_type).map_err(QueryResult::err)?
+                    && !seen
+                        .insert(self.distinct_value_key(&value, field).map_err(QueryResult::err)?)
+                {
+                    return Err(Self::unique_violation(&table.name, column));
+                }
+            }
+        }
+        self.check_column_foreign_key_parents(q.table_id, column, rows)
+    }
+
+    /// Renames the master row of a sequence; its counter page stays.
+    fn rename_sequence(&mut self, from: &str, to: &str) -> Result<(), QueryResult> {
+        let create_sequence_sql = self.stored_sql(from)?.replacen(
+            &format!("SEQUENCE {}", from),
+            &format!("SEQUENCE {}", to),
+            1,
+        );
+        self.release_master_sql(from)?;
+        let sql_value = self.master_sql_value(&create_sequence_sql)?;
+        let update_master_row = format!(
+            "UPDATE {} SET name = '{}', sql = '{}' WHERE name = '{}'",
+            MASTER_TABLE_NAME,
+            to.replace("'", "''"),
+            sql_value.replace("'", "''"),
+            from.replace("'", "''")
+        );
+        self.run_query_internal(&update_master_row, true)?;
+        Ok(())
+    }
+}
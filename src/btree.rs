@@ -61,8 +61,16 @@ impl BTreeNode {
         PagerProxy::set_key(index, self, key, row)
     }
 
+    /// Keys only move between nodes here, so unlike `PagerProxy::set_key_encoded` this keeps
+    /// the payload chains of the replaced row alive
     fn set_key_encoded(&self, index: usize, key: Key, row: Row) -> Result<(), Status> {
-        PagerProxy::set_key_encoded(index, self, key, row)
+        let mut keys_and_rows = self.get_keys_encoded()?;
+        if index >= keys_and_rows.0.len() {
+            return Err(Status::InternalExceptionIndexOutOfRange);
+        }
+        keys_and_rows.0[index] = key;
+        keys_and_rows.1[index] = row;
+        PagerProxy::set_keys_encoded(self, keys_and_rows.0, keys_and_rows.1)
     }
 
     fn set_keys(&self, keys: Vec<Key>, rows: Vec<Row>) -> Result<(), Status> {
@@ -231,8 +239,8 @@ impl Btree {
         })
     }
 
-    fn compare(&self, a: &Key, b: &Key) -> Result<std::cmp::Ordering, Status> {
-        Serializer::compare_with_type(a, b, &self.table_schema.get_key_type()?)
+    pub(crate) fn compare(&self, a: &Key, b: &Key) -> Result<std::cmp::Ordering, Status> {
+        self.table_schema.compare_keys(a, b, &self.pager_accessor)
     }
 
     pub fn insert(&mut self, k: Key, v: Row) -> Result<(), Status> {
@@ -365,7 +373,7 @@ impl Btree {
             }
         } else {
             if i < x.get_keys_count()? && k == x.get_key_encoded(i)?.0 {
-                self.delete_internal_node(x, k, i, t)?;
+                new_root = self.delete_internal_node(x, k, i, t)?;
             } else {
                 if x.get_child(i)?.get_keys_count()? < t {
                     new_root = self.fill(x, i, t)?;
@@ -402,7 +410,8 @@ impl Btree {
             self.delete_from(&mut x.get_child(i + 1)?, succ_key_and_row.0, t)
         } else {
             let nr = self.merge(x, i, t)?;
-            self.delete_from(&mut x.get_child(i)?, k, t)
+            let r = self.delete_from(&mut x.get_child(i)?, k, t)?;
+            Ok(r.or(nr))
         }
     }
 
@@ -464,7 +473,7 @@ impl Btree {
         child.insert_key(0, k.0, k.1)?;
         //ToDo why is this so ugly? -> Clean Up
         let mut sibling_children = sibling.get_children()?;
-        let mut sibling_keys = sibling.get_keys()?;
+        let mut sibling_keys = sibling.get_keys_encoded()?;
         let opt_last_sibling_key = sibling_keys.0.pop();
         let opt_last_sibling_row = sibling_keys.1.pop();
         let mut last_sibling_key;
@@ -475,7 +484,7 @@ impl Btree {
         } else {
             return Err(Status::InternalExceptionIndexOutOfRange);
         }
-        sibling.set_keys(sibling_keys.0, sibling_keys.1)?;
+        sibling.set_keys_encoded(sibling_keys.0, sibling_keys.1)?;
         x.set_key_encoded(i - 1, last_sibling_key, last_sibling_row)?;
         if !child.is_leaf() {
             let sc = sibling_children.pop().unwrap();
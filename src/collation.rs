@@ -0,0 +1,71 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// How text values compare. A column's collation also orders its B-tree keys and any index
+/// on it, so seeks agree with filters. New collations are added as variants here and named
+/// in `from_name`; they are persisted through the `COLLATE` clause of the table definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collation {
+    /// Byte order of the UTF-8 encoding, which is code point order.
+    #[default]
+    Binary,
+    /// Code point order after lowercasing each character, so 'Alice' = 'alice' and 'a' < 'Z'.
+    NoCase,
+}
+
+impl Collation {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_uppercase().as_str() {
+            "BINARY" => Ok(Collation::Binary),
+            "NOCASE" => Ok(Collation::NoCase),
+            _ => Err(format!("Unknown collation: {}", name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Collation::Binary => "BINARY",
+            Collation::NoCase => "NOCASE",
+        }
+    }
+
+    /// Compares two UTF-8 strings; invalid sequences are compared as U+FFFD.
+    pub fn compare(&self, a: &[u8], b: &[u8]) -> Ordering {
+        match self {
+            Collation::Binary => a.cmp(b),
+            Collation::NoCase => {
+                if a.is_ascii() && b.is_ascii() {
+                    return a
+                        .iter()
+                        .map(u8::to_ascii_lowercase)
+                        .cmp(b.iter().map(u8::to_ascii_lowercase));
+                }
+                let a = String::from_utf8_lossy(a);
+                let b = String::from_utf8_lossy(b);
+                a.chars()
+                    .flat_map(char::to_lowercase)
+                    .cmp(b.chars().flat_map(char::to_lowercase))
+            }
+        }
+    }
+
+    /// Returns bytes that are equal exactly when `compare` says the strings are equal,
+    /// for hashing and equality joins.
+    pub fn key(&self, text: &[u8]) -> Vec<u8> {
+        match self {
+            Collation::Binary => text.to_vec(),
+            Collation::NoCase => String::from_utf8_lossy(text)
+                .chars()
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+                .into_bytes(),
+        }
+    }
+}
+
+impl Display for Collation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
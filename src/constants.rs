@@ -42,6 +42,11 @@ pub enum KeyMeta {
     Tomb = 0,
 }
 
+/// Field-Flag Definition
+/// - Bit 0: Indicates if the Field is NULL
+/// - Bit 1: Indicates if the Field is stored externally
+/// (a Boolean keeps its value in bit 0, so its flags are shifted up by one. Before NULLs,
+/// no flag of a Boolean was ever set, so its byte held 0 or 1 and reads the same today.)
 #[repr(u8)]
 pub enum FieldMeta {
     Null = 0,
@@ -54,12 +59,27 @@ pub const PAGE_SIZE: usize = 4093;
 pub const PAGE_SIZE_WITH_META: usize = PAGE_SIZE + 3;
 /// B-Tree node metadata bytes: `num_keys` + `flag`.
 pub const NODE_METADATA_SIZE: usize = 2;
+/// Bytes of a sequence counter (big-endian i64) at the start of its page.
+pub const SEQUENCE_COUNTER_SIZE: usize = 8;
 /// Fixed byte length for `String` values.
 pub const STRING_SIZE: usize = 256;
+/// Bytes reserved per character of a `Varchar(n)`, the longest UTF-8 encoding. Rows are
+/// fixed-width, so every cell reserves this for all n characters, whatever it holds.
+pub const VARCHAR_BYTES_PER_CHAR: usize = 4;
 /// Fixed byte length for `Integer` values (including flag byte).
 pub const INTEGER_SIZE: usize = 5;
+/// Largest supported precision for `Decimal(p, s)` (fits into an i128).
+pub const DECIMAL_MAX_PRECISION: usize = 38;
 /// Fixed byte length for `Date` values.
 pub const DATE_SIZE: usize = 5;
+/// Fixed byte length for `Timestamp` values (i64 microseconds since epoch + flag byte).
+pub const TIMESTAMP_SIZE: usize = 9;
+/// Fixed byte length for `TimestampTz` values (UTC microseconds, i16 offset minutes, flag byte).
+pub const TIMESTAMPTZ_SIZE: usize = 11;
+/// Fixed byte length for `Time` values (microseconds since midnight + flag byte).
+pub const TIME_SIZE: usize = 9;
+/// Fixed byte length for `Uuid` values (16 raw bytes + flag byte).
+pub const UUID_SIZE: usize = 17;
 /// Fixed byte length for `Boolean` values.
 pub const BOOLEAN_SIZE: usize = 1;
 /// Fixed byte length for `Null` placeholders.
@@ -112,3 +132,11 @@ pub const EXTERNAL_MARKER_OFFSET: usize = EXTERNAL_LEN_OFFSET + 2;
 pub const EXTERNAL_ORIG_FLAG_OFFSET: usize = EXTERNAL_MARKER_OFFSET + 1;
 /// Minimum field length required to hold externalization metadata.
 pub const EXTERNAL_META_MIN_FIELD_LEN: usize = EXTERNAL_ORIG_FLAG_OFFSET + 1;
+
+/// Fixed in-row byte length of payload-backed fields (`Blob`, `Text`, `Json`):
+/// inline prefix, u32 content length, payload pointer and flag byte.
+pub const PAYLOAD_FIELD_SIZE: usize = PAYLOAD_FIELD_PTR_OFFSET + POSITION_SIZE + 1;
+/// Offset of the total content length (u32) in a payload-backed field.
+pub const PAYLOAD_FIELD_LEN_OFFSET: usize = INLINE_STRING_PREFIX_LEN;
+/// Offset of the payload chain pointer in a payload-backed field.
+pub const PAYLOAD_FIELD_PTR_OFFSET: usize = PAYLOAD_FIELD_LEN_OFFSET + 4;
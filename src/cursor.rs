@@ -1,6 +1,7 @@
 use crate::btree::{BTreeNode, Btree};
 use crate::debug::Status;
 use crate::pager::{Key, Position, Row};
+use std::cmp::Ordering;
 
 #[derive(Debug, Clone)]
 pub struct BTreeCursor {
@@ -206,11 +207,11 @@ impl BTreeCursor {
         loop {
             let (keys, _) = current.get_keys()?;
             let mut i = 0usize;
-            while i < keys.len() && &keys[i] < k {
+            while i < keys.len() && self.btree.compare(&keys[i], k)? == Ordering::Less {
                 i += 1;
             }
 
-            if i < keys.len() && &keys[i] == k {
+            if i < keys.len() && self.btree.compare(&keys[i], k)? == Ordering::Equal {
                 self.stack.push((current, i));
                 return Ok(());
             }
@@ -225,6 +226,48 @@ impl BTreeCursor {
         }
     }
 
+    /// Positions the cursor on the first key that is not less than `k`, or invalidates it if
+    /// there is none. Unlike `go_to`, `k` does not have to be in the tree, and among keys
+    /// equal to `k` (duplicates, or keys starting with the prefix `k`) the first is found.
+    pub fn go_to_greater_than_equal(&mut self, k: &Key) -> Result<(), Status> {
+        self.stack.clear();
+
+        let root = match &self.btree.root {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+
+        if root.get_keys_count()? == 0 {
+            return Ok(());
+        }
+
+        let mut current = root.clone();
+
+        loop {
+            let (keys, _) = current.get_keys()?;
+            let mut i = 0usize;
+            while i < keys.len() && self.btree.compare(&keys[i], k)? == Ordering::Less {
+                i += 1;
+            }
+
+            self.stack.push((current.clone(), i));
+            if current.is_leaf() {
+                break;
+            }
+            current = current.get_child(i)?;
+        }
+
+        // past the end of the leaf, the next key is the nearest ancestor's
+        while let Some((node, idx)) = self.stack.last() {
+            if *idx < node.get_keys_count()? {
+                break;
+            }
+            self.stack.pop();
+        }
+
+        Ok(())
+    }
+
     pub fn go_to_less_than_equal(&mut self, k: &Key) -> Result<(), Status> {
         self.stack.clear();
 
@@ -243,13 +286,13 @@ impl BTreeCursor {
         loop {
             let (keys, _) = current.get_keys()?;
             let mut i = 0usize;
-            while i < keys.len() && &keys[i] < k {
+            while i < keys.len() && self.btree.compare(&keys[i], k)? == Ordering::Less {
                 i += 1;
             }
 
             self.stack.push((current.clone(), i));
 
-            if i < keys.len() && &keys[i] == k {
+            if i < keys.len() && self.btree.compare(&keys[i], k)? == Ordering::Equal {
                 found = true;
                 break;
             }
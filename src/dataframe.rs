@@ -1,7 +1,10 @@
 use crate::btree::Btree;
+use crate::collation::Collation;
 use crate::cursor::BTreeCursor;
 use crate::debug::Status;
-use crate::pager::{PagerAccessor, Position, Row, Type};
+use crate::json::{self, JsonPath, JsonValue};
+use crate::pager::{PagerAccessor, Position, Row, STRING_SIZE, Type};
+use crate::pager_proxy::PageManager;
 use crate::parser::ParsedSetOperator;
 use crate::planner::{
     CompiledLogicalOp, CompiledPredicateExpr,
@@ -22,6 +25,11 @@ pub struct DataFrame {
     pub header: Vec<Field>,
     pub(crate) row_source: Source,
     cursor_started: bool,
+    /// Needed to resolve payload-backed fields; `None` for frames built from memory.
+    pager_accessor: Option<PagerAccessor>,
+    /// Content of payload-backed fields read in advance, by field descriptor, for rows whose
+    /// payloads may be released before the frame is read.
+    payload_contents: HashMap<Vec<u8>, Vec<u8>>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,11 +54,22 @@ pub enum PreparedPredicateExpr {
         column_idx: usize,
         op: SqlStatementComparisonOperator,
         value: Vec<u8>,
+        collation: Collation,
     },
     InSubquery {
         column_idx: usize,
         strategy: PreparedInStrategy,
     },
+    JsonCompare {
+        column_idx: usize,
+        path: JsonPath,
+        op: SqlStatementComparisonOperator,
+        value: String,
+    },
+    IsNull {
+        column_idx: usize,
+        negated: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +87,26 @@ impl DataFrame {
             header,
             row_source: Source::Memory(MemorySource { data, idx: 0 }),
             cursor_started: false,
+            pager_accessor: None,
+            payload_contents: HashMap::new(),
+        }
+    }
+
+    /// A frame of rows that may outlive their payloads, e.g. the rows a DELETE returns;
+    /// `payload_contents` maps each payload-backed field of the rows to its content.
+    pub fn from_returned_rows(
+        identifier: String,
+        header: Vec<Field>,
+        data: Vec<Row>,
+        payload_contents: HashMap<Vec<u8>, Vec<u8>>,
+    ) -> DataFrame {
+        DataFrame {
+            identifier,
+            header,
+            row_source: Source::Memory(MemorySource { data, idx: 0 }),
+            cursor_started: false,
+            pager_accessor: None,
+            payload_contents,
         }
     }
 
@@ -79,11 +118,14 @@ impl DataFrame {
         seek_key: Option<Vec<u8>>,
     ) -> DataFrame {
         let schema = btree.table_schema.clone();
+        let pager_accessor = Some(btree.pager_accessor.clone());
         DataFrame {
             identifier,
             header,
             row_source: Source::BTree(BTreeScanSource::new(btree, schema, operation, seek_key)),
             cursor_started: false,
+            pager_accessor,
+            payload_contents: HashMap::new(),
         }
     }
 
@@ -97,6 +139,7 @@ impl DataFrame {
         index_operation: SqlConditionOpCode,
         index_seek_key: Option<Vec<u8>>,
     ) -> DataFrame {
+        let pager_accessor = Some(base_btree.pager_accessor.clone());
         DataFrame {
             identifier,
             header,
@@ -109,6 +152,8 @@ impl DataFrame {
                 index_seek_key,
             )),
             cursor_started: false,
+            pager_accessor,
+            payload_contents: HashMap::new(),
         }
     }
 
@@ -120,12 +165,15 @@ impl DataFrame {
                 field_type: Type::String,
                 name: "Message".to_string(),
                 table_name: "".to_string(),
+                collation: Collation::Binary,
             }],
             row_source: Source::Memory(MemorySource {
                 data: vec![Serializer::parse_string(message).to_vec()],
                 idx: 0,
             }),
             cursor_started: false,
+            pager_accessor: None,
+            payload_contents: HashMap::new(),
         }
     }
 
@@ -160,6 +208,18 @@ impl DataFrame {
         Ok(rows)
     }
 
+    /// Reads the content behind a payload-backed field (e.g. `Blob`) of this frame's rows.
+    pub fn read_payload_field(&self, field_bytes: &[u8]) -> Result<Vec<u8>, Status> {
+        if let Some(content) = self.payload_contents.get(field_bytes) {
+            return Ok(content.clone());
+        }
+        let pager_accessor = self
+            .pager_accessor
+            .clone()
+            .ok_or(Status::InternalExceptionPagerMismatch)?;
+        PageManager::read_payload_field(pager_accessor, field_bytes)
+    }
+
     pub fn filter_prepared(
         self,
         condition: PreparedConditionExpr,
@@ -176,6 +236,8 @@ impl DataFrame {
                 context,
             )),
             cursor_started: false,
+            pager_accessor: self.pager_accessor,
+            payload_contents: self.payload_contents,
         }
     }
 
@@ -198,6 +260,25 @@ impl DataFrame {
                 self.header,
             )),
             cursor_started: false,
+            pager_accessor: self.pager_accessor,
+            payload_contents: self.payload_contents,
+        }
+    }
+
+    /// Appends the text found at each `(column, path)` of a JSON column as a STRING field.
+    pub fn json_extract(self, new_header: Vec<Field>, extractions: Vec<(usize, JsonPath)>) -> DataFrame {
+        DataFrame {
+            identifier: format!("JsonExtract({})", self.identifier),
+            header: new_header,
+            row_source: Source::JsonExtract(JsonExtractSource {
+                source: Box::new(self.row_source),
+                source_header: self.header,
+                extractions,
+                pager_accessor: self.pager_accessor.clone(),
+            }),
+            cursor_started: false,
+            pager_accessor: self.pager_accessor,
+            payload_contents: self.payload_contents,
         }
     }
 
@@ -242,6 +323,8 @@ impl DataFrame {
             header: result_header,
             row_source: new_source,
             cursor_started: false,
+            pager_accessor: self.pager_accessor.or(other.pager_accessor),
+            payload_contents: self.payload_contents.into_iter().chain(other.payload_contents).collect(),
         })
     }
 
@@ -263,6 +346,8 @@ impl DataFrame {
             header: self.header,
             row_source: Source::SetOp(new_source),
             cursor_started: false,
+            pager_accessor: self.pager_accessor.or(other.pager_accessor),
+            payload_contents: self.payload_contents.into_iter().chain(other.payload_contents).collect(),
         })
     }
 }
@@ -290,9 +375,23 @@ impl Display for DataFrame {
                             Serializer::get_size_of_type(field_type).map_err(|_| fmt::Error)?;
 
                         let field_value = &row[position..position + field_len];
-                        let formatted_value =
-                            Serializer::format_field(&field_value.to_vec(), field_type)
+                        let is_null = *field_type != Type::Null
+                            && Serializer::is_null(&field_value.to_vec(), field_type)
                                 .map_err(|_| fmt::Error)?;
+                        let formatted_value =
+                            if field_type.is_payload_backed()
+                                && !is_null
+                                && (self.pager_accessor.is_some()
+                                    || self.payload_contents.contains_key(field_value))
+                            {
+                                let content = self
+                                    .read_payload_field(field_value)
+                                    .map_err(|_| fmt::Error)?;
+                                Serializer::format_payload_content(&content, field_type)
+                            } else {
+                                Serializer::format_field(&field_value.to_vec(), field_type)
+                                    .map_err(|_| fmt::Error)?
+                            };
 
                         write!(f, "{}\t", formatted_value)?;
                         position += field_len;
@@ -319,6 +418,7 @@ pub enum Source {
     SetOp(SetOperationSource),
     Filter(FilterSource),
     Project(ProjectSource),
+    JsonExtract(JsonExtractSource),
 }
 
 pub trait RowSource {
@@ -348,6 +448,7 @@ impl RowSource for Source {
             Source::SetOp(s) => s.next(),
             Source::Filter(s) => s.next(),
             Source::Project(s) => s.next(),
+            Source::JsonExtract(s) => s.next(),
         }
     }
 
@@ -362,6 +463,7 @@ impl RowSource for Source {
             Source::SetOp(s) => s.reset(),
             Source::Filter(s) => s.reset(),
             Source::Project(s) => s.reset(),
+            Source::JsonExtract(s) => s.reset(),
         }
     }
 
@@ -376,6 +478,7 @@ impl RowSource for Source {
             Source::SetOp(s) => s.lookup(target_row),
             Source::Filter(s) => s.lookup(target_row),
             Source::Project(s) => s.lookup(target_row),
+            Source::JsonExtract(s) => s.lookup(target_row),
         }
     }
 }
@@ -642,16 +745,13 @@ impl BTreeScanSource {
     ) -> Result<(), Status> {
         match op_code {
             SqlConditionOpCode::SelectFTS => cursor.move_to_start(),
-            SqlConditionOpCode::SelectKeyRange => {
+            // the seek key may be absent, repeated or a prefix of the stored keys; rows
+            // past it are left to the filter
+            SqlConditionOpCode::SelectKeyRange
+            | SqlConditionOpCode::SelectKeyUnique
+            | SqlConditionOpCode::SelectIndexUnique => {
                 if let Some(val) = seek_key {
-                    cursor.go_to(val)
-                } else {
-                    cursor.move_to_start()
-                }
-            }
-            SqlConditionOpCode::SelectKeyUnique | SqlConditionOpCode::SelectIndexUnique => {
-                if let Some(val) = seek_key {
-                    cursor.go_to(val)
+                    cursor.go_to_greater_than_equal(val)
                 } else {
                     cursor.move_to_start()
                 }
@@ -693,7 +793,8 @@ impl IndexLookupSource {
 impl RowSource for IndexLookupSource {
     fn next(&mut self) -> Result<Option<Vec<u8>>, Status> {
         while let Some(index_row) = self.index_source.next()? {
-            let base_pk = Serializer::get_field_on_row(&index_row, 1, &self.index_source.schema)?;
+            // everything after idx_value is the base key
+            let (_, base_pk) = Serializer::split_key_and_row(&index_row, &self.index_source.schema)?;
             self.base_cursor.go_to(&base_pk)?;
             if !self.base_cursor.is_valid() {
                 continue;
@@ -809,11 +910,22 @@ enum RuntimePredicateExpr {
         column_idx: usize,
         op: SqlStatementComparisonOperator,
         value: Vec<u8>,
+        collation: Collation,
     },
     InSubquery {
         column_idx: usize,
         strategy: RuntimeInStrategy,
     },
+    JsonCompare {
+        column_idx: usize,
+        path: JsonPath,
+        op: SqlStatementComparisonOperator,
+        value: String,
+    },
+    IsNull {
+        column_idx: usize,
+        negated: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -847,6 +959,28 @@ impl FilterSource {
         }
     }
 
+    /// Evaluates `condition` on a single full row of `schema`, e.g. for CHECK constraints.
+    pub fn evaluate_on_row(
+        condition: PreparedConditionExpr,
+        row: &Row,
+        schema: TableSchema,
+        context: ConditionEvalContext,
+    ) -> Result<Option<bool>, Status> {
+        let filter = Self::new(
+            Box::new(Source::Memory(MemorySource::new(vec![]))),
+            condition,
+            schema,
+            context,
+        );
+        let mut runtime = filter.prepare_runtime_condition(&filter.condition)?;
+        Self::evaluate_runtime_condition(
+            &mut runtime,
+            row,
+            &filter.schema,
+            &filter.context.pager_accessor,
+        )
+    }
+
     fn initialize_runtime_condition(&mut self) -> Result<(), Status> {
         self.runtime_condition = Some(self.prepare_runtime_condition(&self.condition)?);
         Ok(())
@@ -878,10 +1012,12 @@ impl FilterSource {
                     column_idx,
                     op,
                     value,
+                    collation,
                 } => Ok(RuntimeConditionExpr::Predicate(RuntimePredicateExpr::Compare {
                     column_idx: *column_idx,
                     op: *op,
                     value: value.clone(),
+                    collation: *collation,
                 })),
                 PreparedPredicateExpr::InSubquery {
                     column_idx,
@@ -901,23 +1037,52 @@ impl FilterSource {
                         strategy: runtime_strategy,
                     }))
                 }
+                PreparedPredicateExpr::IsNull {
+                    column_idx,
+                    negated,
+                } => Ok(RuntimeConditionExpr::Predicate(RuntimePredicateExpr::IsNull {
+                    column_idx: *column_idx,
+                    negated: *negated,
+                })),
+                PreparedPredicateExpr::JsonCompare {
+                    column_idx,
+                    path,
+                    op,
+                    value,
+                } => Ok(RuntimeConditionExpr::Predicate(RuntimePredicateExpr::JsonCompare {
+                    column_idx: *column_idx,
+                    path: path.clone(),
+                    op: *op,
+                    value: value.clone(),
+                })),
             },
         }
     }
 
+    /// Evaluates `expr` in three-valued logic: `None` is unknown, e.g. a comparison with NULL.
+    /// Only rows evaluating to `Some(true)` pass the filter.
     fn evaluate_runtime_condition(
         expr: &mut RuntimeConditionExpr,
         row: &Row,
         schema: &TableSchema,
-    ) -> Result<bool, Status> {
+        pager_accessor: &PagerAccessor,
+    ) -> Result<Option<bool>, Status> {
         match expr {
             RuntimeConditionExpr::Logical { op, left, right } => {
-                let l = Self::evaluate_runtime_condition(left, row, schema)?;
-                let r = Self::evaluate_runtime_condition(right, row, schema)?;
+                let l = Self::evaluate_runtime_condition(left, row, schema, pager_accessor)?;
+                let r = Self::evaluate_runtime_condition(right, row, schema, pager_accessor)?;
                 Ok(match op {
-                    CompiledLogicalOp::And => l && r,
-                    CompiledLogicalOp::Or => l || r,
-                    CompiledLogicalOp::Xor => l ^ r,
+                    CompiledLogicalOp::And => match (l, r) {
+                        (Some(false), _) | (_, Some(false)) => Some(false),
+                        (Some(true), Some(true)) => Some(true),
+                        _ => None,
+                    },
+                    CompiledLogicalOp::Or => match (l, r) {
+                        (Some(true), _) | (_, Some(true)) => Some(true),
+                        (Some(false), Some(false)) => Some(false),
+                        _ => None,
+                    },
+                    CompiledLogicalOp::Xor => l.zip(r).map(|(l, r)| l ^ r),
                 })
             }
             RuntimeConditionExpr::Predicate(pred) => match pred {
@@ -925,42 +1090,78 @@ impl FilterSource {
                     column_idx,
                     op,
                     value,
+                    collation,
                 } => {
                     if *op == SqlStatementComparisonOperator::None {
-                        return Ok(true);
+                        return Ok(Some(true));
                     }
-                    let lhs = Serializer::get_field_on_row(row, *column_idx, schema)?;
+                    let mut lhs = Serializer::get_field_on_row(row, *column_idx, schema)?;
                     let field_type = &schema.fields[*column_idx].field_type;
-                    let ord = Serializer::compare_with_type(&lhs, value, field_type)?;
-                    Ok(match op {
-                        SqlStatementComparisonOperator::Equal => ord == std::cmp::Ordering::Equal,
-                        SqlStatementComparisonOperator::Greater => {
-                            ord == std::cmp::Ordering::Greater
-                        }
-                        SqlStatementComparisonOperator::GreaterOrEqual => {
-                            ord == std::cmp::Ordering::Greater || ord == std::cmp::Ordering::Equal
-                        }
-                        SqlStatementComparisonOperator::Lesser => ord == std::cmp::Ordering::Less,
-                        LesserOrEqual => {
-                            ord == std::cmp::Ordering::Less || ord == std::cmp::Ordering::Equal
-                        }
-                        SqlStatementComparisonOperator::None => true,
-                    })
+                    if Serializer::is_null(&lhs, field_type)? {
+                        return Ok(None);
+                    }
+                    if field_type.is_payload_backed() {
+                        lhs = PageManager::read_payload_field(pager_accessor.clone(), &lhs)?;
+                    }
+                    let ord =
+                        Serializer::compare_with_collation(&lhs, value, field_type, *collation)?;
+                    Ok(Some(Self::ordering_matches(op, ord)))
+                }
+                RuntimePredicateExpr::JsonCompare {
+                    column_idx,
+                    path,
+                    op,
+                    value,
+                } => {
+                    let field = Serializer::get_field_on_row(row, *column_idx, schema)?;
+                    if Serializer::is_null(&field, &Type::Json)? {
+                        return Ok(None);
+                    }
+                    let content = PageManager::read_payload_field(pager_accessor.clone(), &field)?;
+                    let document = JsonValue::parse(&Serializer::format_payload_content(&content, &Type::Json))?;
+                    Ok(document
+                        .get(path)
+                        .and_then(JsonValue::to_text)
+                        .map(|text| Self::ordering_matches(op, json::compare_text(&text, value))))
+                }
+                RuntimePredicateExpr::IsNull {
+                    column_idx,
+                    negated,
+                } => {
+                    let field = Serializer::get_field_on_row(row, *column_idx, schema)?;
+                    let is_null = Serializer::is_null(&field, &schema.fields[*column_idx].field_type)?;
+                    Ok(Some(is_null != *negated))
                 }
                 RuntimePredicateExpr::InSubquery {
                     column_idx,
                     strategy,
                 } => {
                     let lhs = Serializer::get_field_on_row(row, *column_idx, schema)?;
+                    if Serializer::is_null(&lhs, &schema.fields[*column_idx].field_type)? {
+                        return Ok(None);
+                    }
                     match strategy {
-                        RuntimeInStrategy::Materialized(set) => Ok(set.contains(&lhs)),
-                        RuntimeInStrategy::Lookup(source) => source.lookup(&lhs),
+                        RuntimeInStrategy::Materialized(set) => Ok(Some(set.contains(&lhs))),
+                        RuntimeInStrategy::Lookup(source) => Ok(Some(source.lookup(&lhs)?)),
                     }
                 }
             },
         }
     }
 
+    fn ordering_matches(op: &SqlStatementComparisonOperator, ord: std::cmp::Ordering) -> bool {
+        match op {
+            SqlStatementComparisonOperator::Equal => ord == std::cmp::Ordering::Equal,
+            SqlStatementComparisonOperator::Greater => ord == std::cmp::Ordering::Greater,
+            SqlStatementComparisonOperator::GreaterOrEqual => {
+                ord == std::cmp::Ordering::Greater || ord == std::cmp::Ordering::Equal
+            }
+            SqlStatementComparisonOperator::Lesser => ord == std::cmp::Ordering::Less,
+            LesserOrEqual => ord == std::cmp::Ordering::Less || ord == std::cmp::Ordering::Equal,
+            SqlStatementComparisonOperator::None => true,
+        }
+    }
+
     fn is_full_row_for_schema(row: &[u8], schema: &TableSchema) -> bool {
         Serializer::split_row_into_fields(&row.to_vec(), &schema.fields).is_ok()
     }
@@ -983,7 +1184,13 @@ impl RowSource for FilterSource {
                 .as_mut()
                 .ok_or(Status::InternalExceptionCompilerError)?;
 
-            if Self::evaluate_runtime_condition(runtime, &row, &self.schema)? {
+            if Self::evaluate_runtime_condition(
+                runtime,
+                &row,
+                &self.schema,
+                &self.context.pager_accessor,
+            )? == Some(true)
+            {
                 return Ok(Some(row));
             }
         }
@@ -1017,7 +1224,12 @@ impl RowSource for FilterSource {
             .runtime_condition
             .as_mut()
             .ok_or(Status::InternalExceptionCompilerError)?;
-        Self::evaluate_runtime_condition(runtime, &target_row.to_vec(), &self.schema)
+        Ok(Self::evaluate_runtime_condition(
+            runtime,
+            &target_row.to_vec(),
+            &self.schema,
+            &self.context.pager_accessor,
+        )? == Some(true))
     }
 }
 
@@ -1089,6 +1301,60 @@ impl RowSource for ProjectSource {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct JsonExtractSource {
+    source: Box<Source>,
+    source_header: Vec<Field>,
+    extractions: Vec<(usize, JsonPath)>,
+    pager_accessor: Option<PagerAccessor>,
+}
+
+impl JsonExtractSource {
+    /// Missing paths and JSON nulls become NULL; longer values than a STRING holds are an error.
+    fn extract(&self, field: &[u8], path: &JsonPath) -> Result<Vec<u8>, Status> {
+        let mut result = vec![0u8; STRING_SIZE];
+        if Serializer::is_null(&field.to_vec(), &Type::Json)? {
+            Serializer::set_is_null(&mut result, true, &Type::String)?;
+            return Ok(result);
+        }
+        let pager_accessor = self
+            .pager_accessor
+            .clone()
+            .ok_or(Status::InternalExceptionPagerMismatch)?;
+        let content = PageManager::read_payload_field(pager_accessor, field)?;
+        let document = JsonValue::parse(&Serializer::format_payload_content(&content, &Type::Json))?;
+        match document.get(path).and_then(JsonValue::to_text) {
+            Some(text) if text.len() >= STRING_SIZE => Err(Status::ExceptionJsonValueTooLong),
+            Some(text) => Ok(Serializer::parse_string(&text).to_vec()),
+            None => {
+                Serializer::set_is_null(&mut result, true, &Type::String)?;
+                Ok(result)
+            }
+        }
+    }
+}
+
+impl RowSource for JsonExtractSource {
+    fn next(&mut self) -> Result<Option<Vec<u8>>, Status> {
+        let mut row = match self.source.next()? {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+        let fields = Serializer::split_row_into_fields(&row, &self.source_header)?
+            .into_iter()
+            .map(|f| f.to_vec())
+            .collect::<Vec<_>>();
+        for (column_idx, path) in &self.extractions {
+            row.extend(self.extract(&fields[*column_idx], path)?);
+        }
+        Ok(Some(row))
+    }
+
+    fn reset(&mut self) -> Result<(), Status> {
+        self.source.reset()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NestedLoopJoinSource {
     left: Box<Source>,
@@ -1142,7 +1408,11 @@ impl RowSource for NestedLoopJoinSource {
 
                 let mut match_found = true;
                 for (l_idx, r_idx) in &self.conditions {
-                    if l_fields[*l_idx] != r_fields[*r_idx] {
+                    let l_field = &self.left_header[*l_idx];
+                    if join_key(l_fields[*l_idx], l_field, l_field)
+                        != join_key(r_fields[*r_idx], &self.right_header[*r_idx], l_field)
+                        || is_null_key(l_fields[*l_idx], l_field)?
+                    {
                         match_found = false;
                         break;
                     }
@@ -1169,6 +1439,16 @@ impl RowSource for NestedLoopJoinSource {
     }
 }
 
+/// NULL never equals anything, so rows with a NULL join key do not join.
+fn is_null_key(field: &[u8], header_field: &Field) -> Result<bool, Status> {
+    Serializer::is_null(&field.to_vec(), &header_field.field_type)
+}
+
+/// Join keys compare under the collation of the left column.
+fn join_key(field: &[u8], header_field: &Field, left_field: &Field) -> Vec<u8> {
+    Serializer::collation_key(field, &header_field.field_type, left_field.collation)
+}
+
 #[derive(Debug, Clone)]
 pub struct HashJoinSource {
     left: Box<Source>,
@@ -1212,7 +1492,14 @@ impl HashJoinSource {
         self.right.reset()?;
         while let Some(r_row) = self.right.next()? {
             let fields = Serializer::split_row_into_fields(&r_row, &self.right_header)?;
-            let key = fields[self.right_join_idx].clone().to_vec();
+            if is_null_key(fields[self.right_join_idx], &self.right_header[self.right_join_idx])? {
+                continue;
+            }
+            let key = join_key(
+                fields[self.right_join_idx],
+                &self.right_header[self.right_join_idx],
+                &self.left_header[self.left_join_idx],
+            );
             map.entry(key).or_default().push(r_row);
         }
 
@@ -1233,7 +1520,8 @@ impl RowSource for HashJoinSource {
         loop {
             if let Some(l_row) = &self.current_left_row {
                 let l_fields = Serializer::split_row_into_fields(l_row, &self.left_header)?;
-                let key = l_fields[self.left_join_idx].to_vec();
+                let left_field = &self.left_header[self.left_join_idx];
+                let key = join_key(l_fields[self.left_join_idx], left_field, left_field);
 
                 if let Some(matches) = map.get(&key) {
                     if self.current_match_index < matches.len() {
@@ -1326,8 +1614,21 @@ impl RowSource for SortMergeJoinSource {
             let r_row = self.r_curr.as_ref().unwrap();
             let l_key_bytes = self.get_key(l_row, &self.left_header, self.left_join_idx)?;
             let r_key_bytes = self.get_key(r_row, &self.right_header, self.right_join_idx)?;
-            let key_type = &self.left_header[self.left_join_idx].field_type;
-            let ordering = Serializer::compare_with_type(&l_key_bytes, &r_key_bytes, key_type)?;
+            if is_null_key(&l_key_bytes, &self.left_header[self.left_join_idx])? {
+                self.l_curr = self.left.next()?;
+                continue;
+            }
+            if is_null_key(&r_key_bytes, &self.right_header[self.right_join_idx])? {
+                self.r_curr = self.right.next()?;
+                continue;
+            }
+            let key_field = &self.left_header[self.left_join_idx];
+            let ordering = Serializer::compare_with_collation(
+                &l_key_bytes,
+                &r_key_bytes,
+                &key_field.field_type,
+                key_field.collation,
+            )?;
 
             match ordering {
                 std::cmp::Ordering::Less => {
@@ -1,10 +1,11 @@
 use crate::btree::Btree;
+use crate::collation::Collation;
 use crate::dataframe::DataFrame;
 use crate::executor::{QueryExecutor, QueryResult};
 use crate::pager::Position;
 use crate::planner::{
-    CompiledConditionExpr, CompiledInStrategy, CompiledPredicateExpr, CompiledQuery,
-    CompiledTransactionStatement, PlanNode, Planner,
+    CompiledConditionExpr, CompiledInStrategy, CompiledPragmaStatement, CompiledPredicateExpr,
+    CompiledQuery, CompiledTransactionStatement, PlanNode, Planner,
 };
 use crate::serializer::Serializer;
 use std::cmp::PartialEq;
@@ -42,6 +43,15 @@ pub enum Status {
     CannotParseInteger,
     CannotParseBoolean,
     CannotParseIllegalDate,
+    CannotParseTimestamp,
+    CannotParseTime,
+    CannotParseDecimal,
+    CannotParseBlob,
+    CannotParseUuid,
+    CannotParseJson,
+    CannotParseJsonPath,
+    ExceptionJsonValueTooLong,
+    ExceptionDecimalOverflow,
     InternalExceptionPagerWriteLock,
     InternalExceptionCompilerError,
     InternalExceptionIntegrityCheckFailed,
@@ -128,13 +138,18 @@ impl Planner {
                     column_idx,
                     op,
                     value,
+                    collation,
                 } => {
                     out.push_str(&format!(
-                        "{}Compare col[{}] {} {}\n",
+                        "{}Compare col[{}] {} {}{}\n",
                         prefix,
                         column_idx,
                         Serializer::format_condition_op(op),
-                        Serializer::format_value_preview(value)
+                        Serializer::format_value_preview(value),
+                        match collation {
+                            Collation::Binary => String::new(),
+                            other => format!(" COLLATE {}", other),
+                        }
                     ));
                 }
                 CompiledPredicateExpr::InSubquery {
@@ -153,6 +168,28 @@ impl Planner {
                         }
                     }
                 }
+                CompiledPredicateExpr::IsNull {
+                    column_idx,
+                    negated,
+                } => {
+                    let test = if *negated { "IS NOT NULL" } else { "IS NULL" };
+                    out.push_str(&format!("{}col[{}] {}\n", prefix, column_idx, test));
+                }
+                CompiledPredicateExpr::JsonCompare {
+                    column_idx,
+                    path,
+                    op,
+                    value,
+                } => {
+                    out.push_str(&format!(
+                        "{}Compare col[{}]->>'{}' {} '{}'\n",
+                        prefix,
+                        column_idx,
+                        path,
+                        Serializer::format_condition_op(op),
+                        value
+                    ));
+                }
             },
         }
     }
@@ -210,6 +247,19 @@ impl Planner {
                 ));
                 Self::render_plan_node(source, &next_prefix, true, out);
             }
+            PlanNode::JsonExtract {
+                source,
+                extractions,
+            } => {
+                out.push_str(&format!("{}{} JsonExtract\n", prefix, branch));
+                for extraction in extractions {
+                    out.push_str(&format!(
+                        "{}  col[{}] {} as {}\n",
+                        next_prefix, extraction.column_idx, extraction.path, extraction.output.name
+                    ));
+                }
+                Self::render_plan_node(source, &next_prefix, true, out);
+            }
             PlanNode::Join {
                 left,
                 right,
@@ -252,6 +302,19 @@ impl Planner {
                 q.data.0.len(),
                 q.data.1.len()
             ),
+            CompiledQuery::InsertBatch(q) => format!(
+                "CompiledQuery::InsertBatch\n└─ table_id={} rows={}",
+                q.table_id,
+                q.rows.len()
+            ),
+            CompiledQuery::InsertSelect(q) => {
+                let mut out = format!(
+                    "CompiledQuery::InsertSelect\n└─ table_id={} fields={:?}\n",
+                    q.table_id, q.fields
+                );
+                Self::render_plan_node(&q.plan, "   ", true, &mut out);
+                out
+            }
             CompiledQuery::Delete(q) => {
                 let mut out = format!(
                     "CompiledQuery::Delete\n└─ table_id={} op={:?}\n",
@@ -299,7 +362,9 @@ impl Planner {
                         "   set[{}]: field[{}] = {}\n",
                         idx,
                         field_idx,
-                        Serializer::format_value_preview(value)
+                        value
+                            .as_deref()
+                            .map_or("NULL".to_string(), Serializer::format_value_preview)
                     ));
                 }
                 out
@@ -309,10 +374,50 @@ impl Planner {
                 q.table_name,
                 q.schema.fields.len()
             ),
+            CompiledQuery::CreateTableAs(q) => {
+                let mut out = format!(
+                    "CompiledQuery::CreateTableAs\n└─ table='{}'\n",
+                    q.table_name
+                );
+                Self::render_plan_node(&q.plan, "   ", true, &mut out);
+                out
+            }
             CompiledQuery::CreateIndex(q) => format!(
                 "CompiledQuery::CreateIndex\n└─ index='{}' base='{}' column='{}' table='{}'",
                 q.index_name, q.base_table_name, q.column_name, q.schema.name
             ),
+            CompiledQuery::CreateSequence(q) => format!(
+                "CompiledQuery::CreateSequence\n└─ sequence='{}' start={} increment={}",
+                q.sequence_name, q.start, q.increment
+            ),
+            CompiledQuery::DropSequence(q) => {
+                format!("CompiledQuery::DropSequence\n└─ sequence='{}'", q.sequence_name)
+            }
+            CompiledQuery::CreateView(q) => {
+                format!("CompiledQuery::CreateView\n└─ view='{}'", q.view_name)
+            }
+            CompiledQuery::CreateMaterializedView(q) => format!(
+                "CompiledQuery::CreateMaterializedView\n└─ view='{}' fields={}",
+                q.schema.name,
+                q.schema.fields.len()
+            ),
+            CompiledQuery::RefreshMaterializedView(q) => format!(
+                "CompiledQuery::RefreshMaterializedView\n└─ table_id={}",
+                q.table_id
+            ),
+            CompiledQuery::DropView(q) => {
+                format!("CompiledQuery::DropView\n└─ view='{}'", q.view_name)
+            }
+            CompiledQuery::CreateTrigger(q) => {
+                format!("CompiledQuery::CreateTrigger\n└─ trigger='{}'", q.trigger_name)
+            }
+            CompiledQuery::DropTrigger(q) => {
+                format!("CompiledQuery::DropTrigger\n└─ trigger='{}'", q.trigger_name)
+            }
+            CompiledQuery::AlterTable(q) => format!(
+                "CompiledQuery::AlterTable\n└─ table_id={} action={:?} dropped_indexes={:?}",
+                q.table_id, q.action, q.dropped_indexes
+            ),
             CompiledQuery::DropTable(q) => {
                 format!("CompiledQuery::DropTable\n└─ table_id={}", q.table_id)
             }
@@ -327,6 +432,10 @@ impl Planner {
                 };
                 format!("CompiledQuery::Transaction\n└─ {}", action)
             }
+            CompiledQuery::Pragma(CompiledPragmaStatement::ForeignKeys(enabled)) => format!(
+                "CompiledQuery::Pragma\n└─ foreign_keys={}",
+                if *enabled { "ON" } else { "OFF" }
+            ),
         }
     }
 }
@@ -1,30 +1,38 @@
 use crate::btree::Btree;
+use crate::collation::Collation;
 use crate::cursor::BTreeCursor;
 use crate::dataframe::{
-    BTreeScanSource, ConditionEvalContext, DataFrame, JoinStrategy, MemorySource,
+    BTreeScanSource, ConditionEvalContext, DataFrame, FilterSource, JoinStrategy, MemorySource,
     PreparedConditionExpr, PreparedInStrategy, PreparedPredicateExpr, RowSource, SetOpStrategy,
     Source,
 };
 use crate::debug::Status;
 use crate::debug::Status::ExceptionQueryMisformed;
 use crate::pager::{
-    Key, PAGE_SIZE, PAGE_SIZE_WITH_META, PageData, PagerAccessor, PagerCore, Position, Row,
+    INTEGER_SIZE, Key, PAGE_SIZE, PAGE_SIZE_WITH_META, PageData, PagerAccessor, PagerCore, Position, Row,
     TableName, TransactionId, Type,
 };
-use crate::pager_proxy::PagerProxy;
+use crate::pager_proxy::{PageManager, PagerProxy};
 use crate::parser::JoinType::Natural;
-use crate::parser::{JoinOp, JoinType, ParsedQuery, ParsedSetOperator, Parser};
+use crate::parser::{
+    Identity, JoinOp, JoinType, ParsedQuery, ParsedSetOperator, Parser, TriggerEvent,
+    TriggerTiming,
+};
 use crate::planner::SqlStatementComparisonOperator::{
     Equal, Greater, GreaterOrEqual, Lesser, LesserOrEqual,
 };
 use crate::planner::{
-    CompiledConditionExpr, CompiledCreateIndexQuery, CompiledCreateTableQuery, CompiledDeleteQuery,
-    CompiledInStrategy, CompiledInsertQuery, CompiledLogicalOp, CompiledPredicateExpr,
-    CompiledQuery,
+    CompiledConditionExpr, CompiledCreateIndexQuery, CompiledCreateSequenceQuery, CompiledCreateTableQuery, CompiledCreateViewQuery, CompiledDeleteQuery,
+    CompiledInStrategy, CompiledInsertBatchQuery, CompiledInsertQuery, CompiledLogicalOp, CompiledPredicateExpr,
+    CompiledPragmaStatement, CompiledQuery,
     CompiledSelectQuery, CompiledTransactionStatement, CompiledUpdateQuery, PlanNode, Planner,
     SqlConditionOpCode, SqlStatementComparisonOperator,
 };
-pub(crate) use crate::schema::{Field, IndexDefinition, Schema, TableIndex, TableSchema};
+use crate::returning::ReturnedRows;
+pub(crate) use crate::schema::{
+    Field, IndexDefinition, Schema, SequenceDefinition, TABLE_TYPE_TEMPORARY, TableIndex,
+    TableSchema, TriggerDefinition, ViewDefinition,
+};
 use crate::serializer::Serializer;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -34,6 +42,12 @@ use std::fs::OpenOptions;
 use std::io::{ErrorKind, Write};
 
 pub(crate) const MASTER_TABLE_NAME: &str = "rustsql_master";
+/// Master `type` of tables whose `Varchar(n)` columns hold n characters. Files written before
+/// that store their tables as `"table"`; those keep n bytes per `Varchar(n)` (loaded as
+/// `Type::VarcharBytes`) until ALTER TABLE rewrites them.
+pub(crate) const MASTER_TYPE_TABLE: &str = "table_v2";
+/// Master `type` of tables from files written before `MASTER_TYPE_TABLE`.
+const MASTER_TYPE_LEGACY_TABLE: &str = "table";
 
 pub static MASTER_TABLE_SQL: &str = "CREATE TABLE rustsql_master (
         name STRING,
@@ -47,6 +61,8 @@ pub static MASTER_TABLE_SQL: &str = "CREATE TABLE rustsql_master (
 pub struct QueryResult {
     pub success: bool,
     pub data: DataFrame,
+    /// Per inserted row that drew values from sequences, their (column, value) pairs
+    pub generated_keys: Vec<Vec<(String, i32)>>,
     status: Status,
 }
 
@@ -61,6 +77,7 @@ impl QueryResult {
         QueryResult {
             success: false,
             data: DataFrame::msg(msg.as_str()),
+            generated_keys: vec![],
             status: ExceptionQueryMisformed,
         }
     }
@@ -70,6 +87,7 @@ impl QueryResult {
         QueryResult {
             success: false,
             data: DataFrame::msg(str),
+            generated_keys: vec![],
             status: ExceptionQueryMisformed,
         }
     }
@@ -79,6 +97,7 @@ impl QueryResult {
         QueryResult {
             success: false,
             data: DataFrame::msg(format!("{:?}", s).as_str()),
+            generated_keys: vec![],
             status: s,
         }
     }
@@ -87,6 +106,7 @@ impl QueryResult {
         QueryResult {
             success: true,
             data: DataFrame::msg("Query Executed Successfully"),
+            generated_keys: vec![],
             status: Status::Success,
         }
     }
@@ -95,6 +115,7 @@ impl QueryResult {
         QueryResult {
             success: true,
             data: data,
+            generated_keys: vec![],
             status: Status::Success,
         }
     }
@@ -110,7 +131,13 @@ pub struct QueryExecutor {
     pub schema: Schema,
     pub btree_node_width: usize,
     request_counter: usize,
-    last_write_table_id: Option<usize>,
+    pub(crate) last_write_table_id: Option<usize>,
+    /// Per session, set by `PRAGMA foreign_keys`; on by default
+    pub(crate) foreign_keys_enabled: bool,
+    /// How many trigger statements the statement running now is nested in
+    pub(crate) trigger_depth: usize,
+    /// Tables of `CREATE TEMP TABLE`, which only this executor sees; released when it is dropped
+    pub(crate) temp_tables: Vec<TableSchema>,
 }
 
 impl QueryExecutor {
@@ -149,10 +176,17 @@ impl QueryExecutor {
                 },
                 tables: vec![Self::make_master_table_schema()],
                 index_definitions: vec![],
+                foreign_keys: vec![],
+                sequences: vec![],
+                views: vec![],
+                triggers: vec![],
             },
             btree_node_width: t,
             request_counter: 0,
             last_write_table_id: None,
+            foreign_keys_enabled: true,
+            trigger_depth: 0,
+            temp_tables: vec![],
         };
 
         bootstrap_executor.schema = bootstrap_executor.load_schema();
@@ -169,10 +203,17 @@ impl QueryExecutor {
                 },
                 tables: vec![Self::make_master_table_schema()],
                 index_definitions: vec![],
+                foreign_keys: vec![],
+                sequences: vec![],
+                views: vec![],
+                triggers: vec![],
             },
             btree_node_width: t,
             request_counter: 0,
             last_write_table_id: None,
+            foreign_keys_enabled: true,
+            trigger_depth: 0,
+            temp_tables: vec![],
         };
 
         bootstrap_executor.schema = bootstrap_executor.load_schema();
@@ -255,6 +296,35 @@ impl QueryExecutor {
         }
     }
 
+    /// Runs `statement` in a transaction of its own unless one is already open, so that its
+    /// changes become visible all at once or not at all.
+    pub(crate) fn run_atomically(
+        &mut self,
+        statement: impl FnOnce(&mut Self) -> Result<QueryResult, QueryResult>,
+    ) -> Result<QueryResult, QueryResult> {
+        if self.pager_accessor.is_transaction_active() {
+            return statement(self);
+        }
+        self.pager_accessor
+            .begin_transaction()
+            .map_err(QueryResult::err)?;
+        match statement(self) {
+            Ok(result) => {
+                self.pager_accessor
+                    .commit_transaction()
+                    .map_err(QueryResult::err)?;
+                Ok(result)
+            }
+            Err(error) => {
+                self.pager_accessor
+                    .rollback_transaction()
+                    .map_err(QueryResult::err)?;
+                self.reload_schema()?;
+                Err(error)
+            }
+        }
+    }
+
     pub fn execute_readonly(&self, query: String) -> QueryResult {
         let result = self
             .compile_query(&query)
@@ -299,7 +369,50 @@ impl QueryExecutor {
         allow_modification_to_system_table: bool,
     ) -> Result<QueryResult, QueryResult> {
         let compiled_query = self.compile_query(query)?;
-        self.execute_compiled(compiled_query, query.to_string(), allow_modification_to_system_table)
+        // UNIQUE columns get their index and identity columns their sequence once the table
+        // exists, and ALTER TABLE recreates the indexes it dropped once the rows are rewritten.
+        // Creating them here instead of inside execute_compiled keeps the statements from
+        // nesting deeper than CREATE INDEX.
+        let dependent_objects: Vec<String> = match &compiled_query {
+            CompiledQuery::CreateTable(q) => q
+                .unique_columns
+                .iter()
+                .map(|column| {
+                    format!(
+                        "CREATE UNIQUE INDEX {} ON {} ({})",
+                        Self::index_table_name(&q.table_name, column),
+                        q.table_name,
+                        column
+                    )
+                })
+                .chain(Self::identity_sequence_names(&q.schema).into_iter().map(
+                    |sequence_name| format!("CREATE SEQUENCE {}", sequence_name),
+                ))
+                .collect(),
+            CompiledQuery::AlterTable(q) => q
+                .created_indexes
+                .iter()
+                .map(|idx| {
+                    format!(
+                        "CREATE {}INDEX {} ON {} ({})",
+                        if idx.unique { "UNIQUE " } else { "" },
+                        idx.index_name,
+                        idx.base_table,
+                        idx.column_name
+                    )
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let result = self.execute_compiled(
+            compiled_query,
+            query.to_string(),
+            allow_modification_to_system_table,
+        )?;
+        for create_sql in dependent_objects {
+            self.run_query_internal(&create_sql, allow_modification_to_system_table)?;
+        }
+        Ok(result)
     }
 
     fn execute_compiled(
@@ -329,6 +442,10 @@ impl QueryExecutor {
                     self.reload_schema()
                 }
             },
+            CompiledQuery::Pragma(CompiledPragmaStatement::ForeignKeys(enabled)) => {
+                self.foreign_keys_enabled = enabled;
+                Ok(QueryResult::went_fine())
+            }
             CompiledQuery::CreateIndex(q) => {
                 if !allow_modification_to_system_table {
                     self.lock_table_if_needed(MASTER_TABLE_NAME)?;
@@ -336,15 +453,24 @@ impl QueryExecutor {
                     self.lock_table_if_needed(&q.index_name)?;
                 }
 
+                if q.unique {
+                    self.check_column_unique(q.table_id, &q.column_name)?;
+                }
+
                 let create_index_sql = format!(
-                    "CREATE INDEX {} ON {} ({})",
-                    q.index_name, q.base_table_name, q.column_name
+                    "CREATE {}INDEX {} ON {} ({})",
+                    if q.unique { "UNIQUE " } else { "" },
+                    q.index_name,
+                    q.base_table_name,
+                    q.column_name
                 );
 
                 self.execute_compiled(
                     CompiledQuery::CreateTable(CompiledCreateTableQuery {
                         table_name: q.schema.name.clone(),
                         schema: q.schema,
+                        unique_columns: vec![],
+                        foreign_keys: vec![],
                     }),
                     create_index_sql,
                     true,
@@ -358,7 +484,8 @@ impl QueryExecutor {
                 Ok(QueryResult::went_fine())
             }
             CompiledQuery::CreateTable(q) => {
-                if !allow_modification_to_system_table {
+                let temporary = q.schema.table_type == TABLE_TYPE_TEMPORARY;
+                if !allow_modification_to_system_table && !temporary {
                     self.lock_table_if_needed(MASTER_TABLE_NAME)?;
                     self.lock_table_if_needed(&q.table_name)?;
                 }
@@ -377,6 +504,31 @@ impl QueryExecutor {
                 if self.schema.table_index.index.contains(&table_name) {
                     return Err(QueryResult::err(Status::ExceptionTableAlreadyExists));
                 }
+                if self.schema.views.iter().any(|view| view.name == q.table_name)
+                    || self.schema.triggers.iter().any(|trigger| trigger.name == q.table_name)
+                {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "'{}' already exists",
+                        q.table_name
+                    )));
+                }
+                self.check_identity_sequence_names(&q.schema)?;
+                for foreign_key in &q.foreign_keys {
+                    if let Ok(parent_id) =
+                        Planner::find_table_id(&self.schema, &foreign_key.parent_table)
+                    {
+                        Planner::check_not_temporary(&self.schema.tables[parent_id], "a FOREIGN KEY")?;
+                    }
+                    Planner::resolve_foreign_key(
+                        &self.schema,
+                        &q.schema,
+                        &q.unique_columns,
+                        foreign_key,
+                    )?;
+                }
+                if temporary {
+                    return self.create_temp_table(q.schema);
+                }
 
                 let mut table_schema = q.schema.clone();
                 table_schema.btree_order = self.btree_node_width;
@@ -397,16 +549,13 @@ impl QueryExecutor {
                 table_schema.free_list = vec![(root_page, initial_free)];
                 let free_list_encoded = Self::encode_free_list_top_10(&table_schema);
 
-                let insert_query = format!(
-                    "INSERT INTO {} (name, type, rootpage, sql, free_list) VALUES ('{}', '{}', {}, '{}', '{}')",
-                    MASTER_TABLE_NAME,
-                    q.table_name.replace("'", "''"),
-                    "table",
+                self.insert_master_row(
+                    &q.table_name,
+                    MASTER_TYPE_TABLE,
                     root_page,
-                    query.replace("'", "''"),
-                    free_list_encoded.replace("'", "''")
-                );
-                self.run_query_internal(&insert_query, true)?;
+                    &query,
+                    &free_list_encoded,
+                )?;
                 let result = self.reload_schema()?;
                 if !allow_modification_to_system_table {
                     let created_table_id = Planner::find_table_id(&self.schema, &q.table_name)?;
@@ -414,6 +563,36 @@ impl QueryExecutor {
                 }
                 Ok(result)
             }
+            CompiledQuery::CreateTableAs(q) => {
+                self.create_table_as(q, allow_modification_to_system_table)
+            }
+            CompiledQuery::CreateSequence(q) => {
+                self.create_sequence(q, allow_modification_to_system_table)
+            }
+            CompiledQuery::DropSequence(q) => {
+                self.drop_sequence(&q.sequence_name, allow_modification_to_system_table)
+            }
+            CompiledQuery::CreateView(q) => {
+                self.create_view(q, query, allow_modification_to_system_table)
+            }
+            CompiledQuery::CreateMaterializedView(q) => {
+                self.create_materialized_view(q, allow_modification_to_system_table)
+            }
+            CompiledQuery::RefreshMaterializedView(q) => {
+                self.refresh_materialized_view(q, allow_modification_to_system_table)
+            }
+            CompiledQuery::DropView(q) => {
+                self.drop_view(&q.view_name, allow_modification_to_system_table)
+            }
+            CompiledQuery::CreateTrigger(q) => {
+                self.create_trigger(q, query, allow_modification_to_system_table)
+            }
+            CompiledQuery::DropTrigger(q) => {
+                self.drop_trigger(&q.trigger_name, allow_modification_to_system_table)
+            }
+            CompiledQuery::AlterTable(q) => {
+                self.alter_table(q, allow_modification_to_system_table)
+            }
             CompiledQuery::DropIndex(q) => self.execute_compiled(
                 CompiledQuery::DropTable(crate::planner::CompiledDropTableQuery {
                     table_id: q.table_id,
@@ -431,6 +610,9 @@ impl QueryExecutor {
                 if q.table_id >= self.schema.tables.len() {
                     return Err(QueryResult::user_input_wrong("Table not found".to_string()));
                 }
+                if self.schema.tables[q.table_id].table_type == TABLE_TYPE_TEMPORARY {
+                    return self.drop_temp_table(q.table_id);
+                }
 
                 if !allow_modification_to_system_table {
                     self.lock_table_if_needed(MASTER_TABLE_NAME)?;
@@ -438,33 +620,43 @@ impl QueryExecutor {
                     self.lock_table_if_needed(&dropped_name)?;
                 }
 
-                let dropped_table = self.schema.tables[q.table_id].clone();
-                let dropped_btree = Btree::init(
-                    dropped_table.btree_order,
-                    self.pager_accessor.clone(),
-                    dropped_table.clone(),
-                )
-                .map_err(QueryResult::err)?;
-
-                let mut dropped_pages = HashSet::new();
-                if let Some(root) = dropped_btree.root {
-                    self.collect_btree_pages(&root, &mut dropped_pages)
-                        .map_err(QueryResult::err)?;
-                } else {
-                    dropped_pages.insert(dropped_table.root.page());
+                // indexes go first, they cannot be loaded without their base table
+                let dropped_name = self.schema.tables[q.table_id].name.clone();
+                let dependent_indexes: Vec<String> = self
+                    .schema
+                    .index_definitions
+                    .iter()
+                    .filter(|idx| idx.base_table == dropped_name)
+                    .map(|idx| idx.index_name.clone())
+                    .collect();
+                for index_name in dependent_indexes {
+                    let table_id = Planner::find_table_id(&self.schema, &index_name)?;
+                    self.execute_compiled(
+                        CompiledQuery::DropTable(crate::planner::CompiledDropTableQuery {
+                            table_id,
+                        }),
+                        query.clone(),
+                        allow_modification_to_system_table,
+                    )?;
                 }
-                self.mark_pages_as_deleted(&dropped_pages)
-                    .map_err(QueryResult::err)?;
+                let table_id = Planner::find_table_id(&self.schema, &dropped_name)?;
 
-                PagerProxy::clear_table_root(&dropped_table, self.pager_accessor.clone())
-                    .map_err(QueryResult::err)?;
-
-                let delete_master_row = format!(
-                    "DELETE FROM {} WHERE name = '{}'",
-                    MASTER_TABLE_NAME,
-                    dropped_table.name.replace("'", "''")
-                );
-                self.run_query_internal(&delete_master_row, true)?;
+                let dropped_table = self.schema.tables[table_id].clone();
+                self.release_table_pages(&dropped_table)?;
+                self.delete_master_row(&dropped_table.name)?;
+                for sequence_name in Self::identity_sequence_names(&dropped_table) {
+                    self.drop_sequence(&sequence_name, true)?;
+                }
+                let dependent_triggers: Vec<String> = self
+                    .schema
+                    .triggers
+                    .iter()
+                    .filter(|trigger| trigger.table_name == dropped_table.name)
+                    .map(|trigger| trigger.name.clone())
+                    .collect();
+                for trigger_name in dependent_triggers {
+                    self.drop_trigger(&trigger_name, true)?;
+                }
 
                 self.reload_schema()
             }
@@ -474,163 +666,414 @@ impl QueryExecutor {
                     .map_err(|s| QueryResult::err(s))?;
                 Ok(QueryResult::return_data(result_df))
             }
-            CompiledQuery::Insert(q) => {
-                if !allow_modification_to_system_table && q.table_id == 0 {
-                    return Err(QueryResult::msg(
-                        "You are not allowed to modify this table.",
-                    ));
-                }
-
-                if !allow_modification_to_system_table {
-                    let table_name = self.schema.tables[q.table_id].name.clone();
-                    self.lock_table_if_needed(&table_name)?;
-                }
-
-                let mut schema = self.schema.tables[q.table_id].clone();
-                if allow_modification_to_system_table && q.table_id == 0 {
-                    schema.free_list.clear();
-                }
-                let mut btree =
-                    Btree::init(schema.btree_order, self.pager_accessor.clone(), schema)
-                        .map_err(|s| QueryResult::err(s))?;
-                let (insert_key, insert_row) = q.data;
-                btree
-                    .insert(insert_key.clone(), insert_row.clone())
-                    .map_err(|s| QueryResult::err(s))?;
-                if !allow_modification_to_system_table {
-                    self.last_write_table_id = Some(q.table_id);
-                    self.insert_row_into_indices(q.table_id, &insert_key, &insert_row)?;
-                }
-                Ok(QueryResult::went_fine())
+            CompiledQuery::InsertSelect(q) => {
+                self.insert_select(q, allow_modification_to_system_table)
             }
-            CompiledQuery::Delete(q) => {
-                if !allow_modification_to_system_table && q.table_id == 0 {
-                    return Err(QueryResult::msg(
-                        "You are not allowed to modify this table.",
-                    ));
-                }
+            write @ (CompiledQuery::Insert(_)
+            | CompiledQuery::InsertBatch(_)
+            | CompiledQuery::Delete(_)
+            | CompiledQuery::Update(_)) => {
+                self.execute_write(write, allow_modification_to_system_table)
+            }
+        }
+    }
 
-                if !allow_modification_to_system_table {
-                    let table_name = self.schema.tables[q.table_id].name.clone();
-                    self.lock_table_if_needed(&table_name)?;
-                }
+    /// Evaluates the CHECK constraints of a table on a full row. As in SQL, a check that is
+    /// unknown because of a NULL passes.
+    pub(crate) fn check_row_constraints(&self, table_id: usize, row: &Row) -> Result<(), QueryResult> {
+        let schema = &self.schema.tables[table_id];
+        for (field, check) in schema.fields.iter().zip(schema.checks.iter()) {
+            let Some(check) = check else {
+                continue;
+            };
+            let prepared = self
+                .prepare_condition_runtime(check)
+                .map_err(QueryResult::err)?;
+            let context = ConditionEvalContext {
+                pager_accessor: self.pager_accessor.clone(),
+                schemas: vec![],
+            };
+            let passed = FilterSource::evaluate_on_row(prepared, row, schema.clone(), context)
+                .map_err(QueryResult::err)?;
+            if passed == Some(false) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "CHECK constraint failed for column '{}.{}'",
+                    schema.name, field.name
+                )));
+            }
+        }
+        Ok(())
+    }
 
-                let schema = &self.schema.tables[q.table_id];
-                let key_offset = {
-                    let mut offset = 0;
-                    for i in 0..schema.key_position {
-                        offset += Serializer::get_size_of_type(&schema.fields[i].field_type)
-                            .map_err(QueryResult::err)?;
-                    }
-                    offset
-                };
-                let key_len =
-                    Serializer::get_size_of_type(&schema.fields[schema.key_position].field_type)
-                        .map_err(QueryResult::err)?;
+    /// Releases the payload chains referenced by a full row of `schema`.
+    pub(crate) fn release_payload_fields(&self, row: &Row, schema: &TableSchema) -> Result<(), Status> {
+        for (field_idx, field) in schema.fields.iter().enumerate() {
+            if field.field_type.is_payload_backed() {
+                let field_bytes = Serializer::get_field_on_row(row, field_idx, schema)?;
+                PageManager::release_payload_field(self.pager_accessor.clone(), &field_bytes)?;
+            }
+        }
+        Ok(())
+    }
 
-                let mut keys_to_delete = Vec::new();
-                let prepared_condition = q
-                    .condition
-                    .as_ref()
-                    .map(|c| self.prepare_condition_runtime(c))
-                    .transpose()
-                    .map_err(QueryResult::err)?;
+    pub(crate) fn lock_table_if_needed(&self, table_name: &str) -> Result<(), QueryResult> {
+        // no other executor sees a temporary table, and another may have one of the same name
+        if self.temp_tables.iter().any(|table| table.name == table_name) {
+            return Ok(());
+        }
+        self.pager_accessor
+            .lock_table_for_transaction(table_name)
+            .map_err(QueryResult::err)
+    }
 
-                let table_schema = schema.clone();
-                let btree = Btree::init(
-                    table_schema.btree_order,
-                    self.pager_accessor.clone(),
-                    table_schema.clone(),
-                )
-                .map_err(QueryResult::err)?;
+    /// Runs an INSERT, UPDATE or DELETE. A statement on a table with triggers runs atomically,
+    /// so a failing trigger also undoes the rows the statement wrote, and so does an INSERT
+    /// of several rows.
+    pub(crate) fn execute_write(
+        &mut self,
+        compiled_query: CompiledQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        let (table_id, returning) = match &compiled_query {
+            CompiledQuery::Insert(q) => (q.table_id, q.returning.clone()),
+            CompiledQuery::InsertBatch(q) => (q.table_id, q.returning.clone()),
+            CompiledQuery::Delete(q) => (q.table_id, q.returning.clone()),
+            CompiledQuery::Update(q) => (q.table_id, q.returning.clone()),
+            _ => return Err(QueryResult::err(Status::InternalExceptionCompilerError)),
+        };
+        if !allow_modification_to_system_table && table_id == 0 {
+            return Err(QueryResult::msg(
+                "You are not allowed to modify this table.",
+            ));
+        }
 
-                let mut scan_df = DataFrame::from_table(
-                    "DeleteScan".to_string(),
-                    table_schema.fields.clone(),
-                    btree,
-                    q.operation.clone(),
-                    q.seek_key.clone(),
-                );
+        if !allow_modification_to_system_table {
+            let table_name = self.schema.tables[table_id].name.clone();
+            self.lock_table_if_needed(&table_name)?;
+        }
 
-                if let Some(cond) = prepared_condition {
-                    scan_df = scan_df.filter_prepared(
-                        cond,
-                        schema.clone(),
-                        ConditionEvalContext {
-                            pager_accessor: self.pager_accessor.clone(),
-                            schemas: self.schema.tables.clone(),
-                        },
-                    );
+        let atomic = self.has_triggers(table_id)
+            || matches!(compiled_query, CompiledQuery::InsertBatch(_));
+        let execute = move |executor: &mut Self| {
+            let mut returned = ReturnedRows::new(table_id, returning);
+            let result = match compiled_query {
+                CompiledQuery::Insert(q) => {
+                    executor.execute_insert(q, allow_modification_to_system_table, &mut returned)
                 }
-
-                let rows = scan_df.fetch().map_err(QueryResult::err)?;
-                for row in rows {
-                    if row.len() < key_offset + key_len {
-                        return Err(QueryResult::err(
-                            Status::InternalExceptionIntegrityCheckFailed,
-                        ));
-                    }
-                    let key = row[key_offset..key_offset + key_len].to_vec();
-                    keys_to_delete.push(key);
+                CompiledQuery::InsertBatch(q) => executor.execute_insert_batch(
+                    q,
+                    allow_modification_to_system_table,
+                    &mut returned,
+                ),
+                CompiledQuery::Delete(q) => {
+                    executor.execute_delete(q, allow_modification_to_system_table, &mut returned)
+                }
+                CompiledQuery::Update(q) => {
+                    executor.execute_update(q, allow_modification_to_system_table, &mut returned)
                 }
+                _ => Err(QueryResult::err(Status::InternalExceptionCompilerError)),
+            }?;
+            Ok(executor.returning_result(returned, result))
+        };
+        if atomic {
+            self.run_atomically(execute)
+        } else {
+            execute(self)
+        }
+    }
 
-                let mut btree_schema = schema.clone();
+    fn execute_insert(
+        &mut self,
+        q: CompiledInsertQuery,
+        allow_modification_to_system_table: bool,
+        returned: &mut ReturnedRows,
+    ) -> Result<QueryResult, QueryResult> {
+        let schema = self.schema.tables[q.table_id].clone();
+        let (full_row, generated_keys) = self.prepare_insert_row(&q)?;
+        if let Some(on_conflict) = &q.on_conflict {
+            let conflict = self.find_conflict(q.table_id, &full_row, on_conflict.target.as_ref());
+            let existing = match conflict {
+                Ok(existing) => existing,
+                Err(error) => {
+                    self.release_inserted_payloads(&q.payloads, &full_row)?;
+                    return Err(error);
+                }
+            };
+            if let Some(existing) = existing {
+                return self.resolve_conflict(
+                    &q,
+                    &full_row,
+                    &existing,
+                    allow_modification_to_system_table,
+                    returned,
+                );
+            }
+        }
+        let (insert_key, insert_row) =
+            Serializer::split_key_and_row(&full_row, &schema).map_err(QueryResult::err)?;
+        let new_rows = [full_row.clone()];
+        // BEFORE triggers may write to this table too, so the B-tree is opened after them
+        let inserted = self
+            .bind_triggers(q.table_id, TriggerTiming::Before, TriggerEvent::Insert, &[], &new_rows)
+            .and_then(|statements| self.run_trigger_statements(statements))
+            .and_then(|_| self.check_row_constraints(q.table_id, &full_row))
+            .and_then(|_| self.check_unique_indices(q.table_id, &new_rows, &HashSet::new()))
+            .and_then(|_| self.check_unique_key(q.table_id, &new_rows, &HashSet::new()))
+            .and_then(|_| self.check_foreign_key_parents(q.table_id, &new_rows, &[]))
+            .and_then(|_| {
+                let mut schema = self.schema.tables[q.table_id].clone();
                 if allow_modification_to_system_table && q.table_id == 0 {
-                    btree_schema.free_list.clear();
+                    schema.free_list.clear();
                 }
+                Btree::init(schema.btree_order, self.pager_accessor.clone(), schema)
+                    .and_then(|mut btree| btree.insert(insert_key.clone(), insert_row.clone()))
+                    .map_err(QueryResult::err)
+            });
+        if let Err(error) = inserted {
+            self.release_inserted_payloads(&q.payloads, &full_row)?;
+            return Err(error);
+        }
+        if !allow_modification_to_system_table {
+            self.last_write_table_id = Some(q.table_id);
+            self.insert_rows_into_indices(q.table_id, &[(insert_key, insert_row)])?;
+        }
+        self.collect_returned_rows(returned, &new_rows)?;
+        let after = self.bind_triggers(
+            q.table_id,
+            TriggerTiming::After,
+            TriggerEvent::Insert,
+            &[],
+            &new_rows,
+        )?;
+        self.run_trigger_statements(after)?;
+        let mut result = QueryResult::went_fine();
+        if !generated_keys.is_empty() {
+            result.generated_keys.push(generated_keys);
+        }
+        Ok(result)
+    }
 
-                let mut btree = Btree::init(
-                    btree_schema.btree_order,
-                    self.pager_accessor.clone(),
-                    btree_schema,
-                )
-                .map_err(|s| QueryResult::err(s))?;
+    /// Inserts the tuples of a multi-row VALUES list. Every row is prepared and checked before
+    /// the first is written, and each index is updated once for the whole batch. On a table
+    /// with triggers, and with ON CONFLICT, the rows are inserted one by one, so each row
+    /// sees the rows before.
+    fn execute_insert_batch(
+        &mut self,
+        q: CompiledInsertBatchQuery,
+        allow_modification_to_system_table: bool,
+        returned: &mut ReturnedRows,
+    ) -> Result<QueryResult, QueryResult> {
+        let mut result = QueryResult::went_fine();
+        // an upserted row may collide with one inserted before it in the same statement
+        if self.has_triggers(q.table_id) || q.rows.iter().any(|row| row.on_conflict.is_some()) {
+            for row in q.rows {
+                let inserted =
+                    self.execute_insert(row, allow_modification_to_system_table, returned)?;
+                result.generated_keys.extend(inserted.generated_keys);
+            }
+            return Ok(result);
+        }
 
-                for key in keys_to_delete {
-                    btree.delete(key).map_err(|s| QueryResult::err(s))?;
+        let mut schema = self.schema.tables[q.table_id].clone();
+        let mut full_rows = Vec::with_capacity(q.rows.len());
+        let checked = q
+            .rows
+            .iter()
+            .try_for_each(|row| {
+                let (full_row, generated_keys) = self.prepare_insert_row(row)?;
+                full_rows.push(full_row);
+                if !generated_keys.is_empty() {
+                    result.generated_keys.push(generated_keys);
                 }
-                if !allow_modification_to_system_table {
-                    self.rebuild_indices_for_table_id(q.table_id)?;
+                self.check_row_constraints(q.table_id, &full_rows[full_rows.len() - 1])
+            })
+            .and_then(|_| self.check_unique_indices(q.table_id, &full_rows, &HashSet::new()))
+            .and_then(|_| self.check_unique_key(q.table_id, &full_rows, &HashSet::new()))
+            .and_then(|_| self.check_foreign_key_parents(q.table_id, &full_rows, &[]))
+            .and_then(|_| {
+                full_rows
+                    .iter()
+                    .map(|row| Serializer::split_key_and_row(row, &schema))
+                    .collect::<Result<Vec<(Key, Row)>, Status>>()
+                    .map_err(QueryResult::err)
+            });
+        let entries = match checked {
+            Ok(entries) => entries,
+            Err(error) => {
+                for (row, full_row) in q.rows.iter().zip(&full_rows) {
+                    self.release_inserted_payloads(&row.payloads, full_row)?;
                 }
-                Ok(QueryResult::went_fine())
+                return Err(error);
             }
-            CompiledQuery::Update(q) => {
-                if !allow_modification_to_system_table && q.table_id == 0 {
-                    return Err(QueryResult::msg(
-                        "You are not allowed to modify this table.",
-                    ));
-                }
+        };
 
-                if !allow_modification_to_system_table {
-                    let table_name = self.schema.tables[q.table_id].name.clone();
-                    self.lock_table_if_needed(&table_name)?;
-                }
+        if allow_modification_to_system_table && q.table_id == 0 {
+            schema.free_list.clear();
+        }
+        let mut btree = Btree::init(schema.btree_order, self.pager_accessor.clone(), schema)
+            .map_err(QueryResult::err)?;
+        for (key, row) in &entries {
+            btree
+                .insert(key.clone(), row.clone())
+                .map_err(QueryResult::err)?;
+        }
+        if !allow_modification_to_system_table {
+            self.last_write_table_id = Some(q.table_id);
+            self.insert_rows_into_indices(q.table_id, &entries)?;
+        }
+        self.collect_returned_rows(returned, &full_rows)?;
+        Ok(result)
+    }
 
-                let table_id = q.table_id;
-                let result = self.execute_update(q, allow_modification_to_system_table)?;
-                if !allow_modification_to_system_table {
-                    self.rebuild_indices_for_table_id(table_id)?;
-                }
-                Ok(result)
+    /// Draws the sequence values, writes the payloads and computes the generated columns of
+    /// a planned row, returning the full row and its generated keys. The payloads are
+    /// released again if a step fails.
+    fn prepare_insert_row(
+        &self,
+        q: &CompiledInsertQuery,
+    ) -> Result<(Row, Vec<(String, i32)>), QueryResult> {
+        let schema = &self.schema.tables[q.table_id];
+        let (mut insert_key, mut insert_row) = q.data.clone();
+        let generated_keys = self.fill_sequence_values(
+            schema,
+            &q.sequence_values,
+            &mut insert_key,
+            &mut insert_row,
+        )?;
+        let mut full_row = Serializer::reconstruct_row(&insert_key, &insert_row, schema)
+            .map_err(QueryResult::err)?;
+        for (offset, content) in &q.payloads {
+            let field = PageManager::write_payload_field(
+                self.pager_accessor.clone(),
+                content,
+                schema.root.page(),
+            )
+            .map_err(QueryResult::err)?;
+            full_row[*offset..*offset + field.len()].copy_from_slice(&field);
+        }
+        match self
+            .fill_generated_values(q.table_id, full_row.clone())
+            .and_then(|row| {
+                self.advance_identity_sequences(q.table_id, std::slice::from_ref(&row))?;
+                Ok(row)
+            })
+        {
+            Ok(row) => Ok((row, generated_keys)),
+            Err(error) => {
+                self.release_inserted_payloads(&q.payloads, &full_row)?;
+                Err(error)
             }
         }
     }
 
-    fn lock_table_if_needed(&self, table_name: &str) -> Result<(), QueryResult> {
-        self.pager_accessor
-            .lock_table_for_transaction(table_name)
-            .map_err(QueryResult::err)
+    /// Releases the payloads an insert wrote for `row`, a full row, when the row is not
+    /// inserted after all.
+    pub(crate) fn release_inserted_payloads(
+        &self,
+        payloads: &[(usize, Vec<u8>)],
+        row: &Row,
+    ) -> Result<(), QueryResult> {
+        for (offset, _) in payloads {
+            PageManager::release_payload_field(self.pager_accessor.clone(), &row[*offset..])
+                .map_err(QueryResult::err)?;
+        }
+        Ok(())
     }
 
-    fn execute_update(
+    fn execute_delete(
         &mut self,
-        q: CompiledUpdateQuery,
+        q: CompiledDeleteQuery,
         allow_modification_to_system_table: bool,
+        returned: &mut ReturnedRows,
     ) -> Result<QueryResult, QueryResult> {
         let schema = self.schema.tables[q.table_id].clone();
-        let key_len = Serializer::get_size_of_type(&schema.fields[schema.key_position].field_type)
+
+        let mut keys_to_delete = Vec::new();
+        let prepared_condition = q
+            .condition
+            .as_ref()
+            .map(|c| self.prepare_condition_runtime(c))
+            .transpose()
             .map_err(QueryResult::err)?;
 
+        let table_schema = schema.clone();
+        let btree = Btree::init(
+            table_schema.btree_order,
+            self.pager_accessor.clone(),
+            table_schema.clone(),
+        )
+        .map_err(QueryResult::err)?;
+
+        let mut scan_df = DataFrame::from_table(
+            "DeleteScan".to_string(),
+            table_schema.fields.clone(),
+            btree,
+            q.operation.clone(),
+            q.seek_key.clone(),
+        );
+
+        if let Some(cond) = prepared_condition {
+            scan_df = scan_df.filter_prepared(
+                cond,
+                schema.clone(),
+                ConditionEvalContext {
+                    pager_accessor: self.pager_accessor.clone(),
+                    schemas: self.schema.tables.clone(),
+                },
+            );
+        }
+
+        let rows = scan_df.fetch().map_err(QueryResult::err)?;
+        let before =
+            self.bind_triggers(q.table_id, TriggerTiming::Before, TriggerEvent::Delete, &rows, &[])?;
+        self.run_trigger_statements(before)?;
+        // bound now, the payloads of the rows are released below
+        let after =
+            self.bind_triggers(q.table_id, TriggerTiming::After, TriggerEvent::Delete, &rows, &[])?;
+        let effects = self.plan_foreign_key_effects(q.table_id, &rows, None)?;
+        for row in &rows {
+            let (key, _) = Serializer::split_key_and_row(row, &table_schema)
+                .map_err(QueryResult::err)?;
+            keys_to_delete.push(key);
+        }
+
+        let mut btree_schema = self.schema.tables[q.table_id].clone();
+        if allow_modification_to_system_table && q.table_id == 0 {
+            btree_schema.free_list.clear();
+        }
+
+        let mut btree = Btree::init(
+            btree_schema.btree_order,
+            self.pager_accessor.clone(),
+            btree_schema,
+        )
+        .map_err(|s| QueryResult::err(s))?;
+
+        for key in keys_to_delete {
+            btree.delete(key).map_err(|s| QueryResult::err(s))?;
+        }
+        // read before the payloads are released
+        self.collect_returned_rows(returned, &rows)?;
+        for row in &rows {
+            self.release_payload_fields(row, &table_schema)
+                .map_err(QueryResult::err)?;
+        }
+        if !allow_modification_to_system_table {
+            self.rebuild_indices_for_table_id(q.table_id)?;
+        }
+        self.apply_foreign_key_effects(effects)?;
+        self.run_trigger_statements(after)?;
+        Ok(QueryResult::went_fine())
+    }
+
+    pub(crate) fn execute_update(
+        &mut self,
+        q: CompiledUpdateQuery,
+        allow_modification_to_system_table: bool,
+        returned: &mut ReturnedRows,
+    ) -> Result<QueryResult, QueryResult> {
+        let schema = self.schema.tables[q.table_id].clone();
+
         let mut updates_to_apply: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> = Vec::new();
         let prepared_condition = q
             .condition
@@ -666,7 +1109,15 @@ impl QueryExecutor {
         }
 
         let rows = scan_df.fetch().map_err(QueryResult::err)?;
+        // old payloads are only released once every row passed its checks; new ones are
+        // released again if a row fails
+        let mut replaced_payloads = Vec::new();
+        let mut written_payloads = Vec::new();
+        let mut new_rows = Vec::new();
+        let old_rows = rows.clone();
         for row in rows {
+            let (original_key, _) =
+                Serializer::split_key_and_row(&row, &schema).map_err(QueryResult::err)?;
             let mut updated_fields = Vec::with_capacity(schema.fields.len());
             for field_idx in 0..schema.fields.len() {
                 updated_fields.push(
@@ -675,28 +1126,91 @@ impl QueryExecutor {
                 );
             }
 
-            let original_key = updated_fields[schema.key_position].clone();
-
             for (field_idx, new_value) in &q.assignments {
-                updated_fields[*field_idx] = new_value.clone();
-            }
-
-            let new_key = updated_fields[schema.key_position].clone();
-            if new_key.len() != key_len {
-                return Err(QueryResult::err(Status::InternalExceptionTypeMismatch));
+                let field_type = &schema.fields[*field_idx].field_type;
+                if field_type.is_payload_backed() {
+                    replaced_payloads.push(updated_fields[*field_idx].clone());
+                }
+                updated_fields[*field_idx] = match new_value {
+                    None => Serializer::null_field(field_type).map_err(QueryResult::err)?,
+                    Some(content) if field_type.is_payload_backed() => {
+                        let field = PageManager::write_payload_field(
+                            self.pager_accessor.clone(),
+                            content,
+                            schema.root.page(),
+                        )
+                        .map_err(QueryResult::err)?;
+                        written_payloads.push(field.clone());
+                        field
+                    }
+                    Some(value) => value.clone(),
+                };
             }
 
-            let mut new_row = Vec::new();
-            for (idx, field_bytes) in updated_fields.into_iter().enumerate() {
-                if idx != schema.key_position {
-                    new_row.extend(field_bytes);
+            let full_row = match self.fill_generated_values(q.table_id, updated_fields.concat()) {
+                Ok(row) => row,
+                Err(error) => {
+                    for field in &written_payloads {
+                        PageManager::release_payload_field(self.pager_accessor.clone(), field)
+                            .map_err(QueryResult::err)?;
+                    }
+                    return Err(error);
                 }
-            }
+            };
+            let (new_key, new_row) =
+                Serializer::split_key_and_row(&full_row, &schema).map_err(QueryResult::err)?;
 
             updates_to_apply.push((original_key, new_key, new_row));
+            new_rows.push(full_row);
         }
 
-        let mut btree_schema = schema.clone();
+        let replaced_keys: HashSet<Key> = updates_to_apply
+            .iter()
+            .map(|(original_key, _, _)| original_key.clone())
+            .collect();
+        let checked = self
+            .bind_triggers(q.table_id, TriggerTiming::Before, TriggerEvent::Update, &old_rows, &new_rows)
+            .and_then(|statements| self.run_trigger_statements(statements))
+            .and_then(|_| {
+                new_rows
+                    .iter()
+                    .try_for_each(|row| self.check_row_constraints(q.table_id, row))
+            })
+            .and_then(|_| self.check_unique_indices(q.table_id, &new_rows, &replaced_keys))
+            .and_then(|_| {
+                // while every row keeps its key, the keys stay as unique as they were
+                if updates_to_apply.iter().any(|(original_key, new_key, _)| original_key != new_key) {
+                    self.check_unique_key(q.table_id, &new_rows, &replaced_keys)
+                } else {
+                    Ok(())
+                }
+            })
+            .and_then(|_| self.check_foreign_key_parents(q.table_id, &new_rows, &old_rows))
+            .and_then(|_| self.advance_identity_sequences(q.table_id, &new_rows))
+            .and_then(|_| self.plan_foreign_key_effects(q.table_id, &old_rows, Some(&new_rows)))
+            .and_then(|effects| {
+                // bound now, the old payloads are released below
+                let after = self.bind_triggers(
+                    q.table_id,
+                    TriggerTiming::After,
+                    TriggerEvent::Update,
+                    &old_rows,
+                    &new_rows,
+                )?;
+                Ok((effects, after))
+            });
+        let (effects, after) = match checked {
+            Ok(checked) => checked,
+            Err(error) => {
+                for field in &written_payloads {
+                    PageManager::release_payload_field(self.pager_accessor.clone(), field)
+                        .map_err(QueryResult::err)?;
+                }
+                return Err(error);
+            }
+        };
+
+        let mut btree_schema = self.schema.tables[q.table_id].clone();
         if allow_modification_to_system_table && q.table_id == 0 {
             btree_schema.free_list.clear();
         }
@@ -717,15 +1231,24 @@ impl QueryExecutor {
         for (_, new_key, new_row) in updates_to_apply {
             btree.insert(new_key, new_row).map_err(QueryResult::err)?;
         }
+        // released only after the B-tree writes, which read the content of old Text keys
+        for field in &replaced_payloads {
+            PageManager::release_payload_field(self.pager_accessor.clone(), field)
+                .map_err(QueryResult::err)?;
+        }
+        self.apply_foreign_key_effects(effects)?;
 
         if !allow_modification_to_system_table {
             self.last_write_table_id = Some(q.table_id);
+            self.rebuild_indices_for_table_id(q.table_id)?;
         }
+        self.collect_returned_rows(returned, &new_rows)?;
+        self.run_trigger_statements(after)?;
 
         Ok(QueryResult::went_fine())
     }
 
-    fn exec_planned_tree(&self, plan: &PlanNode) -> Result<DataFrame, Status> {
+    pub(crate) fn exec_planned_tree(&self, plan: &PlanNode) -> Result<DataFrame, Status> {
         match plan {
             PlanNode::SeqScan {
                 table_id,
@@ -830,6 +1353,20 @@ impl QueryExecutor {
                 ))
             }
 
+            PlanNode::JsonExtract {
+                source,
+                extractions,
+            } => {
+                let source_df = self.exec_planned_tree(source)?;
+                Ok(source_df.json_extract(
+                    plan.get_header(&self.schema)?,
+                    extractions
+                        .iter()
+                        .map(|e| (e.column_idx, e.path.clone()))
+                        .collect(),
+                ))
+            }
+
             PlanNode::Join {
                 left,
                 right,
@@ -856,7 +1393,12 @@ impl QueryExecutor {
                     .position(|f| f.name == right_col.name && f.table_name == right_col.table_name)
                     .ok_or(Status::DataFrameJoinError)?;
                 let is_index_or_key = |op: &JoinOp| *op == JoinOp::Key || *op == JoinOp::Index;
-                let strategy = if is_index_or_key(left_join_op) && is_index_or_key(right_join_op) {
+                // both sides come out of trees ordered by their own collation
+                let same_order = left_df.header[l_idx].collation == right_df.header[r_idx].collation;
+                let strategy = if is_index_or_key(left_join_op)
+                    && is_index_or_key(right_join_op)
+                    && same_order
+                {
                     JoinStrategy::SortMerge
                 } else {
                     JoinStrategy::Hash
@@ -918,11 +1460,13 @@ impl QueryExecutor {
                     column_idx,
                     op,
                     value,
+                    collation,
                 } => Ok(PreparedConditionExpr::Predicate(
                     PreparedPredicateExpr::Compare {
                         column_idx: *column_idx,
                         op: *op,
                         value: value.clone(),
+                        collation: *collation,
                     },
                 )),
                 CompiledPredicateExpr::InSubquery {
@@ -949,6 +1493,28 @@ impl QueryExecutor {
                         },
                     ))
                 }
+                CompiledPredicateExpr::IsNull {
+                    column_idx,
+                    negated,
+                } => Ok(PreparedConditionExpr::Predicate(
+                    PreparedPredicateExpr::IsNull {
+                        column_idx: *column_idx,
+                        negated: *negated,
+                    },
+                )),
+                CompiledPredicateExpr::JsonCompare {
+                    column_idx,
+                    path,
+                    op,
+                    value,
+                } => Ok(PreparedConditionExpr::Predicate(
+                    PreparedPredicateExpr::JsonCompare {
+                        column_idx: *column_idx,
+                        path: path.clone(),
+                        op: *op,
+                        value: value.clone(),
+                    },
+                )),
             },
         }
     }
@@ -974,9 +1540,7 @@ impl QueryExecutor {
                 }
 
                 if let Some(ref last) = last_key {
-                    if Serializer::compare_with_type(last, &key, &table_schema.get_key_type()?)?
-                        != std::cmp::Ordering::Less
-                    {
+                    if cursor.btree.compare(last, &key)? != std::cmp::Ordering::Less {
                         return Err(Status::InternalExceptionIntegrityCheckFailed);
                     }
                 }
@@ -1042,9 +1606,14 @@ impl QueryExecutor {
             },
             tables: vec![master_table_schema.clone()],
             index_definitions: vec![],
+            foreign_keys: vec![],
+            sequences: vec![],
+            views: vec![],
+            triggers: vec![],
         };
         let mut pending_indices: Vec<(String, i32, String, crate::parser::ParsedCreateIndexQuery)> =
             vec![];
+        let mut pending_foreign_keys = vec![];
         let select_query = CompiledSelectQuery {
             plan: PlanNode::Project {
                 source: Box::new(PlanNode::SeqScan {
@@ -1060,26 +1629,31 @@ impl QueryExecutor {
                         field_type: Type::String,
                         name: "name".to_string(),
                         table_name: MASTER_TABLE_NAME.to_string(),
+                        collation: Collation::Binary,
                     },
                     Field {
                         field_type: Type::String,
                         name: "sql".to_string(),
                         table_name: MASTER_TABLE_NAME.to_string(),
+                        collation: Collation::Binary,
                     },
                     Field {
                         field_type: Type::Integer,
                         name: "rootpage".to_string(),
                         table_name: MASTER_TABLE_NAME.to_string(),
+                        collation: Collation::Binary,
                     },
                     Field {
                         field_type: Type::String,
                         name: "type".to_string(),
                         table_name: MASTER_TABLE_NAME.to_string(),
+                        collation: Collation::Binary,
                     },
                     Field {
                         field_type: Type::String,
                         name: "free_list".to_string(),
                         table_name: MASTER_TABLE_NAME.to_string(),
+                        collation: Collation::Binary,
                     },
                 ],
                 lookup_key_field_idx: None,
@@ -1094,6 +1668,7 @@ impl QueryExecutor {
             let name = Serializer::get_field_on_row(entry, 0, &master_table_schema)
                 .expect("Failed to get field: Name");
             let sql = Serializer::format_field_on_row(entry, 1, &master_table_schema)
+                .and_then(|value| self.master_sql_definition(&value))
                 .expect("Failed to format field: SQL");
             let rootpage = Serializer::bytes_to_int(
                 <[u8; 5]>::try_from(
@@ -1102,6 +1677,8 @@ impl QueryExecutor {
                 )
                 .unwrap(),
             );
+            let entry_type = Serializer::format_field_on_row(entry, 3, &master_table_schema)
+                .expect("Failed to format field: Type");
             let free_list_encoded = Serializer::format_field_on_row(entry, 4, &master_table_schema)
                 .expect("Failed to format field: free_list");
             let mut parser = Parser::new(sql.clone());
@@ -1123,6 +1700,16 @@ impl QueryExecutor {
                     table.schema.root = Position::new(rootpage as usize, 0);
                     table.schema.btree_order = self.btree_node_width; //ToDo Store this in the System Table
                     table.schema.free_list = TableSchema::free_list_from_string(&free_list_encoded);
+                    if entry_type == MASTER_TYPE_LEGACY_TABLE {
+                        for field in &mut table.schema.fields {
+                            if let Type::Varchar(max) = field.field_type {
+                                field.field_type = Type::VarcharBytes(max);
+                            }
+                        }
+                    }
+                    for foreign_key in table.foreign_keys {
+                        pending_foreign_keys.push((table.table_name.clone(), foreign_key));
+                    }
                     if let Some(existing_idx) = schema
                         .table_index
                         .index
@@ -1143,9 +1730,46 @@ impl QueryExecutor {
                         idx,
                     ));
                 }
+                ParsedQuery::CreateSequence(sequence) => {
+                    schema.sequences.push(SequenceDefinition {
+                        name: sequence.sequence_name,
+                        page: rootpage as usize,
+                        increment: sequence.increment,
+                    });
+                }
+                ParsedQuery::CreateView(view) => {
+                    if view.materialized {
+                        let mut table = Planner::stored_materialized_view_schema(&view)
+                            .expect("Failed to load materialized view");
+                        table.root = Position::new(rootpage as usize, 0);
+                        table.btree_order = self.btree_node_width;
+                        table.free_list = TableSchema::free_list_from_string(&free_list_encoded);
+                        schema.table_index.index.push(view.view_name.as_bytes().to_vec());
+                        schema.tables.push(table);
+                    }
+                    schema.views.push(ViewDefinition {
+                        tables: Planner::referenced_tables(&view.query),
+                        name: view.view_name,
+                        materialized: view.materialized,
+                        query: view.query,
+                    });
+                }
+                ParsedQuery::CreateTrigger(trigger) => {
+                    schema.triggers.push(TriggerDefinition {
+                        columns: Planner::trigger_row_references(&trigger.statements)
+                            .into_iter()
+                            .map(|(_, column)| column)
+                            .collect(),
+                        name: trigger.trigger_name,
+                        table_name: trigger.table_name,
+                        timing: trigger.timing,
+                        event: trigger.event,
+                        statements: trigger.statements,
+                    });
+                }
                 _ => {
                     panic!(
-                        "in the system table should only be create table or create index queries"
+                        "in the system table should only be create table, index, sequence, view or trigger queries"
                     )
                 }
             }
@@ -1166,25 +1790,25 @@ impl QueryExecutor {
                 .find(|f| f.name == column_name)
                 .expect("Index column not found in base table");
 
+            let mut fields = vec![Field {
+                field_type: base_column.field_type.clone(),
+                name: "idx_value".to_string(),
+                table_name: index_name.clone(),
+                collation: base_column.collation,
+            }];
+            fields.extend(base_schema.base_key_fields(&index_name));
+
             let index_schema = TableSchema {
                 next_position: Position::make_empty(),
                 root: Position::new(rootpage as usize, 0),
                 has_key: true,
-                key_position: 0,
-                fields: vec![
-                    Field {
-                        field_type: base_column.field_type.clone(),
-                        name: "idx_value".to_string(),
-                        table_name: index_name.clone(),
-                    },
-                    Field {
-                        field_type: base_schema.fields[base_schema.key_position]
-                            .field_type
-                            .clone(),
-                        name: "base_pk".to_string(),
-                        table_name: index_name.clone(),
-                    },
-                ],
+                key_positions: vec![0],
+                fields,
+                column_defaults: vec![],
+                not_null: vec![],
+                checks: vec![],
+                identity: vec![],
+                generated: vec![],
                 table_type: 0,
                 entry_count: 0,
                 name: index_name.clone(),
@@ -1209,11 +1833,237 @@ impl QueryExecutor {
                 index_name,
                 base_table: idx.table_name,
                 column_name,
+                unique: idx.unique,
             });
         }
+
+        // parents may be stored after their children, and may rely on a unique index
+        for (table_name, foreign_key) in pending_foreign_keys {
+            let child_id = Planner::find_table_id(&schema, &table_name)
+                .expect("Table not found while loading foreign key");
+            let definition = Planner::resolve_foreign_key(
+                &schema,
+                &schema.tables[child_id],
+                &[],
+                &foreign_key,
+            )
+            .expect("Failed to resolve foreign key");
+            schema.foreign_keys.push(definition);
+        }
+
+        // a temporary table hides a permanent table of the same name, with what belongs to it
+        for table in &self.temp_tables {
+            let table_name = TableName::from(table.name.as_str());
+            match schema.table_index.index.iter().position(|t| t == &table_name) {
+                Some(existing_idx) => {
+                    schema.index_definitions.retain(|idx| idx.base_table != table.name);
+                    schema
+                        .foreign_keys
+                        .retain(|fk| fk.child_table != table.name && fk.parent_table != table.name);
+                    schema.triggers.retain(|trigger| trigger.table_name != table.name);
+                    schema.tables[existing_idx] = table.clone();
+                }
+                None => {
+                    schema.table_index.index.push(table_name);
+                    schema.tables.push(table.clone());
+                }
+            }
+        }
         schema
     }
 
+    fn create_sequence(
+        &mut self,
+        q: CompiledCreateSequenceQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+        }
+        if self.name_in_use(&q.sequence_name) {
+            let is_sequence = self
+                .schema
+                .sequences
+                .iter()
+                .any(|seq| seq.name == q.sequence_name);
+            if q.if_not_exists && is_sequence {
+                return Ok(QueryResult::went_fine());
+            }
+            return Err(QueryResult::user_input_wrong(format!(
+                "'{}' already exists",
+                q.sequence_name
+            )));
+        }
+
+        let page = self
+            .pager_accessor
+            .access_pager_write(|p| p.create_sequence_page(q.start))
+            .map_err(QueryResult::err)?;
+        let create_sequence_sql = format!(
+            "CREATE SEQUENCE {} START WITH {} INCREMENT BY {}",
+            q.sequence_name, q.start, q.increment
+        );
+        self.insert_master_row(&q.sequence_name, "sequence", page, &create_sequence_sql, "")?;
+        self.reload_schema()
+    }
+
+    pub(crate) fn drop_sequence(
+        &mut self,
+        sequence_name: &str,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+        }
+        // the counter page stays: other connections draw from it until they reload
+        self.delete_master_row(sequence_name)?;
+        self.reload_schema()
+    }
+
+    fn create_view(
+        &mut self,
+        q: CompiledCreateViewQuery,
+        query: String,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+        }
+        if self.name_in_use(&q.view_name) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "'{}' already exists",
+                q.view_name
+            )));
+        }
+        // views own no pages; the statement is all there is to store
+        self.insert_master_row(&q.view_name, "view", 0, &query, "")?;
+        self.reload_schema()
+    }
+
+    fn drop_view(
+        &mut self,
+        view_name: &str,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+        }
+        self.delete_master_row(view_name)?;
+        self.reload_schema()
+    }
+
+    fn check_identity_sequence_names(&self, table: &TableSchema) -> Result<(), QueryResult> {
+        for sequence_name in Self::identity_sequence_names(table) {
+            if self.name_in_use(&sequence_name) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "'{}' already exists",
+                    sequence_name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the placeholders of `sequence_values` in the row with values drawn from
+    /// their sequences and returns them as the generated keys of the insert.
+    fn fill_sequence_values(
+        &self,
+        table_schema: &TableSchema,
+        sequence_values: &[(usize, String)],
+        insert_key: &mut Key,
+        insert_row: &mut Row,
+    ) -> Result<Vec<(String, i32)>, QueryResult> {
+        let mut generated_keys = Vec::new();
+        if sequence_values.is_empty() {
+            return Ok(generated_keys);
+        }
+        let mut full_row = Serializer::reconstruct_row(insert_key, insert_row, table_schema)
+            .map_err(QueryResult::err)?;
+        for (field_idx, sequence_name) in sequence_values {
+            let value = self.next_sequence_value(sequence_name)?;
+            let field = Serializer::parse_int(&value.to_string()).map_err(QueryResult::err)?;
+            full_row = Self::replace_field(&full_row, *field_idx, table_schema, &field)
+                .map_err(QueryResult::err)?;
+            generated_keys.push((table_schema.fields[*field_idx].name.clone(), value));
+        }
+        (*insert_key, *insert_row) =
+            Serializer::split_key_and_row(&full_row, table_schema).map_err(QueryResult::err)?;
+        Ok(generated_keys)
+    }
+
+    /// Moves the sequences of the table's BY DEFAULT identity columns past the values `rows`
+    /// store in them, so values given explicitly are not drawn again later.
+    fn advance_identity_sequences(&self, table_id: usize, rows: &[Row]) -> Result<(), QueryResult> {
+        let table = &self.schema.tables[table_id];
+        for (field_idx, identity) in table.identity.iter().enumerate() {
+            if *identity != Some(Identity::ByDefault) {
+                continue;
+            }
+            let sequence_name = Planner::identity_sequence_name(&table.name, &table.fields[field_idx].name);
+            let Some(sequence) = self.schema.sequences.iter().find(|seq| seq.name == sequence_name) else {
+                continue;
+            };
+            let mut furthest: Option<i64> = None;
+            for row in rows {
+                let field = Serializer::get_field_on_row(row, field_idx, table).map_err(QueryResult::err)?;
+                if Serializer::is_null(&field, &Type::Integer).map_err(QueryResult::err)? {
+                    continue;
+                }
+                let mut bytes = [0u8; INTEGER_SIZE];
+                bytes.copy_from_slice(&field);
+                let value = Serializer::bytes_to_int(bytes) as i64;
+                furthest = Some(match furthest {
+                    Some(other) if sequence.increment > 0 => other.max(value),
+                    Some(other) => other.min(value),
+                    None => value,
+                });
+            }
+            if let Some(value) = furthest {
+                self.pager_accessor
+                    .access_pager_write(|p| p.advance_sequence_past(sequence.page, value, sequence.increment))
+                    .map_err(QueryResult::err)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the next value of `sequence_name`, which must fit an Integer column.
+    pub(crate) fn next_sequence_value(&self, sequence_name: &str) -> Result<i32, QueryResult> {
+        let sequence = self
+            .schema
+            .sequences
+            .iter()
+            .find(|seq| seq.name == sequence_name)
+            .ok_or_else(|| {
+                QueryResult::user_input_wrong(format!("Sequence '{}' not found", sequence_name))
+            })?;
+        let value = self
+            .pager_accessor
+            .access_pager_write(|p| p.next_sequence_value(sequence.page, sequence.increment))
+            .map_err(QueryResult::err)?;
+        i32::try_from(value).map_err(|_| {
+            QueryResult::user_input_wrong(format!("Sequence '{}' is exhausted", sequence_name))
+        })
+    }
+
+    fn identity_sequence_names(table: &TableSchema) -> Vec<String> {
+        table
+            .fields
+            .iter()
+            .zip(table.identity.iter())
+            .filter(|(_, identity)| identity.is_some())
+            .map(|(field, _)| Planner::identity_sequence_name(&table.name, &field.name))
+            .collect()
+    }
+
+    /// Tables, indexes, sequences, views and triggers share the names of the master table.
+    pub(crate) fn name_in_use(&self, name: &str) -> bool {
+        self.schema.table_index.index.contains(&name.as_bytes().to_vec())
+            || self.schema.sequences.iter().any(|seq| seq.name == name)
+            || self.schema.views.iter().any(|view| view.name == name)
+            || self.schema.triggers.iter().any(|trigger| trigger.name == name)
+    }
+
     fn make_master_table_schema() -> TableSchema {
         let mut parser = Parser::new(MASTER_TABLE_SQL.parse().unwrap());
         let parsed_query = parser
@@ -0,0 +1,419 @@
+use crate::btree::Btree;
+use crate::cursor::BTreeCursor;
+use crate::dataframe::RowSource;
+use crate::debug::Status;
+use crate::executor::{QueryExecutor, QueryResult};
+use crate::pager::{Key, Row};
+use crate::parser::ForeignKeyAction;
+use crate::planner::{Planner, SqlConditionOpCode};
+use crate::schema::{ForeignKeyDefinition, TableSchema};
+use crate::serializer::Serializer;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// Changes to referencing rows that a DELETE or UPDATE of their parents causes. They are
+/// collected before anything is written, so a RESTRICT anywhere leaves every table untouched.
+#[derive(Default)]
+pub(crate) struct ForeignKeyEffects {
+    /// (table id, full row) of rows to delete
+    deletes: Vec<(usize, Row)>,
+    /// (table id, old full row, new full row) of rows to rewrite
+    updates: Vec<(usize, Row, Row)>,
+}
+
+impl QueryExecutor {
+    fn foreign_key_violation(foreign_key: &ForeignKeyDefinition) -> QueryResult {
+        QueryResult::user_input_wrong(format!(
+            "FOREIGN KEY constraint failed: '{}.{}' references '{}.{}'",
+            foreign_key.child_table,
+            foreign_key.child_column,
+            foreign_key.parent_table,
+            foreign_key.parent_column
+        ))
+    }
+
+    pub(crate) fn field_position(schema: &TableSchema, column: &str) -> Result<usize, QueryResult> {
+        schema
+            .fields
+            .iter()
+            .position(|f| f.name == column)
+            .ok_or_else(|| QueryResult::err(Status::InternalExceptionCompilerError))
+    }
+
+    /// Rejects `rows`, full rows about to be written to `table_id`, whose foreign key values
+    /// have no parent row. `old_rows` are the rows they replace, by position; values an UPDATE
+    /// leaves unchanged are not checked again. NULLs reference nothing.
+    pub(crate) fn check_foreign_key_parents(
+        &self,
+        table_id: usize,
+        rows: &[Row],
+        old_rows: &[Row],
+    ) -> Result<(), QueryResult> {
+        self.check_parents(table_id, None, rows, old_rows)
+    }
+
+    /// Like `check_foreign_key_parents`, but only for the foreign keys of `column`.
+    pub(crate) fn check_column_foreign_key_parents(
+        &self,
+        table_id: usize,
+        column: &str,
+        rows: &[Row],
+    ) -> Result<(), QueryResult> {
+        self.check_parents(table_id, Some(column), rows, &[])
+    }
+
+    fn check_parents(
+        &self,
+        table_id: usize,
+        column: Option<&str>,
+        rows: &[Row],
+        old_rows: &[Row],
+    ) -> Result<(), QueryResult> {
+        if !self.foreign_keys_enabled {
+            return Ok(());
+        }
+        let child = &self.schema.tables[table_id];
+        for foreign_key in self.schema.foreign_keys.iter().filter(|fk| {
+            fk.child_table == child.name && column.is_none_or(|column| fk.child_column == column)
+        }) {
+            let child_idx = Self::field_position(child, &foreign_key.child_column)?;
+            let field = &child.fields[child_idx];
+            let parent_id = Planner::find_table_id(&self.schema, &foreign_key.parent_table)?;
+            let parent_idx =
+                Self::field_position(&self.schema.tables[parent_id], &foreign_key.parent_column)?;
+
+            for (row_idx, row) in rows.iter().enumerate() {
+                let value =
+                    Serializer::get_field_on_row(row, child_idx, child).map_err(QueryResult::err)?;
+                if Serializer::is_null(&value, &field.field_type).map_err(QueryResult::err)? {
+                    continue;
+                }
+                if let Some(old_row) = old_rows.get(row_idx)
+                    && Serializer::get_field_on_row(old_row, child_idx, child)
+                        .map_err(QueryResult::err)?
+                        == value
+                {
+                    continue;
+                }
+                // a row may reference another row written by the same statement
+                if parent_id == table_id {
+                    let wanted =
+                        Serializer::collation_key(&value, &field.field_type, field.collation);
+                    let mut found = false;
+                    for candidate in rows {
+                        let parent_value = Serializer::get_field_on_row(candidate, parent_idx, child)
+                            .map_err(QueryResult::err)?;
+                        found |= Serializer::collation_key(
+                            &parent_value,
+                            &field.field_type,
+                            field.collation,
+                        ) == wanted;
+                    }
+                    if found {
+                        continue;
+                    }
+                }
+                if self
+                    .rows_with_value(parent_id, parent_idx, &value)
+                    .map_err(QueryResult::err)?
+                    .is_empty()
+                {
+                    return Err(Self::foreign_key_violation(foreign_key));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects what deleting (`new_rows` is `None`) or updating the full rows `old_rows` of
+    /// `table_id` does to the rows that reference them, following CASCADE and SET NULL into
+    /// further tables. Fails if a RESTRICT foreign key still has referencing rows.
+    pub(crate) fn plan_foreign_key_effects(
+        &self,
+        table_id: usize,
+        old_rows: &[Row],
+        new_rows: Option<&[Row]>,
+    ) -> Result<ForeignKeyEffects, QueryResult> {
+        let mut effects = ForeignKeyEffects::default();
+        let table = &self.schema.tables[table_id];
+        if !self.foreign_keys_enabled
+            || !self
+                .schema
+                .foreign_keys
+                .iter()
+                .any(|fk| fk.parent_table == table.name)
+        {
+            return Ok(effects);
+        }
+
+        // rows the statement changes itself are not referencing rows to act on
+        let mut touched = HashSet::new();
+        for row in old_rows {
+            let (key, _) = Serializer::split_key_and_row(row, table).map_err(QueryResult::err)?;
+            touched.insert((table_id, key));
+        }
+        let changes: Vec<(Row, Option<Row>)> = match new_rows {
+            Some(new_rows) => old_rows
+                .iter()
+                .cloned()
+                .zip(new_rows.iter().cloned().map(Some))
+                .collect(),
+            None => old_rows.iter().cloned().map(|row| (row, None)).collect(),
+        };
+        self.collect_foreign_key_effects(table_id, &changes, &mut touched, &mut effects)?;
+        Ok(effects)
+    }
+
+    fn collect_foreign_key_effects(
+        &self,
+        table_id: usize,
+        changes: &[(Row, Option<Row>)],
+        touched: &mut HashSet<(usize, Key)>,
+        effects: &mut ForeignKeyEffects,
+    ) -> Result<(), QueryResult> {
+        let parent = &self.schema.tables[table_id];
+        for foreign_key in self
+            .schema
+            .foreign_keys
+            .iter()
+            .filter(|fk| fk.parent_table == parent.name)
+        {
+            let parent_idx = Self::field_position(parent, &foreign_key.parent_column)?;
+            let field = &parent.fields[parent_idx];
+            let child_id = Planner::find_table_id(&self.schema, &foreign_key.child_table)?;
+            let child = &self.schema.tables[child_id];
+            let child_idx = Self::field_position(child, &foreign_key.child_column)?;
+
+            for (old_row, new_row) in changes {
+                let old_value = Serializer::get_field_on_row(old_row, parent_idx, parent)
+                    .map_err(QueryResult::err)?;
+                if Serializer::is_null(&old_value, &field.field_type).map_err(QueryResult::err)? {
+                    continue;
+                }
+                let new_value = match new_row {
+                    Some(row) => Some(
+                        Serializer::get_field_on_row(row, parent_idx, parent)
+                            .map_err(QueryResult::err)?,
+                    ),
+                    None => None,
+                };
+                if let Some(new_value) = &new_value
+                    && Serializer::collation_key(new_value, &field.field_type, field.collation)
+                        == Serializer::collation_key(&old_value, &field.field_type, field.collation)
+                {
+                    continue;
+                }
+                let action = match new_value {
+                    Some(_) => foreign_key.on_update,
+                    None => foreign_key.on_delete,
+                };
+
+                let mut child_changes = Vec::new();
+                for row in self
+                    .rows_with_value(child_id, child_idx, &old_value)
+                    .map_err(QueryResult::err)?
+                {
+                    let (key, _) =
+                        Serializer::split_key_and_row(&row, child).map_err(QueryResult::err)?;
+                    if !touched.insert((child_id, key.clone())) {
+                        continue;
+                    }
+                    let replacement = match (action, &new_value) {
+                        (ForeignKeyAction::Restrict, _) => {
+                            return Err(Self::foreign_key_violation(foreign_key));
+                        }
+                        (ForeignKeyAction::Cascade, None) => None,
+                        (ForeignKeyAction::Cascade, Some(new_value)) => Some(
+                            Self::replace_field(&row, child_idx, child, new_value)
+                                .map_err(QueryResult::err)?,
+                        ),
+                        (ForeignKeyAction::SetNull, _) => {
+                            let null = Serializer::null_field(&child.fields[child_idx].field_type)
+                                .map_err(QueryResult::err)?;
+                            Some(
+                                Self::replace_field(&row, child_idx, child, &null)
+                                    .map_err(QueryResult::err)?,
+                            )
+                        }
+                    };
+                    let replacement = replacement
+                        .map(|new_child_row| self.fill_generated_values(child_id, new_child_row))
+                        .transpose()?;
+                    match &replacement {
+                        Some(new_child_row) => {
+                            self.check_row_constraints(child_id, new_child_row)?;
+                            self.check_unique_indices(
+                                child_id,
+                                &[new_child_row.clone()],
+                                &HashSet::from([key]),
+                            )?;
+                            effects
+                                .updates
+                                .push((child_id, row.clone(), new_child_row.clone()));
+                        }
+                        None => effects.deletes.push((child_id, row.clone())),
+                    }
+                    child_changes.push((row, replacement));
+                }
+                if !child_changes.is_empty() {
+                    self.collect_foreign_key_effects(child_id, &child_changes, touched, effects)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes what `plan_foreign_key_effects` collected and rebuilds the indexes of the
+    /// tables it changed.
+    pub(crate) fn apply_foreign_key_effects(
+        &mut self,
+        effects: ForeignKeyEffects,
+    ) -> Result<(), QueryResult> {
+        let mut changed_tables: Vec<usize> = effects
+            .deletes
+            .iter()
+            .map(|(table_id, _)| *table_id)
+            .chain(effects.updates.iter().map(|(table_id, _, _)| *table_id))
+            .collect();
+        changed_tables.sort();
+        changed_tables.dedup();
+        for &table_id in &changed_tables {
+            let table_name = self.schema.tables[table_id].name.clone();
+            self.lock_table_if_needed(&table_name)?;
+        }
+
+        for (table_id, row) in &effects.deletes {
+            let table = self.schema.tables[*table_id].clone();
+            let (key, _) = Serializer::split_key_and_row(row, &table).map_err(QueryResult::err)?;
+            let mut btree = Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+                .map_err(QueryResult::err)?;
+            btree.delete(key).map_err(QueryResult::err)?;
+            self.release_payload_fields(row, &table)
+                .map_err(QueryResult::err)?;
+        }
+        // keys may change, so every old row goes before any new one is inserted
+        for (table_id, old_row, _) in &effects.updates {
+            let table = self.schema.tables[*table_id].clone();
+            let (key, _) =
+                Serializer::split_key_and_row(old_row, &table).map_err(QueryResult::err)?;
+            let mut btree = Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+                .map_err(QueryResult::err)?;
+            btree.delete(key).map_err(QueryResult::err)?;
+        }
+        for (table_id, _, new_row) in &effects.updates {
+            let table = self.schema.tables[*table_id].clone();
+            let (key, row) =
+                Serializer::split_key_and_row(new_row, &table).map_err(QueryResult::err)?;
+            let mut btree = Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+                .map_err(QueryResult::err)?;
+            btree.insert(key, row).map_err(QueryResult::err)?;
+        }
+
+        for table_id in changed_tables {
+            self.rebuild_indices_for_table_id(table_id)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn replace_field(
+        row: &Row,
+        field_idx: usize,
+        schema: &TableSchema,
+        value: &[u8],
+    ) -> Result<Row, Status> {
+        let mut fields = Vec::with_capacity(schema.fields.len());
+        for idx in 0..schema.fields.len() {
+            fields.push(if idx == field_idx {
+                value.to_vec()
+            } else {
+                Serializer::get_field_on_row(row, idx, schema)?
+            });
+        }
+        Ok(fields.concat())
+    }
+
+    /// Full rows of `table_id` whose column `field_idx` equals `value` under the column's
+    /// collation. Seeks the key tree when the column leads the key, else uses an index on the
+    /// column if there is one, and scans the table otherwise.
+    pub(crate) fn rows_with_value(
+        &self,
+        table_id: usize,
+        field_idx: usize,
+        value: &[u8],
+    ) -> Result<Vec<Row>, Status> {
+        let table = self.schema.tables[table_id].clone();
+        let value = value.to_vec();
+        let mut rows = Vec::new();
+
+        if table.key_positions.first() == Some(&field_idx) {
+            let mut cursor = BTreeCursor::new(Btree::init(
+                table.btree_order,
+                self.pager_accessor.clone(),
+                table.clone(),
+            )?);
+            cursor.go_to_greater_than_equal(&value)?;
+            while let Some((key, row)) = cursor.current()? {
+                // a prefix of the key compares equal to every key starting with it
+                if cursor.btree.compare(&key, &value)? != Ordering::Equal {
+                    break;
+                }
+                if !Serializer::is_tomb(&key, &table)? {
+                    rows.push(Serializer::reconstruct_row(&key, &row, &table)?);
+                }
+                cursor.advance()?;
+            }
+            return Ok(rows);
+        }
+
+        let field = &table.fields[field_idx];
+        if let Some(index_table_id) = self.find_index_table_id_for_base_column(&table.name, &field.name)
+        {
+            let index_schema = self.schema.tables[index_table_id].clone();
+            let mut index_cursor = BTreeCursor::new(Btree::init(
+                index_schema.btree_order,
+                self.pager_accessor.clone(),
+                index_schema.clone(),
+            )?);
+            let mut base_keys = Vec::new();
+            index_cursor.go_to_greater_than_equal(&value)?;
+            while let Some((key, base_key)) = index_cursor.current()? {
+                if index_cursor.btree.compare(&key, &value)? != Ordering::Equal {
+                    break;
+                }
+                if !Serializer::is_tomb(&key, &index_schema)? {
+                    base_keys.push(base_key);
+                }
+                index_cursor.advance()?;
+            }
+
+            let mut cursor = BTreeCursor::new(Btree::init(
+                table.btree_order,
+                self.pager_accessor.clone(),
+                table.clone(),
+            )?);
+            for base_key in base_keys {
+                cursor.go_to_greater_than_equal(&base_key)?;
+                if let Some((key, row)) = cursor.current()?
+                    && cursor.btree.compare(&key, &base_key)? == Ordering::Equal
+                {
+                    rows.push(Serializer::reconstruct_row(&key, &row, &table)?);
+                }
+            }
+            return Ok(rows);
+        }
+
+        let wanted = Serializer::collation_key(&value, &field.field_type, field.collation);
+        let mut source = self.create_scan_source(table_id, SqlConditionOpCode::SelectFTS, None)?;
+        source.reset()?;
+        while let Some(row) = source.next()? {
+            let candidate = Serializer::get_field_on_row(&row, field_idx, &table)?;
+            if !Serializer::is_null(&candidate, &field.field_type)?
+                && Serializer::collation_key(&candidate, &field.field_type, field.collation)
+                    == wanted
+            {
+                rows.push(row);
+            }
+        }
+        Ok(rows)
+    }
+}
@@ -0,0 +1,179 @@
+use crate::debug::Status;
+use crate::executor::{QueryExecutor, QueryResult};
+use crate::json::JsonValue;
+use crate::pager::Row;
+use crate::pager_proxy::PageManager;
+use crate::planner::{CompiledScalarExpr, Planner, ScalarFunction, ScalarOperator};
+use crate::schema::TableSchema;
+use crate::serializer::Serializer;
+
+impl QueryExecutor {
+    /// Computes the generated columns of a full row, replacing the NULLs the planner left in
+    /// their place. As in SQL, an expression with a NULL operand is NULL.
+    pub(crate) fn fill_generated_values(
+        &self,
+        table_id: usize,
+        row: Row,
+    ) -> Result<Row, QueryResult> {
+        let schema = &self.schema.tables[table_id];
+        let mut row = row;
+        for (field_idx, expr) in schema.generated.iter().enumerate() {
+            let Some(expr) = expr else {
+                continue;
+            };
+            let field = &schema.fields[field_idx];
+            let value = match self.evaluate_scalar(table_id, &row, expr)? {
+                Some(text) => Planner::compile_value(&text, field)?,
+                None if schema.not_null.get(field_idx) == Some(&true) => {
+                    return Err(Planner::not_null_violation(schema, field_idx));
+                }
+                None => Serializer::null_field(&field.field_type).map_err(QueryResult::err)?,
+            };
+            row = Self::replace_field(&row, field_idx, schema, &value).map_err(QueryResult::err)?;
+        }
+        Ok(row)
+    }
+
+    /// The text of a field of a row of `table`, `None` for NULL.
+    pub(crate) fn field_text(
+        &self,
+        table: &TableSchema,
+        row: &Row,
+        field_idx: usize,
+    ) -> Result<Option<String>, Status> {
+        let field_type = &table.fields[field_idx].field_type;
+        let value = Serializer::get_field_on_row(row, field_idx, table)?;
+        if Serializer::is_null(&value, field_type)? {
+            return Ok(None);
+        }
+        if field_type.is_payload_backed() {
+            let content = PageManager::read_payload_field(self.pager_accessor.clone(), &value)?;
+            Ok(Some(Serializer::format_payload_content(&content, field_type)))
+        } else {
+            Serializer::format_field(&value, field_type).map(Some)
+        }
+    }
+
+    fn evaluate_scalar(
+        &self,
+        table_id: usize,
+        row: &Row,
+        expr: &CompiledScalarExpr,
+    ) -> Result<Option<String>, QueryResult> {
+        let table = &self.schema.tables[table_id];
+        match expr {
+            CompiledScalarExpr::Column(column_idx) => self
+                .field_text(table, row, *column_idx)
+                .map_err(QueryResult::err),
+            CompiledScalarExpr::JsonExtract { column_idx, path } => {
+                let Some(text) = self
+                    .field_text(table, row, *column_idx)
+                    .map_err(QueryResult::err)?
+                else {
+                    return Ok(None);
+                };
+                let json = JsonValue::parse(&text).map_err(QueryResult::err)?;
+                Ok(json.get(path).and_then(JsonValue::to_text))
+            }
+            CompiledScalarExpr::Literal(text) => Ok(Some(text.clone())),
+            CompiledScalarExpr::Function { function, args } => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    match self.evaluate_scalar(table_id, row, arg)? {
+                        Some(value) => values.push(value),
+                        None => return Ok(None),
+                    }
+                }
+                Self::apply_scalar_function(*function, &values[0]).map(Some)
+            }
+            CompiledScalarExpr::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let Some(left) = self.evaluate_scalar(table_id, row, left)? else {
+                    return Ok(None);
+                };
+                let Some(right) = self.evaluate_scalar(table_id, row, right)? else {
+                    return Ok(None);
+                };
+                Self::apply_scalar_operator(*operator, &left, &right).map(Some)
+            }
+        }
+    }
+
+    fn apply_scalar_function(function: ScalarFunction, value: &str) -> Result<String, QueryResult> {
+        let date_part = |position: usize| {
+            // dates and timestamps both start with `YYYY-MM-DD`
+            value
+                .get(..10)
+                .and_then(|date| date.split('-').nth(position))
+                .and_then(|part| part.parse::<i64>().ok())
+                .map(|part| part.to_string())
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!("'{}' is not a date", value))
+                })
+        };
+        match function {
+            ScalarFunction::Lower => Ok(value.to_lowercase()),
+            ScalarFunction::Upper => Ok(value.to_uppercase()),
+            ScalarFunction::Trim => Ok(value.trim().to_string()),
+            ScalarFunction::Length => Ok(value.chars().count().to_string()),
+            ScalarFunction::Abs => {
+                if let Ok(number) = value.parse::<i64>() {
+                    number.checked_abs().map(|n| n.to_string()).ok_or_else(|| {
+                        QueryResult::user_input_wrong("Integer overflow in abs()".to_string())
+                    })
+                } else {
+                    Self::parse_number(value).map(|number| number.abs().to_string())
+                }
+            }
+            ScalarFunction::Year => date_part(0),
+            ScalarFunction::Month => date_part(1),
+            ScalarFunction::Day => date_part(2),
+        }
+    }
+
+    fn apply_scalar_operator(
+        operator: ScalarOperator,
+        left: &str,
+        right: &str,
+    ) -> Result<String, QueryResult> {
+        if operator == ScalarOperator::Concat {
+            return Ok(format!("{}{}", left, right));
+        }
+        if let (Ok(left), Ok(right)) = (left.parse::<i64>(), right.parse::<i64>()) {
+            let result = match operator {
+                ScalarOperator::Add => left.checked_add(right),
+                ScalarOperator::Subtract => left.checked_sub(right),
+                ScalarOperator::Multiply => left.checked_mul(right),
+                ScalarOperator::Divide if right == 0 => {
+                    return Err(QueryResult::user_input_wrong("Division by zero".to_string()));
+                }
+                ScalarOperator::Divide => left.checked_div(right),
+                ScalarOperator::Concat => unreachable!(),
+            };
+            return result.map(|n| n.to_string()).ok_or_else(|| {
+                QueryResult::user_input_wrong("Integer overflow in generated column".to_string())
+            });
+        }
+        let (left, right) = (Self::parse_number(left)?, Self::parse_number(right)?);
+        let result = match operator {
+            ScalarOperator::Add => left + right,
+            ScalarOperator::Subtract => left - right,
+            ScalarOperator::Multiply => left * right,
+            ScalarOperator::Divide if right == 0.0 => {
+                return Err(QueryResult::user_input_wrong("Division by zero".to_string()));
+            }
+            ScalarOperator::Divide => left / right,
+            ScalarOperator::Concat => unreachable!(),
+        };
+        Ok(result.to_string())
+    }
+
+    fn parse_number(value: &str) -> Result<f64, QueryResult> {
+        value
+            .parse::<f64>()
+            .map_err(|_| QueryResult::user_input_wrong(format!("'{}' is not a number", value)))
+    }
+}
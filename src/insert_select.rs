@@ -0,0 +1,183 @@
+use crate::dataframe::DataFrame;
+use crate::executor::{QueryExecutor, QueryResult, TableSchema};
+use crate::pager::Row;
+use crate::parser::ParsedValue;
+use crate::planner::{
+    CompiledConditionExpr, CompiledCreateTableAsQuery, CompiledInStrategy,
+    CompiledInsertBatchQuery, CompiledInsertSelectQuery, CompiledPredicateExpr, CompiledQuery,
+    InsertValue, PlanNode, Planner,
+};
+use crate::serializer::Serializer;
+
+/// Rows of the query inserted per batch while the result streams into the table.
+const INSERT_SELECT_BATCH_ROWS: usize = 256;
+
+impl QueryExecutor {
+    /// Creates the table from the statement the planner derived from the query's result and
+    /// streams the result into it, in one transaction.
+    pub(crate) fn create_table_as(
+        &mut self,
+        q: CompiledCreateTableAsQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        let result_schema = q.plan.get_schema(&self.schema).map_err(QueryResult::err)?;
+        let fields: Vec<String> = result_schema.fields.iter().map(|f| f.name.clone()).collect();
+        self.run_atomically(|executor| {
+            // the frame reads the tables as they are before the new one exists
+            let result = executor.exec_planned_tree(&q.plan).map_err(QueryResult::err)?;
+            executor.run_query_internal(&q.create_sql, allow_modification_to_system_table)?;
+            let table_id = Planner::find_table_id(&executor.schema, &q.table_name)?;
+            executor.stream_into_table(
+                table_id,
+                &fields,
+                &result_schema,
+                result,
+                false,
+                allow_modification_to_system_table,
+            )
+        })
+    }
+
+    /// Inserts the result of the query into the table, in one transaction. The result streams
+    /// into the table in batches, unless the query reads the table or the table has triggers:
+    /// then the query runs to completion first, so it only sees the rows that were there
+    /// before the statement.
+    pub(crate) fn insert_select(
+        &mut self,
+        q: CompiledInsertSelectQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        let result_schema = q.plan.get_schema(&self.schema).map_err(QueryResult::err)?;
+        let read_all_first =
+            self.has_triggers(q.table_id) || Self::plan_reads_table(&q.plan, q.table_id);
+        self.run_atomically(|executor| {
+            let result = executor.exec_planned_tree(&q.plan).map_err(QueryResult::err)?;
+            executor.stream_into_table(
+                q.table_id,
+                &q.fields,
+                &result_schema,
+                result,
+                read_all_first,
+                allow_modification_to_system_table,
+            )
+        })
+    }
+
+    /// Inserts the rows of `result`, whose column `i` goes to the column named `fields[i]`,
+    /// as batch INSERTs of their encoded values. Defaults, constraints, triggers and indexes
+    /// apply as they do to rows given as VALUES. With `read_all_first`, every row is read
+    /// before the first is written.
+    fn stream_into_table(
+        &mut self,
+        table_id: usize,
+        fields: &[String],
+        result_schema: &TableSchema,
+        mut result: DataFrame,
+        read_all_first: bool,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        let mut inserted = QueryResult::went_fine();
+        let mut finished = false;
+        while !finished {
+            let mut compiled_rows = Vec::new();
+            while !finished && (read_all_first || compiled_rows.len() < INSERT_SELECT_BATCH_ROWS) {
+                let rows = result
+                    .fetch_n(INSERT_SELECT_BATCH_ROWS)
+                    .map_err(QueryResult::err)?;
+                finished = rows.len() < INSERT_SELECT_BATCH_ROWS;
+                for row in &rows {
+                    let values =
+                        self.insert_values_of_row(table_id, fields, result_schema, &result, row)?;
+                    compiled_rows.push(Planner::plan_insert_values(&self.schema, table_id, fields, values)?);
+                }
+            }
+            if compiled_rows.is_empty() {
+                break;
+            }
+            let batch = CompiledQuery::InsertBatch(CompiledInsertBatchQuery {
+                table_id,
+                rows: compiled_rows,
+                returning: None,
+            });
+            let written = self.execute_write(batch, allow_modification_to_system_table)?;
+            inserted.generated_keys.extend(written.generated_keys);
+        }
+        Ok(inserted)
+    }
+
+    /// The values of a result row for the columns named `fields`. Cells of the column's type
+    /// are taken as they are, payloads as their content; other cells convert through their
+    /// text.
+    fn insert_values_of_row(
+        &self,
+        table_id: usize,
+        fields: &[String],
+        result_schema: &TableSchema,
+        result: &DataFrame,
+        row: &Row,
+    ) -> Result<Vec<Option<InsertValue>>, QueryResult> {
+        let table = &self.schema.tables[table_id];
+        let mut values = Vec::with_capacity(fields.len());
+        for (field_idx, (source, name)) in result_schema.fields.iter().zip(fields).enumerate() {
+            let target_type = table
+                .fields
+                .iter()
+                .find(|f| &f.name == name)
+                .map(|f| &f.field_type);
+            let cell = Serializer::get_field_on_row(row, field_idx, result_schema)
+                .map_err(QueryResult::err)?;
+            let value = if Serializer::is_null(&cell, &source.field_type).map_err(QueryResult::err)? {
+                None
+            } else if source.field_type.is_payload_backed() {
+                let content = result.read_payload_field(&cell).map_err(QueryResult::err)?;
+                Some(if target_type == Some(&source.field_type) {
+                    InsertValue::Encoded(content)
+                } else {
+                    InsertValue::Value(ParsedValue::Literal(Serializer::format_payload_content(
+                        &content,
+                        &source.field_type,
+                    )))
+                })
+            } else if target_type == Some(&source.field_type) {
+                Some(InsertValue::Encoded(cell))
+            } else {
+                Some(InsertValue::Value(ParsedValue::Literal(
+                    Serializer::format_field(&cell, &source.field_type).map_err(QueryResult::err)?,
+                )))
+            };
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Whether running `plan` reads table `table_id`, in a scan or an IN subquery.
+    fn plan_reads_table(plan: &PlanNode, table_id: usize) -> bool {
+        match plan {
+            PlanNode::SeqScan { table_id: scanned, .. } => *scanned == table_id,
+            PlanNode::Filter { source, condition } => {
+                Self::plan_reads_table(source, table_id)
+                    || Self::condition_reads_table(condition, table_id)
+            }
+            PlanNode::Project { source, .. } | PlanNode::JsonExtract { source, .. } => {
+                Self::plan_reads_table(source, table_id)
+            }
+            PlanNode::Join { left, right, .. } | PlanNode::SetOperation { left, right, .. } => {
+                Self::plan_reads_table(left, table_id) || Self::plan_reads_table(right, table_id)
+            }
+        }
+    }
+
+    fn condition_reads_table(condition: &CompiledConditionExpr, table_id: usize) -> bool {
+        match condition {
+            CompiledConditionExpr::Predicate(CompiledPredicateExpr::InSubquery {
+                strategy: CompiledInStrategy::Materialize(plan) | CompiledInStrategy::Lookup(plan),
+                ..
+            }) => Self::plan_reads_table(plan, table_id),
+            CompiledConditionExpr::Predicate(_) => false,
+            CompiledConditionExpr::Logical { left, right, .. } => {
+                Self::condition_reads_table(left, table_id)
+                    || Self::condition_reads_table(right, table_id)
+            }
+        }
+    }
+}
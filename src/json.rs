@@ -0,0 +1,398 @@
+use crate::debug::Status;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// Nesting limit for documents; deeper input is rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// A parsed JSON document. Numbers keep their source text so values round-trip exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonPathStep {
+    Key(String),
+    Index(usize),
+}
+
+/// A path like `$.a.b[0]` or `$["key with spaces"]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    pub steps: Vec<JsonPathStep>,
+}
+
+impl JsonValue {
+    pub fn parse(input: &str) -> Result<JsonValue, Status> {
+        let mut parser = JsonParser {
+            bytes: input.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        if parser.pos != parser.bytes.len() {
+            return Err(Status::CannotParseJson);
+        }
+        Ok(value)
+    }
+
+    pub fn get(&self, path: &JsonPath) -> Option<&JsonValue> {
+        let mut current = self;
+        for step in &path.steps {
+            current = match (step, current) {
+                (JsonPathStep::Key(key), JsonValue::Object(members)) => {
+                    &members.iter().find(|(name, _)| name == key)?.1
+                }
+                (JsonPathStep::Index(idx), JsonValue::Array(items)) => items.get(*idx)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// The SQL text of a value: strings without quotes, `null` as `None`, everything else as JSON.
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            JsonValue::Null => None,
+            JsonValue::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+impl Display for JsonValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{}", b),
+            JsonValue::Number(n) => write!(f, "{}", n),
+            JsonValue::String(s) => write_json_string(f, s),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_json_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_json_string(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl JsonPath {
+    /// Parses `$`, followed by any number of `.key`, `["key"]` and `[n]` steps.
+    pub fn parse(path: &str) -> Result<JsonPath, Status> {
+        let rest = path
+            .trim()
+            .strip_prefix('$')
+            .ok_or(Status::CannotParseJsonPath)?;
+        let mut steps = Vec::new();
+        let mut chars = rest.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            match c {
+                '.' => {
+                    let key_start = start + 1;
+                    let mut key_end = rest.len();
+                    while let Some(&(i, next)) = chars.peek() {
+                        if next == '.' || next == '[' {
+                            key_end = i;
+                            break;
+                        }
+                        chars.next();
+                    }
+                    let key = &rest[key_start..key_end];
+                    if key.is_empty() {
+                        return Err(Status::CannotParseJsonPath);
+                    }
+                    steps.push(JsonPathStep::Key(key.to_string()));
+                }
+                '[' => {
+                    let close = rest[start..]
+                        .find(']')
+                        .map(|i| start + i)
+                        .ok_or(Status::CannotParseJsonPath)?;
+                    let inner = &rest[start + 1..close];
+                    let step = if let Some(key) = inner
+                        .strip_prefix('"')
+                        .and_then(|k| k.strip_suffix('"'))
+                    {
+                        JsonPathStep::Key(key.to_string())
+                    } else {
+                        JsonPathStep::Index(
+                            inner.parse().map_err(|_| Status::CannotParseJsonPath)?,
+                        )
+                    };
+                    steps.push(step);
+                    while chars.peek().is_some_and(|&(i, _)| i <= close) {
+                        chars.next();
+                    }
+                }
+                _ => return Err(Status::CannotParseJsonPath),
+            }
+        }
+        Ok(JsonPath { steps })
+    }
+
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$")?;
+        for step in &self.steps {
+            match step {
+                JsonPathStep::Key(key) => write!(f, "[\"{}\"]", key)?,
+                JsonPathStep::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares an extracted value with a literal: numerically if both are numbers, else as text.
+pub fn compare_text(extracted: &str, literal: &str) -> Ordering {
+    match (extracted.parse::<f64>(), literal.parse::<f64>()) {
+        (Ok(a), Ok(b)) if a.is_finite() && b.is_finite() => a.total_cmp(&b),
+        _ => extracted.cmp(literal),
+    }
+}
+
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn parse_value(&mut self, depth: usize) -> Result<JsonValue, Status> {
+        if depth > MAX_DEPTH {
+            return Err(Status::CannotParseJson);
+        }
+        self.skip_whitespace();
+        match self.peek().ok_or(Status::CannotParseJson)? {
+            b'{' => self.parse_object(depth),
+            b'[' => self.parse_array(depth),
+            b'"' => Ok(JsonValue::String(self.parse_string()?)),
+            b't' => self.parse_literal("true", JsonValue::Bool(true)),
+            b'f' => self.parse_literal("false", JsonValue::Bool(false)),
+            b'n' => self.parse_literal("null", JsonValue::Null),
+            b'-' | b'0'..=b'9' => self.parse_number(),
+            _ => Err(Status::CannotParseJson),
+        }
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<JsonValue, Status> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(Status::CannotParseJson);
+            }
+            let name = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            let value = self.parse_value(depth + 1)?;
+            // like most databases, a repeated key replaces the earlier one
+            members.retain(|(existing, _)| existing != &name);
+            members.push((name, value));
+            self.skip_whitespace();
+            match self.next() {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(JsonValue::Object(members)),
+                _ => return Err(Status::CannotParseJson),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<JsonValue, Status> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value(depth + 1)?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(b',') => continue,
+                Some(b']') => return Ok(JsonValue::Array(items)),
+                _ => return Err(Status::CannotParseJson),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, Status> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // the input is a &str and we only stop at ASCII bytes, so this is valid UTF-8
+            out.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+            match self.next() {
+                Some(b'"') => return Ok(out),
+                Some(b'\\') => {
+                    let c = match self.next().ok_or(Status::CannotParseJson)? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.parse_unicode_escape()?,
+                        _ => return Err(Status::CannotParseJson),
+                    };
+                    out.push(c);
+                }
+                _ => return Err(Status::CannotParseJson),
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, Status> {
+        let high = self.parse_hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or(Status::CannotParseJson);
+        }
+        // surrogate pair
+        self.expect(b'\\')?;
+        self.expect(b'u')?;
+        let low = self.parse_hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(Status::CannotParseJson);
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or(Status::CannotParseJson)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, Status> {
+        let hex = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .ok_or(Status::CannotParseJson)?;
+        if !hex.iter().all(u8::is_ascii_hexdigit) {
+            return Err(Status::CannotParseJson);
+        }
+        self.pos += 4;
+        u32::from_str_radix(std::str::from_utf8(hex).unwrap(), 16).map_err(|_| Status::CannotParseJson)
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, Status> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(Status::CannotParseJson),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if !self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                return Err(Status::CannotParseJson);
+            }
+            self.skip_digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if !self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                return Err(Status::CannotParseJson);
+            }
+            self.skip_digits();
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+        Ok(JsonValue::Number(text.to_string()))
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, Status> {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(Status::CannotParseJson)
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Status> {
+        if self.next() == Some(byte) {
+            Ok(())
+        } else {
+            Err(Status::CannotParseJson)
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+}
@@ -1,17 +1,29 @@
 #![allow(warnings)]
 
+pub mod alter;
 pub mod btree;
+pub mod collation;
 pub mod constants;
 pub mod crypto;
 pub mod cursor;
 pub mod dataframe;
 pub mod debug;
 pub mod executor;
+pub mod foreign_keys;
+pub mod generated;
+pub mod insert_select;
+pub mod json;
 pub mod maintenance;
+pub mod master_table;
+pub mod materialized_views;
 pub mod pager;
 pub mod pager_proxy;
 pub mod parser;
 pub mod planner;
+pub mod returning;
 pub mod schema;
 pub mod serializer;
 pub mod server;
+pub mod temp_tables;
+pub mod triggers;
+pub mod upsert;
@@ -1,49 +1,49 @@
-use rustql::executor::{self, QueryExecutor};
+use rustql::executor::QueryExecutor;
 use std::io;
 use std::io::Write;
 
-/// # NEXT STEPS
-/// - [x] Fix Serializer: Accessing Data on Pages with Cell>0.
-///     - [x] Add Tests
-/// - Improve Space Management
-///     - Implement Different Page Types.
-///         1. Key Page
-///         2. Row Page
-///         3. Overflow Page
-///     - PagerProxy could handle this (with Serializer callback if overflow)?
-///     - Implement FindSpace Method in the Pager? in the BTree? (probably better for locality (do this in the range of the Key))
-/// - Refactor DataFrames
-///     -  [x] implement Joins and Setoperations There -> Break up the Executor Struct
-/// - [x] Fix the Parser -> tests::test_join_subquery_with_union
-/// - [x] varchar
-/// - implement a lookup table in the schema (instead of search the table_index for a name)
-/// - implement methods for memory saving
-///     - [x] enable the Parent-Hint during the split
-///     - [x] implement overflow pages, and use the free_space parameter correctly (dont assume maximum length of a node)
-///     3. set a max cache size
-///     4. VACUUM
-/// # Gameplan:
-/// - [x] create an Iterator-Pattern on the BTree, add a cursor, implement this in the executor, preferably before joins etc
-/// - autosaving, autocleanup, auto-vacuum (?)
-///     - the pager should store a translation layer for positions
-///     - Pages that have been accessed close to each other should be stored in proximity
-///     - when the page is written to disk, the translation layer is applied. Then we would have to collect and update each reference to that page
-///         - can this be done in the background?
-/// - constraints (unique, nullable), primary key, foreign key,
-/// - [x] create a more ambitious executer -> "real" compilation + virtual-machine -- this would enable subqueries etc
-/// - [x] joins, setops
-/// - indices
-/// - [x] views i.e. virtual tables / virtual b-trees (is this necessary for joins also?) ---> DataFrame ^^
-///
-/// # Virtual Memory Strategy for working with multiple things
-/// - [x] Pages just like sqlite
-///     1. Main Pages
-///     2. Overflow Pages
-///     3. Data Pages (Strings and Varchars)
-/// - [x] Maybe a freelist for pages? (free table ?)
-/// - How to detect, if a page is not used anymore?
-///    1. perform a tomb cleanup
-///    2. if the first node has 0 keys, the page is unused
+// # NEXT STEPS
+// - [x] Fix Serializer: Accessing Data on Pages with Cell>0.
+//     - [x] Add Tests
+// - Improve Space Management
+//     - Implement Different Page Types.
+//         1. Key Page
+//         2. Row Page
+//         3. Overflow Page
+//     - PagerProxy could handle this (with Serializer callback if overflow)?
+//     - Implement FindSpace Method in the Pager? in the BTree? (probably better for locality (do this in the range of the Key))
+// - Refactor DataFrames
+//     -  [x] implement Joins and Setoperations There -> Break up the Executor Struct
+// - [x] Fix the Parser -> tests::test_join_subquery_with_union
+// - [x] varchar
+// - implement a lookup table in the schema (instead of search the table_index for a name)
+// - implement methods for memory saving
+//     - [x] enable the Parent-Hint during the split
+//     - [x] implement overflow pages, and use the free_space parameter correctly (dont assume maximum length of a node)
+//     3. set a max cache size
+//     4. VACUUM
+// # Gameplan:
+// - [x] create an Iterator-Pattern on the BTree, add a cursor, implement this in the executor, preferably before joins etc
+// - autosaving, autocleanup, auto-vacuum (?)
+//     - the pager should store a translation layer for positions
+//     - Pages that have been accessed close to each other should be stored in proximity
+//     - when the page is written to disk, the translation layer is applied. Then we would have to collect and update each reference to that page
+//         - can this be done in the background?
+// - constraints (unique, nullable), primary key, foreign key,
+// - [x] create a more ambitious executer -> "real" compilation + virtual-machine -- this would enable subqueries etc
+// - [x] joins, setops
+// - indices
+// - [x] views i.e. virtual tables / virtual b-trees (is this necessary for joins also?) ---> DataFrame ^^
+//
+// # Virtual Memory Strategy for working with multiple things
+// - [x] Pages just like sqlite
+//     1. Main Pages
+//     2. Overflow Pages
+//     3. Data Pages (Strings and Varchars)
+// - [x] Maybe a freelist for pages? (free table ?)
+// - How to detect, if a page is not used anymore?
+//    1. perform a tomb cleanup
+//    2. if the first node has 0 keys, the page is unused
 
 const BTREE_NODE_SIZE: usize = 3; //this means a maximum of 5 keys per node
 pub const TOMB_THRESHOLD: usize = 10; //10 percent
@@ -87,7 +87,7 @@ fn main() {
     exec.prepare("CREATE INDEX idx_a_v ON A (v)".into());
     exec.prepare("CREATE INDEX idx_Lg_age ON Lg (age)".into());
 
-    let query = r#"
+    let _query = r#"
         SELECT A.id FROM (SELECT A.id FROM A INNER JOIN D ON D.id = A.id UNION SELECT B.id FROM B WHERE B.v > 5) INTERSECT (SELECT C.id FROM C INNER JOIN Lg ON Lg.age = C.id)
     "#;
     let q2 = r#"
@@ -138,7 +138,7 @@ fn handle_cli(executor: &mut QueryExecutor) -> bool {
         let result = executor.prepare(command.to_string());
         println!("{}", result);
     }
-    return false;
+    false
 }
 
 //SELECT * FROM Namen INNER JOIN Orders ON Namen.Id = Orders.Id
@@ -4,10 +4,11 @@ use crate::dataframe::RowSource;
 use crate::debug::Status;
 use crate::executor::{QueryExecutor, QueryResult, MASTER_TABLE_NAME};
 use crate::pager::{Key, PageData, Position, Row, Type};
-use crate::pager_proxy::PagerProxy;
+use crate::pager_proxy::{PageManager, PagerProxy};
 use crate::planner::{Planner, SqlConditionOpCode};
-use crate::schema::TableSchema;
+use crate::schema::{Field, TableSchema};
 use crate::serializer::Serializer;
+use std::cmp::Ordering;
 use std::collections::HashSet;
 
 impl QueryExecutor {
@@ -56,15 +57,210 @@ impl QueryExecutor {
     pub(crate) fn should_index_field(field_type: &Type) -> bool {
         matches!(
             field_type,
-            Type::Integer | Type::String | Type::Varchar(_) | Type::Date
+            Type::Integer
+                | Type::String
+                | Type::Varchar(_)
+                | Type::VarcharBytes(_)
+                | Type::Date
+                | Type::Timestamp
+                | Type::TimestampTz
+                | Type::Time
+                | Type::Uuid
+                | Type::Decimal(..)
+                | Type::Text
         )
     }
 
-    pub(crate) fn insert_row_into_indices(
-        &mut self,
+    pub(crate) fn unique_violation(table: &str, column: &str) -> QueryResult {
+        QueryResult::user_input_wrong(format!(
+            "UNIQUE constraint failed for column '{}.{}'",
+            table, column
+        ))
+    }
+
+    /// Bytes of `value`, a field of `field`, that are equal exactly when the values are equal
+    /// under the field's collation. Payload-backed fields are compared by their content.
+    pub(crate) fn distinct_value_key(
+        &self,
+        value: &[u8],
+        field: &Field,
+    ) -> Result<Vec<u8>, Status> {
+        if field.field_type.is_payload_backed() {
+            let content = PageManager::read_payload_field(self.pager_accessor.clone(), value)?;
+            return Ok(Serializer::collation_key(&content, &field.field_type, field.collation));
+        }
+        Ok(Serializer::collation_key(value, &field.field_type, field.collation))
+    }
+
+    /// Rejects `rows`, full rows about to be written to `table_id`, if they repeat a value of
+    /// a unique index among themselves or with a stored row. Stored rows whose key is in
+    /// `replaced_keys` are being overwritten and do not count. NULLs never conflict.
+    pub(crate) fn check_unique_indices(
+        &self,
         table_id: usize,
+        rows: &[Row],
+        replaced_keys: &HashSet<Key>,
+    ) -> Result<(), QueryResult> {
+        let base = &self.schema.tables[table_id];
+        for definition in self
+            .schema
+            .index_definitions
+            .iter()
+            .filter(|idx| idx.unique && idx.base_table == base.name)
+        {
+            let Some(field_idx) = base
+                .fields
+                .iter()
+                .position(|f| f.name == definition.column_name)
+            else {
+                continue;
+            };
+            let field = &base.fields[field_idx];
+            let index_table_id = Planner::find_table_id(&self.schema, &definition.index_name)?;
+            let index_schema = self.schema.tables[index_table_id].clone();
+            let mut cursor = BTreeCursor::new(
+                Btree::init(
+                    index_schema.btree_order,
+                    self.pager_accessor.clone(),
+                    index_schema.clone(),
+                )
+                .map_err(QueryResult::err)?,
+            );
+
+            let mut seen = HashSet::new();
+            for row in rows {
+                let value =
+                    Serializer::get_field_on_row(row, field_idx, base).map_err(QueryResult::err)?;
+                if Serializer::is_null(&value, &field.field_type).map_err(QueryResult::err)? {
+                    continue;
+                }
+                let repeated = !seen
+                    .insert(self.distinct_value_key(&value, field).map_err(QueryResult::err)?);
+                if repeated
+                    || Self::index_contains(&mut cursor, &index_schema, &value, replaced_keys)
+                        .map_err(QueryResult::err)?
+                {
+                    return Err(Self::unique_violation(&base.name, &field.name));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rejects `rows`, full rows about to be written to `table_id`, if their keys repeat among
+    /// themselves or a stored key. Stored rows whose key is in `replaced_keys` are being
+    /// overwritten and do not count.
+    pub(crate) fn check_unique_key(
+        &self,
+        table_id: usize,
+        rows: &[Row],
+        replaced_keys: &HashSet<Key>,
+    ) -> Result<(), QueryResult> {
+        let table = &self.schema.tables[table_id];
+        let mut cursor = BTreeCursor::new(
+            Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+                .map_err(QueryResult::err)?,
+        );
+
+        let mut seen = HashSet::new();
+        for row in rows {
+            let mut collated = Vec::new();
+            for &idx in &table.key_positions {
+                let field = &table.fields[idx];
+                let value =
+                    Serializer::get_field_on_row(row, idx, table).map_err(QueryResult::err)?;
+                // one entry per column: Text contents differ in length
+                collated.push(self.distinct_value_key(&value, field).map_err(QueryResult::err)?);
+            }
+            let (key, _) = Serializer::split_key_and_row(row, table).map_err(QueryResult::err)?;
+            if !seen.insert(collated)
+                || Self::key_is_stored(&mut cursor, table, &key, replaced_keys)
+                    .map_err(QueryResult::err)?
+            {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "PRIMARY KEY constraint failed for table '{}'",
+                    table.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the table holds a row with key `key` that is not in `ignored_keys`.
+    fn key_is_stored(
+        cursor: &mut BTreeCursor,
+        table: &TableSchema,
         key: &Key,
-        row: &Row,
+        ignored_keys: &HashSet<Key>,
+    ) -> Result<bool, Status> {
+        cursor.go_to_greater_than_equal(key)?;
+        while let Some((stored, _)) = cursor.current()? {
+            if cursor.btree.compare(&stored, key)? != Ordering::Equal {
+                break;
+            }
+            if !Serializer::is_tomb(&stored, table)? && !ignored_keys.contains(&stored) {
+                return Ok(true);
+            }
+            cursor.advance()?;
+        }
+        Ok(false)
+    }
+
+    /// Whether the index holds `value` for a base row whose key is not in `ignored_keys`.
+    fn index_contains(
+        cursor: &mut BTreeCursor,
+        index_schema: &TableSchema,
+        value: &Key,
+        ignored_keys: &HashSet<Key>,
+    ) -> Result<bool, Status> {
+        cursor.go_to_greater_than_equal(value)?;
+        while let Some((key, base_key)) = cursor.current()? {
+            if cursor.btree.compare(&key, value)? != Ordering::Equal {
+                break;
+            }
+            if !Serializer::is_tomb(&key, index_schema)? && !ignored_keys.contains(&base_key) {
+                return Ok(true);
+            }
+            cursor.advance()?;
+        }
+        Ok(false)
+    }
+
+    /// Fails if two stored rows of `table_id` share a non-NULL value in `column`, before a
+    /// unique index is built on it.
+    pub(crate) fn check_column_unique(
+        &self,
+        table_id: usize,
+        column: &str,
+    ) -> Result<(), QueryResult> {
+        let base = self.schema.tables[table_id].clone();
+        let Some(field_idx) = base.fields.iter().position(|f| f.name == column) else {
+            return Ok(());
+        };
+        let field = &base.fields[field_idx];
+
+        let mut source = self
+            .create_scan_source(table_id, SqlConditionOpCode::SelectFTS, None)
+            .map_err(QueryResult::err)?;
+        source.reset().map_err(QueryResult::err)?;
+        let mut seen = HashSet::new();
+        while let Some(row) = source.next().map_err(QueryResult::err)? {
+            let value =
+                Serializer::get_field_on_row(&row, field_idx, &base).map_err(QueryResult::err)?;
+            if Serializer::is_null(&value, &field.field_type).map_err(QueryResult::err)? {
+                continue;
+            }
+            if !seen.insert(self.distinct_value_key(&value, field).map_err(QueryResult::err)?) {
+                return Err(Self::unique_violation(&base.name, column));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn insert_rows_into_indices(
+        &mut self,
+        table_id: usize,
+        rows: &[(Key, Row)],
     ) -> Result<(), QueryResult> {
         if table_id == 0 || table_id >= self.schema.tables.len() {
             return Ok(());
@@ -75,10 +271,9 @@ impl QueryExecutor {
             return Ok(());
         }
 
-        let full_row = Serializer::reconstruct_row(key, row, &base).map_err(QueryResult::err)?;
-
+        // the index B-trees are opened once per batch, not once per row
         for (field_idx, field) in base.fields.iter().enumerate() {
-            if field_idx == base.key_position || !Self::should_index_field(&field.field_type) {
+            if !Self::should_index_field(&field.field_type) {
                 continue;
             }
 
@@ -89,20 +284,26 @@ impl QueryExecutor {
                 };
 
             let index_schema = self.schema.tables[index_table_id].clone();
-            let idx_key =
-                Serializer::get_field_on_row(&full_row, field_idx, &base).map_err(QueryResult::err)?;
-            let base_pk = Serializer::get_field_on_row(&full_row, base.key_position, &base)
-                .map_err(QueryResult::err)?;
-
             let mut index_btree = Btree::init(
                 index_schema.btree_order,
                 self.pager_accessor.clone(),
                 index_schema,
             )
             .map_err(QueryResult::err)?;
-            index_btree
-                .insert(idx_key, base_pk)
-                .map_err(QueryResult::err)?;
+            for (key, row) in rows {
+                let full_row =
+                    Serializer::reconstruct_row(key, row, &base).map_err(QueryResult::err)?;
+                let idx_key = Serializer::get_field_on_row(&full_row, field_idx, &base)
+                    .map_err(QueryResult::err)?;
+                // NULLs are not indexed: no comparison can match them, and as index keys their
+                // flag would read as a tombstone
+                if Serializer::is_null(&idx_key, &field.field_type).map_err(QueryResult::err)? {
+                    continue;
+                }
+                index_btree
+                    .insert(idx_key, key.clone())
+                    .map_err(QueryResult::err)?;
+            }
         }
 
         Ok(())
@@ -118,10 +319,8 @@ impl QueryExecutor {
             return Ok(());
         }
 
-        let base_key_pos = base.key_position;
-
         for (field_idx, field) in base.fields.iter().enumerate() {
-            if field_idx == base_key_pos || !Self::should_index_field(&field.field_type) {
+            if !Self::should_index_field(&field.field_type) {
                 continue;
             }
 
@@ -150,8 +349,11 @@ impl QueryExecutor {
             while let Some(base_row) = base_source.next().map_err(QueryResult::err)? {
                 let idx_key =
                     Serializer::get_field_on_row(&base_row, field_idx, &base).map_err(QueryResult::err)?;
-                let base_pk = Serializer::get_field_on_row(&base_row, base_key_pos, &base)
-                    .map_err(QueryResult::err)?;
+                if Serializer::is_null(&idx_key, &field.field_type).map_err(QueryResult::err)? {
+                    continue;
+                }
+                let (base_pk, _) =
+                    Serializer::split_key_and_row(&base_row, &base).map_err(QueryResult::err)?;
                 index_btree
                     .insert(idx_key, base_pk)
                     .map_err(QueryResult::err)?;
@@ -170,7 +372,7 @@ impl QueryExecutor {
         let has_varchar = schema
             .fields
             .iter()
-            .any(|f| matches!(f.field_type, Type::Varchar(_)));
+            .any(|f| matches!(f.field_type, Type::Varchar(_) | Type::VarcharBytes(_)));
         if has_varchar && effective_schema.get_node_size_in_bytes()? > crate::pager::PAGE_SIZE {
             return Ok(if page_data[0] == 0 && page_data[1] == 0 {
                 0
@@ -218,6 +420,24 @@ impl QueryExecutor {
         Ok(())
     }
 
+    /// Marks the B-tree pages of `table` as deleted and clears its root, as when the table
+    /// is dropped. Payload chains are left alone.
+    pub(crate) fn release_table_pages(&self, table: &TableSchema) -> Result<(), QueryResult> {
+        let btree = Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+            .map_err(QueryResult::err)?;
+
+        let mut pages = HashSet::new();
+        if let Some(root) = btree.root {
+            self.collect_btree_pages(&root, &mut pages)
+                .map_err(QueryResult::err)?;
+        } else {
+            pages.insert(table.root.page());
+        }
+        self.mark_pages_as_deleted(&pages).map_err(QueryResult::err)?;
+
+        PagerProxy::clear_table_root(table, self.pager_accessor.clone()).map_err(QueryResult::err)
+    }
+
     pub(crate) fn mark_pages_as_deleted(&self, pages: &HashSet<usize>) -> Result<(), Status> {
         for page in pages {
             let pos = Position::new(*page, 0);
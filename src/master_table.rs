@@ -0,0 +1,134 @@
+use crate::constants::STRING_SIZE;
+use crate::dataframe::RowSource;
+use crate::debug::Status;
+use crate::executor::{QueryExecutor, QueryResult, MASTER_TABLE_NAME};
+use crate::pager::Position;
+use crate::pager_proxy::PageManager;
+use crate::planner::SqlConditionOpCode;
+use crate::serializer::Serializer;
+
+/// Start of a master `sql` value that refers to a definition kept in payload pages of the
+/// master table: `PAYLOAD <first page> <length>`. Stored statements start with a keyword
+/// such as CREATE, so none reads like this.
+const MASTER_SQL_PAYLOAD_PREFIX: &str = "PAYLOAD ";
+
+impl QueryExecutor {
+    /// Adds the master row of a new table, index, view, sequence or trigger.
+    pub(crate) fn insert_master_row(
+        &mut self,
+        name: &str,
+        entry_type: &str,
+        rootpage: usize,
+        sql: &str,
+        free_list: &str,
+    ) -> Result<(), QueryResult> {
+        let sql_value = self.master_sql_value(sql)?;
+        let insert_query = format!(
+            "INSERT INTO {} (name, type, rootpage, sql, free_list) VALUES ('{}', '{}', {}, '{}', '{}')",
+            MASTER_TABLE_NAME,
+            name.replace("'", "''"),
+            entry_type,
+            rootpage,
+            sql_value.replace("'", "''"),
+            free_list.replace("'", "''")
+        );
+        if let Err(error) = self.run_query_internal(&insert_query, true) {
+            self.release_master_sql_value(&sql_value)?;
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Removes the master row of `name` and the pages of its stored definition.
+    pub(crate) fn delete_master_row(&mut self, name: &str) -> Result<(), QueryResult> {
+        self.release_master_sql(name)?;
+        let delete_master_row = format!(
+            "DELETE FROM {} WHERE name = '{}'",
+            MASTER_TABLE_NAME,
+            name.replace("'", "''")
+        );
+        self.run_query_internal(&delete_master_row, true)?;
+        Ok(())
+    }
+
+    /// The value the master `sql` column stores for the definition `sql`. The column is a
+    /// STRING; a longer definition goes to payload pages of the master table, and the value
+    /// refers to them.
+    pub(crate) fn master_sql_value(&self, sql: &str) -> Result<String, QueryResult> {
+        if sql.len() < STRING_SIZE {
+            return Ok(sql.to_string());
+        }
+        let start = PageManager::write_payload_to_data_pages(
+            self.pager_accessor.clone(),
+            sql.as_bytes(),
+            self.schema.tables[0].root.page(),
+        )
+        .map_err(QueryResult::err)?;
+        Ok(format!(
+            "{}{} {}",
+            MASTER_SQL_PAYLOAD_PREFIX,
+            start.page(),
+            sql.len()
+        ))
+    }
+
+    /// The definition a master `sql` value holds or refers to.
+    pub(crate) fn master_sql_definition(&self, value: &str) -> Result<String, Status> {
+        let Some((start, len)) = Self::master_sql_payload(value) else {
+            return Ok(value.to_string());
+        };
+        let content = PageManager::read_payload_from_pages(self.pager_accessor.clone(), start)?;
+        if content.len() != len {
+            return Err(Status::InternalExceptionPageCorrupted);
+        }
+        String::from_utf8(content).map_err(|_| Status::InternalExceptionPageCorrupted)
+    }
+
+    /// Releases the payload pages of the stored definition of `name`, before its master row
+    /// is deleted or given a new definition.
+    pub(crate) fn release_master_sql(&self, name: &str) -> Result<(), QueryResult> {
+        match self.stored_master_sql_value(name)? {
+            Some(value) => self.release_master_sql_value(&value),
+            None => Ok(()),
+        }
+    }
+
+    /// The statement the master table stores for `name`.
+    pub(crate) fn stored_sql(&self, name: &str) -> Result<String, QueryResult> {
+        let value = self
+            .stored_master_sql_value(name)?
+            .ok_or_else(|| QueryResult::user_input_wrong(format!("Table '{}' not found", name)))?;
+        self.master_sql_definition(&value).map_err(QueryResult::err)
+    }
+
+    fn release_master_sql_value(&self, value: &str) -> Result<(), QueryResult> {
+        match Self::master_sql_payload(value) {
+            Some((start, _)) => PageManager::release_payload_chain(self.pager_accessor.clone(), start)
+                .map_err(QueryResult::err),
+            None => Ok(()),
+        }
+    }
+
+    fn stored_master_sql_value(&self, name: &str) -> Result<Option<String>, QueryResult> {
+        let master_table = &self.schema.tables[0];
+        let mut source = self
+            .create_scan_source(0, SqlConditionOpCode::SelectFTS, None)
+            .map_err(QueryResult::err)?;
+        source.reset().map_err(QueryResult::err)?;
+        while let Some(row) = source.next().map_err(QueryResult::err)? {
+            let row_name =
+                Serializer::format_field_on_row(&row, 0, master_table).map_err(QueryResult::err)?;
+            if row_name == name {
+                return Serializer::format_field_on_row(&row, 3, master_table)
+                    .map(Some)
+                    .map_err(QueryResult::err);
+            }
+        }
+        Ok(None)
+    }
+
+    fn master_sql_payload(value: &str) -> Option<(Position, usize)> {
+        let (page, len) = value.strip_prefix(MASTER_SQL_PAYLOAD_PREFIX)?.split_once(' ')?;
+        Some((Position::new(page.parse().ok()?, 0), len.parse().ok()?))
+    }
+}
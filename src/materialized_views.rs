@@ -0,0 +1,152 @@
+use crate::btree::Btree;
+use crate::dataframe::RowSource;
+use crate::executor::{QueryExecutor, QueryResult, MASTER_TABLE_NAME};
+use crate::pager::Position;
+use crate::pager_proxy::{PageManager, PagerProxy};
+use crate::planner::{
+    CompiledCreateMaterializedViewQuery, CompiledRefreshMaterializedViewQuery, PlanNode, Planner,
+    SqlConditionOpCode,
+};
+use crate::schema::TableSchema;
+use crate::serializer::Serializer;
+
+impl QueryExecutor {
+    /// Creates the table of a materialized view, fills it from the view's query and stores
+    /// the statement in the master table.
+    pub(crate) fn create_materialized_view(
+        &mut self,
+        q: CompiledCreateMaterializedViewQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+            self.lock_table_if_needed(&q.schema.name)?;
+        }
+        if self.name_in_use(&q.schema.name) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "'{}' already exists",
+                q.schema.name
+            )));
+        }
+
+        self.run_atomically(|executor| {
+            let (table, free_list_encoded) = executor.materialize(&q.schema, &q.plan)?;
+            executor.insert_master_row(
+                &table.name,
+                "materialized view",
+                table.root.page(),
+                &q.sql,
+                &free_list_encoded,
+            )?;
+            let result = executor.reload_schema()?;
+            if !allow_modification_to_system_table {
+                executor.last_write_table_id = Some(Planner::find_table_id(&executor.schema, &table.name)?);
+            }
+            Ok(result)
+        })
+    }
+
+    /// Recomputes a materialized view into a new B-tree and swaps it in through the master
+    /// row. The old pages are only released once the new contents are complete, and all of
+    /// it happens in one transaction, so readers see either the old or the new result.
+    pub(crate) fn refresh_materialized_view(
+        &mut self,
+        q: CompiledRefreshMaterializedViewQuery,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        let old_table = self.schema.tables[q.table_id].clone();
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+            self.lock_table_if_needed(&old_table.name)?;
+        }
+
+        self.run_atomically(|executor| {
+            let (table, free_list_encoded) = executor.materialize(&old_table, &q.plan)?;
+
+            if old_table.fields.iter().any(|f| f.field_type.is_payload_backed()) {
+                let mut source = executor
+                    .create_scan_source(q.table_id, SqlConditionOpCode::SelectFTS, None)
+                    .map_err(QueryResult::err)?;
+                source.reset().map_err(QueryResult::err)?;
+                while let Some(row) = source.next().map_err(QueryResult::err)? {
+                    executor
+                        .release_payload_fields(&row, &old_table)
+                        .map_err(QueryResult::err)?;
+                }
+            }
+            executor.release_table_pages(&old_table)?;
+
+            let update_master_row = format!(
+                "UPDATE {} SET rootpage = {}, free_list = '{}' WHERE name = '{}'",
+                MASTER_TABLE_NAME,
+                table.root.page(),
+                free_list_encoded.replace("'", "''"),
+                table.name.replace("'", "''")
+            );
+            executor.run_query_internal(&update_master_row, true)?;
+            let result = executor.reload_schema()?;
+            let table_id = Planner::find_table_id(&executor.schema, &table.name)?;
+            executor.rebuild_indices_for_table_id(table_id)?;
+            if !allow_modification_to_system_table {
+                executor.last_write_table_id = Some(table_id);
+            }
+            Ok(result)
+        })
+    }
+
+    /// Runs `plan` into a new B-tree for `table`, numbering the rows by their rowid. Payloads
+    /// are copied, the view owns its values. Returns the table with its new root and the
+    /// encoded free list.
+    fn materialize(
+        &self,
+        table: &TableSchema,
+        plan: &PlanNode,
+    ) -> Result<(TableSchema, String), QueryResult> {
+        let result_schema = plan.get_schema(&self.schema).map_err(QueryResult::err)?;
+        let rows = self
+            .exec_planned_tree(plan)
+            .and_then(|result| result.fetch())
+            .map_err(QueryResult::err)?;
+
+        let mut table = table.clone();
+        table.btree_order = self.btree_node_width;
+        let root_page =
+            PagerProxy::create_empty_node_on_new_page(&table, self.pager_accessor.clone())
+                .map_err(QueryResult::err)?
+                .position
+                .page();
+        table.root = Position::new(root_page, 0);
+        let page_capacity = table.max_nodes_per_page().map_err(QueryResult::err)?;
+        table.free_list = vec![(root_page, page_capacity.saturating_sub(1))];
+        let free_list_encoded = Self::encode_free_list_top_10(&table);
+
+        let mut btree = Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+            .map_err(QueryResult::err)?;
+        for (idx, result_row) in rows.iter().enumerate() {
+            let mut row = Serializer::parse_int(&(idx + 1).to_string())
+                .map_err(QueryResult::err)?
+                .to_vec();
+            for (field_idx, field) in result_schema.fields.iter().enumerate() {
+                let mut value = Serializer::get_field_on_row(result_row, field_idx, &result_schema)
+                    .map_err(QueryResult::err)?;
+                if field.field_type.is_payload_backed()
+                    && !Serializer::is_null(&value, &field.field_type).map_err(QueryResult::err)?
+                {
+                    let content =
+                        PageManager::read_payload_field(self.pager_accessor.clone(), &value)
+                            .map_err(QueryResult::err)?;
+                    value = PageManager::write_payload_field(
+                        self.pager_accessor.clone(),
+                        &content,
+                        root_page,
+                    )
+                    .map_err(QueryResult::err)?;
+                }
+                row.extend(value);
+            }
+            let (key, row) = Serializer::split_key_and_row(&row, &table).map_err(QueryResult::err)?;
+            btree.insert(key, row).map_err(QueryResult::err)?;
+        }
+        Ok((table, free_list_encoded))
+    }
+}
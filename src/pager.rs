@@ -1,8 +1,9 @@
 use crate::btree::BTreeNode;
 pub use crate::constants::{
-    BOOLEAN_SIZE, DATE_SIZE, INTEGER_SIZE, INTEGER_SIZE_WITHOUT_FLAG, NODE_METADATA_SIZE,
+    BOOLEAN_SIZE, DATE_SIZE, DECIMAL_MAX_PRECISION, INTEGER_SIZE, INTEGER_SIZE_WITHOUT_FLAG, NODE_METADATA_SIZE,
     NULL_SIZE, PAGE_SIZE, PAGE_SIZE_WITH_META, PAGES_START_AT, POSITION_SIZE, ROW_NAME_SIZE,
-    STRING_SIZE, TABLE_NAME_SIZE, TYPE_SIZE,
+    SEQUENCE_COUNTER_SIZE, STRING_SIZE, TABLE_NAME_SIZE, TIME_SIZE, TIMESTAMP_SIZE, TIMESTAMPTZ_SIZE, TYPE_SIZE,
+    UUID_SIZE, VARCHAR_BYTES_PER_CHAR,
 };
 use crate::crypto::generate_random_hash;
 use crate::debug::Status;
@@ -29,11 +30,22 @@ pub enum Type {
     Null, //TODO remove this. this is not a type. each type can be null
     Integer,
     String,
+    /// Up to n characters, `VARCHAR_BYTES_PER_CHAR * n + 1` bytes in the row
     Varchar(usize),
+    /// `Varchar(n)` of a table written before Varchar lengths counted characters: up to n
+    /// bytes of UTF-8, n + 1 in the row
+    VarcharBytes(usize),
+    Text,
+    Decimal(usize, usize),
     //Double, future feature
     Date,
+    Timestamp,
+    TimestampTz,
+    Time,
     Boolean,
-    //Blob    future feature, requires special treatment
+    Blob,
+    Uuid,
+    Json,
     //Character future feature (?)
 }
 
@@ -43,9 +55,17 @@ impl Debug for Type {
             Type::Null => write!(f, "Null"),
             Type::Integer => write!(f, "Integer"),
             Type::String => write!(f, "String"),
-            Type::Varchar(max) => write!(f, "Varchar({})", max),
+            Type::Varchar(max) | Type::VarcharBytes(max) => write!(f, "Varchar({})", max),
+            Type::Text => write!(f, "Text"),
+            Type::Decimal(precision, scale) => write!(f, "Decimal({},{})", precision, scale),
             Type::Date => write!(f, "Date"),
+            Type::Timestamp => write!(f, "Timestamp"),
+            Type::TimestampTz => write!(f, "TimestampTz"),
+            Type::Time => write!(f, "Time"),
             Type::Boolean => write!(f, "Boolean"),
+            Type::Blob => write!(f, "Blob"),
+            Type::Uuid => write!(f, "Uuid"),
+            Type::Json => write!(f, "Json"),
         }
         .expect("Wierd Error");
         Ok(())
@@ -58,11 +78,24 @@ impl Type {
             Type::Null => "Null".to_string(),
             Type::Integer => "Integer".to_string(),
             Type::String => "String".to_string(),
-            Type::Varchar(len) => format!("Varchar({})", len),
+            Type::Varchar(len) | Type::VarcharBytes(len) => format!("Varchar({})", len),
+            Type::Text => "Text".to_string(),
+            Type::Decimal(precision, scale) => format!("Decimal({},{})", precision, scale),
             Type::Date => "Date".to_string(),
+            Type::Timestamp => "Timestamp".to_string(),
+            Type::TimestampTz => "TimestampTz".to_string(),
+            Type::Time => "Time".to_string(),
             Type::Boolean => "Boolean".to_string(),
+            Type::Blob => "Blob".to_string(),
+            Type::Uuid => "Uuid".to_string(),
+            Type::Json => "Json".to_string(),
         }
     }
+
+    /// Types whose content lives in payload pages; the row only holds a fixed-size descriptor.
+    pub fn is_payload_backed(&self) -> bool {
+        matches!(self, Type::Blob | Type::Text | Type::Json)
+    }
 }
 
 //represents a whole page except the position i.e. keys, child-position and data
@@ -137,6 +170,8 @@ pub type TransactionId = u64;
 #[derive(Clone, Debug)]
 struct TransactionState {
     page_overrides: HashMap<usize, PageContainer>,
+    /// pages the transaction appended to the file
+    created_pages: Vec<usize>,
     locked_tables: HashSet<String>,
     active: bool,
 }
@@ -312,6 +347,7 @@ impl PagerCore {
                 tx_id,
                 Arc::new(RwLock::new(TransactionState {
                     page_overrides: HashMap::new(),
+                    created_pages: Vec::new(),
                     locked_tables: HashSet::new(),
                     active: true,
                 })),
@@ -432,7 +468,7 @@ impl PagerCore {
             .cloned()
             .ok_or(ExceptionNoActiveTransaction)?;
 
-        // Lock order: tx -> table_locks -> current_transaction_ids
+        // Lock order: tx -> cache -> table_locks -> current_transaction_ids
         let mut tx = tx_handle
             .write()
             .map_err(|_| Status::InternalExceptionPagerWriteLock)?;
@@ -440,6 +476,27 @@ impl PagerCore {
             return Err(ExceptionNoActiveTransaction);
         }
 
+        // the page indices stay taken, so the pages are kept as deleted pages: every index
+        // below the next one must be readable
+        let created_pages = std::mem::take(&mut tx.created_pages);
+        if !created_pages.is_empty() {
+            let mut cache = self
+                .cache
+                .write()
+                .map_err(|_| Status::InternalExceptionPagerWriteLock)?;
+            for page_idx in created_pages {
+                let mut page_container = PageContainer {
+                    data: [0; PAGE_SIZE],
+                    position: Position::new(page_idx, 0),
+                    free_space: PAGE_SIZE,
+                    flag: 0,
+                };
+                Serializer::set_is_deleted(&mut page_container, true)?;
+                Serializer::write_byte_at_position(&mut page_container.flag, 0, true);
+                cache.insert(page_idx, page_container);
+            }
+        }
+
         let mut table_locks = self
             .table_locks
             .write()
@@ -726,7 +783,6 @@ impl PagerCore {
                     page_from_disk
                 };
 
-                Serializer::write_byte_at_position(&mut page.flag, 0, true);
                 tx.page_overrides.insert(position.page(), page);
             }
 
@@ -734,6 +790,8 @@ impl PagerCore {
                 .page_overrides
                 .get_mut(&position.page())
                 .ok_or(Status::InternalExceptionCacheDenied)?;
+            // pages created in the transaction start out clean as well
+            Serializer::write_byte_at_position(&mut page.flag, 0, true);
             return func(page);
         }
 
@@ -780,6 +838,7 @@ impl PagerCore {
                     return Err(ExceptionNoActiveTransaction);
                 }
                 tx.page_overrides.insert(position.page(), page_container);
+                tx.created_pages.push(position.page());
             }
 
             return Ok(position.page());
@@ -799,6 +858,68 @@ impl PagerCore {
         Ok(position.page)
     }
 
+    /// Creates a page holding a sequence counter set to `start`. Sequences hand out values
+    /// outside of transactions, so the page goes to the committed pages right away.
+    pub fn create_sequence_page(&self, start: i64) -> Result<usize, Status> {
+        let page_index = self.next_page_index.fetch_add(1, Ordering::SeqCst);
+        let mut page_container = PageContainer {
+            data: [0; PAGE_SIZE],
+            position: Position::new(page_index, 0),
+            free_space: PAGE_SIZE - SEQUENCE_COUNTER_SIZE,
+            flag: 0,
+        };
+        page_container.data[..SEQUENCE_COUNTER_SIZE].copy_from_slice(&start.to_be_bytes());
+        Serializer::write_byte_at_position(&mut page_container.flag, 0, true);
+        self.cache
+            .write()
+            .map_err(|_| Status::InternalExceptionPagerWriteLock)?
+            .insert(page_index, page_container);
+        Ok(page_index)
+    }
+
+    /// Returns the counter on sequence page `page` and advances it by `increment`. Holding the
+    /// cache lock makes this atomic for every connection sharing the pager, and no rollback
+    /// takes a value back.
+    pub fn next_sequence_value(&self, page: usize, increment: i64) -> Result<i64, Status> {
+        self.update_sequence_counter(page, |counter| counter.saturating_add(increment))
+    }
+
+    /// Moves the counter on sequence page `page` past `value`, a value stored without drawing
+    /// it, so the sequence does not hand it out later. Counters already past it stay.
+    pub fn advance_sequence_past(&self, page: usize, value: i64, increment: i64) -> Result<(), Status> {
+        self.update_sequence_counter(page, |counter| {
+            let passed = if increment > 0 { counter > value } else { counter < value };
+            if passed { counter } else { value.saturating_add(increment) }
+        })?;
+        Ok(())
+    }
+
+    /// Replaces the counter on sequence page `page` by `update(counter)` under the cache lock
+    /// and returns the old counter.
+    fn update_sequence_counter(
+        &self,
+        page: usize,
+        update: impl FnOnce(i64) -> i64,
+    ) -> Result<i64, Status> {
+        let mut cache = self
+            .cache
+            .write()
+            .map_err(|_| Status::InternalExceptionPagerWriteLock)?;
+        if !cache.contains_key(&page) {
+            let page_container = self.read_page_from_disk(&Position::new(page, 0))?;
+            cache.insert(page, page_container);
+        }
+        let page_container = cache
+            .get_mut(&page)
+            .ok_or(Status::InternalExceptionCacheDenied)?;
+        let mut counter = [0u8; SEQUENCE_COUNTER_SIZE];
+        counter.copy_from_slice(&page_container.data[..SEQUENCE_COUNTER_SIZE]);
+        let value = i64::from_be_bytes(counter);
+        page_container.data[..SEQUENCE_COUNTER_SIZE].copy_from_slice(&update(value).to_be_bytes());
+        Serializer::write_byte_at_position(&mut page_container.flag, 0, true);
+        Ok(value)
+    }
+
     #[deprecated] //this is not wrong, I just don't see any use for this !?
     fn insert_page_at_position(&self, position: &Position, page_data: PageData) -> Status {
         let page = PageContainer {
@@ -73,21 +73,10 @@ impl PageManager {
             return Ok(());
         }
 
-        let mut current_page = start.page();
-        let mut guard = 0usize;
-        while current_page > 0 {
-            if guard > 1024 {
-                return Err(Status::InternalExceptionPageCorrupted);
-            }
-            guard += 1;
-
-            let pos = Position::new(current_page, 0);
-            let page_read = pager_interface.access_pager_write(|p| p.access_page_read(&pos))?;
-            if !Self::is_payload_page_candidate(&page_read)? {
-                break;
+        Self::walk_payload_chain(&pager_interface, start.page(), |pos, page_read| {
+            if !Self::is_payload_page_candidate(page_read)? {
+                return Ok(None);
             }
-
-            let next_page = Serializer::get_payload_next_page_index(&page_read.data);
             pager_interface.access_pager_write(|p| {
                 p.with_page_write(&pos, |page| {
                     Serializer::set_payload_page_deprecated(&mut page.data, true);
@@ -95,9 +84,34 @@ impl PageManager {
                     Ok(())
                 })
             })?;
-            current_page = next_page;
-        }
+            Ok(Some(Serializer::get_payload_next_page_index(&page_read.data)))
+        })
+    }
+
+    /// Reads the pages of the payload chain starting at `start_page` and hands each to `visit`,
+    /// which returns the page to follow next, or `None` to stop. A chain cannot be longer than
+    /// the file; anything beyond that is a cycle.
+    fn walk_payload_chain(
+        pager_interface: &PagerAccessor,
+        start_page: usize,
+        mut visit: impl FnMut(Position, &PageContainer) -> Result<Option<usize>, Status>,
+    ) -> Result<(), Status> {
+        let max_chain_len = pager_interface.get_next_page_index();
+        let mut current_page = start_page;
+        let mut guard = 0usize;
+        while current_page > 0 {
+            if guard > max_chain_len {
+                return Err(Status::InternalExceptionPageCorrupted);
+            }
+            guard += 1;
 
+            let pos = Position::new(current_page, 0);
+            let page = pager_interface.access_pager_write(|p| p.access_page_read(&pos))?;
+            match visit(pos, &page)? {
+                Some(next_page) => current_page = next_page,
+                None => break,
+            }
+        }
         Ok(())
     }
 
@@ -156,7 +170,7 @@ impl PageManager {
     }
 
     fn is_field_externalized(field_type: &Type, field_bytes: &[u8]) -> Result<bool, Status> {
-        if !matches!(field_type, Type::String | Type::Varchar(_)) {
+        if !matches!(field_type, Type::String | Type::Varchar(_) | Type::VarcharBytes(_)) {
             return Ok(false);
         }
         if !Self::can_externalize_field(field_bytes.len()) {
@@ -241,17 +255,7 @@ impl PageManager {
         }
 
         let mut payload = Vec::new();
-        let mut current_page = start.page();
-        let mut guard = 0usize;
-
-        while current_page > 0 {
-            if guard > 1024 {
-                return Err(Status::InternalExceptionPageCorrupted);
-            }
-            guard += 1;
-
-            let pos = Position::new(current_page, 0);
-            let page = pager_interface.access_pager_write(|p| p.access_page_read(&pos))?;
+        Self::walk_payload_chain(&pager_interface, start.page(), |_, page| {
             let is_new_header = Serializer::has_payload_magic(&page.data);
             let header_size = if is_new_header {
                 PAYLOAD_HEADER_SIZE
@@ -271,41 +275,65 @@ impl PageManager {
             let chunk_end = chunk_start + chunk_len;
             payload.extend_from_slice(&page.data[chunk_start..chunk_end]);
 
-            current_page = Serializer::get_payload_next_page_index(&page.data);
-        }
+            Ok(Some(Serializer::get_payload_next_page_index(&page.data)))
+        })?;
 
         Ok(payload)
     }
 
+    /// Stores `content` as a payload-backed field. The inline prefix stays in the row, the
+    /// rest goes to a payload chain owned by `owner_root_page`. Returns the in-row descriptor.
+    pub fn write_payload_field(
+        pager_interface: PagerAccessor,
+        content: &[u8],
+        owner_root_page: usize,
+    ) -> Result<Vec<u8>, Status> {
+        let tail = content.get(INLINE_STRING_PREFIX_LEN..).unwrap_or(&[]);
+        let pointer = Self::write_payload_to_data_pages(pager_interface, tail, owner_root_page)?;
+        Ok(Serializer::payload_field_to_bytes(content, pointer))
+    }
+
+    pub fn read_payload_field(
+        pager_interface: PagerAccessor,
+        field: &[u8],
+    ) -> Result<Vec<u8>, Status> {
+        let len = Serializer::get_payload_field_len(field);
+        let mut content = Serializer::get_payload_field_inline(field).to_vec();
+        if len > content.len() {
+            let pointer = Serializer::get_payload_field_pointer(field);
+            content.extend(Self::read_payload_from_pages(pager_interface, pointer)?);
+        }
+        if content.len() != len {
+            return Err(Status::InternalExceptionPageCorrupted);
+        }
+        Ok(content)
+    }
+
+    /// Marks the payload chain of a payload-backed field as deprecated, so that later
+    /// writes for the same table can reuse its pages.
+    pub fn release_payload_field(pager_interface: PagerAccessor, field: &[u8]) -> Result<(), Status> {
+        Self::mark_payload_chain_deprecated(
+            pager_interface,
+            Serializer::get_payload_field_pointer(field),
+        )
+    }
+
+    /// Marks the payload chain starting at `start` as deprecated, like `release_payload_field`.
+    pub fn release_payload_chain(pager_interface: PagerAccessor, start: Position) -> Result<(), Status> {
+        Self::mark_payload_chain_deprecated(pager_interface, start)
+    }
+
     fn collect_payload_chain_pages_from_head(
         pager_interface: &PagerAccessor,
         start_page: usize,
         referenced_pages: &mut HashSet<usize>,
     ) -> Result<(), Status> {
-        let mut current_page = start_page;
-        let mut guard = 0usize;
-
-        while current_page > 0 {
-            if guard > 1024 {
-                return Err(Status::InternalExceptionPageCorrupted);
-            }
-            guard += 1;
-
-            if !referenced_pages.insert(current_page) {
-                break;
+        Self::walk_payload_chain(pager_interface, start_page, |pos, page| {
+            if !referenced_pages.insert(pos.page()) || !Self::is_payload_page_candidate(page)? {
+                return Ok(None);
             }
-
-            let pos = Position::new(current_page, 0);
-            let page = pager_interface.access_pager_write(|p| p.access_page_read(&pos))?;
-
-            if !Self::is_payload_page_candidate(&page)? {
-                break;
-            }
-
-            current_page = Serializer::get_payload_next_page_index(&page.data);
-        }
-
-        Ok(())
+            Ok(Some(Serializer::get_payload_next_page_index(&page.data)))
+        })
     }
 
     /// Marks payload pages (data/overflow) that are not reachable from the given
@@ -363,7 +391,7 @@ impl PageManager {
             row,
             &node.table_schema,
             |_, field_type, field_bytes| {
-                if !matches!(field_type, Type::String | Type::Varchar(_)) {
+                if !matches!(field_type, Type::String | Type::Varchar(_) | Type::VarcharBytes(_)) {
                     return Ok(field_bytes.to_vec());
                 }
 
@@ -411,7 +439,7 @@ impl PageManager {
             row,
             &node.table_schema,
             |_, field_type, field_bytes| {
-                if !matches!(field_type, Type::String | Type::Varchar(_)) {
+                if !matches!(field_type, Type::String | Type::Varchar(_) | Type::VarcharBytes(_)) {
                     return Ok(field_bytes.to_vec());
                 }
 
@@ -509,7 +537,7 @@ impl PageManager {
         let has_varchar = schema
             .fields
             .iter()
-            .any(|f| matches!(f.field_type, Type::Varchar(_)));
+            .any(|f| matches!(f.field_type, Type::Varchar(_) | Type::VarcharBytes(_)));
         Ok(has_varchar && schema.get_node_size_in_bytes()? > PAGE_SIZE)
     }
 
@@ -53,13 +53,48 @@ pub struct ParsedJoin {
 pub struct ParsedInsertQuery {
     pub table_name: String,
     pub fields: Vec<String>,
-    pub values: Vec<String>,
+    /// `None` is the NULL keyword
+    pub values: Vec<Option<ParsedValue>>,
+    /// the tuples after the first of `VALUES (...), (...), ...`
+    pub additional_rows: Vec<Vec<Option<ParsedValue>>>,
+    pub on_conflict: Option<ParsedOnConflict>,
+    /// the columns of `RETURNING ...`; `*` stands for all
+    pub returning: Option<Vec<String>>,
+}
+
+/// `ON CONFLICT [(column, ...)] DO NOTHING` or `... DO UPDATE SET column = value, ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedOnConflict {
+    /// the conflict target; empty if there is none
+    pub columns: Vec<String>,
+    /// the assignments of DO UPDATE, `None` for DO NOTHING
+    pub update: Option<Vec<(String, ParsedConflictValue)>>,
+}
+
+/// A value assigned by `ON CONFLICT ... DO UPDATE SET`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedConflictValue {
+    /// a value, `None` is the NULL keyword
+    Value(Option<ParsedValue>),
+    /// `excluded.column`, the value the INSERT proposed for the column
+    Excluded(String),
+}
+
+/// `INSERT INTO name [(column, ...)] SELECT ...`
+#[derive(Debug)]
+pub struct ParsedInsertSelectQuery {
+    pub table_name: String,
+    /// empty if there is no column list
+    pub fields: Vec<String>,
+    pub query: ParsedQueryTreeNode,
 }
 
 #[derive(Debug, Clone)]
 pub struct ParsedSelectQuery {
     pub source: ParsedSource,
     pub result: Vec<String>, //Vec<(String, String)>, //table alias, field name
+    /// What to compute for each entry of `result`, which holds the column labels.
+    pub result_exprs: Vec<ParsedValueExpr>,
     pub conditions: Option<ParsedConditionExpr>,
 }
 
@@ -86,16 +121,45 @@ pub enum ParsedPredicateExpr {
         left: ParsedValueExpr,
         operator: String,
         right: ParsedValueExpr,
+        /// `COLLATE` after either operand; otherwise the column's collation applies
+        collation: Option<String>,
     },
     InSubquery {
         left: ParsedValueExpr,
         subquery: Box<ParsedQueryTreeNode>,
     },
+    /// `left IS NULL`, or `left IS NOT NULL` if negated
+    IsNull {
+        left: ParsedValueExpr,
+        negated: bool,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParsedValueExpr {
     Token(String),
+    /// `json_extract(column, '$.a.b')` or `column->>'key'`; `path` is always a JSON path.
+    JsonExtract { column: String, path: String },
+}
+
+/// The expression of a `GENERATED ALWAYS AS (...) STORED` column. Binary operators need
+/// spaces around them, like comparison operators.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedScalarExpr {
+    /// A column of the same row, including `col->>'key'` and `json_extract(col, 'path')`
+    Column(ParsedValueExpr),
+    /// A quoted literal or a number
+    Literal(String),
+    Function {
+        name: String,
+        args: Vec<ParsedScalarExpr>,
+    },
+    /// `+`, `-`, `*`, `/` or `||`
+    Binary {
+        operator: String,
+        left: Box<ParsedScalarExpr>,
+        right: Box<ParsedScalarExpr>,
+    },
 }
 
 #[derive(Debug)]
@@ -108,12 +172,182 @@ pub struct ParsedDropIndexQuery {
     pub index_name: String,
 }
 
+/// Zero-argument functions that may stand in for a literal in VALUES, SET and DEFAULT.
+pub const VALUE_FUNCTIONS: [&str; 1] = ["gen_random_uuid"];
+
+/// A value of VALUES, SET or DEFAULT; where a value may be NULL, the NULL keyword is `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedValue {
+    /// A quoted literal or a number, as written without quotes
+    Literal(String),
+    /// A call of one of the `VALUE_FUNCTIONS`, by its lowercase name
+    Call(String),
+    /// `nextval('sequence')`, by the sequence name
+    NextVal(String),
+}
+
+/// How an `AUTOINCREMENT` / `GENERATED ... AS IDENTITY` column treats explicit values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Identity {
+    /// `GENERATED ALWAYS AS IDENTITY`: values always come from the sequence
+    Always,
+    /// `GENERATED BY DEFAULT AS IDENTITY` and `AUTOINCREMENT`: the sequence fills omitted values
+    ByDefault,
+}
+
 #[derive(Debug)]
 pub struct ParsedCreateTableQuery {
     pub table_name: String,
     pub table_fields: Vec<String>,
     pub table_types: Vec<String>,
+    pub table_defaults: Vec<Option<ParsedValue>>,
+    /// `COLLATE` names by field position
+    pub table_collations: Vec<Option<String>>,
+    pub table_not_null: Vec<bool>,
+    /// `CHECK (...)` conditions by field position
+    pub table_checks: Vec<Option<ParsedConditionExpr>>,
+    /// `PRIMARY KEY` columns in key order; empty means the first column is the key
+    pub primary_key: Vec<String>,
+    pub table_unique: Vec<bool>,
+    /// Identity columns by field position
+    pub table_identity: Vec<Option<Identity>>,
+    /// `GENERATED ALWAYS AS (...) STORED` expressions by field position
+    pub table_generated: Vec<Option<ParsedScalarExpr>>,
+    pub foreign_keys: Vec<ParsedForeignKey>,
     pub if_not_exists: bool,
+    /// `CREATE TEMP TABLE`: the table is only seen by the executor that created it
+    pub temporary: bool,
+    /// `WITH ROWID` after the column list: the table is keyed by a hidden `rowid` column
+    pub with_rowid: bool,
+    /// Column definitions as written, by field position; ALTER TABLE rebuilds the statement
+    /// from them
+    pub column_definitions: Vec<String>,
+    /// Table-level `PRIMARY KEY (...)` and `FOREIGN KEY (...)` clauses as written
+    pub table_constraints: Vec<String>,
+}
+
+/// `CREATE TABLE name AS SELECT ...`; the columns are the query's result columns.
+#[derive(Debug)]
+pub struct ParsedCreateTableAsQuery {
+    pub table_name: String,
+    pub temporary: bool,
+    pub query: ParsedQueryTreeNode,
+}
+
+/// What an `ALTER TABLE` statement changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedAlterTableAction {
+    /// `ADD [COLUMN] <column definition>`; the definition is kept as written
+    AddColumn { column: String, definition: String },
+    /// `DROP [COLUMN] name`
+    DropColumn(String),
+    /// `RENAME [COLUMN] old TO new`
+    RenameColumn { from: String, to: String },
+    /// `ALTER [COLUMN] name [SET DATA] TYPE type`
+    AlterColumnType { column: String, column_type: String },
+}
+
+#[derive(Debug)]
+pub struct ParsedAlterTableQuery {
+    pub table_name: String,
+    pub action: ParsedAlterTableAction,
+}
+
+/// `CREATE SEQUENCE name [START [WITH] n] [INCREMENT [BY] n]`
+#[derive(Debug)]
+pub struct ParsedCreateSequenceQuery {
+    pub sequence_name: String,
+    pub start: i64,
+    pub increment: i64,
+    pub if_not_exists: bool,
+}
+
+#[derive(Debug)]
+pub struct ParsedDropSequenceQuery {
+    pub sequence_name: String,
+}
+
+/// `CREATE VIEW name AS SELECT ...` or
+/// `CREATE MATERIALIZED VIEW name [(column [type [COLLATE name]], ...)] AS SELECT ...`
+#[derive(Debug)]
+pub struct ParsedCreateViewQuery {
+    pub view_name: String,
+    pub materialized: bool,
+    /// names the result columns of a materialized view; empty if there is no column list
+    pub columns: Vec<ParsedViewColumn>,
+    pub query: ParsedQueryTreeNode,
+    /// the text of `query`
+    pub query_sql: String,
+}
+
+/// A column of a materialized view. A type or collation must match the query's result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedViewColumn {
+    pub name: String,
+    pub column_type: Option<String>,
+    pub collation: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ParsedDropViewQuery {
+    pub view_name: String,
+    pub materialized: bool,
+}
+
+#[derive(Debug)]
+pub struct ParsedRefreshMaterializedViewQuery {
+    pub view_name: String,
+}
+
+/// Whether a trigger runs before or after the row is written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerTiming {
+    Before,
+    After,
+}
+
+/// The statement kind that fires a trigger.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerEvent {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// `CREATE TRIGGER name BEFORE|AFTER INSERT|UPDATE|DELETE ON table FOR EACH ROW
+/// BEGIN statement; ... END`
+#[derive(Debug)]
+pub struct ParsedCreateTriggerQuery {
+    pub trigger_name: String,
+    pub timing: TriggerTiming,
+    pub event: TriggerEvent,
+    pub table_name: String,
+    /// The body as written; `NEW.column` and `OLD.column` are bound once per row
+    pub statements: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ParsedDropTriggerQuery {
+    pub trigger_name: String,
+}
+
+/// What happens to referencing rows when their parent row is deleted or its key changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForeignKeyAction {
+    Restrict,
+    Cascade,
+    SetNull,
+}
+
+/// `col ... REFERENCES parent (parent_col)` or `FOREIGN KEY (col) REFERENCES parent (parent_col)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedForeignKey {
+    pub column: String,
+    pub parent_table: String,
+    /// `None` refers to the parent's primary key
+    pub parent_column: Option<String>,
+    pub on_delete: ForeignKeyAction,
+    pub on_update: ForeignKeyAction,
 }
 
 #[derive(Debug)]
@@ -121,32 +355,53 @@ pub struct ParsedCreateIndexQuery {
     pub index_name: String,
     pub table_name: String,
     pub columns: Vec<String>,
+    pub unique: bool,
 }
 
 #[derive(Debug)]
 pub struct ParsedDeleteQuery {
     pub table_name: String,
     pub conditions: Option<ParsedConditionExpr>,
+    pub returning: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
 pub struct ParsedUpdateQuery {
     pub table_name: String,
-    pub assignments: Vec<(String, String)>,
+    /// (field, value); a `None` value is the NULL keyword
+    pub assignments: Vec<(String, Option<ParsedValue>)>,
     pub conditions: Option<ParsedConditionExpr>,
+    pub returning: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
 pub enum ParsedQuery {
     CreateTable(ParsedCreateTableQuery),
+    CreateTableAs(ParsedCreateTableAsQuery),
     CreateIndex(ParsedCreateIndexQuery),
+    CreateSequence(ParsedCreateSequenceQuery),
+    CreateView(ParsedCreateViewQuery),
+    CreateTrigger(ParsedCreateTriggerQuery),
     DropTable(ParsedDropQuery),
     DropIndex(ParsedDropIndexQuery),
+    DropSequence(ParsedDropSequenceQuery),
+    DropView(ParsedDropViewQuery),
+    DropTrigger(ParsedDropTriggerQuery),
+    RefreshMaterializedView(ParsedRefreshMaterializedViewQuery),
+    AlterTable(ParsedAlterTableQuery),
     Select(ParsedQueryTreeNode),
     Insert(ParsedInsertQuery),
+    InsertSelect(ParsedInsertSelectQuery),
     Delete(ParsedDeleteQuery),
     Update(ParsedUpdateQuery),
     Transaction(ParsedTransactionStatement),
+    Pragma(ParsedPragmaStatement),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedPragmaStatement {
+    /// `PRAGMA foreign_keys = ON | OFF`
+    ForeignKeys(bool),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -183,7 +438,7 @@ impl Lexer {
             return None;
         }
 
-        let current_char = self.input.chars().nth(self.position)?;
+        let current_char = self.char_at(self.position)?;
 
         if current_char == '(' || current_char == ')' || current_char == ',' {
             self.position += 1;
@@ -195,34 +450,45 @@ impl Lexer {
         }
 
         let start = self.position;
-        while self.position < self.input.len() {
-            let c = self.input.chars().nth(self.position).unwrap();
+        while let Some(c) = self.char_at(self.position) {
             if c.is_whitespace() || c == '(' || c == ')' || c == ',' {
                 break;
             }
-            self.position += 1;
+            self.position += c.len_utf8();
         }
 
         Some(self.input[start..self.position].to_string())
     }
 
+    /// Whether the next token is a quoted literal, which keeps `'NULL'` apart from `NULL`.
+    pub fn next_is_quoted(&self) -> bool {
+        self.input[self.position..].trim_start().starts_with('\'')
+    }
+
+    /// Reads a quoted literal; a doubled quote inside it stands for one quote.
     fn read_quoted_token(&mut self) -> Option<String> {
         self.position += 1;
-        let start = self.position;
+        let mut token = String::new();
 
-        while self.position < self.input.len() {
-            let c = self.input.chars().nth(self.position).unwrap();
+        while let Some(c) = self.char_at(self.position) {
+            self.position += c.len_utf8();
             if c == '\'' {
-                let token = &self.input[start..self.position];
+                if self.char_at(self.position) != Some('\'') {
+                    return Some(token);
+                }
                 self.position += 1;
-                return Some(token.to_string());
             }
-            self.position += 1;
+            token.push(c);
         }
 
         None
     }
 
+    // `position` is a byte offset, so this stays O(1) even for long literals
+    fn char_at(&self, position: usize) -> Option<char> {
+        self.input.get(position..)?.chars().next()
+    }
+
     fn skip_whitespace(&mut self) {
         while self.position < self.input.len()
             && self.input[self.position..=self.position]
@@ -253,6 +519,18 @@ impl Parser {
         match statement_type.to_uppercase().as_str() {
             "CREATE" => self.parse_create(),
             "DROP" => self.parse_drop(),
+            "ALTER" => self.parse_alter(),
+            "REFRESH" => {
+                self.expect_token("MATERIALIZED")?;
+                self.expect_token("VIEW")?;
+                let view_name = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected view name".to_string())?;
+                Ok(ParsedQuery::RefreshMaterializedView(
+                    ParsedRefreshMaterializedViewQuery { view_name },
+                ))
+            }
             "SELECT" => Ok(ParsedQuery::Select(self.parse_select(true)?)),
             "(" => {
                 self.expect_token("SELECT")?;
@@ -264,6 +542,7 @@ impl Parser {
             "BEGIN" => self.parse_begin_transaction(),
             "COMMIT" => self.parse_commit_transaction(),
             "ROLLBACK" => self.parse_rollback_transaction(),
+            "PRAGMA" => self.parse_pragma(),
             _ => Err(format!("Unknown statement type: {}", statement_type)),
         }
     }
@@ -293,6 +572,27 @@ impl Parser {
         ))
     }
 
+    fn parse_pragma(&mut self) -> Result<ParsedQuery, String> {
+        let name = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected pragma name".to_string())?;
+        if name.to_lowercase() != "foreign_keys" {
+            return Err(format!("Unknown pragma: {}", name));
+        }
+        self.expect_token("=")?;
+        let value = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected ON or OFF".to_string())?;
+        let enabled = match value.to_uppercase().as_str() {
+            "ON" | "TRUE" | "1" => true,
+            "OFF" | "FALSE" | "0" => false,
+            _ => return Err(format!("Expected ON or OFF, but found '{}'", value)),
+        };
+        Ok(ParsedQuery::Pragma(ParsedPragmaStatement::ForeignKeys(enabled)))
+    }
+
     fn parse_create(&mut self) -> Result<ParsedQuery, String> {
         let object_type = self
             .lexer
@@ -300,16 +600,31 @@ impl Parser {
             .ok_or_else(|| "Expected object type after CREATE".to_string())?;
 
         match object_type.to_uppercase().as_str() {
-            "TABLE" => self.parse_create_table(),
-            "INDEX" => self.parse_create_index(),
+            "TABLE" => self.parse_create_table(false),
+            "TEMP" | "TEMPORARY" => {
+                self.expect_token("TABLE")?;
+                self.parse_create_table(true)
+            }
+            "INDEX" => self.parse_create_index(false),
+            "UNIQUE" => {
+                self.expect_token("INDEX")?;
+                self.parse_create_index(true)
+            }
+            "SEQUENCE" => self.parse_create_sequence(),
+            "VIEW" => self.parse_create_view(false),
+            "MATERIALIZED" => {
+                self.expect_token("VIEW")?;
+                self.parse_create_view(true)
+            }
+            "TRIGGER" => self.parse_create_trigger(),
             _ => Err(format!(
-                "Expected 'TABLE' or 'INDEX', but found '{}'",
+                "Expected 'TABLE', 'TEMP TABLE', 'INDEX', 'UNIQUE INDEX', 'SEQUENCE', 'VIEW', 'MATERIALIZED VIEW' or 'TRIGGER', but found '{}'",
                 object_type
             )),
         }
     }
 
-    fn parse_create_table(&mut self) -> Result<ParsedQuery, String> {
+    fn parse_create_table(&mut self, temporary: bool) -> Result<ParsedQuery, String> {
         let mut if_not_exists = false;
         let table_name = match self.lexer.next_token() {
             Some(token) if token.to_uppercase() == "IF" => {
@@ -323,10 +638,30 @@ impl Parser {
             Some(token) => token,
             None => return Err("Expected table name or IF NOT EXISTS".to_string()),
         };
+        if self.peek_token().is_some_and(|t| t.eq_ignore_ascii_case("AS")) {
+            self.expect_token("AS")?;
+            return Ok(ParsedQuery::CreateTableAs(ParsedCreateTableAsQuery {
+                table_name,
+                temporary,
+                query: self.parse_nested_select()?,
+            }));
+        }
         self.expect_token("(")?;
         let mut fields = Vec::new();
         let mut types = Vec::new();
+        let mut defaults = Vec::new();
+        let mut collations = Vec::new();
+        let mut not_nulls = Vec::new();
+        let mut checks = Vec::new();
+        let mut uniques = Vec::new();
+        let mut identities = Vec::new();
+        let mut generated_columns = Vec::new();
+        let mut foreign_keys = Vec::new();
+        let mut primary_key: Vec<String> = Vec::new();
+        let mut column_definitions = Vec::new();
+        let mut table_constraints = Vec::new();
         loop {
+            let definition_start = self.lexer.position;
             let field_name = self
                 .lexer
                 .next_token()
@@ -336,6 +671,62 @@ impl Parser {
                 return Err("Invalid name: )".to_string());
             }
 
+            if field_name.to_uppercase() == "PRIMARY"
+                && self.peek_token().is_some_and(|t| t.to_uppercase() == "KEY")
+            {
+                if !primary_key.is_empty() {
+                    return Err("Only one PRIMARY KEY is allowed per table".to_string());
+                }
+                self.expect_token("KEY")?;
+                self.expect_token("(")?;
+                loop {
+                    let column = self
+                        .lexer
+                        .next_token()
+                        .ok_or_else(|| "Expected column name in PRIMARY KEY".to_string())?;
+                    if column == ")" || column == "," {
+                        return Err("Expected column name in PRIMARY KEY".to_string());
+                    }
+                    primary_key.push(column);
+                    match self.lexer.next_token().as_deref() {
+                        Some(",") => continue,
+                        Some(")") => break,
+                        _ => return Err("Expected ',' or ')' in PRIMARY KEY".to_string()),
+                    }
+                }
+                table_constraints.push(self.text_since(definition_start));
+                match self.lexer.next_token().as_deref() {
+                    Some(",") => continue,
+                    Some(")") => break,
+                    _ => return Err("Expected ',' or ')' in field definition".to_string()),
+                }
+            }
+
+            if field_name.to_uppercase() == "FOREIGN"
+                && self.peek_token().is_some_and(|t| t.to_uppercase() == "KEY")
+            {
+                self.expect_token("KEY")?;
+                self.expect_token("(")?;
+                let column = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected column name in FOREIGN KEY".to_string())?;
+                if column == ")" || column == "," {
+                    return Err("Expected column name in FOREIGN KEY".to_string());
+                }
+                if self.lexer.next_token().as_deref() != Some(")") {
+                    return Err("Only single-column FOREIGN KEYs are supported".to_string());
+                }
+                self.expect_token("REFERENCES")?;
+                foreign_keys.push(self.parse_references(column)?);
+                table_constraints.push(self.text_since(definition_start));
+                match self.lexer.next_token().as_deref() {
+                    Some(",") => continue,
+                    Some(")") => break,
+                    _ => return Err("Expected ',' or ')' in field definition".to_string()),
+                }
+            }
+
             let field_type = self.parse_column_type()?;
 
             fields.push(field_name);
@@ -346,6 +737,110 @@ impl Parser {
 
             types.push(field_type);
 
+            let mut default = None;
+            let mut collation = None;
+            let mut not_null = false;
+            let mut unique = false;
+            let mut identity = None;
+            let mut generated = None;
+            let mut check = None;
+            loop {
+                match self.peek_token() {
+                    Some(token) if token.to_uppercase() == "DEFAULT" => {
+                        self.expect_token("DEFAULT")?;
+                        // DEFAULT NULL is the same as having no default
+                        default = self.parse_value_token("Expected default value")?;
+                    }
+                    Some(token) if token.to_uppercase() == "COLLATE" => {
+                        collation = Some(self.parse_collate()?);
+                    }
+                    Some(token) if token.to_uppercase() == "NOT" => {
+                        self.expect_token("NOT")?;
+                        self.expect_token("NULL")?;
+                        not_null = true;
+                    }
+                    Some(token) if token.to_uppercase() == "UNIQUE" => {
+                        self.expect_token("UNIQUE")?;
+                        unique = true;
+                    }
+                    Some(token) if token.to_uppercase() == "PRIMARY" => {
+                        if !primary_key.is_empty() {
+                            return Err("Only one PRIMARY KEY is allowed per table".to_string());
+                        }
+                        self.expect_token("PRIMARY")?;
+                        self.expect_token("KEY")?;
+                        primary_key.push(fields[fields.len() - 1].clone());
+                    }
+                    Some(token) if token.to_uppercase() == "REFERENCES" => {
+                        self.expect_token("REFERENCES")?;
+                        let column = fields[fields.len() - 1].clone();
+                        foreign_keys.push(self.parse_references(column)?);
+                    }
+                    Some(token) if token.to_uppercase() == "AUTOINCREMENT" => {
+                        self.expect_token("AUTOINCREMENT")?;
+                        identity = Some(Identity::ByDefault);
+                    }
+                    Some(token) if token.to_uppercase() == "GENERATED" => {
+                        self.expect_token("GENERATED")?;
+                        let kind = self
+                            .lexer
+                            .next_token()
+                            .ok_or_else(|| "Expected ALWAYS or BY DEFAULT".to_string())?;
+                        identity = match kind.to_uppercase().as_str() {
+                            "ALWAYS" => Some(Identity::Always),
+                            "BY" => {
+                                self.expect_token("DEFAULT")?;
+                                Some(Identity::ByDefault)
+                            }
+                            _ => {
+                                return Err(format!(
+                                    "Expected ALWAYS or BY DEFAULT, but found '{}'",
+                                    kind
+                                ));
+                            }
+                        };
+                        self.expect_token("AS")?;
+                        if identity == Some(Identity::Always)
+                            && self.peek_token().as_deref() == Some("(")
+                        {
+                            self.expect_token("(")?;
+                            generated = Some(self.parse_scalar_expr()?);
+                            self.expect_token(")")?;
+                            identity = None;
+                            match self.lexer.next_token() {
+                                Some(token) if token.eq_ignore_ascii_case("STORED") => {}
+                                Some(token) if token.eq_ignore_ascii_case("VIRTUAL") => {
+                                    return Err(
+                                        "Only STORED generated columns are supported".to_string()
+                                    );
+                                }
+                                _ => return Err("Expected STORED after the expression".to_string()),
+                            }
+                        } else {
+                            self.expect_token("IDENTITY")?;
+                        }
+                    }
+                    Some(token) if token.to_uppercase() == "CHECK" => {
+                        if check.is_some() {
+                            return Err("Only one CHECK constraint is allowed per column".to_string());
+                        }
+                        self.expect_token("CHECK")?;
+                        self.expect_token("(")?;
+                        check = Some(self.parse_condition_expr()?);
+                        self.expect_token(")")?;
+                    }
+                    _ => break,
+                }
+            }
+            defaults.push(default);
+            collations.push(collation);
+            not_nulls.push(not_null);
+            uniques.push(unique);
+            identities.push(identity);
+            generated_columns.push(generated);
+            checks.push(check);
+            column_definitions.push(self.text_since(definition_start));
+
             match self.lexer.next_token().as_deref() {
                 Some(",") => continue,
                 Some(")") => break,
@@ -353,15 +848,330 @@ impl Parser {
             }
         }
 
+        let with_rowid = self.peek_token().is_some_and(|t| t.to_uppercase() == "WITH");
+        if with_rowid {
+            self.expect_token("WITH")?;
+            self.expect_token("ROWID")?;
+        }
+
         Ok(ParsedQuery::CreateTable(ParsedCreateTableQuery {
             table_name,
             table_fields: fields,
             table_types: types,
+            table_defaults: defaults,
+            table_collations: collations,
+            table_not_null: not_nulls,
+            table_checks: checks,
+            primary_key,
+            table_unique: uniques,
+            table_identity: identities,
+            table_generated: generated_columns,
+            foreign_keys,
             if_not_exists,
+            temporary,
+            with_rowid,
+            column_definitions,
+            table_constraints,
         }))
     }
 
-    fn parse_create_index(&mut self) -> Result<ParsedQuery, String> {
+    fn parse_create_view(&mut self, materialized: bool) -> Result<ParsedQuery, String> {
+        let view_name = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected view name".to_string())?;
+        let mut columns = Vec::new();
+        if materialized && self.peek_token().as_deref() == Some("(") {
+            self.expect_token("(")?;
+            loop {
+                let name = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected column name".to_string())?;
+                let column_type = match self.peek_token().as_deref() {
+                    Some("," | ")") => None,
+                    _ => Some(self.parse_column_type()?),
+                };
+                let collation = self.parse_optional_collate()?;
+                columns.push(ParsedViewColumn {
+                    name,
+                    column_type,
+                    collation,
+                });
+                match self.lexer.next_token().as_deref() {
+                    Some(",") => continue,
+                    Some(")") => break,
+                    Some(token) => return Err(format!("Expected ',' or ')', but found '{}'", token)),
+                    None => return Err("Expected ')' after the column list".to_string()),
+                }
+            }
+        }
+        self.expect_token("AS")?;
+        let query_start = self.lexer.position;
+        let query = self.parse_nested_select()?;
+        Ok(ParsedQuery::CreateView(ParsedCreateViewQuery {
+            view_name,
+            materialized,
+            columns,
+            query,
+            query_sql: self.text_since(query_start),
+        }))
+    }
+
+    /// Reads the `SELECT ...` of a statement that takes a query, like `CREATE VIEW ... AS`.
+    fn parse_nested_select(&mut self) -> Result<ParsedQueryTreeNode, String> {
+        match self.lexer.next_token() {
+            Some(token) if token.to_uppercase() == "SELECT" => self.parse_select(true),
+            Some(token) if token == "(" => {
+                self.expect_token("SELECT")?;
+                self.parse_select(true)
+            }
+            Some(token) => Err(format!("Expected 'SELECT', but found '{}'", token)),
+            None => Err("Expected 'SELECT'".to_string()),
+        }
+    }
+
+    fn parse_create_trigger(&mut self) -> Result<ParsedQuery, String> {
+        let trigger_name = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected trigger name".to_string())?;
+        let timing = match self.lexer.next_token() {
+            Some(token) if token.eq_ignore_ascii_case("BEFORE") => TriggerTiming::Before,
+            Some(token) if token.eq_ignore_ascii_case("AFTER") => TriggerTiming::After,
+            Some(token) => return Err(format!("Expected 'BEFORE' or 'AFTER', but found '{}'", token)),
+            None => return Err("Expected 'BEFORE' or 'AFTER'".to_string()),
+        };
+        let event = match self.lexer.next_token() {
+            Some(token) if token.eq_ignore_ascii_case("INSERT") => TriggerEvent::Insert,
+            Some(token) if token.eq_ignore_ascii_case("UPDATE") => TriggerEvent::Update,
+            Some(token) if token.eq_ignore_ascii_case("DELETE") => TriggerEvent::Delete,
+            Some(token) => {
+                return Err(format!(
+                    "Expected 'INSERT', 'UPDATE' or 'DELETE', but found '{}'",
+                    token
+                ));
+            }
+            None => return Err("Expected 'INSERT', 'UPDATE' or 'DELETE'".to_string()),
+        };
+        self.expect_token("ON")?;
+        let table_name = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected table name".to_string())?;
+        self.expect_token("FOR")?;
+        self.expect_token("EACH")?;
+        self.expect_token("ROW")?;
+        self.expect_token("BEGIN")?;
+
+        // the body is split on semicolons outside of quoted literals, up to a final END
+        let body = &self.lexer.input[self.lexer.position..];
+        let mut statements = Vec::new();
+        let mut start = 0;
+        let mut quoted = false;
+        for (idx, c) in body.char_indices() {
+            if c == '\'' {
+                quoted = !quoted;
+            } else if c == ';' && !quoted {
+                statements.push(body[start..idx].trim().to_string());
+                start = idx + 1;
+            }
+        }
+        let rest = body[start..].trim();
+        if !rest.is_empty() {
+            statements.push(rest.to_string());
+        }
+        statements.retain(|statement| !statement.is_empty());
+        if !statements
+            .pop()
+            .is_some_and(|last| last.eq_ignore_ascii_case("END"))
+        {
+            return Err("Expected ';' and END after the trigger statements".to_string());
+        }
+        if statements.is_empty() {
+            return Err("Expected at least one statement between BEGIN and END".to_string());
+        }
+        for statement in &statements {
+            let bound = Self::bind_row_references(statement, &mut |_, _| Ok("''".to_string()))?;
+            match Parser::new(bound).parse_query()? {
+                ParsedQuery::Insert(_)
+                | ParsedQuery::InsertSelect(_)
+                | ParsedQuery::Update(_)
+                | ParsedQuery::Delete(_)
+                | ParsedQuery::Select(_) => {}
+                _ => {
+                    return Err(format!(
+                        "Triggers may only run INSERT, UPDATE, DELETE and SELECT, but found '{}'",
+                        statement
+                    ));
+                }
+            }
+        }
+        self.lexer.position = self.lexer.input.len();
+
+        Ok(ParsedQuery::CreateTrigger(ParsedCreateTriggerQuery {
+            trigger_name,
+            timing,
+            event,
+            table_name,
+            statements,
+        }))
+    }
+
+    /// Replaces the `NEW.column` and `OLD.column` references of a trigger statement with the
+    /// literal `literal` returns for the row (`NEW` or `OLD`) and column. Quoted literals keep
+    /// their text.
+    pub fn bind_row_references(
+        statement: &str,
+        literal: &mut dyn FnMut(&str, &str) -> Result<String, String>,
+    ) -> Result<String, String> {
+        let mut lexer = Lexer::new(statement.to_string());
+        let mut bound = String::new();
+        let mut copied = 0;
+        loop {
+            lexer.skip_whitespace();
+            let start = lexer.position;
+            let quoted = lexer.next_is_quoted();
+            let Some(token) = lexer.next_token() else {
+                break;
+            };
+            if quoted {
+                continue;
+            }
+            let Some((row, column)) = token.split_once('.') else {
+                continue;
+            };
+            let row = row.to_uppercase();
+            if row != "NEW" && row != "OLD" {
+                continue;
+            }
+            bound.push_str(&statement[copied..start]);
+            bound.push_str(&literal(&row, column)?);
+            copied = lexer.position;
+        }
+        bound.push_str(&statement[copied..]);
+        Ok(bound)
+    }
+
+    fn parse_create_sequence(&mut self) -> Result<ParsedQuery, String> {
+        let mut if_not_exists = false;
+        let sequence_name = match self.lexer.next_token() {
+            Some(token) if token.to_uppercase() == "IF" => {
+                self.expect_token("NOT")?;
+                self.expect_token("EXISTS")?;
+                if_not_exists = true;
+                self.lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected sequence name".to_string())?
+            }
+            Some(token) => token,
+            None => return Err("Expected sequence name or IF NOT EXISTS".to_string()),
+        };
+
+        let mut start = 1;
+        let mut increment = 1;
+        while let Some(option) = self.lexer.next_token() {
+            let (target, filler) = match option.to_uppercase().as_str() {
+                "START" => (&mut start, "WITH"),
+                "INCREMENT" => (&mut increment, "BY"),
+                _ => {
+                    return Err(format!(
+                        "Expected START or INCREMENT, but found '{}'",
+                        option
+                    ));
+                }
+            };
+            let mut value = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| format!("Expected a number after {}", option))?;
+            if value.eq_ignore_ascii_case(filler) {
+                value = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| format!("Expected a number after {}", option))?;
+            }
+            *target = value
+                .parse::<i64>()
+                .map_err(|_| format!("'{}' is not a valid integer", value))?;
+        }
+
+        Ok(ParsedQuery::CreateSequence(ParsedCreateSequenceQuery {
+            sequence_name,
+            start,
+            increment,
+            if_not_exists,
+        }))
+    }
+
+    /// Parses what follows `REFERENCES`: the parent table, an optional parent column and the
+    /// `ON DELETE` / `ON UPDATE` actions, which default to RESTRICT.
+    fn parse_references(&mut self, column: String) -> Result<ParsedForeignKey, String> {
+        let parent_table = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected table name after REFERENCES".to_string())?;
+        let mut parent_column = None;
+        if self.peek_token().is_some_and(|t| t == "(") {
+            self.expect_token("(")?;
+            let name = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected column name after REFERENCES".to_string())?;
+            if name == ")" || name == "," {
+                return Err("Expected column name after REFERENCES".to_string());
+            }
+            if self.lexer.next_token().as_deref() != Some(")") {
+                return Err("Only single-column FOREIGN KEYs are supported".to_string());
+            }
+            parent_column = Some(name);
+        }
+
+        let mut on_delete = ForeignKeyAction::Restrict;
+        let mut on_update = ForeignKeyAction::Restrict;
+        while self.peek_token().is_some_and(|t| t.to_uppercase() == "ON") {
+            self.expect_token("ON")?;
+            let event = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected DELETE or UPDATE after ON".to_string())?;
+            let action = self.parse_foreign_key_action()?;
+            match event.to_uppercase().as_str() {
+                "DELETE" => on_delete = action,
+                "UPDATE" => on_update = action,
+                _ => return Err(format!("Expected DELETE or UPDATE after ON, but found '{}'", event)),
+            }
+        }
+
+        Ok(ParsedForeignKey {
+            column,
+            parent_table,
+            parent_column,
+            on_delete,
+            on_update,
+        })
+    }
+
+    fn parse_foreign_key_action(&mut self) -> Result<ForeignKeyAction, String> {
+        let action = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected CASCADE, SET NULL or RESTRICT".to_string())?;
+        match action.to_uppercase().as_str() {
+            "CASCADE" => Ok(ForeignKeyAction::Cascade),
+            "RESTRICT" => Ok(ForeignKeyAction::Restrict),
+            "SET" => {
+                self.expect_token("NULL")?;
+                Ok(ForeignKeyAction::SetNull)
+            }
+            _ => Err(format!(
+                "Expected CASCADE, SET NULL or RESTRICT, but found '{}'",
+                action
+            )),
+        }
+    }
+
+    fn parse_create_index(&mut self, unique: bool) -> Result<ParsedQuery, String> {
         let index_name = self
             .lexer
             .next_token()
@@ -402,6 +1212,7 @@ impl Parser {
             index_name,
             table_name,
             columns,
+            unique,
         }))
     }
 
@@ -429,7 +1240,10 @@ impl Parser {
                     break;
                 }
                 if token == "," {
-                    return Err("Unexpected ',' in type parameter".to_string());
+                    if params.is_empty() || matches!(self.peek_token().as_deref(), Some(")" | ",")) {
+                        return Err("Unexpected ',' in type parameter".to_string());
+                    }
+                    continue;
                 }
                 params.push(token);
             }
@@ -438,12 +1252,154 @@ impl Parser {
                 return Err("Expected type parameter".to_string());
             }
 
-            return Ok(format!("{}({})", base_type, params.join("")));
+            return Ok(format!("{}({})", base_type, params.join(",")));
         }
 
         Ok(base_type)
     }
 
+    fn parse_alter(&mut self) -> Result<ParsedQuery, String> {
+        self.expect_token("TABLE")?;
+        let table_name = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected table name".to_string())?;
+        let operation = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected ADD, DROP, RENAME or ALTER".to_string())?;
+        let operation = operation.to_uppercase();
+        if self.peek_token().is_some_and(|t| t.to_uppercase() == "COLUMN") {
+            self.expect_token("COLUMN")?;
+        }
+
+        let action = match operation.as_str() {
+            "ADD" => {
+                let definition = self.lexer.input[self.lexer.position..].trim().to_string();
+                self.lexer.position = self.lexer.input.len();
+                let mut parser =
+                    Parser::new(format!("CREATE TABLE {} ({})", table_name, definition));
+                let column = match parser.parse_query()? {
+                    ParsedQuery::CreateTable(create)
+                        if create.table_fields.len() == 1 && create.table_constraints.is_empty() =>
+                    {
+                        create.table_fields[0].clone()
+                    }
+                    _ => return Err("ADD COLUMN takes a single column definition".to_string()),
+                };
+                ParsedAlterTableAction::AddColumn { column, definition }
+            }
+            "DROP" => ParsedAlterTableAction::DropColumn(
+                self.lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected column name".to_string())?,
+            ),
+            "RENAME" => {
+                let from = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected column name".to_string())?;
+                if from.to_uppercase() == "TO" {
+                    return Err("Renaming tables is not supported".to_string());
+                }
+                self.expect_token("TO")?;
+                let to = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected new column name".to_string())?;
+                ParsedAlterTableAction::RenameColumn { from, to }
+            }
+            "ALTER" => {
+                let column = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected column name".to_string())?;
+                if self.peek_token().is_some_and(|t| t.to_uppercase() == "SET") {
+                    self.expect_token("SET")?;
+                    self.expect_token("DATA")?;
+                }
+                self.expect_token("TYPE")?;
+                let column_type = self.parse_column_type()?;
+                ParsedAlterTableAction::AlterColumnType {
+                    column,
+                    column_type,
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Expected ADD, DROP, RENAME or ALTER, but found '{}'",
+                    operation
+                ));
+            }
+        };
+
+        Ok(ParsedQuery::AlterTable(ParsedAlterTableQuery { table_name, action }))
+    }
+
+    /// Renames the column `from` of `table_name` to `to` in one of the table's column
+    /// definitions or constraints. Quoted literals and the columns that a REFERENCES clause
+    /// names in another table keep their text.
+    pub fn rename_column_in(text: &str, table_name: &str, from: &str, to: &str) -> String {
+        let mut lexer = Lexer::new(text.to_string());
+        let mut renamed = String::new();
+        let mut copied = 0;
+        let mut after_references = false;
+        let mut in_other_parent = false;
+        loop {
+            lexer.skip_whitespace();
+            let start = lexer.position;
+            let quoted = lexer.next_is_quoted();
+            let Some(token) = lexer.next_token() else {
+                break;
+            };
+            if quoted {
+                continue;
+            }
+            if after_references {
+                after_references = false;
+                in_other_parent = token != table_name
+                    && lexer.clone().next_token().as_deref() == Some("(");
+                continue;
+            }
+            if in_other_parent {
+                in_other_parent = token != ")";
+                continue;
+            }
+            if token.to_uppercase() == "REFERENCES" {
+                after_references = true;
+                continue;
+            }
+            // `col->>'key'` is read as one token
+            let is_column = token == from
+                || token
+                    .strip_prefix(from)
+                    .is_some_and(|rest| rest.starts_with("->"));
+            if is_column {
+                renamed.push_str(&text[copied..start]);
+                renamed.push_str(to);
+                copied = start + from.len();
+            }
+        }
+        renamed.push_str(&text[copied..]);
+        renamed
+    }
+
+    /// Replaces the type of a column definition, keeping its name and constraints.
+    pub fn with_column_type(definition: &str, column_type: &str) -> Result<String, String> {
+        let mut parser = Parser::new(definition.to_string());
+        let column = parser
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected field name".to_string())?;
+        parser.parse_column_type()?;
+        Ok(format!(
+            "{} {}{}",
+            column,
+            column_type,
+            &definition[parser.lexer.position..]
+        ))
+    }
+
     fn parse_drop(&mut self) -> Result<ParsedQuery, String> {
         let object_type = self
             .lexer
@@ -465,8 +1421,36 @@ impl Parser {
                     .ok_or_else(|| "Expected index name".to_string())?;
                 Ok(ParsedQuery::DropIndex(ParsedDropIndexQuery { index_name }))
             }
+            "SEQUENCE" => {
+                let sequence_name = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected sequence name".to_string())?;
+                Ok(ParsedQuery::DropSequence(ParsedDropSequenceQuery { sequence_name }))
+            }
+            "VIEW" | "MATERIALIZED" => {
+                let materialized = object_type.eq_ignore_ascii_case("MATERIALIZED");
+                if materialized {
+                    self.expect_token("VIEW")?;
+                }
+                let view_name = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected view name".to_string())?;
+                Ok(ParsedQuery::DropView(ParsedDropViewQuery {
+                    view_name,
+                    materialized,
+                }))
+            }
+            "TRIGGER" => {
+                let trigger_name = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected trigger name".to_string())?;
+                Ok(ParsedQuery::DropTrigger(ParsedDropTriggerQuery { trigger_name }))
+            }
             _ => Err(format!(
-                "Expected 'TABLE' or 'INDEX', but found '{}'",
+                "Expected 'TABLE', 'INDEX', 'SEQUENCE', 'VIEW', 'MATERIALIZED VIEW' or 'TRIGGER', but found '{}'",
                 object_type
             )),
         }
@@ -477,6 +1461,7 @@ impl Parser {
         allow_setop_after_closing_paren: bool,
     ) -> Result<ParsedQueryTreeNode, String> {
         let mut fields = Vec::new();
+        let mut exprs = Vec::new();
         loop {
             let token = self
                 .lexer
@@ -485,7 +1470,9 @@ impl Parser {
             if token.to_uppercase() == "FROM" {
                 break;
             }
-            fields.push(token);
+            let (label, expr) = self.parse_value_expr(token)?;
+            fields.push(label);
+            exprs.push(expr);
 
             let next_token = self.lexer.next_token();
             if let Some(",") = next_token.clone().as_deref() {
@@ -506,6 +1493,7 @@ impl Parser {
         let select_query = ParsedSelectQuery {
             source,
             result: fields,
+            result_exprs: exprs,
             conditions,
         };
 
@@ -599,39 +1587,141 @@ impl Parser {
             }
         }
 
+        if self
+            .peek_token()
+            .is_some_and(|t| t == "(" || t.eq_ignore_ascii_case("SELECT"))
+        {
+            return Ok(ParsedQuery::InsertSelect(ParsedInsertSelectQuery {
+                table_name,
+                fields,
+                query: self.parse_nested_select()?,
+            }));
+        }
+
         self.expect_token("VALUES")?;
-        self.expect_token("(")?;
-        let mut values = Vec::new();
+        let mut rows = Vec::new();
         loop {
-            let value = self
+            let values = self.parse_value_tuple()?;
+            if has_explicit_fields && fields.len() != values.len() {
+                return Err(format!(
+                    "Mismatched fields and values count: {} fields, {} values",
+                    fields.len(),
+                    values.len()
+                ));
+            }
+            rows.push(values);
+            if self.peek_token().as_deref() != Some(",") {
+                break;
+            }
+            self.expect_token(",")?;
+        }
+        let additional_rows = rows.split_off(1);
+        let on_conflict = if self.peek_token().is_some_and(|t| t.eq_ignore_ascii_case("ON")) {
+            self.expect_token("ON")?;
+            self.expect_token("CONFLICT")?;
+            Some(self.parse_on_conflict()?)
+        } else {
+            None
+        };
+        let returning = self.parse_returning()?;
+
+        Ok(ParsedQuery::Insert(ParsedInsertQuery {
+            table_name,
+            fields,
+            values: rows.remove(0),
+            additional_rows,
+            on_conflict,
+            returning,
+        }))
+    }
+
+    fn parse_on_conflict(&mut self) -> Result<ParsedOnConflict, String> {
+        let mut columns = Vec::new();
+        if self.peek_token().as_deref() == Some("(") {
+            self.expect_token("(")?;
+            loop {
+                let column = self
+                    .lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected column name in ON CONFLICT".to_string())?;
+                if column == ")" || column == "," {
+                    return Err("Expected column name in ON CONFLICT".to_string());
+                }
+                columns.push(column);
+                match self.lexer.next_token().as_deref() {
+                    Some(",") => continue,
+                    Some(")") => break,
+                    _ => return Err("Expected ',' or ')' in ON CONFLICT".to_string()),
+                }
+            }
+        }
+        self.expect_token("DO")?;
+        let action = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected NOTHING or UPDATE".to_string())?;
+        if action.eq_ignore_ascii_case("NOTHING") {
+            return Ok(ParsedOnConflict {
+                columns,
+                update: None,
+            });
+        }
+        if !action.eq_ignore_ascii_case("UPDATE") {
+            return Err(format!("Expected NOTHING or UPDATE, but found '{}'", action));
+        }
+        self.expect_token("SET")?;
+        let mut assignments = Vec::new();
+        loop {
+            let column = self
                 .lexer
                 .next_token()
-                .ok_or_else(|| "Expected value".to_string())?;
-            if value == ")" {
+                .ok_or_else(|| "Expected field name in SET clause".to_string())?;
+            self.expect_token("=")?;
+            let excluded = match self.peek_token() {
+                Some(token) if !self.lexer.next_is_quoted() => token
+                    .get(..9)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("excluded."))
+                    .map(|_| token[9..].to_string()),
+                _ => None,
+            };
+            let value = match excluded {
+                Some(excluded_column) => {
+                    self.lexer.next_token();
+                    ParsedConflictValue::Excluded(excluded_column)
+                }
+                None => ParsedConflictValue::Value(
+                    self.parse_value_token("Expected value in SET clause")?,
+                ),
+            };
+            assignments.push((column, value));
+            if self.peek_token().as_deref() != Some(",") {
+                break;
+            }
+            self.expect_token(",")?;
+        }
+        Ok(ParsedOnConflict {
+            columns,
+            update: Some(assignments),
+        })
+    }
+
+    /// Reads one `(value, ...)` of a VALUES list.
+    fn parse_value_tuple(&mut self) -> Result<Vec<Option<ParsedValue>>, String> {
+        self.expect_token("(")?;
+        let mut values = Vec::new();
+        loop {
+            let value = self.parse_value_token("Expected value")?;
+            if value == Some(ParsedValue::Literal(")".to_string())) {
                 return Err("Expected value".to_string());
             }
             values.push(value);
 
             match self.lexer.next_token().as_deref() {
                 Some(",") => continue,
-                Some(")") => break,
+                Some(")") => return Ok(values),
                 _ => return Err("Expected ',' or ')' after value".to_string()),
             }
         }
-
-        if has_explicit_fields && fields.len() != values.len() {
-            return Err(format!(
-                "Mismatched fields and values count: {} fields, {} values",
-                fields.len(),
-                values.len()
-            ));
-        }
-
-        Ok(ParsedQuery::Insert(ParsedInsertQuery {
-            table_name,
-            fields,
-            values,
-        }))
     }
 
     pub fn parse_delete(&mut self) -> Result<ParsedQuery, String> {
@@ -642,10 +1732,12 @@ impl Parser {
             .ok_or_else(|| "Expected table name".to_string())?;
 
         let conditions = self.parse_where_conditions()?;
+        let returning = self.parse_returning()?;
 
         Ok(ParsedQuery::Delete(ParsedDeleteQuery {
             table_name,
             conditions,
+            returning,
         }))
     }
 
@@ -663,10 +1755,7 @@ impl Parser {
                 .next_token()
                 .ok_or_else(|| "Expected field name in SET clause".to_string())?;
             self.expect_token("=")?;
-            let value = self
-                .lexer
-                .next_token()
-                .ok_or_else(|| "Expected value in SET clause".to_string())?;
+            let value = self.parse_value_token("Expected value in SET clause")?;
 
             assignments.push((field_name, value));
 
@@ -684,14 +1773,43 @@ impl Parser {
         }
 
         let conditions = self.parse_where_conditions()?;
+        let returning = self.parse_returning()?;
 
         Ok(ParsedQuery::Update(ParsedUpdateQuery {
             table_name,
             assignments,
             conditions,
+            returning,
         }))
     }
 
+    /// Reads an optional `RETURNING column, ...` or `RETURNING *` of a write statement.
+    fn parse_returning(&mut self) -> Result<Option<Vec<String>>, String> {
+        if !self
+            .peek_token()
+            .is_some_and(|t| t.eq_ignore_ascii_case("RETURNING"))
+        {
+            return Ok(None);
+        }
+        self.expect_token("RETURNING")?;
+        let mut columns = Vec::new();
+        loop {
+            let column = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected column name after RETURNING".to_string())?;
+            if column == "," || column == "(" || column == ")" {
+                return Err("Expected column name after RETURNING".to_string());
+            }
+            columns.push(column);
+            if self.peek_token().as_deref() != Some(",") {
+                break;
+            }
+            self.expect_token(",")?;
+        }
+        Ok(Some(columns))
+    }
+
     fn parse_where_conditions(&mut self) -> Result<Option<ParsedConditionExpr>, String> {
         if let Some(token) = self.peek_token() {
             if token.to_uppercase() == "WHERE" {
@@ -767,40 +1885,165 @@ impl Parser {
             }
         }
 
-        let left = self
+        let left_token = self
             .lexer
             .next_token()
             .ok_or_else(|| "Expected left-side expression in condition".to_string())?;
+        let (_, left) = self.parse_value_expr(left_token)?;
+        let mut collation = self.parse_optional_collate()?;
         let operator = self
             .lexer
             .next_token()
             .ok_or_else(|| "Expected operator in condition".to_string())?;
 
+        if collation.is_some() && matches!(operator.to_uppercase().as_str(), "IS" | "IN") {
+            return Err("COLLATE is only supported in comparisons".to_string());
+        }
+
+        if operator.to_uppercase() == "IS" {
+            let mut negated = false;
+            let mut next = self.lexer.next_token();
+            if next.as_deref().is_some_and(|t| t.eq_ignore_ascii_case("NOT")) {
+                negated = true;
+                next = self.lexer.next_token();
+            }
+            if !next.as_deref().is_some_and(|t| t.eq_ignore_ascii_case("NULL")) {
+                return Err("Expected NULL after IS".to_string());
+            }
+            return Ok(ParsedConditionExpr::Predicate(ParsedPredicateExpr::IsNull {
+                left,
+                negated,
+            }));
+        }
+
         if operator.to_uppercase() == "IN" {
             self.expect_token("(")?;
             self.expect_token("SELECT")?;
             let subquery = self.parse_select(false)?;
             return Ok(ParsedConditionExpr::Predicate(
                 ParsedPredicateExpr::InSubquery {
-                    left: ParsedValueExpr::Token(left),
+                    left,
                     subquery: Box::new(subquery),
                 },
             ));
         }
 
+        let right_is_quoted = self.lexer.next_is_quoted();
         let right = self
             .lexer
             .next_token()
             .ok_or_else(|| "Expected right-side expression in condition".to_string())?;
+        if !right_is_quoted && right.eq_ignore_ascii_case("NULL") {
+            // `x = NULL` is never true; the only useful test is IS [NOT] NULL
+            return Err("Use IS NULL or IS NOT NULL to compare with NULL".to_string());
+        }
+        if let Some(right_collation) = self.parse_optional_collate()? {
+            if collation.is_some() {
+                return Err("Only one COLLATE clause is allowed per comparison".to_string());
+            }
+            collation = Some(right_collation);
+        }
 
         Ok(ParsedConditionExpr::Predicate(
             ParsedPredicateExpr::Compare {
-                left: ParsedValueExpr::Token(left),
+                left,
                 operator,
                 right: ParsedValueExpr::Token(right),
+                collation,
             },
         ))
     }
+
+    /// Reads a generated column's expression: `||`, `+` and `-` bind looser than `*` and `/`.
+    fn parse_scalar_expr(&mut self) -> Result<ParsedScalarExpr, String> {
+        let mut left = self.parse_scalar_term()?;
+        while let Some(operator) = self
+            .peek_token()
+            .filter(|token| matches!(token.as_str(), "+" | "-" | "||"))
+        {
+            self.lexer.next_token();
+            let right = self.parse_scalar_term()?;
+            left = ParsedScalarExpr::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_scalar_term(&mut self) -> Result<ParsedScalarExpr, String> {
+        let mut left = self.parse_scalar_primary()?;
+        while let Some(operator) = self
+            .peek_token()
+            .filter(|token| matches!(token.as_str(), "*" | "/"))
+        {
+            self.lexer.next_token();
+            let right = self.parse_scalar_primary()?;
+            left = ParsedScalarExpr::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_scalar_primary(&mut self) -> Result<ParsedScalarExpr, String> {
+        let is_quoted = self.lexer.next_is_quoted();
+        let token = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| "Expected an expression".to_string())?;
+        if is_quoted {
+            return Ok(ParsedScalarExpr::Literal(token));
+        }
+        if token == "(" {
+            let inner = self.parse_scalar_expr()?;
+            self.expect_token(")")?;
+            return Ok(inner);
+        }
+        if matches!(token.as_str(), ")" | "," | "+" | "-" | "*" | "/" | "||") {
+            return Err(format!("Expected an expression, but found '{}'", token));
+        }
+        if token.parse::<f64>().is_ok() {
+            return Ok(ParsedScalarExpr::Literal(token));
+        }
+        if !token.eq_ignore_ascii_case("json_extract") && self.peek_token().as_deref() == Some("(")
+        {
+            self.expect_token("(")?;
+            let mut args = Vec::new();
+            loop {
+                args.push(self.parse_scalar_expr()?);
+                match self.lexer.next_token().as_deref() {
+                    Some(",") => continue,
+                    Some(")") => break,
+                    _ => return Err(format!("Expected ',' or ')' in {}()", token)),
+                }
+            }
+            return Ok(ParsedScalarExpr::Function {
+                name: token.to_lowercase(),
+                args,
+            });
+        }
+        let (_, column) = self.parse_value_expr(token)?;
+        Ok(ParsedScalarExpr::Column(column))
+    }
+
+    /// Reads `COLLATE name`, returning the name.
+    fn parse_collate(&mut self) -> Result<String, String> {
+        self.expect_token("COLLATE")?;
+        self.lexer
+            .next_token()
+            .ok_or_else(|| "Expected collation name after COLLATE".to_string())
+    }
+
+    fn parse_optional_collate(&mut self) -> Result<Option<String>, String> {
+        match self.peek_token() {
+            Some(token) if token.eq_ignore_ascii_case("COLLATE") => Ok(Some(self.parse_collate()?)),
+            _ => Ok(None),
+        }
+    }
     fn parse_source(&mut self) -> Result<ParsedSource, String> {
         let mut sources = vec![self.parse_single_source()?];
         let mut conditions = vec![];
@@ -905,7 +2148,107 @@ impl Parser {
         Ok(())
     }
 
+    /// Completes a column reference whose first token was already read, returning its label
+    /// and expression. Handles `json_extract(col, 'path')`, `col->>'key'` and `col ->> 'key'`.
+    fn parse_value_expr(&mut self, first: String) -> Result<(String, ParsedValueExpr), String> {
+        if first.eq_ignore_ascii_case("json_extract") && self.peek_token().as_deref() == Some("(") {
+            self.expect_token("(")?;
+            let column = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected column in json_extract".to_string())?;
+            self.expect_token(",")?;
+            let path = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected path in json_extract".to_string())?;
+            self.expect_token(")")?;
+            let label = format!("json_extract({}, '{}')", column, path);
+            return Ok((label, ParsedValueExpr::JsonExtract { column, path }));
+        }
+
+        let (column, key) = if let Some((column, key)) = first.split_once("->>") {
+            let key = if key.is_empty() {
+                self.lexer
+                    .next_token()
+                    .ok_or_else(|| "Expected key after '->>'".to_string())?
+            } else {
+                // an unquoted token keeps the quotes of a glued `'key'`
+                key.trim_matches('\'').to_string()
+            };
+            (column.to_string(), key)
+        } else if self.peek_token().as_deref() == Some("->>") {
+            self.expect_token("->>")?;
+            let key = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected key after '->>'".to_string())?;
+            (first, key)
+        } else {
+            return Ok((first.clone(), ParsedValueExpr::Token(first)));
+        };
+
+        if column.is_empty() {
+            return Err("Expected column before '->>'".to_string());
+        }
+        let label = format!("{}->>'{}'", column, key);
+        let path = if key.starts_with('$') {
+            key
+        } else if key.contains('"') {
+            return Err(format!("Unsupported JSON key: {}", key));
+        } else {
+            format!("$[\"{}\"]", key)
+        };
+        Ok((label, ParsedValueExpr::JsonExtract { column, path }))
+    }
+
+    /// Reads a literal, the NULL keyword (`None`), a call like `gen_random_uuid()` or
+    /// `nextval('seq')`.
+    fn parse_value_token(&mut self, missing: &str) -> Result<Option<ParsedValue>, String> {
+        let is_quoted = self.lexer.next_is_quoted();
+        let value = self
+            .lexer
+            .next_token()
+            .ok_or_else(|| missing.to_string())?;
+        if is_quoted {
+            return Ok(Some(ParsedValue::Literal(value)));
+        }
+        if value.eq_ignore_ascii_case("NULL") {
+            return Ok(None);
+        }
+        if self.peek_token().as_deref() != Some("(") {
+            return Ok(Some(ParsedValue::Literal(value)));
+        }
+
+        let name = value.to_lowercase();
+        if name == "nextval" {
+            self.expect_token("(")?;
+            if !self.lexer.next_is_quoted() {
+                return Err("nextval() takes a quoted sequence name".to_string());
+            }
+            let sequence = self
+                .lexer
+                .next_token()
+                .ok_or_else(|| "Expected sequence name".to_string())?;
+            self.expect_token(")")?;
+            return Ok(Some(ParsedValue::NextVal(sequence)));
+        }
+        if !VALUE_FUNCTIONS.contains(&name.as_str()) {
+            return Err(format!("Unknown function: {}", value));
+        }
+        self.expect_token("(")?;
+        self.expect_token(")")
+            .map_err(|_| format!("{}() takes no arguments", name))?;
+        Ok(Some(ParsedValue::Call(name)))
+    }
+
     //TODO reexamine if this is clean. potentially eliminate this
+    /// The input from byte offset `start` up to the current position, without surrounding
+    /// whitespace.
+    fn text_since(&self, start: usize) -> String {
+        self.lexer.input[start..self.lexer.position].trim().to_string()
+    }
+
     fn peek_token(&mut self) -> Option<String> {
         let mut lexer = self.lexer.clone();
         lexer.next_token()
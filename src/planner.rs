@@ -1,14 +1,22 @@
+use crate::collation::Collation;
 use crate::debug::Status;
 use crate::executor::{Field, QueryResult};
-use crate::pager::{Key, Position, Row, TableName, Type};
+use crate::json::{JsonPath, JsonValue};
+use crate::pager::{DECIMAL_MAX_PRECISION, Key, Position, Row, STRING_SIZE, TableName, Type};
 use crate::parser::{
-    JoinOp, JoinType, ParsedConditionExpr, ParsedCreateIndexQuery, ParsedCreateTableQuery,
-    ParsedDeleteQuery, ParsedDropIndexQuery, ParsedDropQuery, ParsedInsertQuery, ParsedJoin,
-    ParsedLogicalOp, ParsedPredicateExpr, ParsedQuery, ParsedQueryTreeNode, ParsedSelectQuery,
-    ParsedSetOperation, ParsedSetOperator, ParsedSource, ParsedTransactionStatement,
-    ParsedUpdateQuery, ParsedValueExpr,
+    ParsedScalarExpr, ForeignKeyAction, Identity, JoinOp, JoinType, ParsedAlterTableAction, ParsedAlterTableQuery,
+    ParsedConditionExpr, ParsedCreateIndexQuery,
+    ParsedCreateSequenceQuery, ParsedCreateTableAsQuery, ParsedCreateTableQuery, ParsedCreateTriggerQuery, ParsedCreateViewQuery, ParsedDeleteQuery, ParsedDropIndexQuery,
+    ParsedDropQuery, ParsedDropSequenceQuery, ParsedDropTriggerQuery, ParsedDropViewQuery, ParsedRefreshMaterializedViewQuery, ParsedViewColumn, ParsedForeignKey, ParsedConflictValue, ParsedValue, ParsedInsertQuery, ParsedInsertSelectQuery, ParsedOnConflict, ParsedJoin, ParsedLogicalOp, ParsedPragmaStatement,
+    ParsedPredicateExpr, ParsedQuery, ParsedQueryTreeNode, ParsedSelectQuery, ParsedSetOperation,
+    ParsedSetOperator, ParsedSource, ParsedTransactionStatement, ParsedUpdateQuery,
+    ParsedValueExpr, Parser, TriggerEvent,
+};
+use crate::executor::QueryExecutor;
+use crate::schema::{
+    ForeignKeyDefinition, IndexDefinition, ROWID_COLUMN, Schema, TABLE_TYPE_MATERIALIZED_VIEW,
+    TABLE_TYPE_TEMPORARY, TableSchema,
 };
-use crate::schema::{Schema, TableSchema};
 use crate::serializer::Serializer;
 use std::str::FromStr;
 
@@ -42,6 +50,63 @@ pub enum SqlStatementComparisonOperator {
 pub struct CompiledInsertQuery {
     pub table_id: usize,
     pub data: (Key, Row),
+    /// Content of payload-backed fields as (byte offset in the full row, content).
+    /// The row holds empty descriptors there until the executor writes the payload pages.
+    pub payloads: Vec<(usize, Vec<u8>)>,
+    /// (field index, sequence name) of `nextval()` values, drawn by the executor
+    pub sequence_values: Vec<(usize, String)>,
+    pub on_conflict: Option<CompiledOnConflict>,
+    /// field indices of the RETURNING columns
+    pub returning: Option<Vec<usize>>,
+}
+
+/// `ON CONFLICT` of an INSERT: what a conflicting row is and what to do with it.
+#[derive(Debug, Clone)]
+pub struct CompiledOnConflict {
+    /// `None` if there is no target; then a row conflicts on its key or any UNIQUE column
+    pub target: Option<ConflictTarget>,
+    /// (field index, value) to set on the existing row, `None` for DO NOTHING
+    pub update: Option<Vec<(usize, CompiledConflictValue)>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictTarget {
+    Key,
+    /// a column with a unique index
+    Column(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompiledConflictValue {
+    /// a value of the statement, `None` for NULL
+    Value(Option<ParsedValue>),
+    /// the field index of the value the INSERT proposed
+    Excluded(usize),
+}
+
+/// `INSERT ... VALUES (...), (...), ...`; every tuple is planned like a single-row insert.
+#[derive(Debug)]
+pub struct CompiledInsertBatchQuery {
+    pub table_id: usize,
+    pub rows: Vec<CompiledInsertQuery>,
+    pub returning: Option<Vec<usize>>,
+}
+
+/// A value an INSERT gives a column.
+#[derive(Debug)]
+pub enum InsertValue {
+    /// a value of VALUES or a column DEFAULT
+    Value(ParsedValue),
+    /// a value already encoded for the column's type, the content for payload-backed types
+    Encoded(Vec<u8>),
+}
+
+/// `INSERT ... SELECT`; result column `i` of `plan` goes to the column named `fields[i]`.
+#[derive(Debug)]
+pub struct CompiledInsertSelectQuery {
+    pub table_id: usize,
+    pub fields: Vec<String>,
+    pub plan: PlanNode,
 }
 
 #[derive(Debug, Clone)]
@@ -57,11 +122,23 @@ pub enum CompiledPredicateExpr {
         column_idx: usize,
         op: SqlStatementComparisonOperator,
         value: Vec<u8>,
+        collation: Collation,
     },
     InSubquery {
         column_idx: usize,
         strategy: CompiledInStrategy,
     },
+    /// Compares the text extracted from a JSON column; a missing path or JSON null is NULL.
+    JsonCompare {
+        column_idx: usize,
+        path: JsonPath,
+        op: SqlStatementComparisonOperator,
+        value: String,
+    },
+    IsNull {
+        column_idx: usize,
+        negated: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +151,64 @@ pub enum CompiledConditionExpr {
     Predicate(CompiledPredicateExpr),
 }
 
+/// The expression of a generated column, evaluated on the text of the row's values.
+#[derive(Debug, Clone)]
+pub enum CompiledScalarExpr {
+    Column(usize),
+    JsonExtract {
+        column_idx: usize,
+        path: JsonPath,
+    },
+    Literal(String),
+    Function {
+        function: ScalarFunction,
+        args: Vec<CompiledScalarExpr>,
+    },
+    Binary {
+        operator: ScalarOperator,
+        left: Box<CompiledScalarExpr>,
+        right: Box<CompiledScalarExpr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalarFunction {
+    Lower,
+    Upper,
+    Trim,
+    Length,
+    Abs,
+    /// The parts of a date, or of the date of a timestamp
+    Year,
+    Month,
+    Day,
+}
+
+impl ScalarFunction {
+    fn from_name(name: &str) -> Option<ScalarFunction> {
+        match name {
+            "lower" => Some(ScalarFunction::Lower),
+            "upper" => Some(ScalarFunction::Upper),
+            "trim" => Some(ScalarFunction::Trim),
+            "length" => Some(ScalarFunction::Length),
+            "abs" => Some(ScalarFunction::Abs),
+            "year" => Some(ScalarFunction::Year),
+            "month" => Some(ScalarFunction::Month),
+            "day" => Some(ScalarFunction::Day),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalarOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Concat,
+}
+
 #[derive(Debug, Clone)]
 pub enum CompiledInStrategy {
     Materialize(Box<PlanNode>),
@@ -113,6 +248,19 @@ pub enum PlanNode {
         left: Box<PlanNode>,
         right: Box<PlanNode>,
     },
+    /// Appends one STRING column per extraction to the rows of `source`.
+    JsonExtract {
+        source: Box<PlanNode>,
+        extractions: Vec<JsonExtraction>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonExtraction {
+    /// position of the JSON column in the source rows
+    pub column_idx: usize,
+    pub path: JsonPath,
+    pub output: Field,
 }
 
 impl PlanNode {
@@ -125,7 +273,12 @@ impl PlanNode {
                 ..
             } => Ok(global_schema.tables[*table_id].clone()),
             PlanNode::Project { fields, source, .. } => {
-                Ok(source.get_schema(global_schema)?.project(fields))
+                let mut schema = source.get_schema(global_schema)?.project(fields);
+                // a Project may relabel its columns, as a view does with its name
+                for (field, requested) in schema.fields.iter_mut().zip(fields) {
+                    field.table_name = requested.table_name.clone();
+                }
+                Ok(schema)
             }
             PlanNode::Join {
                 left,
@@ -145,6 +298,19 @@ impl PlanNode {
                 left.get_schema(global_schema)
             }
             PlanNode::Filter { source, .. } => source.get_schema(global_schema),
+            PlanNode::JsonExtract {
+                source,
+                extractions,
+            } => {
+                let mut schema = source.get_schema(global_schema)?;
+                schema
+                    .fields
+                    .extend(extractions.iter().map(|e| e.output.clone()));
+                schema.column_defaults = vec![];
+                schema.not_null = vec![];
+                schema.checks = vec![];
+                Ok(schema)
+            }
         }
     }
 
@@ -165,6 +331,7 @@ pub struct CompiledDeleteQuery {
     pub operation: SqlConditionOpCode,
     pub seek_key: Option<Vec<u8>>,
     pub condition: Option<CompiledConditionExpr>,
+    pub returning: Option<Vec<usize>>,
 }
 
 #[derive(Debug)]
@@ -173,13 +340,19 @@ pub struct CompiledUpdateQuery {
     pub operation: SqlConditionOpCode,
     pub seek_key: Option<Vec<u8>>,
     pub condition: Option<CompiledConditionExpr>,
-    pub assignments: Vec<(usize, Vec<u8>)>,
+    /// (field index, value); payload-backed fields carry their raw content, `None` sets NULL.
+    pub assignments: Vec<(usize, Option<Vec<u8>>)>,
+    pub returning: Option<Vec<usize>>,
 }
 
 #[derive(Debug)]
 pub struct CompiledCreateTableQuery {
     pub table_name: String,
     pub schema: TableSchema,
+    /// Columns with a `UNIQUE` constraint; each gets a unique index once the table exists
+    pub unique_columns: Vec<String>,
+    /// Checked against the parent tables by `resolve_foreign_key` when the table is created
+    pub foreign_keys: Vec<ParsedForeignKey>,
 }
 
 #[derive(Debug)]
@@ -188,7 +361,80 @@ pub struct CompiledCreateIndexQuery {
     pub table_id: usize,
     pub base_table_name: String,
     pub column_name: String,
+    pub unique: bool,
+    pub schema: TableSchema,
+}
+
+#[derive(Debug)]
+pub struct CompiledCreateSequenceQuery {
+    pub sequence_name: String,
+    pub start: i64,
+    pub increment: i64,
+    pub if_not_exists: bool,
+}
+
+#[derive(Debug)]
+pub struct CompiledDropSequenceQuery {
+    pub sequence_name: String,
+}
+
+/// `CREATE VIEW`; the executor stores the statement in the master table.
+#[derive(Debug)]
+pub struct CompiledCreateViewQuery {
+    pub view_name: String,
+}
+
+/// `CREATE MATERIALIZED VIEW`; the executor creates the table and fills it from `plan`.
+#[derive(Debug)]
+pub struct CompiledCreateMaterializedViewQuery {
     pub schema: TableSchema,
+    pub plan: PlanNode,
+    /// the statement stored in the master table, which lists every column with its type
+    pub sql: String,
+}
+
+/// `CREATE TABLE ... AS SELECT`; the executor runs `plan`, creates the table and inserts
+/// the result.
+#[derive(Debug)]
+pub struct CompiledCreateTableAsQuery {
+    pub table_name: String,
+    /// the statement that creates the table, which lists every column with its type
+    pub create_sql: String,
+    pub plan: PlanNode,
+}
+
+#[derive(Debug)]
+pub struct CompiledRefreshMaterializedViewQuery {
+    pub table_id: usize,
+    pub plan: PlanNode,
+}
+
+#[derive(Debug)]
+pub struct CompiledDropViewQuery {
+    pub view_name: String,
+}
+
+/// `CREATE TRIGGER`; the executor stores the statement in the master table.
+#[derive(Debug)]
+pub struct CompiledCreateTriggerQuery {
+    pub trigger_name: String,
+}
+
+#[derive(Debug)]
+pub struct CompiledDropTriggerQuery {
+    pub trigger_name: String,
+}
+
+/// `ALTER TABLE`; the executor rewrites the rows and the stored CREATE TABLE statement.
+#[derive(Debug)]
+pub struct CompiledAlterTableQuery {
+    pub table_id: usize,
+    pub action: ParsedAlterTableAction,
+    /// Index tables on the changed column, dropped before the rows are rewritten
+    pub dropped_indexes: Vec<String>,
+    /// Indexes created once the table is rewritten: the dropped ones under their new column
+    /// name or type, and the unique index of an added UNIQUE column
+    pub created_indexes: Vec<IndexDefinition>,
 }
 
 #[derive(Debug)]
@@ -204,14 +450,32 @@ pub struct CompiledDropIndexQuery {
 #[derive(Debug)]
 pub enum CompiledQuery {
     CreateTable(CompiledCreateTableQuery),
+    CreateTableAs(CompiledCreateTableAsQuery),
     CreateIndex(CompiledCreateIndexQuery),
+    CreateSequence(CompiledCreateSequenceQuery),
+    CreateView(CompiledCreateViewQuery),
+    CreateMaterializedView(CompiledCreateMaterializedViewQuery),
     DropTable(CompiledDropTableQuery),
     DropIndex(CompiledDropIndexQuery),
+    DropSequence(CompiledDropSequenceQuery),
+    DropView(CompiledDropViewQuery),
+    CreateTrigger(CompiledCreateTriggerQuery),
+    DropTrigger(CompiledDropTriggerQuery),
+    RefreshMaterializedView(CompiledRefreshMaterializedViewQuery),
+    AlterTable(CompiledAlterTableQuery),
     Select(CompiledSelectQuery),
     Insert(CompiledInsertQuery),
+    InsertBatch(CompiledInsertBatchQuery),
+    InsertSelect(CompiledInsertSelectQuery),
     Delete(CompiledDeleteQuery),
     Update(CompiledUpdateQuery),
     Transaction(CompiledTransactionStatement),
+    Pragma(CompiledPragmaStatement),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompiledPragmaStatement {
+    ForeignKeys(bool),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -229,10 +493,16 @@ impl Planner {
     pub fn plan(schema: &Schema, query: ParsedQuery) -> Result<CompiledQuery, QueryResult> {
         match query {
             ParsedQuery::Insert(insert_query) => Self::plan_insert_query(schema, insert_query),
+            ParsedQuery::InsertSelect(insert_query) => {
+                Self::plan_insert_select_query(schema, insert_query)
+            }
             ParsedQuery::Select(tree_node) => Self::plan_select_query(schema, tree_node),
             ParsedQuery::CreateTable(create_table_query) => {
                 Self::plan_create_table_query(create_table_query)
             }
+            ParsedQuery::CreateTableAs(create_table_query) => {
+                Self::plan_create_table_as_query(schema, create_table_query)
+            }
             ParsedQuery::CreateIndex(create_index_query) => {
                 Self::plan_create_index_query(schema, create_index_query)
             }
@@ -242,9 +512,38 @@ impl Planner {
             ParsedQuery::DropIndex(drop_index_query) => {
                 Self::plan_drop_index_query(schema, drop_index_query)
             }
+            ParsedQuery::CreateSequence(create_sequence_query) => {
+                Self::plan_create_sequence_query(create_sequence_query)
+            }
+            ParsedQuery::DropSequence(drop_sequence_query) => {
+                Self::plan_drop_sequence_query(schema, drop_sequence_query)
+            }
+            ParsedQuery::CreateView(create_view_query) => {
+                Self::plan_create_view_query(schema, create_view_query)
+            }
+            ParsedQuery::DropView(drop_view_query) => {
+                Self::plan_drop_view_query(schema, drop_view_query)
+            }
+            ParsedQuery::RefreshMaterializedView(refresh_query) => {
+                Self::plan_refresh_materialized_view_query(schema, refresh_query)
+            }
+            ParsedQuery::CreateTrigger(create_trigger_query) => {
+                Self::plan_create_trigger_query(schema, create_trigger_query)
+            }
+            ParsedQuery::DropTrigger(drop_trigger_query) => {
+                Self::plan_drop_trigger_query(schema, drop_trigger_query)
+            }
+            ParsedQuery::AlterTable(alter_table_query) => {
+                Self::plan_alter_table_query(schema, alter_table_query)
+            }
             ParsedQuery::Delete(delete_query) => Self::plan_delete_query(schema, delete_query),
             ParsedQuery::Update(update_query) => Self::plan_update_query(schema, update_query),
             ParsedQuery::Transaction(tx) => Self::plan_transaction_query(tx),
+            ParsedQuery::Pragma(pragma) => Ok(CompiledQuery::Pragma(match pragma {
+                ParsedPragmaStatement::ForeignKeys(enabled) => {
+                    CompiledPragmaStatement::ForeignKeys(enabled)
+                }
+            })),
         }
     }
 
@@ -253,45 +552,148 @@ impl Planner {
         mut insert_query: ParsedInsertQuery,
     ) -> Result<CompiledQuery, QueryResult> {
         let table_id = Self::find_table_id(schema, &insert_query.table_name)?;
-        let table_schema = &schema.tables[table_id];
+        Self::check_not_materialized(&schema.tables[table_id])?;
 
         if insert_query.fields.len() == 0 {
-            insert_query.fields = table_schema
-                .fields
-                .clone()
-                .into_iter()
-                .map(|f| f.name)
-                .collect();
+            insert_query.fields = Self::implicit_insert_columns(&schema.tables[table_id]);
+        }
+
+        let on_conflict = match &insert_query.on_conflict {
+            Some(on_conflict) => Some(Self::plan_on_conflict(&schema.tables[table_id], schema, on_conflict)?),
+            None => None,
+        };
+        let returning = Self::plan_returning(&schema.tables[table_id], &insert_query.returning)?;
+        let mut first =
+            Self::plan_insert_row(schema, table_id, &insert_query.fields, &insert_query.values)?;
+        first.on_conflict = on_conflict.clone();
+        if insert_query.additional_rows.is_empty() {
+            first.returning = returning;
+            return Ok(CompiledQuery::Insert(first));
         }
+        let mut rows = vec![first];
+        for values in &insert_query.additional_rows {
+            let mut row = Self::plan_insert_row(schema, table_id, &insert_query.fields, values)?;
+            row.on_conflict = on_conflict.clone();
+            rows.push(row);
+        }
+        Ok(CompiledQuery::InsertBatch(CompiledInsertBatchQuery {
+            table_id,
+            rows,
+            returning,
+        }))
+    }
+
+    /// Compiles one tuple of VALUES into a full row, filling defaults for the other columns.
+    fn plan_insert_row(
+        schema: &Schema,
+        table_id: usize,
+        fields: &[String],
+        values: &[Option<ParsedValue>],
+    ) -> Result<CompiledInsertQuery, QueryResult> {
+        let values = values
+            .iter()
+            .map(|value| value.clone().map(InsertValue::Value))
+            .collect();
+        Self::plan_insert_values(schema, table_id, fields, values)
+    }
 
-        if insert_query.fields.len() != insert_query.values.len() {
+    /// Compiles the values of one row into a full row, filling defaults for the other columns.
+    /// `None` is NULL.
+    pub(crate) fn plan_insert_values(
+        schema: &Schema,
+        table_id: usize,
+        fields: &[String],
+        mut values: Vec<Option<InsertValue>>,
+    ) -> Result<CompiledInsertQuery, QueryResult> {
+        let table_schema = &schema.tables[table_id];
+        if fields.len() != values.len() {
             return Err(QueryResult::user_input_wrong(format!(
                 "Column count doesn't match value count: {} columns vs {} values",
-                insert_query.fields.len(),
-                insert_query.values.len()
+                fields.len(),
+                values.len()
             )));
         }
+        for (idx, field) in fields.iter().enumerate() {
+            if !table_schema.fields.iter().any(|f| &f.name == field) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' not found",
+                    table_schema.name, field
+                )));
+            }
+            if fields[..idx].contains(field) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' is listed more than once",
+                    field
+                )));
+            }
+        }
 
-        let mut ordered_data = Vec::new();
-
-        for schema_field in &table_schema.fields {
-            let user_val_index = insert_query
-                .fields
-                .iter()
-                .position(|f| f == &schema_field.name);
+        let mut ordered_data: Vec<Vec<u8>> = Vec::new();
+        let mut payloads = Vec::new();
+        let mut sequence_values = Vec::new();
+
+        for (field_idx, schema_field) in table_schema.fields.iter().enumerate() {
+            let user_val_index = fields.iter().position(|f| f == &schema_field.name);
+            let default = table_schema
+                .column_defaults
+                .get(field_idx)
+                .and_then(Option::as_ref);
+            if user_val_index.is_some() {
+                Self::check_insertable(table_schema, field_idx)?;
+            }
+            if table_schema.is_generated_column(field_idx) {
+                // computed by the executor once the rest of the row is known
+                ordered_data.push(
+                    Serializer::null_field(&schema_field.field_type).map_err(QueryResult::err)?,
+                );
+                continue;
+            }
 
-            match user_val_index {
-                Some(idx) => {
-                    let value_str = &insert_query.values[idx];
-                    let compiled_val = Self::compile_value(value_str, schema_field)?;
-                    ordered_data.push(compiled_val);
+            let value = match user_val_index {
+                Some(idx) => values[idx].take(),
+                None => default.cloned().map(InsertValue::Value),
+            };
+            match value {
+                Some(InsertValue::Value(ParsedValue::NextVal(sequence_name))) => {
+                    Self::check_sequence_target(schema_field)?;
+                    if !schema.sequences.iter().any(|seq| seq.name == sequence_name) {
+                        return Err(QueryResult::user_input_wrong(format!(
+                            "Sequence '{}' not found",
+                            sequence_name
+                        )));
+                    }
+                    sequence_values.push((field_idx, sequence_name.to_string()));
+                    // a placeholder of the right width, replaced when the row is inserted
+                    ordered_data.push(Serializer::parse_int("0").map_err(QueryResult::err)?.to_vec());
+                }
+                Some(value) => {
+                    let compiled_val = match value {
+                        InsertValue::Value(value) => Self::compile_parsed_value(&value, schema_field)?,
+                        InsertValue::Encoded(encoded) => encoded,
+                    };
+                    if schema_field.field_type.is_payload_backed() {
+                        let row_offset = ordered_data.iter().map(Vec::len).sum();
+                        payloads.push((row_offset, compiled_val));
+                        ordered_data.push(Serializer::payload_field_to_bytes(
+                            &[],
+                            Position::make_empty(),
+                        ));
+                    } else {
+                        ordered_data.push(compiled_val);
+                    }
                 }
-                None => {
+                None if table_schema.is_key_column(field_idx) => {
                     return Err(QueryResult::user_input_wrong(format!(
-                        "Missing value for field '{}'",
+                        "Key column '{}' cannot be NULL",
                         schema_field.name
                     )));
                 }
+                None if table_schema.not_null.get(field_idx) == Some(&true) => {
+                    return Err(Self::not_null_violation(table_schema, field_idx));
+                }
+                None => ordered_data.push(
+                    Serializer::null_field(&schema_field.field_type).map_err(QueryResult::err)?,
+                ),
             }
         }
 
@@ -301,15 +703,202 @@ impl Planner {
             ));
         }
 
-        let key = ordered_data[0].clone();
-        let row: Vec<u8> = ordered_data[1..].iter().flat_map(|r| r.clone()).collect();
+        let (key, row) = Serializer::split_key_and_row(&ordered_data.concat(), table_schema)
+            .map_err(QueryResult::err)?;
 
-        Ok(CompiledQuery::Insert(CompiledInsertQuery {
+        Ok(CompiledInsertQuery {
             table_id,
             data: (key, row),
+            payloads,
+            sequence_values,
+            on_conflict: None,
+            returning: None,
+        })
+    }
+
+    /// Resolves the columns of RETURNING; `*` stands for the columns `SELECT *` shows.
+    fn plan_returning(
+        table_schema: &TableSchema,
+        columns: &Option<Vec<String>>,
+    ) -> Result<Option<Vec<usize>>, QueryResult> {
+        let Some(columns) = columns else {
+            return Ok(None);
+        };
+        let mut fields = Vec::new();
+        for column in columns {
+            if column == "*" {
+                fields.extend(
+                    (0..table_schema.fields.len())
+                        .filter(|idx| table_schema.fields[*idx].name != ROWID_COLUMN),
+                );
+                continue;
+            }
+            let field_idx = table_schema
+                .fields
+                .iter()
+                .position(|f| f.name == *column)
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!(
+                        "Column '{}.{}' not found",
+                        table_schema.name, column
+                    ))
+                })?;
+            fields.push(field_idx);
+        }
+        Ok(Some(fields))
+    }
+
+    /// Resolves the target of `ON CONFLICT`, which must be the key columns or a column with a
+    /// unique index, and the columns and literals of DO UPDATE.
+    fn plan_on_conflict(
+        table_schema: &TableSchema,
+        schema: &Schema,
+        on_conflict: &ParsedOnConflict,
+    ) -> Result<CompiledOnConflict, QueryResult> {
+        let field_position = |column: &str| {
+            table_schema
+                .fields
+                .iter()
+                .position(|f| f.name == column)
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!(
+                        "Column '{}.{}' not found",
+                        table_schema.name, column
+                    ))
+                })
+        };
+        let mut columns = Vec::new();
+        for column in &on_conflict.columns {
+            columns.push(field_position(column)?);
+        }
+        let target = if columns.is_empty() {
+            None
+        } else if columns.len() == table_schema.key_positions.len()
+            && table_schema.key_positions.iter().all(|idx| columns.contains(idx))
+        {
+            Some(ConflictTarget::Key)
+        } else if columns.len() == 1
+            && schema.index_definitions.iter().any(|idx| {
+                idx.unique
+                    && idx.base_table == table_schema.name
+                    && idx.column_name == table_schema.fields[columns[0]].name
+            })
+        {
+            Some(ConflictTarget::Column(columns[0]))
+        } else {
+            return Err(QueryResult::user_input_wrong(format!(
+                "ON CONFLICT ({}) names neither the key nor a UNIQUE column of '{}'",
+                on_conflict.columns.join(", "),
+                table_schema.name
+            )));
+        };
+
+        let update = match &on_conflict.update {
+            None => None,
+            Some(_) if target.is_none() => {
+                return Err(QueryResult::user_input_wrong(
+                    "ON CONFLICT DO UPDATE needs a conflict target".to_string(),
+                ));
+            }
+            Some(assignments) => {
+                let mut compiled = Vec::new();
+                for (column, value) in assignments {
+                    let field_idx = field_position(column)?;
+                    let value = match value {
+                        ParsedConflictValue::Excluded(source) => {
+                            CompiledConflictValue::Excluded(field_position(source)?)
+                        }
+                        ParsedConflictValue::Value(Some(value))
+                            if !matches!(value, ParsedValue::NextVal(_)) =>
+                        {
+                            Self::compile_parsed_value(value, &table_schema.fields[field_idx])?;
+                            CompiledConflictValue::Value(Some(value.clone()))
+                        }
+                        ParsedConflictValue::Value(value) => {
+                            CompiledConflictValue::Value(value.clone())
+                        }
+                    };
+                    compiled.push((field_idx, value));
+                }
+                Some(compiled)
+            }
+        };
+        Ok(CompiledOnConflict { target, update })
+    }
+
+    /// Checks the target columns and the width of the query; the executor compiles each
+    /// result row into an INSERT of its encoded values.
+    fn plan_insert_select_query(
+        schema: &Schema,
+        insert_query: ParsedInsertSelectQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let table_id = Self::find_table_id(schema, &insert_query.table_name)?;
+        let table_schema = &schema.tables[table_id];
+        Self::check_not_materialized(table_schema)?;
+        let fields = if insert_query.fields.is_empty() {
+            Self::implicit_insert_columns(table_schema)
+        } else {
+            insert_query.fields
+        };
+        for field in &fields {
+            let field_idx = table_schema
+                .fields
+                .iter()
+                .position(|f| &f.name == field)
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!(
+                        "Column '{}.{}' not found",
+                        table_schema.name, field
+                    ))
+                })?;
+            Self::check_insertable(table_schema, field_idx)?;
+        }
+
+        let plan = Self::plan_tree_node(schema, insert_query.query)?;
+        let result_columns = plan.get_schema(schema).map_err(QueryResult::err)?.fields.len();
+        if fields.len() != result_columns {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column count doesn't match the query: {} columns vs {} result columns",
+                fields.len(),
+                result_columns
+            )));
+        }
+        Ok(CompiledQuery::InsertSelect(CompiledInsertSelectQuery {
+            table_id,
+            fields,
+            plan,
         }))
     }
 
+    /// The columns an INSERT without a column list fills, in order.
+    fn implicit_insert_columns(table_schema: &TableSchema) -> Vec<String> {
+        table_schema
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(idx, f)| f.name != ROWID_COLUMN && !table_schema.is_generated_column(*idx))
+            .map(|(_, f)| f.name.clone())
+            .collect()
+    }
+
+    /// Rejects an INSERT that names a column which only takes computed values.
+    fn check_insertable(table_schema: &TableSchema, field_idx: usize) -> Result<(), QueryResult> {
+        let field = &table_schema.fields[field_idx];
+        if table_schema.identity.get(field_idx) == Some(&Some(Identity::Always)) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}' is GENERATED ALWAYS AS IDENTITY and takes no values",
+                field.name
+            )));
+        }
+        if table_schema.is_generated_column(field_idx) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}' is generated and takes no values",
+                field.name
+            )));
+        }
+        Ok(())
+    }
+
     fn plan_select_query(
         schema: &Schema,
         tree_node: ParsedQueryTreeNode,
@@ -319,19 +908,62 @@ impl Planner {
     }
 
     fn plan_create_table_query(
-        create_table_query: ParsedCreateTableQuery,
+        mut create_table_query: ParsedCreateTableQuery,
     ) -> Result<CompiledQuery, QueryResult> {
+        if create_table_query
+            .table_fields
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(ROWID_COLUMN))
+        {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column name '{}' is reserved",
+                ROWID_COLUMN
+            )));
+        }
+        if create_table_query.with_rowid {
+            if !create_table_query.primary_key.is_empty() {
+                return Err(QueryResult::user_input_wrong(
+                    "A table WITH ROWID cannot declare a PRIMARY KEY".to_string(),
+                ));
+            }
+            Self::add_rowid_column(&mut create_table_query);
+        }
+        // their indexes, sequences and parents would be stored for every executor to see
+        if create_table_query.temporary {
+            let unsupported = if create_table_query.table_unique.iter().any(|u| *u) {
+                Some("UNIQUE columns")
+            } else if !create_table_query.foreign_keys.is_empty() {
+                Some("FOREIGN KEYs")
+            } else if create_table_query.table_identity.iter().any(Option::is_some) {
+                Some("identity columns")
+            } else {
+                None
+            };
+            if let Some(unsupported) = unsupported {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Temporary tables cannot have {}",
+                    unsupported
+                )));
+            }
+        }
+
         let mut fields = Vec::new();
-        for (name, type_str) in create_table_query
+        for ((name, type_str), collation) in create_table_query
             .table_fields
             .iter()
             .zip(create_table_query.table_types.iter())
+            .zip(create_table_query.table_collations.iter())
         {
             let field_type = Type::from_str(type_str).map_err(QueryResult::user_input_wrong)?;
+            let collation = match collation {
+                Some(name) => Self::compile_collation(name, &field_type)?,
+                None => Collation::Binary,
+            };
             fields.push(Field {
                 name: name.clone(),
                 field_type,
                 table_name: create_table_query.table_name.clone(),
+                collation,
             });
         }
 
@@ -341,25 +973,241 @@ impl Planner {
             ));
         }
 
-        let schema = TableSchema {
+        let key_positions = Self::compile_primary_key(&create_table_query.primary_key, &fields)?;
+
+        let mut column_defaults = create_table_query.table_defaults;
+        for ((field, default), identity) in fields
+            .iter()
+            .zip(column_defaults.iter_mut())
+            .zip(create_table_query.table_identity.iter())
+        {
+            if identity.is_some() {
+                if default.is_some() {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Column '{}' cannot have both a DEFAULT and an IDENTITY",
+                        field.name
+                    )));
+                }
+                *default = Some(ParsedValue::NextVal(Self::identity_sequence_name(
+                    &create_table_query.table_name,
+                    &field.name,
+                )));
+            }
+            match default {
+                Some(ParsedValue::NextVal(_)) => {
+                    Self::check_sequence_target(field)?
+                }
+                Some(default) => {
+                    Self::compile_parsed_value(default, field)?;
+                }
+                None => {}
+            }
+        }
+
+        let mut schema = TableSchema {
             root: Position::make_empty(),
             next_position: Position::make_empty(),
             has_key: true,
-            key_position: 0,
+            key_positions,
             fields,
+            column_defaults,
+            not_null: create_table_query.table_not_null,
+            checks: vec![],
+            identity: create_table_query.table_identity,
+            generated: vec![],
             entry_count: 0,
-            table_type: 0,
+            table_type: if create_table_query.temporary {
+                TABLE_TYPE_TEMPORARY
+            } else {
+                0
+            },
             name: create_table_query.table_name.clone(),
             btree_order: 0,
             free_list: vec![],
         };
+        let mut checks = Vec::new();
+        for check in &create_table_query.table_checks {
+            checks.push(match check {
+                Some(check) => Some(Self::compile_check(check, &schema)?),
+                None => None,
+            });
+        }
+        schema.checks = checks;
 
-        Ok(CompiledQuery::CreateTable(CompiledCreateTableQuery {
-            table_name: create_table_query.table_name,
+        let is_generated: Vec<bool> = create_table_query
+            .table_generated
+            .iter()
+            .map(Option::is_some)
+            .collect();
+        let mut generated = Vec::new();
+        for (field_idx, expr) in create_table_query.table_generated.iter().enumerate() {
+            generated.push(match expr {
+                Some(expr) => Some(Self::compile_generated_column(
+                    expr,
+                    field_idx,
+                    &schema,
+                    &is_generated,
+                )?),
+                None => None,
+            });
+        }
+        schema.generated = generated;
+
+        let mut unique_columns = Vec::new();
+        for (field, unique) in schema.fields.iter().zip(create_table_query.table_unique.iter()) {
+            if *unique {
+                Self::check_indexable(field)?;
+                unique_columns.push(field.name.clone());
+            }
+        }
+
+        for foreign_key in &create_table_query.foreign_keys {
+            let field_idx = schema
+                .fields
+                .iter()
+                .position(|f| f.name == foreign_key.column)
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!(
+                        "Column '{}' in FOREIGN KEY not found",
+                        foreign_key.column
+                    ))
+                })?;
+            Self::check_indexable(&schema.fields[field_idx])?;
+            let set_null = foreign_key.on_delete == ForeignKeyAction::SetNull
+                || foreign_key.on_update == ForeignKeyAction::SetNull;
+            if set_null
+                && (schema.is_key_column(field_idx) || schema.not_null[field_idx])
+            {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' cannot be NULL, so its FOREIGN KEY cannot SET NULL",
+                    foreign_key.column
+                )));
+            }
+        }
+
+        Ok(CompiledQuery::CreateTable(CompiledCreateTableQuery {
+            table_name: create_table_query.table_name,
             schema,
+            unique_columns,
+            foreign_keys: create_table_query.foreign_keys,
         }))
     }
 
+    /// Resolves a foreign key of `child` against `schema`. `child` may not exist yet; its
+    /// UNIQUE columns without an index so far are passed as `child_unique_columns`. The
+    /// parent column must be the parent's single-column key or carry a unique index, and
+    /// must have the type and collation of the child column.
+    pub(crate) fn resolve_foreign_key(
+        schema: &Schema,
+        child: &TableSchema,
+        child_unique_columns: &[String],
+        foreign_key: &ParsedForeignKey,
+    ) -> Result<ForeignKeyDefinition, QueryResult> {
+        let parent = if foreign_key.parent_table == child.name {
+            child
+        } else {
+            &schema.tables[Self::find_table_id(schema, &foreign_key.parent_table)?]
+        };
+        let mut parent_unique_columns: Vec<String> = schema
+            .index_definitions
+            .iter()
+            .filter(|idx| idx.unique && idx.base_table == parent.name)
+            .map(|idx| idx.column_name.clone())
+            .collect();
+        if parent.name == child.name {
+            parent_unique_columns.extend(child_unique_columns.iter().cloned());
+        }
+
+        let parent_column = match &foreign_key.parent_column {
+            Some(column) => column.clone(),
+            None if parent.key_positions.len() == 1 => {
+                parent.fields[parent.key_positions[0]].name.clone()
+            }
+            None => {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Table '{}' has a composite key; name the referenced column",
+                    parent.name
+                )));
+            }
+        };
+        let parent_idx = parent
+            .fields
+            .iter()
+            .position(|f| f.name == parent_column)
+            .ok_or_else(|| {
+                QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' not found",
+                    parent.name, parent_column
+                ))
+            })?;
+        if parent.key_positions != [parent_idx] && !parent_unique_columns.contains(&parent_column)
+        {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}.{}' is referenced by a FOREIGN KEY, so it must be the PRIMARY KEY or UNIQUE",
+                parent.name, parent_column
+            )));
+        }
+
+        let child_field = child
+            .fields
+            .iter()
+            .find(|f| f.name == foreign_key.column)
+            .ok_or_else(|| {
+                QueryResult::user_input_wrong(format!(
+                    "Column '{}' in FOREIGN KEY not found",
+                    foreign_key.column
+                ))
+            })?;
+        let parent_field = &parent.fields[parent_idx];
+        if child_field.field_type != parent_field.field_type
+            || child_field.collation != parent_field.collation
+        {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}.{}' must have the type and collation of '{}.{}'",
+                child.name, child_field.name, parent.name, parent_column
+            )));
+        }
+        // a cascade would copy the payload descriptor of the parent into the child rows
+        if parent_field.field_type.is_payload_backed() {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}.{}' is of type '{:?}', which a FOREIGN KEY cannot reference",
+                parent.name, parent_column, parent_field.field_type
+            )));
+        }
+
+        Ok(ForeignKeyDefinition {
+            child_table: child.name.clone(),
+            child_column: child_field.name.clone(),
+            parent_table: parent.name.clone(),
+            parent_column,
+            on_delete: foreign_key.on_delete,
+            on_update: foreign_key.on_update,
+        })
+    }
+
+    fn check_indexable(field: &Field) -> Result<(), QueryResult> {
+        if !matches!(
+            field.field_type,
+            Type::Integer
+                | Type::String
+                | Type::Varchar(_)
+                | Type::VarcharBytes(_)
+                | Type::Date
+                | Type::Timestamp
+                | Type::TimestampTz
+                | Type::Time
+                | Type::Uuid
+                | Type::Decimal(..)
+                | Type::Text
+        ) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Type '{:?}' is not indexable",
+                field.field_type
+            )));
+        }
+        Ok(())
+    }
+
     fn plan_create_index_query(
         schema: &Schema,
         create_index_query: ParsedCreateIndexQuery,
@@ -373,6 +1221,7 @@ impl Planner {
         let table_id = Self::find_table_id(schema, &create_index_query.table_name)?;
         let table_schema = &schema.tables[table_id];
         let column_name = create_index_query.columns[0].clone();
+        Self::check_not_temporary(table_schema, "an index")?;
 
         let field = table_schema
             .fields
@@ -385,38 +1234,28 @@ impl Planner {
                 ))
             })?;
 
-        if !matches!(
-            field.field_type,
-            Type::Integer | Type::String | Type::Varchar(_) | Type::Date
-        ) {
-            return Err(QueryResult::user_input_wrong(format!(
-                "Type '{:?}' is not indexable",
-                field.field_type
-            )));
-        }
+        Self::check_indexable(field)?;
 
-        let pk_type = table_schema.fields[table_schema.key_position]
-            .field_type
-            .clone();
         let index_table_name = create_index_query.index_name.clone();
+        let mut index_fields = vec![Field {
+            name: "idx_value".to_string(),
+            field_type: field.field_type.clone(),
+            table_name: index_table_name.clone(),
+            collation: field.collation,
+        }];
+        index_fields.extend(table_schema.base_key_fields(&index_table_name));
 
         let index_schema = TableSchema {
             root: Position::make_empty(),
             next_position: Position::make_empty(),
             has_key: true,
-            key_position: 0,
-            fields: vec![
-                Field {
-                    name: "idx_value".to_string(),
-                    field_type: field.field_type.clone(),
-                    table_name: index_table_name.clone(),
-                },
-                Field {
-                    name: "base_pk".to_string(),
-                    field_type: pk_type,
-                    table_name: index_table_name.clone(),
-                },
-            ],
+            key_positions: vec![0],
+            fields: index_fields,
+            column_defaults: vec![],
+            not_null: vec![],
+            checks: vec![],
+            identity: vec![],
+            generated: vec![],
             entry_count: 0,
             table_type: 0,
             name: index_table_name,
@@ -429,6 +1268,7 @@ impl Planner {
             table_id,
             base_table_name: create_index_query.table_name,
             column_name,
+            unique: create_index_query.unique,
             schema: index_schema,
         }))
     }
@@ -438,25 +1278,315 @@ impl Planner {
         drop_table_query: ParsedDropQuery,
     ) -> Result<CompiledQuery, QueryResult> {
         let table_id = Self::find_table_id(schema, &drop_table_query.table_name)?;
+        if schema.tables[table_id].table_type == TABLE_TYPE_MATERIALIZED_VIEW {
+            return Err(QueryResult::user_input_wrong(format!(
+                "'{}' is a materialized view, use DROP MATERIALIZED VIEW",
+                drop_table_query.table_name
+            )));
+        }
+        if let Some(foreign_key) = schema.foreign_keys.iter().find(|fk| {
+            fk.parent_table == drop_table_query.table_name && fk.child_table != fk.parent_table
+        }) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Table '{}' is referenced by a FOREIGN KEY of '{}'",
+                foreign_key.parent_table, foreign_key.child_table
+            )));
+        }
+        Self::check_not_used_by_views(schema, "Table", &drop_table_query.table_name)?;
         Ok(CompiledQuery::DropTable(CompiledDropTableQuery {
             table_id,
         }))
     }
 
+    fn plan_alter_table_query(
+        schema: &Schema,
+        alter_table_query: ParsedAlterTableQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let table_name = alter_table_query.table_name;
+        let table_id = Self::find_table_id(schema, &table_name)?;
+        let table = &schema.tables[table_id];
+        Self::check_not_materialized(&schema.tables[table_id])?;
+        Self::check_not_temporary(table, "ALTER TABLE")?;
+        if table_id == 0 || table_name.starts_with('_') {
+            return Err(QueryResult::msg("You are not allowed to modify this table."));
+        }
+        let column_position = |column: &str| {
+            table
+                .fields
+                .iter()
+                .position(|f| f.name == column)
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!(
+                        "Column '{}.{}' not found",
+                        table_name, column
+                    ))
+                })
+        };
+        let indexes_on = |column: &str| -> Vec<IndexDefinition> {
+            schema
+                .index_definitions
+                .iter()
+                .filter(|idx| idx.base_table == table_name && idx.column_name == column)
+                .cloned()
+                .collect()
+        };
+        let check_alterable = |column: &str| {
+            if column == ROWID_COLUMN {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' cannot be altered",
+                    table_name, column
+                )));
+            }
+            Ok(())
+        };
+        let check_not_referenced = |column: &str| {
+            if let Some(trigger) = schema.triggers.iter().find(|trigger| {
+                trigger.table_name == table_name && trigger.columns.iter().any(|c| c == column)
+            }) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' is used by trigger '{}'",
+                    table_name, column, trigger.name
+                )));
+            }
+            match schema.foreign_keys.iter().find(|fk| {
+                fk.parent_table == table_name
+                    && fk.parent_column == column
+                    && fk.child_table != table_name
+            }) {
+                Some(foreign_key) => Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' is referenced by a FOREIGN KEY of '{}'",
+                    table_name, column, foreign_key.child_table
+                ))),
+                None => Ok(()),
+            }
+        };
+        // a rename is written into the generated expressions along with the definitions
+        let check_not_generated_from = |column: &str| {
+            let uses_column = |expr: &CompiledScalarExpr| {
+                Self::scalar_expr_columns(expr)
+                    .iter()
+                    .any(|&idx| table.fields[idx].name == column)
+            };
+            match table.generated.iter().enumerate().find(|(_, expr)| {
+                expr.as_ref().is_some_and(|expr| uses_column(expr))
+            }) {
+                Some((field_idx, _)) => Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' is used by generated column '{}'",
+                    table_name, column, table.fields[field_idx].name
+                ))),
+                None => Ok(()),
+            }
+        };
+
+        let mut dropped_indexes = vec![];
+        let mut created_indexes = vec![];
+        match &alter_table_query.action {
+            ParsedAlterTableAction::AddColumn { column, definition } => {
+                if column_position(column).is_ok() {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Column '{}.{}' already exists",
+                        table_name, column
+                    )));
+                }
+                let mut parser =
+                    Parser::new(format!("CREATE TABLE {} ({})", table_name, definition));
+                let Ok(ParsedQuery::CreateTable(added)) = parser.parse_query() else {
+                    return Err(QueryResult::err(Status::InternalExceptionCompilerError));
+                };
+                if !added.primary_key.is_empty() {
+                    return Err(QueryResult::user_input_wrong(
+                        "ALTER TABLE cannot add PRIMARY KEY columns".to_string(),
+                    ));
+                }
+                if added.table_identity[0].is_some() {
+                    return Err(QueryResult::user_input_wrong(
+                        "ALTER TABLE cannot add identity columns".to_string(),
+                    ));
+                }
+                if added.table_generated[0].is_some() {
+                    return Err(QueryResult::user_input_wrong(
+                        "ALTER TABLE cannot add generated columns".to_string(),
+                    ));
+                }
+                if added.table_unique[0] {
+                    created_indexes.push(IndexDefinition {
+                        index_name: QueryExecutor::index_table_name(&table_name, column),
+                        base_table: table_name.clone(),
+                        column_name: column.clone(),
+                        unique: true,
+                    });
+                }
+            }
+            ParsedAlterTableAction::DropColumn(column) => {
+                let position = column_position(column)?;
+                if table.is_key_column(position) {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Key column '{}.{}' cannot be dropped",
+                        table_name, column
+                    )));
+                }
+                check_not_referenced(column)?;
+                check_not_generated_from(column)?;
+                dropped_indexes = indexes_on(column)
+                    .into_iter()
+                    .map(|idx| idx.index_name)
+                    .collect();
+            }
+            ParsedAlterTableAction::RenameColumn { from, to } => {
+                let position = column_position(from)?;
+                if column_position(to).is_ok() {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Column '{}.{}' already exists",
+                        table_name, to
+                    )));
+                }
+                check_alterable(from)?;
+                check_not_referenced(from)?;
+                let mut new_names = vec![];
+                if table.identity.get(position).is_some_and(Option::is_some) {
+                    new_names.push(Self::identity_sequence_name(&table_name, to));
+                }
+                for idx in indexes_on(from) {
+                    dropped_indexes.push(idx.index_name.clone());
+                    // indexes named after their column follow it
+                    let index_name = if idx.index_name
+                        == QueryExecutor::index_table_name(&table_name, from)
+                    {
+                        let index_name = QueryExecutor::index_table_name(&table_name, to);
+                        new_names.push(index_name.clone());
+                        index_name
+                    } else {
+                        idx.index_name
+                    };
+                    created_indexes.push(IndexDefinition {
+                        index_name,
+                        column_name: to.clone(),
+                        ..idx
+                    });
+                }
+                for name in new_names {
+                    if schema.table_index.index.contains(&name.as_bytes().to_vec())
+                        || schema.sequences.iter().any(|seq| seq.name == name)
+                    {
+                        return Err(QueryResult::user_input_wrong(format!(
+                            "'{}' already exists",
+                            name
+                        )));
+                    }
+                }
+            }
+            ParsedAlterTableAction::AlterColumnType {
+                column,
+                column_type,
+            } => {
+                let position = column_position(column)?;
+                check_alterable(column)?;
+                let old_type = &table.fields[position].field_type;
+                let new_type = Type::from_str(column_type).map_err(QueryResult::user_input_wrong)?;
+                if !Self::is_widening(old_type, &new_type) {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Cannot change column '{}.{}' from {:?} to {:?}; only widening type changes are supported",
+                        table_name, column, old_type, new_type
+                    )));
+                }
+                check_not_referenced(column)?;
+                check_not_generated_from(column)?;
+                if !indexes_on(column).is_empty() {
+                    Self::check_indexable(&Field {
+                        field_type: new_type,
+                        ..table.fields[position].clone()
+                    })?;
+                }
+                // index tables hold the column's values, and the base key of every row
+                let affected: Vec<IndexDefinition> = if table.is_key_column(position) {
+                    schema
+                        .index_definitions
+                        .iter()
+                        .filter(|idx| idx.base_table == table_name)
+                        .cloned()
+                        .collect()
+                } else {
+                    indexes_on(column)
+                };
+                dropped_indexes = affected.iter().map(|idx| idx.index_name.clone()).collect();
+                created_indexes = affected;
+            }
+        }
+        // the rewrite gives the Varchar columns of an older file the character layout, so the
+        // index tables holding their values or the base key are rebuilt as well
+        if table.fields.iter().any(|f| matches!(f.field_type, Type::VarcharBytes(_))) {
+            for idx in schema
+                .index_definitions
+                .iter()
+                .filter(|idx| idx.base_table == table_name)
+            {
+                if !dropped_indexes.contains(&idx.index_name) {
+                    dropped_indexes.push(idx.index_name.clone());
+                    created_indexes.push(idx.clone());
+                }
+            }
+        }
+
+        Ok(CompiledQuery::AlterTable(CompiledAlterTableQuery {
+            table_id,
+            action: alter_table_query.action,
+            dropped_indexes,
+            created_indexes,
+        }))
+    }
+
+    /// Type changes that ALTER COLUMN TYPE accepts: every value of `from` keeps its meaning
+    /// in `to`.
+    fn is_widening(from: &Type, to: &Type) -> bool {
+        match (from, to) {
+            _ if from == to => true,
+            (Type::Varchar(from_len) | Type::VarcharBytes(from_len), Type::Varchar(to_len)) => {
+                to_len >= from_len
+            }
+            (Type::Varchar(_) | Type::VarcharBytes(_) | Type::String, Type::Text) => true,
+            (Type::Decimal(from_precision, from_scale), Type::Decimal(to_precision, to_scale)) => {
+                to_scale >= from_scale
+                    && to_precision.saturating_sub(*to_scale)
+                        >= from_precision.saturating_sub(*from_scale)
+            }
+            // an Integer has up to 10 digits
+            (Type::Integer, Type::Decimal(precision, scale)) => {
+                precision.saturating_sub(*scale) >= 10
+            }
+            _ => false,
+        }
+    }
+
     fn plan_drop_index_query(
         schema: &Schema,
         drop_index_query: ParsedDropIndexQuery,
     ) -> Result<CompiledQuery, QueryResult> {
         let table_id = Self::find_table_id(schema, &drop_index_query.index_name)?;
-        let is_index = schema
+        let Some(definition) = schema
             .index_definitions
             .iter()
-            .any(|idx| idx.index_name == drop_index_query.index_name);
-        if !is_index {
+            .find(|idx| idx.index_name == drop_index_query.index_name)
+        else {
             return Err(QueryResult::user_input_wrong(format!(
                 "Index '{}' not found",
                 drop_index_query.index_name
             )));
+        };
+        // a referenced column that is not the key relies on its unique index
+        let base_id = Self::find_table_id(schema, &definition.base_table)?;
+        let base = &schema.tables[base_id];
+        let is_key = base.key_positions.len() == 1
+            && base.fields[base.key_positions[0]].name == definition.column_name;
+        if let Some(foreign_key) = schema.foreign_keys.iter().find(|fk| {
+            definition.unique
+                && !is_key
+                && fk.parent_table == definition.base_table
+                && fk.parent_column == definition.column_name
+        }) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Index '{}' is required by a FOREIGN KEY of '{}'",
+                definition.index_name, foreign_key.child_table
+            )));
         }
         Ok(CompiledQuery::DropIndex(CompiledDropIndexQuery {
             table_id,
@@ -468,6 +1598,7 @@ impl Planner {
         delete_query: ParsedDeleteQuery,
     ) -> Result<CompiledQuery, QueryResult> {
         let table_id = Self::find_table_id(schema, &delete_query.table_name)?;
+        Self::check_not_materialized(&schema.tables[table_id])?;
         let table_schema = &schema.tables[table_id];
 
         let condition = match delete_query.conditions {
@@ -477,38 +1608,805 @@ impl Planner {
 
         let (operation, seek_key) =
             Self::derive_scan_hint_for_table(schema, table_schema, &condition);
+        let returning = Self::plan_returning(table_schema, &delete_query.returning)?;
 
         Ok(CompiledQuery::Delete(CompiledDeleteQuery {
             table_id,
             operation,
             seek_key,
             condition,
+            returning,
+        }))
+    }
+
+    fn plan_update_query(
+        schema: &Schema,
+        update_query: ParsedUpdateQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let table_id = Self::find_table_id(schema, &update_query.table_name)?;
+        Self::check_not_materialized(&schema.tables[table_id])?;
+        let table_schema = &schema.tables[table_id];
+
+        let condition = match &update_query.conditions {
+            Some(expr) => Some(Self::compile_condition_expr(schema, table_schema, expr)?),
+            None => None,
+        };
+
+        let (operation, seek_key) =
+            Self::derive_scan_hint_for_table(schema, table_schema, &condition);
+        let assignments = Self::compile_update_assignments(&update_query, table_schema)?;
+        let returning = Self::plan_returning(table_schema, &update_query.returning)?;
+
+        Ok(CompiledQuery::Update(CompiledUpdateQuery {
+            table_id,
+            operation,
+            seek_key,
+            condition,
+            assignments,
+            returning,
+        }))
+    }
+
+    fn plan_create_sequence_query(
+        create_sequence_query: ParsedCreateSequenceQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        if create_sequence_query.increment == 0 {
+            return Err(QueryResult::user_input_wrong(
+                "A sequence cannot INCREMENT BY 0".to_string(),
+            ));
+        }
+        Ok(CompiledQuery::CreateSequence(CompiledCreateSequenceQuery {
+            sequence_name: create_sequence_query.sequence_name,
+            start: create_sequence_query.start,
+            increment: create_sequence_query.increment,
+            if_not_exists: create_sequence_query.if_not_exists,
+        }))
+    }
+
+    fn plan_drop_sequence_query(
+        schema: &Schema,
+        drop_sequence_query: ParsedDropSequenceQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let sequence_name = drop_sequence_query.sequence_name;
+        if !schema.sequences.iter().any(|seq| seq.name == sequence_name) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Sequence '{}' not found",
+                sequence_name
+            )));
+        }
+        for table in &schema.tables {
+            for (field, default) in table.fields.iter().zip(table.column_defaults.iter()) {
+                if *default == Some(ParsedValue::NextVal(sequence_name.clone())) {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Sequence '{}' is used by the DEFAULT of '{}.{}'",
+                        sequence_name, table.name, field.name
+                    )));
+                }
+            }
+        }
+        Ok(CompiledQuery::DropSequence(CompiledDropSequenceQuery {
+            sequence_name,
+        }))
+    }
+
+    /// Derives the columns of the new table from the query's result. Like a materialized
+    /// view, the table is keyed by a hidden rowid, so any result can be stored.
+    fn plan_create_table_as_query(
+        schema: &Schema,
+        create_table_query: ParsedCreateTableAsQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let table_name = create_table_query.table_name;
+        if create_table_query.temporary {
+            return Err(QueryResult::user_input_wrong(
+                "CREATE TEMP TABLE ... AS SELECT is not supported, the table would need a rowid"
+                    .to_string(),
+            ));
+        }
+        let plan = Self::plan_tree_node(schema, create_table_query.query)?;
+        let result_schema = plan.get_schema(schema).map_err(QueryResult::err)?;
+        let mut column_definitions = Vec::new();
+        for (idx, field) in result_schema.fields.iter().enumerate() {
+            if !field.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Result column '{}' cannot be a column name of '{}'",
+                    field.name, table_name
+                )));
+            }
+            if result_schema.fields[..idx].iter().any(|f| f.name == field.name) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' appears twice in the result of the query",
+                    field.name
+                )));
+            }
+            column_definitions.push(Self::column_definition(field));
+        }
+        let create_sql = format!(
+            "CREATE TABLE {} ({}) WITH ROWID",
+            table_name,
+            column_definitions.join(", ")
+        );
+        // planning the statement once checks the names and types before the query runs
+        match Parser::new(create_sql.clone()).parse_query() {
+            Ok(ParsedQuery::CreateTable(create)) => {
+                Self::plan_create_table_query(create)?;
+            }
+            Ok(_) => return Err(QueryResult::err(Status::InternalExceptionCompilerError)),
+            Err(error) => return Err(QueryResult::user_input_wrong(error)),
+        }
+        Ok(CompiledQuery::CreateTableAs(CompiledCreateTableAsQuery {
+            table_name,
+            create_sql,
+            plan,
         }))
     }
 
-    fn plan_update_query(
-        schema: &Schema,
-        update_query: ParsedUpdateQuery,
-    ) -> Result<CompiledQuery, QueryResult> {
-        let table_id = Self::find_table_id(schema, &update_query.table_name)?;
-        let table_schema = &schema.tables[table_id];
+    /// A column definition `name Type [COLLATE name]` for a CREATE statement.
+    fn column_definition(field: &Field) -> String {
+        match field.collation {
+            Collation::Binary => format!("{} {:?}", field.name, field.field_type),
+            collation => format!("{} {:?} COLLATE {}", field.name, field.field_type, collation),
+        }
+    }
+
+    fn plan_create_view_query(
+        schema: &Schema,
+        create_view_query: ParsedCreateViewQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        for table_name in Self::referenced_tables(&create_view_query.query) {
+            if let Ok(table_id) = Self::find_table_id(schema, &table_name) {
+                Self::check_not_temporary(&schema.tables[table_id], "a view")?;
+            }
+        }
+        if create_view_query.materialized {
+            return Self::plan_create_materialized_view_query(schema, create_view_query);
+        }
+        // planning the query once checks its tables and columns
+        Self::plan_tree_node(schema, create_view_query.query)?;
+        Ok(CompiledQuery::CreateView(CompiledCreateViewQuery {
+            view_name: create_view_query.view_name,
+        }))
+    }
+
+    fn plan_create_materialized_view_query(
+        schema: &Schema,
+        create_view_query: ParsedCreateViewQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let view_name = create_view_query.view_name;
+        let plan = Self::plan_tree_node(schema, create_view_query.query)?;
+        let result_schema = plan.get_schema(schema).map_err(QueryResult::err)?;
+        if !create_view_query.columns.is_empty()
+            && create_view_query.columns.len() != result_schema.fields.len()
+        {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Materialized view '{}' lists {} columns, but its query returns {}",
+                view_name,
+                create_view_query.columns.len(),
+                result_schema.fields.len()
+            )));
+        }
+
+        let mut fields: Vec<Field> = Vec::new();
+        for (idx, result_field) in result_schema.fields.iter().enumerate() {
+            let column = create_view_query.columns.get(idx).cloned().unwrap_or(ParsedViewColumn {
+                name: result_field.name.clone(),
+                column_type: None,
+                collation: None,
+            });
+            let field = Field {
+                name: column.name.clone(),
+                field_type: result_field.field_type.clone(),
+                table_name: view_name.clone(),
+                collation: result_field.collation,
+            };
+            if column.column_type.is_some() || column.collation.is_some() {
+                let declared = Self::view_column_field(&view_name, &column)?;
+                if declared.field_type != field.field_type || declared.collation != field.collation {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Column '{}' of materialized view '{}' is {:?} {}, but the query returns {:?} {}",
+                        column.name,
+                        view_name,
+                        declared.field_type,
+                        declared.collation,
+                        field.field_type,
+                        field.collation
+                    )));
+                }
+            }
+            // the stored statement declares Varchar(n), which is n characters once loaded
+            if let Type::VarcharBytes(max) = field.field_type {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' of materialized view '{}' is a VARCHAR({}) of a table from an older file; ALTER that column to VARCHAR({}) first",
+                    column.name, view_name, max, max
+                )));
+            }
+            if !field.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Result column '{}' needs a name, list the column names after the view name",
+                    field.name
+                )));
+            }
+            if field.name.eq_ignore_ascii_case(ROWID_COLUMN) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column name '{}' is reserved",
+                    ROWID_COLUMN
+                )));
+            }
+            if fields.iter().any(|f| f.name == field.name) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' appears twice, list the column names after the view name",
+                    field.name
+                )));
+            }
+            fields.push(field);
+        }
+
+        let column_definitions: Vec<String> = fields.iter().map(Self::column_definition).collect();
+        let sql = format!(
+            "CREATE MATERIALIZED VIEW {} ({}) AS {}",
+            view_name,
+            column_definitions.join(", "),
+            create_view_query.query_sql
+        );
+        Ok(CompiledQuery::CreateMaterializedView(
+            CompiledCreateMaterializedViewQuery {
+                schema: Self::materialized_view_schema(&view_name, fields),
+                plan,
+                sql,
+            },
+        ))
+    }
+
+    /// The table of a materialized view loaded from the master table, whose statement lists
+    /// every column with its type.
+    pub(crate) fn stored_materialized_view_schema(
+        create_view_query: &ParsedCreateViewQuery,
+    ) -> Result<TableSchema, QueryResult> {
+        let fields = create_view_query
+            .columns
+            .iter()
+            .map(|column| Self::view_column_field(&create_view_query.view_name, column))
+            .collect::<Result<Vec<Field>, QueryResult>>()?;
+        Ok(Self::materialized_view_schema(&create_view_query.view_name, fields))
+    }
+
+    fn view_column_field(view_name: &str, column: &ParsedViewColumn) -> Result<Field, QueryResult> {
+        let field_type = match &column.column_type {
+            Some(type_str) => Type::from_str(type_str).map_err(QueryResult::user_input_wrong)?,
+            None => {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' of materialized view '{}' has no type",
+                    column.name, view_name
+                )));
+            }
+        };
+        let collation = match &column.collation {
+            Some(name) => Self::compile_collation(name, &field_type)?,
+            None => Collation::Binary,
+        };
+        Ok(Field {
+            name: column.name.clone(),
+            field_type,
+            table_name: view_name.to_string(),
+            collation,
+        })
+    }
+
+    /// Keys the rows of a materialized view by a hidden rowid, like a table `WITH ROWID`,
+    /// so any result fits: repeated and NULL values included.
+    fn materialized_view_schema(view_name: &str, fields: Vec<Field>) -> TableSchema {
+        let mut table_fields = vec![Field {
+            name: ROWID_COLUMN.to_string(),
+            field_type: Type::Integer,
+            table_name: view_name.to_string(),
+            collation: Collation::Binary,
+        }];
+        table_fields.extend(fields);
+        let column_count = table_fields.len();
+        TableSchema {
+            root: Position::make_empty(),
+            next_position: Position::make_empty(),
+            has_key: true,
+            key_positions: vec![0],
+            fields: table_fields,
+            column_defaults: vec![None; column_count],
+            not_null: vec![false; column_count],
+            checks: vec![None; column_count],
+            identity: vec![None; column_count],
+            generated: vec![None; column_count],
+            entry_count: 0,
+            table_type: TABLE_TYPE_MATERIALIZED_VIEW,
+            name: view_name.to_string(),
+            btree_order: 0,
+            free_list: vec![],
+        }
+    }
+
+    fn plan_refresh_materialized_view_query(
+        schema: &Schema,
+        refresh_query: ParsedRefreshMaterializedViewQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let view = schema
+            .views
+            .iter()
+            .find(|view| view.materialized && view.name == refresh_query.view_name)
+            .ok_or_else(|| {
+                QueryResult::user_input_wrong(format!(
+                    "Materialized view '{}' not found",
+                    refresh_query.view_name
+                ))
+            })?;
+        let table_id = Self::find_table_id(schema, &view.name)?;
+        let plan = Self::plan_tree_node(schema, view.query.clone())?;
+        let result_schema = plan.get_schema(schema).map_err(QueryResult::err)?;
+        let columns = &schema.tables[table_id].fields[1..];
+        let matches = result_schema.fields.len() == columns.len()
+            && result_schema
+                .fields
+                .iter()
+                .zip(columns.iter())
+                .all(|(result, column)| {
+                    result.field_type == column.field_type && result.collation == column.collation
+                });
+        if !matches {
+            return Err(QueryResult::user_input_wrong(format!(
+                "The query of materialized view '{}' no longer returns its columns",
+                view.name
+            )));
+        }
+        Ok(CompiledQuery::RefreshMaterializedView(
+            CompiledRefreshMaterializedViewQuery { table_id, plan },
+        ))
+    }
+
+    fn check_not_materialized(table: &TableSchema) -> Result<(), QueryResult> {
+        if table.table_type == TABLE_TYPE_MATERIALIZED_VIEW {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Materialized view '{}' can only be changed by REFRESH MATERIALIZED VIEW",
+                table.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rejects `what` on a temporary table, since other executors would see it but not the table.
+    pub(crate) fn check_not_temporary(table: &TableSchema, what: &str) -> Result<(), QueryResult> {
+        if table.table_type == TABLE_TYPE_TEMPORARY {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Temporary table '{}' cannot be used in {}",
+                table.name, what
+            )));
+        }
+        Ok(())
+    }
+
+    fn plan_drop_view_query(
+        schema: &Schema,
+        drop_view_query: ParsedDropViewQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let view_name = drop_view_query.view_name;
+        let Some(view) = schema.views.iter().find(|view| view.name == view_name) else {
+            return Err(QueryResult::user_input_wrong(format!(
+                "View '{}' not found",
+                view_name
+            )));
+        };
+        if view.materialized != drop_view_query.materialized {
+            return Err(QueryResult::user_input_wrong(if view.materialized {
+                format!("'{}' is a materialized view, use DROP MATERIALIZED VIEW", view_name)
+            } else {
+                format!("'{}' is not a materialized view, use DROP VIEW", view_name)
+            }));
+        }
+        Self::check_not_used_by_views(schema, "View", &view_name)?;
+        if view.materialized {
+            // the table goes the way of any table, its indexes included
+            return Ok(CompiledQuery::DropTable(CompiledDropTableQuery {
+                table_id: Self::find_table_id(schema, &view_name)?,
+            }));
+        }
+        Ok(CompiledQuery::DropView(CompiledDropViewQuery { view_name }))
+    }
+
+    fn plan_create_trigger_query(
+        schema: &Schema,
+        create_trigger_query: ParsedCreateTriggerQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let table_name = create_trigger_query.table_name;
+        let table_id = Self::find_table_id(schema, &table_name)?;
+        let table = &schema.tables[table_id];
+        if table_id == 0
+            || table_name.starts_with('_')
+            || table.table_type == TABLE_TYPE_MATERIALIZED_VIEW
+            || table.table_type == TABLE_TYPE_TEMPORARY
+        {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Cannot create a trigger on '{}'",
+                table_name
+            )));
+        }
+        for (row, column) in Self::trigger_row_references(&create_trigger_query.statements) {
+            match (row.as_str(), create_trigger_query.event) {
+                ("NEW", TriggerEvent::Delete) => {
+                    return Err(QueryResult::user_input_wrong(
+                        "DELETE triggers have no NEW row".to_string(),
+                    ));
+                }
+                ("OLD", TriggerEvent::Insert) => {
+                    return Err(QueryResult::user_input_wrong(
+                        "INSERT triggers have no OLD row".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+            if !table.fields.iter().any(|field| field.name == column) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}.{}' not found",
+                    table_name, column
+                )));
+            }
+        }
+        Ok(CompiledQuery::CreateTrigger(CompiledCreateTriggerQuery {
+            trigger_name: create_trigger_query.trigger_name,
+        }))
+    }
+
+    /// The `NEW.column` and `OLD.column` references of trigger statements as (row, column).
+    pub(crate) fn trigger_row_references(statements: &[String]) -> Vec<(String, String)> {
+        let mut references = Vec::new();
+        for statement in statements {
+            let _ = Parser::bind_row_references(statement, &mut |row, column| {
+                references.push((row.to_string(), column.to_string()));
+                Ok(String::new())
+            });
+        }
+        references
+    }
+
+    fn plan_drop_trigger_query(
+        schema: &Schema,
+        drop_trigger_query: ParsedDropTriggerQuery,
+    ) -> Result<CompiledQuery, QueryResult> {
+        let trigger_name = drop_trigger_query.trigger_name;
+        if !schema.triggers.iter().any(|trigger| trigger.name == trigger_name) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Trigger '{}' not found",
+                trigger_name
+            )));
+        }
+        Ok(CompiledQuery::DropTrigger(CompiledDropTriggerQuery { trigger_name }))
+    }
+
+    fn check_not_used_by_views(schema: &Schema, kind: &str, name: &str) -> Result<(), QueryResult> {
+        match schema
+            .views
+            .iter()
+            .find(|view| view.tables.iter().any(|table| table == name))
+        {
+            Some(view) => Err(QueryResult::user_input_wrong(format!(
+                "{} '{}' is used by view '{}'",
+                kind, name, view.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Rejects `action` on `table_name` if it breaks a view reading the table, directly or
+    /// through other views: the view names a dropped or renamed column, or no longer plans
+    /// against `altered`, the schema after the change. A materialized view must also still
+    /// return its columns.
+    pub(crate) fn check_views_survive_alter(
+        altered: &Schema,
+        table_name: &str,
+        action: &ParsedAlterTableAction,
+    ) -> Result<(), QueryResult> {
+        let (changed, removed_column) = match action {
+            ParsedAlterTableAction::AddColumn { .. } => (format!("Table '{}'", table_name), None),
+            ParsedAlterTableAction::DropColumn(column)
+            | ParsedAlterTableAction::RenameColumn { from: column, .. } => {
+                (format!("Column '{}.{}'", table_name, column), Some(column))
+            }
+            ParsedAlterTableAction::AlterColumnType { column, .. } => {
+                (format!("Column '{}.{}'", table_name, column), None)
+            }
+        };
+        let mut affected = vec![table_name.to_string()];
+        let mut views = vec![];
+        while let Some(view) = altered.views.iter().find(|view| {
+            !affected.contains(&view.name)
+                && view.tables.iter().any(|table| affected.contains(table))
+        }) {
+            affected.push(view.name.clone());
+            views.push(view);
+        }
+        for view in views {
+            // a condition on a column that does not exist still plans
+            let names_column = removed_column
+                .is_some_and(|column| Self::node_names_column(&view.query, column));
+            let planned = if view.materialized {
+                Self::plan_refresh_materialized_view_query(
+                    altered,
+                    ParsedRefreshMaterializedViewQuery { view_name: view.name.clone() },
+                )
+                .map(|_| ())
+            } else {
+                Self::plan_tree_node(altered, view.query.clone()).map(|_| ())
+            };
+            if names_column || planned.is_err() {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "{} is used by view '{}'",
+                    changed, view.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a query names `column`, bare or qualified, in its results, conditions or
+    /// join conditions, including those of its subqueries.
+    fn node_names_column(node: &ParsedQueryTreeNode, column: &str) -> bool {
+        match node {
+            ParsedQueryTreeNode::SetOperation(set_operation) => set_operation
+                .operands
+                .iter()
+                .any(|operand| Self::node_names_column(operand, column)),
+            ParsedQueryTreeNode::SingleQuery(select_query) => {
+                select_query
+                    .result_exprs
+                    .iter()
+                    .any(|expr| Self::value_names_column(expr, column))
+                    || Self::source_names_column(&select_query.source, column)
+                    || select_query
+                        .conditions
+                        .as_ref()
+                        .is_some_and(|condition| Self::condition_names_column(condition, column))
+            }
+        }
+    }
+
+    fn source_names_column(source: &ParsedSource, column: &str) -> bool {
+        match source {
+            ParsedSource::Table(_) => false,
+            ParsedSource::SubQuery(sub_node) => Self::node_names_column(sub_node, column),
+            ParsedSource::Join(join) => {
+                join.conditions.iter().any(|condition| {
+                    Self::token_names_column(&condition.left, column)
+                        || Self::token_names_column(&condition.right, column)
+                }) || join
+                    .sources
+                    .iter()
+                    .any(|join_source| Self::source_names_column(join_source, column))
+            }
+        }
+    }
+
+    fn condition_names_column(condition: &ParsedConditionExpr, column: &str) -> bool {
+        match condition {
+            ParsedConditionExpr::Logical { left, right, .. } => {
+                Self::condition_names_column(left, column)
+                    || Self::condition_names_column(right, column)
+            }
+            ParsedConditionExpr::Predicate(ParsedPredicateExpr::Compare { left, right, .. }) => {
+                Self::value_names_column(left, column) || Self::value_names_column(right, column)
+            }
+            ParsedConditionExpr::Predicate(ParsedPredicateExpr::IsNull { left, .. }) => {
+                Self::value_names_column(left, column)
+            }
+            ParsedConditionExpr::Predicate(ParsedPredicateExpr::InSubquery { left, subquery }) => {
+                Self::value_names_column(left, column) || Self::node_names_column(subquery, column)
+            }
+        }
+    }
+
+    fn value_names_column(value: &ParsedValueExpr, column: &str) -> bool {
+        match value {
+            ParsedValueExpr::Token(token) => Self::token_names_column(token, column),
+            ParsedValueExpr::JsonExtract { column: token, .. } => {
+                Self::token_names_column(token, column)
+            }
+        }
+    }
+
+    fn token_names_column(token: &str, column: &str) -> bool {
+        token == column || token.rsplit_once('.').is_some_and(|(_, name)| name == column)
+    }
+
+    /// The tables and views a query reads from, including those of its IN subqueries.
+    pub(crate) fn referenced_tables(node: &ParsedQueryTreeNode) -> Vec<String> {
+        let mut tables = Vec::new();
+        Self::collect_node_tables(node, &mut tables);
+        tables
+    }
+
+    fn collect_node_tables(node: &ParsedQueryTreeNode, tables: &mut Vec<String>) {
+        match node {
+            ParsedQueryTreeNode::SetOperation(set_operation) => {
+                for operand in &set_operation.operands {
+                    Self::collect_node_tables(operand, tables);
+                }
+            }
+            ParsedQueryTreeNode::SingleQuery(select_query) => {
+                Self::collect_source_tables(&select_query.source, tables);
+                if let Some(conditions) = &select_query.conditions {
+                    Self::collect_condition_tables(conditions, tables);
+                }
+            }
+        }
+    }
+
+    fn collect_source_tables(source: &ParsedSource, tables: &mut Vec<String>) {
+        match source {
+            ParsedSource::Table(table_name) => {
+                if !tables.contains(table_name) {
+                    tables.push(table_name.clone());
+                }
+            }
+            ParsedSource::SubQuery(sub_node) => Self::collect_node_tables(sub_node, tables),
+            ParsedSource::Join(join) => {
+                for join_source in &join.sources {
+                    Self::collect_source_tables(join_source, tables);
+                }
+            }
+        }
+    }
+
+    fn collect_condition_tables(condition: &ParsedConditionExpr, tables: &mut Vec<String>) {
+        match condition {
+            ParsedConditionExpr::Logical { left, right, .. } => {
+                Self::collect_condition_tables(left, tables);
+                Self::collect_condition_tables(right, tables);
+            }
+            ParsedConditionExpr::Predicate(ParsedPredicateExpr::InSubquery { subquery, .. }) => {
+                Self::collect_node_tables(subquery, tables)
+            }
+            ParsedConditionExpr::Predicate(_) => {}
+        }
+    }
+
+    fn compile_generated_column(
+        expr: &ParsedScalarExpr,
+        field_idx: usize,
+        schema: &TableSchema,
+        is_generated: &[bool],
+    ) -> Result<CompiledScalarExpr, QueryResult> {
+        let field = &schema.fields[field_idx];
+        if field.field_type.is_payload_backed() {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Generated column '{}' cannot be of type '{:?}'",
+                field.name, field.field_type
+            )));
+        }
+        if schema.is_key_column(field_idx) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Key column '{}' cannot be generated",
+                field.name
+            )));
+        }
+        if schema.column_defaults[field_idx].is_some() {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Generated column '{}' cannot have a DEFAULT",
+                field.name
+            )));
+        }
+        Self::compile_scalar_expr(expr, schema, is_generated)
+    }
+
+    /// Compiles a generated column's expression; it may only use the row's other,
+    /// non-generated columns.
+    fn compile_scalar_expr(
+        expr: &ParsedScalarExpr,
+        schema: &TableSchema,
+        is_generated: &[bool],
+    ) -> Result<CompiledScalarExpr, QueryResult> {
+        let check_source = |column_idx: usize| {
+            if is_generated[column_idx] {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' is generated and cannot be used in another generated column",
+                    schema.fields[column_idx].name
+                )));
+            }
+            Ok(())
+        };
+        match expr {
+            ParsedScalarExpr::Column(ParsedValueExpr::JsonExtract { column, path }) => {
+                let (column_idx, path) = Self::resolve_json_extract(column, path, schema)?;
+                check_source(column_idx)?;
+                Ok(CompiledScalarExpr::JsonExtract { column_idx, path })
+            }
+            ParsedScalarExpr::Column(column) => {
+                let column_idx = Self::resolve_value_as_column_index(column, schema)?;
+                check_source(column_idx)?;
+                Ok(CompiledScalarExpr::Column(column_idx))
+            }
+            ParsedScalarExpr::Literal(text) => Ok(CompiledScalarExpr::Literal(text.clone())),
+            ParsedScalarExpr::Function { name, args } => {
+                let function = ScalarFunction::from_name(name).ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!("Unknown function '{}'", name))
+                })?;
+                if args.len() != 1 {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Function '{}' takes one argument",
+                        name
+                    )));
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| Self::compile_scalar_expr(arg, schema, is_generated))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(CompiledScalarExpr::Function { function, args })
+            }
+            ParsedScalarExpr::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let operator = match operator.as_str() {
+                    "+" => ScalarOperator::Add,
+                    "-" => ScalarOperator::Subtract,
+                    "*" => ScalarOperator::Multiply,
+                    "/" => ScalarOperator::Divide,
+                    "||" => ScalarOperator::Concat,
+                    other => {
+                        return Err(QueryResult::user_input_wrong(format!(
+                            "Unknown operator '{}'",
+                            other
+                        )));
+                    }
+                };
+                Ok(CompiledScalarExpr::Binary {
+                    operator,
+                    left: Box::new(Self::compile_scalar_expr(left, schema, is_generated)?),
+                    right: Box::new(Self::compile_scalar_expr(right, schema, is_generated)?),
+                })
+            }
+        }
+    }
+
+    /// Field positions a generated column's expression reads.
+    fn scalar_expr_columns(expr: &CompiledScalarExpr) -> Vec<usize> {
+        match expr {
+            CompiledScalarExpr::Column(column_idx)
+            | CompiledScalarExpr::JsonExtract { column_idx, .. } => vec![*column_idx],
+            CompiledScalarExpr::Literal(_) => vec![],
+            CompiledScalarExpr::Function { args, .. } => {
+                args.iter().flat_map(Self::scalar_expr_columns).collect()
+            }
+            CompiledScalarExpr::Binary { left, right, .. } => {
+                let mut columns = Self::scalar_expr_columns(left);
+                columns.extend(Self::scalar_expr_columns(right));
+                columns
+            }
+        }
+    }
 
-        let condition = match &update_query.conditions {
-            Some(expr) => Some(Self::compile_condition_expr(schema, table_schema, expr)?),
-            None => None,
-        };
+    /// Makes the rowid the first column and the key: an Integer drawn from its identity
+    /// sequence, so rows are keyed in insertion order.
+    fn add_rowid_column(create_table_query: &mut ParsedCreateTableQuery) {
+        let q = create_table_query;
+        q.table_fields.insert(0, ROWID_COLUMN.to_string());
+        q.table_types.insert(0, "Integer".to_string());
+        q.table_defaults.insert(0, None);
+        q.table_collations.insert(0, None);
+        q.table_not_null.insert(0, false);
+        q.table_checks.insert(0, None);
+        q.table_unique.insert(0, false);
+        q.table_identity.insert(0, Some(Identity::Always));
+        q.table_generated.insert(0, None);
+        q.primary_key = vec![ROWID_COLUMN.to_string()];
+    }
 
-        let (operation, seek_key) =
-            Self::derive_scan_hint_for_table(schema, table_schema, &condition);
-        let assignments = Self::compile_update_assignments(&update_query, table_schema)?;
+    /// Name of the sequence behind an identity column, created and dropped with its table.
+    pub(crate) fn identity_sequence_name(table_name: &str, column_name: &str) -> String {
+        format!("{}_{}_seq", table_name, column_name)
+    }
 
-        Ok(CompiledQuery::Update(CompiledUpdateQuery {
-            table_id,
-            operation,
-            seek_key,
-            condition,
-            assignments,
-        }))
+    fn check_sequence_target(field: &Field) -> Result<(), QueryResult> {
+        if field.field_type != Type::Integer {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}' takes nextval() values, so it must be an Integer",
+                field.name
+            )));
+        }
+        Ok(())
     }
 
     fn plan_transaction_query(
@@ -522,10 +2420,17 @@ impl Planner {
         Ok(CompiledQuery::Transaction(compiled))
     }
 
+    pub(crate) fn not_null_violation(table_schema: &TableSchema, field_idx: usize) -> QueryResult {
+        QueryResult::user_input_wrong(format!(
+            "NOT NULL constraint failed for column '{}.{}'",
+            table_schema.name, table_schema.fields[field_idx].name
+        ))
+    }
+
     fn compile_update_assignments(
         update_query: &ParsedUpdateQuery,
         table_schema: &TableSchema,
-    ) -> Result<Vec<(usize, Vec<u8>)>, QueryResult> {
+    ) -> Result<Vec<(usize, Option<Vec<u8>>)>, QueryResult> {
         let mut compiled = Vec::new();
 
         for (field_name, value_str) in &update_query.assignments {
@@ -556,6 +2461,27 @@ impl Planner {
 
             let field_idx = matched_indices[0];
 
+            if table_schema.is_generated_column(field_idx) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' is generated and cannot be assigned",
+                    field_name
+                )));
+            }
+            if table_schema.fields[field_idx].name == ROWID_COLUMN {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' is the rowid and cannot be assigned",
+                    field_name
+                )));
+            }
+            // an assigned value would be outside the sequence, which only BY DEFAULT
+            // columns move past
+            if table_schema.identity.get(field_idx) == Some(&Some(Identity::Always)) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' is GENERATED ALWAYS AS IDENTITY and cannot be assigned",
+                    field_name
+                )));
+            }
+
             if compiled.iter().any(|(idx, _)| *idx == field_idx) {
                 return Err(QueryResult::user_input_wrong(format!(
                     "Column '{}' is assigned more than once",
@@ -564,7 +2490,19 @@ impl Planner {
             }
 
             let field_schema = &table_schema.fields[field_idx];
-            let compiled_val = Self::compile_value(value_str, field_schema)?;
+            let compiled_val = match value_str {
+                Some(value) => Some(Self::compile_parsed_value(value, field_schema)?),
+                None if table_schema.is_key_column(field_idx) => {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Key column '{}' cannot be NULL",
+                        field_name
+                    )));
+                }
+                None if table_schema.not_null.get(field_idx) == Some(&true) => {
+                    return Err(Self::not_null_violation(table_schema, field_idx));
+                }
+                None => None,
+            };
             compiled.push((field_idx, compiled_val));
         }
 
@@ -597,12 +2535,41 @@ impl Planner {
             .get_schema(schema)
             .map_err(|_| QueryResult::user_input_wrong("".to_string()))?;
         let mut projected_fields = Vec::new();
+        // columns the source must still deliver, i.e. without the extracted outputs
+        let mut required_fields = Vec::new();
+        // (JSON column, path, output field)
+        let mut json_extractions = Vec::new();
         if select_query.result.len() == 1 && select_query.result[0] == "*" {
-            projected_fields = source_schema.fields.clone();
+            projected_fields = source_schema
+                .fields
+                .iter()
+                .filter(|f| f.name != ROWID_COLUMN)
+                .cloned()
+                .collect();
+            required_fields = projected_fields.clone();
         } else {
-            for req_field in select_query.result {
-                let field = Self::resolve_field(&req_field, &source_schema)?;
-                projected_fields.push(field);
+            for (label, expr) in select_query.result.iter().zip(&select_query.result_exprs) {
+                match expr {
+                    ParsedValueExpr::Token(req_field) => {
+                        let field = Self::resolve_field(req_field, &source_schema)?;
+                        projected_fields.push(field.clone());
+                        Self::push_unique_field(&mut required_fields, field);
+                    }
+                    ParsedValueExpr::JsonExtract { column, path } => {
+                        let (column_idx, path) =
+                            Self::resolve_json_extract(column, path, &source_schema)?;
+                        let column_field = source_schema.fields[column_idx].clone();
+                        let output = Field {
+                            field_type: Type::String,
+                            name: label.clone(),
+                            table_name: String::new(),
+                            collation: Collation::Binary,
+                        };
+                        projected_fields.push(output.clone());
+                        Self::push_unique_field(&mut required_fields, column_field.clone());
+                        json_extractions.push((column_field, path, output));
+                    }
+                }
             }
         }
 
@@ -617,8 +2584,31 @@ impl Planner {
             source_plan
         };
 
-        let optimized_plan =
-            Self::pushdown_projections(schema, filtered_plan, projected_fields.clone())?;
+        let mut optimized_plan =
+            Self::pushdown_projections(schema, filtered_plan, required_fields)?;
+
+        if !json_extractions.is_empty() {
+            let source_schema = optimized_plan
+                .get_schema(schema)
+                .map_err(|_| QueryResult::user_input_wrong("".to_string()))?;
+            let mut extractions = Vec::new();
+            for (column_field, path, output) in json_extractions {
+                let column_idx = source_schema
+                    .fields
+                    .iter()
+                    .position(|f| Self::same_field(f, &column_field))
+                    .ok_or_else(|| QueryResult::msg("JSON column was projected away"))?;
+                extractions.push(JsonExtraction {
+                    column_idx,
+                    path,
+                    output,
+                });
+            }
+            optimized_plan = PlanNode::JsonExtract {
+                source: Box::new(optimized_plan),
+                extractions,
+            };
+        }
 
         let optimized_schema = optimized_plan
             .get_schema(schema)
@@ -701,6 +2691,14 @@ impl Planner {
     }
 
     fn plan_table_source(schema: &Schema, table_name: String) -> Result<PlanNode, QueryResult> {
+        if let Some(view) = schema
+            .views
+            .iter()
+            .find(|view| !view.materialized && view.name == table_name)
+        {
+            let plan = Self::plan_tree_node(schema, view.query.clone())?;
+            return Self::relabel_view_plan(schema, plan, &view.name);
+        }
         let table_id = Self::find_table_id(schema, &table_name)?;
         Ok(PlanNode::SeqScan {
             table_id,
@@ -712,6 +2710,42 @@ impl Planner {
         })
     }
 
+    /// Labels the columns of a view's plan with the view's name, so `view.column` resolves
+    /// like a table's column, in joins too. The view's own Project takes the labels, other
+    /// plans get one.
+    fn relabel_view_plan(
+        schema: &Schema,
+        plan: PlanNode,
+        view_name: &str,
+    ) -> Result<PlanNode, QueryResult> {
+        let (source, fields) = match plan {
+            PlanNode::Project { source, fields, .. } => (*source, fields),
+            other => {
+                let fields = other
+                    .get_schema(schema)
+                    .map_err(|_| QueryResult::user_input_wrong("".to_string()))?
+                    .fields;
+                (other, fields)
+            }
+        };
+        let fields: Vec<Field> = fields
+            .into_iter()
+            .map(|field| Field {
+                table_name: view_name.to_string(),
+                ..field
+            })
+            .collect();
+        let source_schema = source
+            .get_schema(schema)
+            .map_err(|_| QueryResult::user_input_wrong("".to_string()))?;
+        let lookup_key_field_idx = Self::compute_project_lookup_hint(&source_schema, &fields);
+        Ok(PlanNode::Project {
+            source: Box::new(source),
+            fields,
+            lookup_key_field_idx,
+        })
+    }
+
     fn plan_join_source(schema: &Schema, parsed_join: ParsedJoin) -> Result<PlanNode, QueryResult> {
         let mut sources = parsed_join.sources.into_iter();
         let first_source = sources
@@ -736,7 +2770,7 @@ impl Planner {
 
             let join_conditions = if cond.join_type == JoinType::Natural {
                 let mut natural_conds = Vec::new();
-                for l_field in &left_schema.fields {
+                for l_field in left_schema.fields.iter().filter(|f| f.name != ROWID_COLUMN) {
                     if let Some(r_field) = right_schema
                         .fields
                         .iter()
@@ -759,6 +2793,7 @@ impl Planner {
                             field_type: Type::Null,
                             name: parts[1].to_string(),
                             table_name: parts[0].to_string(),
+                            collation: Collation::Binary,
                         };
                         if l_sch.get_column_and_field(&field).is_some() {
                             return Ok(('L', field));
@@ -766,8 +2801,8 @@ impl Planner {
                             return Ok(('R', field));
                         } else {
                             return Err(QueryResult::user_input_wrong(format!(
-                                "Column '{:?}' not found in join source",
-                                field
+                                "Column '{}' not found in join source",
+                                token
                             )));
                         }
                     } else if parts.len() == 1 {
@@ -948,7 +2983,34 @@ impl Planner {
                     left,
                     operator,
                     right,
+                    collation,
                 } => {
+                    if let ParsedValueExpr::JsonExtract { column, path } = left {
+                        if collation.is_some() {
+                            return Err(QueryResult::user_input_wrong(
+                                "COLLATE is not supported on JSON extraction".to_string(),
+                            ));
+                        }
+                        let (column_idx, path) = Self::resolve_json_extract(column, path, schema)?;
+                        let value = match right {
+                            ParsedValueExpr::Token(t) => t.clone(),
+                            ParsedValueExpr::JsonExtract { .. } => {
+                                return Err(QueryResult::user_input_wrong(
+                                    "JSON extraction is only supported on the left of a comparison"
+                                        .to_string(),
+                                ));
+                            }
+                        };
+                        let op = Self::compile_comparison_operator(operator)?;
+                        return Ok(CompiledConditionExpr::Predicate(
+                            CompiledPredicateExpr::JsonCompare {
+                                column_idx,
+                                path,
+                                op,
+                                value,
+                            },
+                        ));
+                    }
                     let col_idx = match Self::resolve_value_as_column_index(left, schema) {
                         Ok(idx) => idx,
                         Err(_) => {
@@ -957,6 +3019,7 @@ impl Planner {
                                     column_idx: 0,
                                     op: SqlStatementComparisonOperator::None,
                                     value: Vec::new(),
+                                    collation: Collation::Binary,
                                 },
                             ));
                         }
@@ -964,14 +3027,39 @@ impl Planner {
                     let field = &schema.fields[col_idx];
                     let right_token = match right {
                         ParsedValueExpr::Token(t) => t,
+                        ParsedValueExpr::JsonExtract { .. } => {
+                            return Err(QueryResult::user_input_wrong(
+                                "JSON extraction is only supported on the left of a comparison"
+                                    .to_string(),
+                            ));
+                        }
                     };
-                    let value = Self::compile_value(&right_token.clone(), field)?;
                     let op = Self::compile_comparison_operator(operator)?;
+                    let (op, value) = match field.field_type {
+                        Type::Decimal(precision, scale) => {
+                            Self::compile_decimal_comparison(right_token, op, precision, scale)?
+                        }
+                        _ => (op, Self::compile_value(right_token, field)?),
+                    };
+                    let collation = match collation {
+                        Some(name) => Self::compile_collation(name, &field.field_type)?,
+                        None => field.collation,
+                    };
                     Ok(CompiledConditionExpr::Predicate(
                         CompiledPredicateExpr::Compare {
                             column_idx: col_idx,
                             op,
                             value,
+                            collation,
+                        },
+                    ))
+                }
+                ParsedPredicateExpr::IsNull { left, negated } => {
+                    let col_idx = Self::resolve_value_as_column_index(left, schema)?;
+                    Ok(CompiledConditionExpr::Predicate(
+                        CompiledPredicateExpr::IsNull {
+                            column_idx: col_idx,
+                            negated: *negated,
                         },
                     ))
                 }
@@ -995,6 +3083,11 @@ impl Planner {
     ) -> Result<usize, QueryResult> {
         let token = match value_expr {
             ParsedValueExpr::Token(t) => t,
+            ParsedValueExpr::JsonExtract { .. } => {
+                return Err(QueryResult::user_input_wrong(
+                    "Expected a column, found a JSON extraction".to_string(),
+                ));
+            }
         };
         let field = Self::resolve_field(token, schema)?;
         schema
@@ -1004,6 +3097,24 @@ impl Planner {
             .ok_or_else(|| QueryResult::user_input_wrong("Column not found in schema".to_string()))
     }
 
+    /// Resolves the column of a JSON extraction, which must be a JSON column, and parses its path.
+    fn resolve_json_extract(
+        column: &str,
+        path: &str,
+        schema: &TableSchema,
+    ) -> Result<(usize, JsonPath), QueryResult> {
+        let column_idx =
+            Self::resolve_value_as_column_index(&ParsedValueExpr::Token(column.to_string()), schema)?;
+        if schema.fields[column_idx].field_type != Type::Json {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Column '{}' is not of type JSON",
+                column
+            )));
+        }
+        let path = JsonPath::parse(path).map_err(QueryResult::err)?;
+        Ok((column_idx, path))
+    }
+
     fn compile_in_strategy(
         schema: &Schema,
         subquery: &Box<ParsedQueryTreeNode>,
@@ -1018,27 +3129,78 @@ impl Planner {
         table_schema: &TableSchema,
         condition: &Option<CompiledConditionExpr>,
     ) -> (SqlConditionOpCode, Option<Vec<u8>>) {
-        if let Some(CompiledConditionExpr::Predicate(CompiledPredicateExpr::Compare {
-            column_idx,
-            op,
-            value,
-        })) = condition
+        match condition
+            .as_ref()
+            .and_then(|c| Self::derive_key_seek(table_schema, c))
         {
-            if *column_idx != table_schema.key_position {
-                return (SqlConditionOpCode::SelectFTS, None);
+            Some((operation, seek_key)) => (operation, Some(seek_key)),
+            None => (SqlConditionOpCode::SelectFTS, None),
+        }
+    }
+
+    /// Seeks into the key tree using the conjuncts of `condition`: equalities on the
+    /// leading key columns form a key prefix, and a lower bound on the next key column may
+    /// extend it. The condition is still applied to every row after the seek.
+    fn derive_key_seek(
+        table_schema: &TableSchema,
+        condition: &CompiledConditionExpr,
+    ) -> Option<(SqlConditionOpCode, Vec<u8>)> {
+        let conjuncts = Self::extract_conjuncts(condition.clone());
+        let mut operation = None;
+        let mut seek_key = Vec::new();
+
+        for &key_idx in &table_schema.key_positions {
+            let bounds: Vec<(&SqlStatementComparisonOperator, &Vec<u8>)> = conjuncts
+                .iter()
+                .filter_map(|term| match term {
+                    CompiledConditionExpr::Predicate(CompiledPredicateExpr::Compare {
+                        column_idx,
+                        op,
+                        value,
+                        collation,
+                    }) if *column_idx == key_idx
+                        // the tree is ordered by the key's own collation
+                        && *collation == table_schema.fields[key_idx].collation =>
+                    {
+                        Some((op, value))
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            let field_type = &table_schema.fields[key_idx].field_type;
+            if let Some((_, value)) = bounds
+                .iter()
+                .find(|(op, _)| **op == SqlStatementComparisonOperator::Equal)
+            {
+                seek_key.extend(Self::seek_part(value, field_type));
+                operation = Some(SqlConditionOpCode::SelectKeyUnique);
+                continue;
             }
-            return match op {
-                SqlStatementComparisonOperator::Equal => {
-                    (SqlConditionOpCode::SelectKeyUnique, Some(value.clone()))
-                }
-                SqlStatementComparisonOperator::Greater
-                | SqlStatementComparisonOperator::GreaterOrEqual => {
-                    (SqlConditionOpCode::SelectKeyRange, Some(value.clone()))
-                }
-                _ => (SqlConditionOpCode::SelectFTS, None),
-            };
+            if let Some((_, value)) = bounds.iter().find(|(op, _)| {
+                matches!(
+                    op,
+                    SqlStatementComparisonOperator::Greater
+                        | SqlStatementComparisonOperator::GreaterOrEqual
+                )
+            }) {
+                seek_key.extend(Self::seek_part(value, field_type));
+                operation = Some(SqlConditionOpCode::SelectKeyRange);
+            }
+            break;
+        }
+
+        operation.map(|operation| (operation, seek_key))
+    }
+
+    /// The bytes a seek starts at for a value compared with a column of `field_type`. A Text
+    /// value is a content, the tree holds payload descriptors, so the seek starts at a
+    /// descriptor of its inline prefix.
+    fn seek_part(value: &[u8], field_type: &Type) -> Vec<u8> {
+        match field_type {
+            Type::Text => Serializer::payload_seek_bound(value),
+            _ => value.to_vec(),
         }
-        (SqlConditionOpCode::SelectFTS, None)
     }
 
     fn apply_scan_hint_to_source(
@@ -1060,46 +3222,37 @@ impl Planner {
                 let mut index_table_id = existing_index_table_id;
                 let mut index_on_column = existing_index_on_column;
 
-                if let CompiledConditionExpr::Predicate(CompiledPredicateExpr::Compare {
+                let table_schema = &global_schema.tables[table_id];
+                if let Some((key_operation, key_seek)) =
+                    Self::derive_key_seek(table_schema, condition)
+                {
+                    operation = key_operation;
+                    seek_key = Some(key_seek);
+                } else if let CompiledConditionExpr::Predicate(CompiledPredicateExpr::Compare {
                     column_idx,
                     op,
                     value,
+                    collation,
                 }) = condition
+                    // the key tree already orders the leading key column
+                    && table_schema.key_positions.first() != Some(column_idx)
+                    // index trees are ordered by the column's own collation
+                    && let Some(field) = table_schema
+                        .fields
+                        .get(*column_idx)
+                        .filter(|f| f.collation == *collation)
+                    && let Some(index_id) =
+                        Self::find_index_table_id(global_schema, &table_name, &field.name)
                 {
-                    let table_schema = &global_schema.tables[table_id];
-                    if *column_idx == table_schema.key_position {
-                        operation = match op {
-                            SqlStatementComparisonOperator::Equal => {
-                                SqlConditionOpCode::SelectKeyUnique
-                            }
-                            SqlStatementComparisonOperator::Greater
-                            | SqlStatementComparisonOperator::GreaterOrEqual => {
-                                SqlConditionOpCode::SelectKeyRange
-                            }
-                            _ => SqlConditionOpCode::SelectFTS,
-                        };
-                        if matches!(
-                            operation,
-                            SqlConditionOpCode::SelectKeyUnique
-                                | SqlConditionOpCode::SelectKeyRange
-                        ) {
-                            seek_key = Some(value.clone());
-                        }
-                    } else if let Some(field) = table_schema.fields.get(*column_idx) {
-                        if let Some(index_id) =
-                            Self::find_index_table_id(global_schema, &table_name, &field.name)
-                        {
-                            operation = match op {
-                                SqlStatementComparisonOperator::Equal => {
-                                    SqlConditionOpCode::SelectIndexUnique
-                                }
-                                _ => SqlConditionOpCode::SelectIndexRange,
-                            };
-                            index_table_id = Some(index_id);
-                            index_on_column = Some(*column_idx);
-                            seek_key = Some(value.clone());
+                    operation = match op {
+                        SqlStatementComparisonOperator::Equal => {
+                            SqlConditionOpCode::SelectIndexUnique
                         }
-                    }
+                        _ => SqlConditionOpCode::SelectIndexRange,
+                    };
+                    index_table_id = Some(index_id);
+                    index_on_column = Some(*column_idx);
+                    seek_key = Some(Self::seek_part(value, &field.field_type));
                 }
 
                 PlanNode::SeqScan {
@@ -1159,6 +3312,63 @@ impl Planner {
                 })
             }
             PlanNode::Filter { source, condition } => {
+                // a filter above a projection or another filter, e.g. on a view, moves down
+                // towards the scan
+                let source = match *source {
+                    PlanNode::Project {
+                        source: project_source,
+                        fields,
+                        lookup_key_field_idx,
+                    } => match Self::projection_positions(global_schema, &project_source, &fields) {
+                        Some(positions) => {
+                            let condition =
+                                Self::map_condition_columns(&condition, &|idx| positions[idx]);
+                            let hinted = Self::apply_scan_hint_to_source(
+                                global_schema,
+                                *project_source,
+                                &condition,
+                            );
+                            return Self::pushdown_projections(
+                                global_schema,
+                                PlanNode::Project {
+                                    source: Box::new(PlanNode::Filter {
+                                        source: Box::new(hinted),
+                                        condition,
+                                    }),
+                                    fields,
+                                    lookup_key_field_idx,
+                                },
+                                required_fields,
+                            );
+                        }
+                        None => Box::new(PlanNode::Project {
+                            source: project_source,
+                            fields,
+                            lookup_key_field_idx,
+                        }),
+                    },
+                    PlanNode::Filter {
+                        source: inner,
+                        condition: inner_condition,
+                    } => {
+                        let condition = CompiledConditionExpr::Logical {
+                            op: CompiledLogicalOp::And,
+                            left: Box::new(inner_condition),
+                            right: Box::new(condition),
+                        };
+                        let hinted =
+                            Self::apply_scan_hint_to_source(global_schema, *inner, &condition);
+                        return Self::pushdown_projections(
+                            global_schema,
+                            PlanNode::Filter {
+                                source: Box::new(hinted),
+                                condition,
+                            },
+                            required_fields,
+                        );
+                    }
+                    other => Box::new(other),
+                };
                 // Keep filter column indices stable while traversing this node.
                 let source_schema = source
                     .get_schema(global_schema)
@@ -1261,6 +3471,18 @@ impl Planner {
                 } else {
                     projected_fields.clone()
                 };
+                // the source knows relabeled fields under its own labels
+                let unoptimized_schema = source
+                    .get_schema(global_schema)
+                    .map_err(|_| QueryResult::user_input_wrong("".to_string()))?;
+                let child_required = child_required
+                    .iter()
+                    .map(|field| {
+                        Self::source_position(&unoptimized_schema, field)
+                            .map(|idx| unoptimized_schema.fields[idx].clone())
+                            .unwrap_or_else(|| field.clone())
+                    })
+                    .collect();
 
                 let optimized_source =
                     Self::pushdown_projections(global_schema, *source, child_required)?;
@@ -1303,14 +3525,14 @@ impl Planner {
         source_schema: &TableSchema,
         projected_fields: &Vec<Field>,
     ) -> Option<usize> {
-        if !source_schema.has_key || source_schema.key_position >= source_schema.fields.len() {
-            return None;
-        }
-
-        let key_field = &source_schema.fields[source_schema.key_position];
+        // lookups by key bytes only work when the key is a single field
+        let key_idx = match source_schema.key_positions.as_slice() {
+            [idx] if source_schema.has_key => idx,
+            _ => return None,
+        };
         projected_fields
             .iter()
-            .position(|f| Self::same_field(f, key_field))
+            .position(|f| Self::source_position(source_schema, f) == Some(*key_idx))
     }
 
     fn split_filter_terms_for_join(
@@ -1376,6 +3598,8 @@ impl Planner {
                 let idx = match pred {
                     CompiledPredicateExpr::Compare { column_idx, .. } => *column_idx,
                     CompiledPredicateExpr::InSubquery { column_idx, .. } => *column_idx,
+                    CompiledPredicateExpr::JsonCompare { column_idx, .. } => *column_idx,
+                    CompiledPredicateExpr::IsNull { column_idx, .. } => *column_idx,
                 };
 
                 if idx < left_width {
@@ -1399,6 +3623,37 @@ impl Planner {
     fn remap_condition_for_right(
         condition: &CompiledConditionExpr,
         offset: usize,
+    ) -> CompiledConditionExpr {
+        Self::map_condition_columns(condition, &|idx| idx - offset)
+    }
+
+    /// Where each projected field sits in the rows of the projection's source, if all of
+    /// them are plain source columns.
+    fn projection_positions(
+        global_schema: &Schema,
+        source: &PlanNode,
+        fields: &Vec<Field>,
+    ) -> Option<Vec<usize>> {
+        let source_schema = source.get_schema(global_schema).ok()?;
+        fields
+            .iter()
+            .map(|field| Self::source_position(&source_schema, field))
+            .collect()
+    }
+
+    /// The column of `source_schema` a Project delivers as `field`. Projects find their
+    /// columns by name, so a relabeled field still finds the source column it came from.
+    fn source_position(source_schema: &TableSchema, field: &Field) -> Option<usize> {
+        source_schema
+            .fields
+            .iter()
+            .position(|f| Self::same_field(f, field))
+            .or_else(|| source_schema.fields.iter().position(|f| f.name == field.name))
+    }
+
+    fn map_condition_columns(
+        condition: &CompiledConditionExpr,
+        map: &dyn Fn(usize) -> usize,
     ) -> CompiledConditionExpr {
         match condition {
             CompiledConditionExpr::Predicate(pred) => match pred {
@@ -1406,23 +3661,43 @@ impl Planner {
                     column_idx,
                     op,
                     value,
+                    collation,
                 } => CompiledConditionExpr::Predicate(CompiledPredicateExpr::Compare {
-                    column_idx: column_idx - offset,
+                    column_idx: map(*column_idx),
                     op: *op,
                     value: value.clone(),
+                    collation: *collation,
                 }),
                 CompiledPredicateExpr::InSubquery {
                     column_idx,
                     strategy,
                 } => CompiledConditionExpr::Predicate(CompiledPredicateExpr::InSubquery {
-                    column_idx: column_idx - offset,
+                    column_idx: map(*column_idx),
                     strategy: strategy.clone(),
                 }),
+                CompiledPredicateExpr::JsonCompare {
+                    column_idx,
+                    path,
+                    op,
+                    value,
+                } => CompiledConditionExpr::Predicate(CompiledPredicateExpr::JsonCompare {
+                    column_idx: map(*column_idx),
+                    path: path.clone(),
+                    op: *op,
+                    value: value.clone(),
+                }),
+                CompiledPredicateExpr::IsNull {
+                    column_idx,
+                    negated,
+                } => CompiledConditionExpr::Predicate(CompiledPredicateExpr::IsNull {
+                    column_idx: map(*column_idx),
+                    negated: *negated,
+                }),
             },
             CompiledConditionExpr::Logical { op, left, right } => CompiledConditionExpr::Logical {
                 op: op.clone(),
-                left: Box::new(Self::remap_condition_for_right(left, offset)),
-                right: Box::new(Self::remap_condition_for_right(right, offset)),
+                left: Box::new(Self::map_condition_columns(left, map)),
+                right: Box::new(Self::map_condition_columns(right, map)),
             },
         }
     }
@@ -1466,7 +3741,29 @@ impl Planner {
         }
     }
 
-    fn compile_value(value: &String, field_schema: &Field) -> Result<Vec<u8>, QueryResult> {
+    /// Compiles a value of VALUES, SET or DEFAULT. A call is evaluated here and its result
+    /// compiled like a literal, so `gen_random_uuid()` also fits text columns.
+    pub(crate) fn compile_parsed_value(
+        value: &ParsedValue,
+        field_schema: &Field,
+    ) -> Result<Vec<u8>, QueryResult> {
+        match value {
+            ParsedValue::Literal(text) => Self::compile_value(text, field_schema),
+            ParsedValue::Call(name) if name == "gen_random_uuid" => Self::compile_value(
+                &Serializer::format_uuid(&Serializer::generate_uuid_v4()),
+                field_schema,
+            ),
+            ParsedValue::Call(name) => Err(QueryResult::user_input_wrong(format!(
+                "Unknown function: {}",
+                name
+            ))),
+            ParsedValue::NextVal(_) => Err(QueryResult::user_input_wrong(
+                "nextval() is only allowed in INSERT values and column defaults".to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn compile_value(value: &String, field_schema: &Field) -> Result<Vec<u8>, QueryResult> {
         match field_schema.field_type {
             Type::Integer => {
                 let _ = value.parse::<i32>().map_err(|_| {
@@ -1476,9 +3773,24 @@ impl Planner {
                     .map_err(QueryResult::err)?
                     .to_vec())
             }
-            Type::String => Ok(Serializer::parse_string(value).to_vec()),
+            Type::String => {
+                if value.len() >= STRING_SIZE {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "Value of {} bytes exceeds STRING ({} bytes), use TEXT for longer values",
+                        value.len(),
+                        STRING_SIZE - 1
+                    )));
+                }
+                Ok(Serializer::parse_string(value).to_vec())
+            }
+            Type::Text => Ok(value.as_bytes().to_vec()),
+            // stored in compact form, so equal documents compare equal
+            Type::Json => Ok(JsonValue::parse(value)
+                .map_err(QueryResult::err)?
+                .to_string()
+                .into_bytes()),
             Type::Varchar(max_len) => {
-                if value.len() > max_len {
+                if value.chars().count() > max_len {
                     return Err(QueryResult::user_input_wrong(format!(
                         "'{}' exceeds VARCHAR({})",
                         value, max_len
@@ -1486,16 +3798,76 @@ impl Planner {
                 }
                 Ok(Serializer::parse_varchar(value, max_len))
             }
+            Type::VarcharBytes(max_len) => {
+                if value.len() > max_len {
+                    return Err(QueryResult::user_input_wrong(format!(
+                        "'{}' needs {} bytes of UTF-8, more than VARCHAR({}) holds in a table of an older file; ALTER the column to VARCHAR({}) to count characters, or use TEXT",
+                        value,
+                        value.len(),
+                        max_len,
+                        max_len
+                    )));
+                }
+                Ok(Serializer::parse_varchar_bytes(value, max_len))
+            }
+            Type::Decimal(precision, scale) => {
+                Serializer::parse_decimal(value, precision, scale).map_err(QueryResult::err)
+            }
             Type::Date => Ok(Vec::from(
                 Serializer::parse_date(value).map_err(QueryResult::err)?,
             )),
+            Type::Timestamp => Serializer::parse_timestamp(value, false).map_err(QueryResult::err),
+            Type::TimestampTz => Serializer::parse_timestamp(value, true).map_err(QueryResult::err),
+            Type::Time => Serializer::parse_time(value).map_err(QueryResult::err),
+            Type::Uuid => Serializer::parse_uuid(value).map_err(QueryResult::err),
             Type::Boolean => Ok(vec![
                 Serializer::parse_bool(value).map_err(QueryResult::err)?,
             ]),
+            // payload-backed values stay raw here, the executor stores them
+            Type::Blob => Serializer::parse_blob(value).map_err(QueryResult::err),
             Type::Null => Ok(vec![]),
         }
     }
 
+    /// Compiles `column op literal` for a `Decimal(precision, scale)` column without
+    /// rounding the literal to the column's scale: the literal becomes the nearest bound
+    /// at that scale in the direction of `op`, so `amount > 2.346` is `amount > 2.34`
+    /// and `amount = 2.345` matches nothing. Out-of-range literals make the comparison
+    /// true or false for every non-NULL value.
+    fn compile_decimal_comparison(
+        literal: &str,
+        op: SqlStatementComparisonOperator,
+        precision: usize,
+        scale: usize,
+    ) -> Result<(SqlStatementComparisonOperator, Vec<u8>), QueryResult> {
+        use SqlStatementComparisonOperator as Op;
+        let (floor, ceiling) =
+            Serializer::decimal_literal_bounds(literal, scale).map_err(QueryResult::err)?;
+        let max = 10i128.pow(precision as u32) - 1;
+        let min = -max;
+        // a comparison that holds for every value, and one that holds for none
+        let all = (Op::LesserOrEqual, max);
+        let none = (Op::Greater, max);
+        let (op, bound) = match op {
+            Op::Equal if floor != ceiling || floor > max || floor < min => none,
+            Op::Equal => (Op::Equal, floor),
+            Op::Greater if floor >= max => none,
+            Op::Greater if floor < min => all,
+            Op::Greater => (Op::Greater, floor),
+            Op::GreaterOrEqual if ceiling > max => none,
+            Op::GreaterOrEqual if ceiling <= min => all,
+            Op::GreaterOrEqual => (Op::GreaterOrEqual, ceiling),
+            Op::Lesser if ceiling <= min => none,
+            Op::Lesser if ceiling > max => all,
+            Op::Lesser => (Op::Lesser, ceiling),
+            Op::LesserOrEqual if floor < min => none,
+            Op::LesserOrEqual if floor >= max => all,
+            Op::LesserOrEqual => (Op::LesserOrEqual, floor),
+            Op::None => (Op::None, floor),
+        };
+        Ok((op, Serializer::decimal_to_bytes(bound, precision)))
+    }
+
     fn compile_comparison_operator(
         token: &str,
     ) -> Result<SqlStatementComparisonOperator, QueryResult> {
@@ -1512,6 +3884,93 @@ impl Planner {
         }
     }
 
+    /// Resolves the `PRIMARY KEY` columns to field positions in key order. Without a
+    /// declaration the first column is the key.
+    fn compile_primary_key(
+        primary_key: &[String],
+        fields: &[Field],
+    ) -> Result<Vec<usize>, QueryResult> {
+        let mut key_positions = Vec::new();
+        for name in primary_key {
+            let idx = fields
+                .iter()
+                .position(|f| f.name == *name)
+                .ok_or_else(|| {
+                    QueryResult::user_input_wrong(format!(
+                        "Column '{}' in PRIMARY KEY not found",
+                        name
+                    ))
+                })?;
+            if key_positions.contains(&idx) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Column '{}' appears more than once in PRIMARY KEY",
+                    name
+                )));
+            }
+            key_positions.push(idx);
+        }
+        if key_positions.is_empty() {
+            key_positions.push(0);
+        }
+
+        for &idx in &key_positions {
+            // Text keys compare by their content; Blob and Json cannot be keys
+            if matches!(fields[idx].field_type, Type::Blob | Type::Json) {
+                return Err(QueryResult::user_input_wrong(format!(
+                    "Type '{:?}' cannot be used for the key column '{}'",
+                    fields[idx].field_type, fields[idx].name
+                )));
+            }
+        }
+        Ok(key_positions)
+    }
+
+    /// Compiles a CHECK condition over the columns of `schema`. Unlike a WHERE clause every
+    /// column must exist, and subqueries are not allowed.
+    fn compile_check(
+        check: &ParsedConditionExpr,
+        schema: &TableSchema,
+    ) -> Result<CompiledConditionExpr, QueryResult> {
+        Self::validate_check(check, schema)?;
+        Self::compile_condition_expr(&Schema::make_empty(), schema, check)
+    }
+
+    fn validate_check(check: &ParsedConditionExpr, schema: &TableSchema) -> Result<(), QueryResult> {
+        match check {
+            ParsedConditionExpr::Logical { left, right, .. } => {
+                Self::validate_check(left, schema)?;
+                Self::validate_check(right, schema)
+            }
+            ParsedConditionExpr::Predicate(
+                ParsedPredicateExpr::Compare { left, .. } | ParsedPredicateExpr::IsNull { left, .. },
+            ) => match left {
+                ParsedValueExpr::JsonExtract { column, path } => {
+                    Self::resolve_json_extract(column, path, schema).map(|_| ())
+                }
+                ParsedValueExpr::Token(_) => {
+                    Self::resolve_value_as_column_index(left, schema).map(|_| ())
+                }
+            },
+            ParsedConditionExpr::Predicate(ParsedPredicateExpr::InSubquery { .. }) => {
+                Err(QueryResult::user_input_wrong(
+                    "Subqueries are not allowed in CHECK constraints".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Resolves a `COLLATE` name; collations only apply to text columns.
+    fn compile_collation(name: &str, field_type: &Type) -> Result<Collation, QueryResult> {
+        let collation = Collation::from_name(name).map_err(QueryResult::user_input_wrong)?;
+        if !matches!(field_type, Type::String | Type::Varchar(_) | Type::VarcharBytes(_) | Type::Text) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "COLLATE {} cannot be applied to type '{:?}'",
+                collation, field_type
+            )));
+        }
+        Ok(collation)
+    }
+
     fn find_index_table_id(schema: &Schema, base_table: &str, column: &str) -> Option<usize> {
         let index_name = schema
             .index_definitions
@@ -1549,12 +4008,46 @@ impl FromStr for Type {
             return Ok(Type::Varchar(length));
         }
 
+        if lowered.starts_with("decimal(") && lowered.ends_with(')') {
+            let inner = &lowered[8..lowered.len() - 1];
+            let mut params = inner.split(',').map(|p| p.trim().parse::<usize>());
+            let precision = match params.next() {
+                Some(Ok(p)) => p,
+                _ => return Err(format!("Invalid type: {}", s)),
+            };
+            let scale = match params.next() {
+                Some(Ok(sc)) => sc,
+                None => 0,
+                Some(Err(_)) => return Err(format!("Invalid type: {}", s)),
+            };
+            if params.next().is_some() {
+                return Err(format!("Invalid type: {}", s));
+            }
+            if precision == 0 || precision > DECIMAL_MAX_PRECISION {
+                return Err(format!(
+                    "Invalid type: DECIMAL precision must be between 1 and {}",
+                    DECIMAL_MAX_PRECISION
+                ));
+            }
+            if scale > precision {
+                return Err("Invalid type: DECIMAL scale must not exceed its precision".to_string());
+            }
+            return Ok(Type::Decimal(precision, scale));
+        }
+
         match lowered.as_str() {
             "null" => Ok(Type::Null),
             "integer" => Ok(Type::Integer),
             "string" => Ok(Type::String),
+            "text" => Ok(Type::Text),
             "date" => Ok(Type::Date),
+            "timestamp" => Ok(Type::Timestamp),
+            "timestamptz" => Ok(Type::TimestampTz),
+            "time" => Ok(Type::Time),
+            "uuid" => Ok(Type::Uuid),
+            "json" => Ok(Type::Json),
             "boolean" => Ok(Type::Boolean),
+            "blob" => Ok(Type::Blob),
             _ => Err(format!("Invalid type: {}", s)),
         }
     }
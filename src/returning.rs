@@ -0,0 +1,78 @@
+use crate::dataframe::DataFrame;
+use crate::executor::{QueryExecutor, QueryResult};
+use crate::pager::Row;
+use crate::pager_proxy::PageManager;
+use crate::serializer::Serializer;
+use std::collections::HashMap;
+
+/// The rows a write statement hands back for RETURNING, projected to the listed columns.
+/// Payload-backed fields are read as the rows are collected, since a DELETE releases them.
+pub(crate) struct ReturnedRows {
+    table_id: usize,
+    /// field indices of the RETURNING columns; `None` if the statement has no RETURNING
+    fields: Option<Vec<usize>>,
+    rows: Vec<Row>,
+    payload_contents: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl ReturnedRows {
+    pub(crate) fn new(table_id: usize, fields: Option<Vec<usize>>) -> Self {
+        ReturnedRows {
+            table_id,
+            fields,
+            rows: Vec::new(),
+            payload_contents: HashMap::new(),
+        }
+    }
+}
+
+impl QueryExecutor {
+    /// Adds full rows of the statement's table to `returned`, unless there is no RETURNING.
+    pub(crate) fn collect_returned_rows(
+        &self,
+        returned: &mut ReturnedRows,
+        rows: &[Row],
+    ) -> Result<(), QueryResult> {
+        let Some(fields) = &returned.fields else {
+            return Ok(());
+        };
+        let table = &self.schema.tables[returned.table_id];
+        for row in rows {
+            let mut projected = Vec::new();
+            for &field_idx in fields {
+                let value =
+                    Serializer::get_field_on_row(row, field_idx, table).map_err(QueryResult::err)?;
+                let field_type = &table.fields[field_idx].field_type;
+                if field_type.is_payload_backed()
+                    && !Serializer::is_null(&value, field_type).map_err(QueryResult::err)?
+                {
+                    let content = PageManager::read_payload_field(self.pager_accessor.clone(), &value)
+                        .map_err(QueryResult::err)?;
+                    returned.payload_contents.insert(value.clone(), content);
+                }
+                projected.extend(value);
+            }
+            returned.rows.push(projected);
+        }
+        Ok(())
+    }
+
+    /// The result of a write statement: its returned rows if it has RETURNING, keeping the
+    /// generated keys.
+    pub(crate) fn returning_result(&self, returned: ReturnedRows, result: QueryResult) -> QueryResult {
+        let Some(fields) = returned.fields else {
+            return result;
+        };
+        let table = &self.schema.tables[returned.table_id];
+        let header = fields.iter().map(|idx| table.fields[*idx].clone()).collect();
+        let data = DataFrame::from_returned_rows(
+            format!("Returning({})", table.name),
+            header,
+            returned.rows,
+            returned.payload_contents,
+        );
+        let mut returning = QueryResult::return_data(data);
+        returning.generated_keys = result.generated_keys;
+        returning
+    }
+}
@@ -1,13 +1,33 @@
+use crate::collation::Collation;
 use crate::debug::Status;
-use crate::pager::{NODE_METADATA_SIZE, PAGE_SIZE, POSITION_SIZE, Position, TableName, Type};
-use crate::parser::JoinOp;
+use crate::pager::{
+    NODE_METADATA_SIZE, PAGE_SIZE, POSITION_SIZE, PagerAccessor, Position, TableName, Type,
+};
+use crate::pager_proxy::PageManager;
+use crate::parser::{
+    ForeignKeyAction, Identity, JoinOp, ParsedQueryTreeNode, ParsedValue, TriggerEvent, TriggerTiming,
+};
+use crate::planner::{CompiledConditionExpr, CompiledScalarExpr};
 use crate::serializer::Serializer;
+use std::cmp::Ordering;
+
+/// The hidden key column of a table created `WITH ROWID`. `SELECT *`, INSERT without a
+/// column list and NATURAL JOIN leave it out, but it can be selected by name.
+pub const ROWID_COLUMN: &str = "rowid";
+
+/// `TableSchema::table_type` of the table holding a materialized view; 0 for ordinary tables.
+pub const TABLE_TYPE_MATERIALIZED_VIEW: u8 = 1;
+/// `TableSchema::table_type` of a `CREATE TEMP TABLE` table, kept by its executor instead of
+/// the master table.
+pub const TABLE_TYPE_TEMPORARY: u8 = 2;
 
 #[derive(Debug, Clone)]
 pub struct Field {
     pub field_type: Type,
     pub name: String,
     pub table_name: String,
+    /// Only meaningful for String, Varchar and Text; Binary everywhere else
+    pub collation: Collation,
 }
 
 #[derive(Clone, Debug)]
@@ -15,6 +35,10 @@ pub struct Schema {
     pub table_index: TableIndex,
     pub tables: Vec<TableSchema>,
     pub index_definitions: Vec<IndexDefinition>,
+    pub foreign_keys: Vec<ForeignKeyDefinition>,
+    pub sequences: Vec<SequenceDefinition>,
+    pub views: Vec<ViewDefinition>,
+    pub triggers: Vec<TriggerDefinition>,
 }
 
 impl Schema {
@@ -23,6 +47,10 @@ impl Schema {
             table_index: TableIndex { index: vec![] },
             tables: vec![],
             index_definitions: vec![],
+            foreign_keys: vec![],
+            sequences: vec![],
+            views: vec![],
+            triggers: vec![],
         }
     }
 }
@@ -32,6 +60,53 @@ pub struct IndexDefinition {
     pub index_name: String,
     pub base_table: String,
     pub column_name: String,
+    /// Set by `CREATE UNIQUE INDEX` and by `UNIQUE` column constraints
+    pub unique: bool,
+}
+
+/// A `REFERENCES` constraint of `child_table.child_column` on `parent_table.parent_column`.
+/// The parent column is the parent's single-column key or has a unique index.
+#[derive(Clone, Debug)]
+pub struct ForeignKeyDefinition {
+    pub child_table: String,
+    pub child_column: String,
+    pub parent_table: String,
+    pub parent_column: String,
+    pub on_delete: ForeignKeyAction,
+    pub on_update: ForeignKeyAction,
+}
+
+/// A `CREATE SEQUENCE`. Its counter lives on page `page`, outside of any transaction.
+#[derive(Clone, Debug)]
+pub struct SequenceDefinition {
+    pub name: String,
+    pub page: usize,
+    pub increment: i64,
+}
+
+/// A `CREATE VIEW`. Its query is planned again wherever the view is used as a source,
+/// unless the view is materialized: then it is read from the table of the same name and
+/// the query only runs on REFRESH.
+#[derive(Clone, Debug)]
+pub struct ViewDefinition {
+    pub name: String,
+    pub materialized: bool,
+    pub query: ParsedQueryTreeNode,
+    /// tables and views the query reads from
+    pub tables: Vec<String>,
+}
+
+/// A `CREATE TRIGGER`. Its statements run for every row the event writes to `table_name`,
+/// in the same transaction, in the order the triggers were created.
+#[derive(Clone, Debug)]
+pub struct TriggerDefinition {
+    pub name: String,
+    pub table_name: String,
+    pub timing: TriggerTiming,
+    pub event: TriggerEvent,
+    pub statements: Vec<String>,
+    /// columns of `table_name` the statements reference as `NEW.column` or `OLD.column`
+    pub columns: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -44,8 +119,20 @@ pub struct TableSchema {
     pub next_position: Position,
     pub root: Position, //if 0 -> no tree
     pub has_key: bool,
-    pub key_position: usize,
+    /// Field positions of the key columns, in key order. The B-tree key is their
+    /// concatenated bytes; the row holds the remaining fields in field order.
+    pub key_positions: Vec<usize>,
     pub fields: Vec<Field>,
+    /// DEFAULT expressions by field position; empty for derived and index schemas
+    pub column_defaults: Vec<Option<ParsedValue>>,
+    /// NOT NULL flags by field position; empty for derived and index schemas
+    pub not_null: Vec<bool>,
+    /// CHECK conditions by field position, evaluated on the full row
+    pub checks: Vec<Option<CompiledConditionExpr>>,
+    /// Identity columns by field position; their DEFAULT is `nextval()` of their sequence
+    pub identity: Vec<Option<Identity>>,
+    /// Expressions of generated columns by field position; empty for derived and index schemas
+    pub generated: Vec<Option<CompiledScalarExpr>>,
     pub table_type: u8,
     pub entry_count: i32,
     pub name: String,
@@ -68,23 +155,109 @@ impl TableSchema {
     }
 
     pub fn get_key_length(&self) -> Result<usize, Status> {
-        if self.fields.is_empty() {
+        if self.fields.is_empty() || self.key_positions.is_empty() {
             return Err(Status::InternalExceptionCompilerError);
         }
-        if self.key_position >= self.fields.len() {
-            return Err(Status::InternalExceptionCompilerError);
+        let mut len = 0usize;
+        for &idx in &self.key_positions {
+            let field = self
+                .fields
+                .get(idx)
+                .ok_or(Status::InternalExceptionCompilerError)?;
+            len += Serializer::get_size_of_type(&field.field_type)?;
         }
-        Serializer::get_size_of_type(&self.fields[self.key_position].field_type)
+        Ok(len)
     }
 
+    /// Type of the leading key column. A composite key keeps its flags (like the tombstone
+    /// bit) in the leading column's bytes, so this is also the type to read them with.
     pub fn get_key_type(&self) -> Result<Type, Status> {
-        if self.fields.is_empty() {
-            return Err(Status::InternalExceptionCompilerError);
-        }
-        if self.key_position >= self.fields.len() {
-            return Err(Status::InternalExceptionCompilerError);
+        let idx = *self
+            .key_positions
+            .first()
+            .ok_or(Status::InternalExceptionCompilerError)?;
+        self.fields
+            .get(idx)
+            .map(|f| f.field_type.clone())
+            .ok_or(Status::InternalExceptionCompilerError)
+    }
+
+    pub fn is_key_column(&self, idx: usize) -> bool {
+        self.key_positions.contains(&idx)
+    }
+
+    pub fn is_generated_column(&self, idx: usize) -> bool {
+        matches!(self.generated.get(idx), Some(Some(_)))
+    }
+
+    /// Orders two keys column by column, each under its own type and collation. Keys may
+    /// be prefixes (fewer columns than the full key); only the columns both have are
+    /// compared, so a prefix is equal to every key that starts with it. Text columns compare
+    /// by their content, read through `pager` where the inline prefixes do not decide.
+    pub fn compare_keys(
+        &self,
+        a: &[u8],
+        b: &[u8],
+        pager: &PagerAccessor,
+    ) -> Result<Ordering, Status> {
+        let mut offset = 0usize;
+        for &idx in &self.key_positions {
+            let field = self
+                .fields
+                .get(idx)
+                .ok_or(Status::InternalExceptionCompilerError)?;
+            let size = Serializer::get_size_of_type(&field.field_type)?;
+            if offset + size > a.len() || offset + size > b.len() {
+                break;
+            }
+            let (a_field, b_field) = (&a[offset..offset + size], &b[offset..offset + size]);
+            let ordering = if !field.field_type.is_payload_backed() {
+                Serializer::compare_with_collation(
+                    &a_field.to_vec(),
+                    &b_field.to_vec(),
+                    &field.field_type,
+                    field.collation,
+                )?
+            } else if let Some(ordering) =
+                Serializer::compare_payload_prefixes(a_field, b_field, field.collation)
+            {
+                ordering
+            } else {
+                Serializer::compare_with_collation(
+                    &PageManager::read_payload_field(pager.clone(), a_field)?,
+                    &PageManager::read_payload_field(pager.clone(), b_field)?,
+                    &field.field_type,
+                    field.collation,
+                )?
+            };
+            if ordering != Ordering::Equal {
+                return Ok(ordering);
+            }
+            offset += size;
         }
-        Ok(self.fields[self.key_position].field_type.clone())
+        Ok(Ordering::Equal)
+    }
+
+    /// Fields an index stores after `idx_value` to point back at a row of this table:
+    /// one `base_pk` field per key column, so the index row is the base key.
+    pub fn base_key_fields(&self, index_name: &str) -> Vec<Field> {
+        let composite = self.key_positions.len() > 1;
+        self.key_positions
+            .iter()
+            .map(|&idx| {
+                let field = &self.fields[idx];
+                Field {
+                    field_type: field.field_type.clone(),
+                    name: if composite {
+                        format!("base_pk_{}", field.name)
+                    } else {
+                        "base_pk".to_string()
+                    },
+                    table_name: index_name.to_string(),
+                    collation: field.collation,
+                }
+            })
+            .collect()
     }
 
     pub fn get_row_length(&self) -> Result<usize, Status> {
@@ -93,7 +266,7 @@ impl TableSchema {
         }
         let mut len = 0usize;
         for (idx, field) in self.fields.iter().enumerate() {
-            if idx == self.key_position {
+            if self.is_key_column(idx) {
                 continue;
             }
             len += Serializer::get_size_of_type(&field.field_type)?;
@@ -160,6 +333,7 @@ impl TableSchema {
                 field_type: f.field_type.clone(),
                 name: f.name.clone(),
                 table_name: f.table_name.clone(),
+                collation: f.collation,
             });
         }
 
@@ -168,6 +342,7 @@ impl TableSchema {
                 field_type: f.field_type.clone(),
                 name: f.name.clone(),
                 table_name: f.table_name.clone(),
+                collation: f.collation,
             });
         }
 
@@ -175,8 +350,13 @@ impl TableSchema {
             next_position: Position::make_empty(),
             root: Position::make_empty(),
             has_key: false,
-            key_position: 0,
+            key_positions: vec![0],
             fields: merged_fields,
+            column_defaults: vec![],
+            not_null: vec![],
+            checks: vec![],
+            identity: vec![],
+            generated: vec![],
             table_type: 0,
             entry_count: self.entry_count,
             name: format!("{}_JOIN_{}", self.name.clone(), other.name.clone()),
@@ -213,13 +393,14 @@ impl TableSchema {
     ) -> Result<(JoinOp, JoinOp), Status> {
         let (left_pos, right_pos) =
             self.get_join_positions_and_validate(other, left_key, right_key)?;
-        let left_op = if self.has_key && self.key_position == left_pos {
+        // the leading column of a composite key repeats, which sort-merge does not handle
+        let left_op = if self.has_key && self.key_positions == [left_pos] {
             JoinOp::Key
         } else {
             JoinOp::Scan
         };
 
-        let right_op = if other.has_key && other.key_position == right_pos {
+        let right_op = if other.has_key && other.key_positions == [right_pos] {
             JoinOp::Key
         } else {
             JoinOp::Scan
@@ -241,11 +422,12 @@ impl TableSchema {
                     None => f.name.clone(),
                 };
 
-                if stored_name == req_name {
+                if f.name == req_name || stored_name == req_name {
                     matched = Some(Field {
                         field_type: f.field_type.clone(),
                         name: f.name.clone(),
                         table_name: f.table_name.clone(),
+                        collation: f.collation,
                     });
                     break;
                 }
@@ -260,8 +442,13 @@ impl TableSchema {
             next_position: self.next_position.clone(),
             root: self.root.clone(),
             has_key: false, //ToDo
-            key_position: 0,
+            key_positions: vec![0],
             fields: projected_fields,
+            column_defaults: vec![],
+            not_null: vec![],
+            checks: vec![],
+            identity: vec![],
+            generated: vec![],
             table_type: self.table_type,
             entry_count: self.entry_count,
             name: self.name.clone(),
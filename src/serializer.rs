@@ -1,9 +1,11 @@
 //also look at pager.rs for comments
 
+use crate::collation::Collation;
 use crate::constants::{
-    FieldMeta, KeyMeta, NodeFlag, PAYLOAD_CHUNK_LEN_OFFSET, PAYLOAD_FLAG_DEPRECATED,
-    PAYLOAD_HEADER_FLAGS_OFFSET, PAYLOAD_MAGIC, PAYLOAD_MAGIC_OFFSET, PAYLOAD_NEXT_PAGE_OFFSET,
-    PAYLOAD_OWNER_ROOT_OFFSET, PageFlag,
+    FieldMeta, INLINE_STRING_PREFIX_LEN, KeyMeta, NodeFlag, PAYLOAD_CHUNK_LEN_OFFSET,
+    PAYLOAD_FIELD_LEN_OFFSET, PAYLOAD_FIELD_PTR_OFFSET, PAYLOAD_FIELD_SIZE,
+    PAYLOAD_FLAG_DEPRECATED, PAYLOAD_HEADER_FLAGS_OFFSET, PAYLOAD_MAGIC, PAYLOAD_MAGIC_OFFSET,
+    PAYLOAD_NEXT_PAGE_OFFSET, PAYLOAD_OWNER_ROOT_OFFSET, PageFlag, VARCHAR_BYTES_PER_CHAR,
 };
 use crate::debug::Status;
 use crate::debug::Status::{
@@ -13,12 +15,16 @@ use crate::debug::Status::{
 };
 use crate::executor::Field;
 use crate::pager::{
-    BOOLEAN_SIZE, DATE_SIZE, Flag, INTEGER_SIZE, Key, NODE_METADATA_SIZE, NULL_SIZE, PAGE_SIZE,
-    POSITION_SIZE, PageContainer, PageData, Position, Row, STRING_SIZE, Type,
+    BOOLEAN_SIZE, DATE_SIZE, DECIMAL_MAX_PRECISION, Flag, INTEGER_SIZE, Key, NODE_METADATA_SIZE, NULL_SIZE, PAGE_SIZE,
+    POSITION_SIZE, PageContainer, PageData, Position, Row, STRING_SIZE, TIME_SIZE, TIMESTAMP_SIZE,
+    TIMESTAMPTZ_SIZE, Type, UUID_SIZE,
 };
 use crate::planner::SqlStatementComparisonOperator;
 use crate::schema::TableSchema;
 
+const MICROS_PER_MINUTE: i64 = 60 * 1_000_000;
+const MICROS_PER_DAY: i64 = 24 * 60 * MICROS_PER_MINUTE;
+
 /// # Responsibilities
 /// - Execute operations on the pages
 /// - Convert RustSQl Datatypes to Strings / Rust-Datatypes
@@ -54,11 +60,7 @@ impl Serializer {
         }
 
         if Self::split_row_into_fields(target_row, &table_schema.fields).is_ok() {
-            return Ok(Some(Self::get_field_on_row_slice(
-                target_row,
-                table_schema.key_position,
-                table_schema,
-            )?));
+            return Ok(Some(Self::split_key_and_row(target_row, table_schema)?.0));
         }
 
         Ok(None)
@@ -168,9 +170,17 @@ impl Serializer {
                 return Err(Status::InternalExceptionInvalidRowLength);
             }
             let field_val = &row[position..position + size];
+            // a comparison with NULL is never true
+            if Serializer::is_null(&field_val.to_vec(), &field.field_type)? {
+                return Ok(false);
+            }
 
-            let cmp_result =
-                Serializer::compare_with_type(&field_val.to_vec(), target_val, &field.field_type)?;
+            let cmp_result = Serializer::compare_with_collation(
+                &field_val.to_vec(),
+                target_val,
+                &field.field_type,
+                field.collation,
+            )?;
 
             let matched = match cmp_result {
                 std::cmp::Ordering::Equal => matches!(
@@ -205,12 +215,22 @@ impl Serializer {
         row: &Row,
         schema: &TableSchema,
     ) -> Result<Vec<u8>, Status> {
+        let mut fields: Vec<Option<&[u8]>> = vec![None; schema.fields.len()];
+        let mut key_cursor = 0;
+        for &idx in &schema.key_positions {
+            let size = Serializer::get_size_of_type(&schema.fields[idx].field_type)?;
+            if key_cursor + size > key.len() {
+                return Err(Status::InternalExceptionIntegrityCheckFailed);
+            }
+            fields[idx] = Some(&key[key_cursor..key_cursor + size]);
+            key_cursor += size;
+        }
+
         let mut full_row = Vec::new();
         let mut row_cursor = 0;
-
         for (i, field) in schema.fields.iter().enumerate() {
-            if i == schema.key_position {
-                full_row.extend_from_slice(key);
+            if let Some(key_field) = fields[i] {
+                full_row.extend_from_slice(key_field);
             } else {
                 let size = Serializer::get_size_of_type(&field.field_type)?;
                 if row_cursor + size > row.len() {
@@ -223,13 +243,40 @@ impl Serializer {
         Ok(full_row)
     }
 
+    /// Inverse of `reconstruct_row`: the key columns' bytes in key order, and the
+    /// remaining fields in field order.
+    pub(crate) fn split_key_and_row(
+        full_row: &[u8],
+        schema: &TableSchema,
+    ) -> Result<(Key, Row), Status> {
+        let fields = Self::split_row_into_fields(full_row, &schema.fields)?;
+        let mut key = Vec::new();
+        for &idx in &schema.key_positions {
+            key.extend_from_slice(fields.get(idx).ok_or(InternalExceptionIndexOutOfRange)?);
+        }
+        let mut row = Vec::new();
+        for (idx, field) in fields.iter().enumerate() {
+            if !schema.is_key_column(idx) {
+                row.extend_from_slice(field);
+            }
+        }
+        Ok((key, row))
+    }
+
     pub(crate) fn get_size_of_type(ty: &Type) -> Result<usize, Status> {
         match ty {
             Type::String => Ok(STRING_SIZE),
-            Type::Varchar(max) => Ok(*max + 1),
+            Type::Varchar(max) => Ok(*max * VARCHAR_BYTES_PER_CHAR + 1),
+            Type::VarcharBytes(max) => Ok(*max + 1),
+            Type::Decimal(precision, _) => Ok(Self::decimal_value_width(*precision) + 1),
             Type::Integer => Ok(INTEGER_SIZE),
             Type::Date => Ok(DATE_SIZE),
+            Type::Timestamp => Ok(TIMESTAMP_SIZE),
+            Type::TimestampTz => Ok(TIMESTAMPTZ_SIZE),
+            Type::Time => Ok(TIME_SIZE),
+            Type::Uuid => Ok(UUID_SIZE),
             Type::Boolean => Ok(BOOLEAN_SIZE),
+            Type::Blob | Type::Text | Type::Json => Ok(PAYLOAD_FIELD_SIZE),
             Type::Null => Ok(NULL_SIZE),
         }
     }
@@ -456,6 +503,13 @@ impl Serializer {
     pub fn set_is_tomb(key: &mut Key, value: bool, schema: &TableSchema) -> Result<(), Status> {
         Self::set_flag_at_position(key, KeyMeta::Tomb as u8, value, &schema.get_key_type()?)
     }
+    /// A NULL of `field_type`: zeroed value bytes with the null flag set. Every NULL is
+    /// encoded this way, so rows holding NULLs in the same places compare equal bytewise.
+    pub fn null_field(field_type: &Type) -> Result<Vec<u8>, Status> {
+        let mut field = vec![0u8; Self::get_size_of_type(field_type)?];
+        Self::set_is_null(&mut field, true, field_type)?;
+        Ok(field)
+    }
     pub fn is_null(field: &Vec<u8>, field_type: &Type) -> Result<bool, Status> {
         Self::get_flag_at_position(field, FieldMeta::Null as u8, field_type)
     }
@@ -485,7 +539,7 @@ impl Serializer {
         let mut cursor = 0usize;
 
         for (field_idx, field) in schema.fields.iter().enumerate() {
-            if field_idx == schema.key_position {
+            if schema.is_key_column(field_idx) {
                 continue;
             }
 
@@ -513,7 +567,7 @@ impl Serializer {
     pub fn row_has_external_non_key_field(row: &Row, schema: &TableSchema) -> Result<bool, Status> {
         let mut cursor = 0usize;
         for (field_idx, field) in schema.fields.iter().enumerate() {
-            if field_idx == schema.key_position {
+            if schema.is_key_column(field_idx) {
                 continue;
             }
 
@@ -522,7 +576,7 @@ impl Serializer {
                 return Err(InternalExceptionInvalidRowLength);
             }
 
-            if matches!(field.field_type, Type::String | Type::Varchar(_)) {
+            if matches!(field.field_type, Type::String | Type::Varchar(_) | Type::VarcharBytes(_)) {
                 let slice = row[cursor..cursor + field_len].to_vec();
                 if Self::is_external(&slice, &field.field_type)? {
                     return Ok(true);
@@ -1162,7 +1216,7 @@ impl Serializer {
         }
 
         let page_len = page.len();
-        if start <= 0 || end >= page_len || end <= start {
+        if start == 0 || end >= page_len || end <= start {
             panic!("invalid shift");
             return Err(InternalExceptionIndexOutOfRange);
         }
@@ -1247,10 +1301,27 @@ impl Serializer {
     pub fn infinity(field_type: &Type) -> Vec<u8> {
         match field_type {
             Type::String => vec![u8::MAX; STRING_SIZE],
-            Type::Varchar(max) => vec![u8::MAX; max + 1],
+            Type::Varchar(max) => vec![u8::MAX; max * VARCHAR_BYTES_PER_CHAR + 1],
+            Type::VarcharBytes(max) => vec![u8::MAX; max + 1],
+            Type::Decimal(precision, _) => {
+                let mut bytes = vec![0xFF; Self::decimal_value_width(*precision)];
+                bytes.push(0);
+                bytes
+            }
             Type::Integer => vec![0x7F; INTEGER_SIZE], // Max positive value for signed integer
             Type::Date => vec![0xFF; DATE_SIZE],       // Max value for date
+            Type::Timestamp | Type::TimestampTz | Type::Time => {
+                let mut bytes = vec![0xFF; Self::get_size_of_type(field_type).unwrap() - 1];
+                bytes.push(0);
+                bytes
+            }
+            Type::Uuid => {
+                let mut bytes = vec![0xFF; UUID_SIZE - 1];
+                bytes.push(0);
+                bytes
+            }
             Type::Boolean => vec![1],                  // True as infinity for boolean
+            Type::Blob | Type::Text | Type::Json => vec![u8::MAX; PAYLOAD_FIELD_SIZE],
             Type::Null => vec![0],                     // Null has no concept of infinity
         }
     }
@@ -1258,10 +1329,17 @@ impl Serializer {
     pub fn negative_infinity(field_type: &Type) -> Vec<u8> {
         match field_type {
             Type::String => vec![u8::MIN; STRING_SIZE],
-            Type::Varchar(max) => vec![u8::MIN; max + 1],
+            Type::Varchar(max) => vec![u8::MIN; max * VARCHAR_BYTES_PER_CHAR + 1],
+            Type::VarcharBytes(max) => vec![u8::MIN; max + 1],
+            Type::Decimal(precision, _) => vec![0x00; Self::decimal_value_width(*precision) + 1],
             Type::Integer => vec![0x80; INTEGER_SIZE], // Min negative value for signed integer
             Type::Date => vec![0x00; DATE_SIZE],       // Min value for date
+            Type::Timestamp => vec![0x00; TIMESTAMP_SIZE],
+            Type::TimestampTz => vec![0x00; TIMESTAMPTZ_SIZE],
+            Type::Time => vec![0x00; TIME_SIZE],
+            Type::Uuid => vec![0x00; UUID_SIZE],
             Type::Boolean => vec![0],                  // False as negative infinity for boolean
+            Type::Blob | Type::Text | Type::Json => vec![u8::MIN; PAYLOAD_FIELD_SIZE],
             Type::Null => vec![0],                     // Null has no concept of negative infinity
         }
     }
@@ -1274,7 +1352,7 @@ impl Serializer {
     ) -> Result<(), Status> {
         match field_type {
             Type::Null => Err(Status::InternalExceptionInvalidFieldType),
-            Type::Boolean => Ok(Self::write_byte_at_position(&mut v[0], position, value)),
+            Type::Boolean => Ok(Self::write_byte_at_position(&mut v[0], position + 1, value)),
             Type::Integer => Ok(Self::write_byte_at_position(&mut v[0], position, value)),
             _ => Ok(Self::write_byte_at_position(
                 &mut v[Self::get_size_of_type(&field_type)? - 1],
@@ -1291,7 +1369,7 @@ impl Serializer {
     ) -> Result<bool, Status> {
         match field_type {
             Type::Null => Err(Status::InternalExceptionInvalidFieldType),
-            Type::Boolean => Ok(Self::byte_to_bool_at_position(v[0], position)),
+            Type::Boolean => Ok(Self::byte_to_bool_at_position(v[0], position + 1)),
             Type::Integer => Ok(Self::byte_to_bool_at_position(v[0], position)),
             _ => Ok(Self::byte_to_bool_at_position(
                 v[Self::get_size_of_type(field_type)? - 1],
@@ -1306,7 +1384,7 @@ impl Serializer {
         field_type: &Type,
     ) -> Result<std::cmp::Ordering, Status> {
         match field_type {
-            Type::String | Type::Varchar(_) => Ok(Self::compare_string_bytes(a, b)),
+            Type::String | Type::Varchar(_) | Type::VarcharBytes(_) => Ok(Self::compare_string_bytes(a, b)),
             Type::Integer => Ok(Self::compare_integers(
                 <[u8; INTEGER_SIZE]>::try_from(a.to_vec()).unwrap(),
                 <[u8; INTEGER_SIZE]>::try_from(b.to_vec()).unwrap(),
@@ -1315,11 +1393,48 @@ impl Serializer {
                 <[u8; DATE_SIZE]>::try_from(a.to_vec()).unwrap(),
                 <[u8; DATE_SIZE]>::try_from(b.to_vec()).unwrap(),
             )),
-            Type::Boolean => Ok(Self::compare_booleans(a[1], b[1])),
+            Type::Decimal(precision, _) => Ok(Self::bytes_to_decimal(a, *precision)
+                .cmp(&Self::bytes_to_decimal(b, *precision))),
+            // order-preserving encodings; the offset of a TimestampTz is not part of the instant
+            Type::Timestamp | Type::TimestampTz | Type::Time => Ok(a[..8].cmp(&b[..8])),
+            // raw byte order, which is also the order of the canonical text form
+            Type::Uuid => Ok(a[..UUID_SIZE - 1].cmp(&b[..UUID_SIZE - 1])),
+            // the value is bit 0 of the single byte, above it are the flags
+            Type::Boolean => Ok(Self::compare_booleans(a[0], b[0])),
+            // callers resolve payload-backed fields to their content first
+            Type::Blob | Type::Text | Type::Json => Ok(a.cmp(b)),
             Type::Null => Ok(std::cmp::Ordering::Equal),
         }
     }
 
+    /// Like `compare_with_type`, but text fields are compared under `collation`.
+    pub fn compare_with_collation(
+        a: &Vec<u8>,
+        b: &Vec<u8>,
+        field_type: &Type,
+        collation: Collation,
+    ) -> Result<std::cmp::Ordering, Status> {
+        match field_type {
+            Type::String | Type::Varchar(_) | Type::VarcharBytes(_) => {
+                Ok(collation.compare(Self::string_content(a), Self::string_content(b)))
+            }
+            Type::Text => Ok(collation.compare(a, b)),
+            _ => Self::compare_with_type(a, b, field_type),
+        }
+    }
+
+    /// Bytes of a fixed-width field that are equal exactly when the values are equal under
+    /// `collation`, for hashing and equality joins.
+    pub fn collation_key(field: &[u8], field_type: &Type, collation: Collation) -> Vec<u8> {
+        match (field_type, collation) {
+            (_, Collation::Binary) => field.to_vec(),
+            (Type::String | Type::Varchar(_) | Type::VarcharBytes(_), _) => collation.key(Self::string_content(field)),
+            // payload-backed fields are passed as their content
+            (Type::Text, _) => collation.key(field),
+            _ => field.to_vec(),
+        }
+    }
+
     pub fn create_node_flag(is_leaf: bool) -> Flag {
         let mut flag = 0u8;
         Self::write_byte_at_position(&mut flag, NodeFlag::Leaf as u8, is_leaf);
@@ -1327,15 +1442,13 @@ impl Serializer {
     }
 
     pub fn compare_strings(a: [u8; STRING_SIZE], b: [u8; STRING_SIZE]) -> std::cmp::Ordering {
-        let str_a = Self::bytes_to_ascii_slice(&a);
-        let str_b = Self::bytes_to_ascii_slice(&b);
-        str_a.cmp(&str_b)
+        Self::compare_string_bytes(&a, &b)
     }
 
+    /// Compares two NUL-padded string fields. UTF-8 byte order matches code point
+    /// order, so the content before the padding is compared without decoding.
     pub fn compare_string_bytes(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
-        let str_a = Self::bytes_to_ascii_slice(a);
-        let str_b = Self::bytes_to_ascii_slice(b);
-        str_a.cmp(&str_b)
+        Self::string_content(a).cmp(Self::string_content(b))
     }
 
     pub fn compare_integers(a: [u8; INTEGER_SIZE], b: [u8; INTEGER_SIZE]) -> std::cmp::Ordering {
@@ -1378,12 +1491,26 @@ impl Serializer {
     }
 
     pub fn format_key(key: &Key, schema: &TableSchema) -> Result<String, Status> {
-        Self::format_field(key, &schema.get_key_type()?)
+        let mut parts = Vec::new();
+        let mut offset = 0usize;
+        for &idx in &schema.key_positions {
+            let field_type = &schema.fields[idx].field_type;
+            let size = Self::get_size_of_type(field_type)?;
+            if offset + size > key.len() {
+                return Err(InternalExceptionInvalidRowLength);
+            }
+            parts.push(Self::format_field(&key[offset..offset + size].to_vec(), field_type)?);
+            offset += size;
+        }
+        Ok(parts.join(", "))
     }
 
     pub fn format_field(bytes: &Vec<u8>, field_type: &Type) -> Result<String, Status> {
+        if *field_type != Type::Null && Self::is_null(bytes, field_type)? {
+            return Ok("NULL".to_string());
+        }
         match field_type {
-            Type::String | Type::Varchar(_) => Ok(Self::format_string_bytes(bytes)),
+            Type::String | Type::Varchar(_) | Type::VarcharBytes(_) => Ok(Self::format_string_bytes(bytes)),
             Type::Date => Ok(Self::format_date(
                 <[u8; DATE_SIZE]>::try_from(bytes.clone()).expect("wrong len for type Date"),
             )),
@@ -1391,6 +1518,12 @@ impl Serializer {
                 <[u8; INTEGER_SIZE]>::try_from(bytes.clone()).expect("wrong len for type Integer"),
             )),
             Type::Boolean => Ok(Self::format_bool(&bytes[0])),
+            Type::Decimal(precision, scale) => Ok(Self::format_decimal(bytes, *precision, *scale)),
+            Type::Timestamp => Ok(Self::format_timestamp(bytes, false)),
+            Type::TimestampTz => Ok(Self::format_timestamp(bytes, true)),
+            Type::Time => Ok(Self::format_time(bytes)),
+            Type::Uuid => Ok(Self::format_uuid(bytes)),
+            Type::Blob | Type::Text | Type::Json => Ok(Self::format_payload_field_preview(bytes, field_type)),
             _ => Err(InternalExceptionTypeMismatch),
         }
     }
@@ -1428,11 +1561,11 @@ impl Serializer {
     }
 
     pub fn format_string(bytes: [u8; STRING_SIZE]) -> String {
-        Self::bytes_to_ascii_slice(&bytes)
+        Self::bytes_to_utf8_slice(&bytes)
     }
 
     pub fn format_string_bytes(bytes: &[u8]) -> String {
-        Self::bytes_to_ascii_slice(bytes)
+        Self::bytes_to_utf8_slice(bytes)
     }
 
     pub fn format_int(bytes: [u8; INTEGER_SIZE]) -> String {
@@ -1445,6 +1578,108 @@ impl Serializer {
         format!("{:04}-{:02}-{:02}", year, month, day)
     }
 
+    pub fn format_decimal(bytes: &[u8], precision: usize, scale: usize) -> String {
+        let unscaled = Self::bytes_to_decimal(bytes, precision);
+        let sign = if unscaled < 0 { "-" } else { "" };
+        let digits = unscaled.unsigned_abs().to_string();
+        if scale == 0 {
+            return format!("{}{}", sign, digits);
+        }
+        let padded = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+
+    pub fn format_timestamp(bytes: &[u8], with_time_zone: bool) -> String {
+        let micros = Self::bytes_to_timestamp(bytes);
+        if !with_time_zone {
+            return Self::format_local_timestamp(micros);
+        }
+        let offset_minutes = i16::from_be_bytes([bytes[8], bytes[9]]) as i64;
+        let sign = if offset_minutes < 0 { '-' } else { '+' };
+        format!(
+            "{}{}{:02}:{:02}",
+            Self::format_local_timestamp(micros + offset_minutes * MICROS_PER_MINUTE),
+            sign,
+            offset_minutes.abs() / 60,
+            offset_minutes.abs() % 60
+        )
+    }
+
+    pub fn format_time(bytes: &[u8]) -> String {
+        let micros = u64::from_be_bytes(bytes[..8].try_into().expect("wrong len for type Time"));
+        Self::format_time_of_day(micros as i64)
+    }
+
+    fn format_local_timestamp(micros: i64) -> String {
+        let days = micros.div_euclid(MICROS_PER_DAY);
+        let (year, month, day) = Self::civil_from_days(days);
+        format!(
+            "{:04}-{:02}-{:02} {}",
+            year,
+            month,
+            day,
+            Self::format_time_of_day(micros.rem_euclid(MICROS_PER_DAY))
+        )
+    }
+
+    fn format_time_of_day(micros: i64) -> String {
+        let seconds = micros / 1_000_000;
+        let fraction = micros % 1_000_000;
+        let mut out = format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+        if fraction != 0 {
+            out += format!(".{:06}", fraction).trim_end_matches('0');
+        }
+        out
+    }
+
+    /// Formats the canonical lowercase `8-4-4-4-12` form.
+    pub fn format_uuid(bytes: &[u8]) -> String {
+        let hex: String = bytes[..UUID_SIZE - 1]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    pub fn format_blob(content: &[u8]) -> String {
+        let hex: String = content.iter().map(|b| format!("{:02X}", b)).collect();
+        format!("X'{}'", hex)
+    }
+
+    /// Formats the resolved content of a payload-backed field.
+    pub fn format_payload_content(content: &[u8], field_type: &Type) -> String {
+        match field_type {
+            Type::Text | Type::Json => String::from_utf8_lossy(content).into_owned(),
+            _ => Self::format_blob(content),
+        }
+    }
+
+    /// Formats a payload-backed field without reading its payload pages: only the inline prefix is shown.
+    pub fn format_payload_field_preview(field: &[u8], field_type: &Type) -> String {
+        let len = Self::get_payload_field_len(field);
+        let inline = Self::format_payload_content(Self::get_payload_field_inline(field), field_type);
+        if len <= INLINE_STRING_PREFIX_LEN {
+            return inline;
+        }
+        match field_type {
+            Type::Text | Type::Json => format!("{}... ({} bytes)", inline, len),
+            _ => format!("{}... ({} bytes)", &inline[..inline.len() - 1], len),
+        }
+    }
+
     pub fn format_bool(byte: &u8) -> String {
         if byte & 1 != 0 {
             "true".to_string()
@@ -1454,18 +1689,26 @@ impl Serializer {
     }
 
     pub fn parse_string(s: &str) -> [u8; STRING_SIZE] {
-        let mut bytes = [0u8; STRING_SIZE];
-        for (i, &c) in s.as_bytes().iter().take(STRING_SIZE).enumerate() {
-            bytes[i] = c;
-        }
-        bytes
+        Self::utf8_to_bytes(s)
     }
 
+    /// Encodes `s` into a `Varchar(max_len)` field, keeping at most `max_len` characters.
     pub fn parse_varchar(s: &str, max_len: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; max_len * VARCHAR_BYTES_PER_CHAR + 1];
+        let end = s.char_indices().nth(max_len).map_or(s.len(), |(i, _)| i);
+        bytes[..end].copy_from_slice(&s.as_bytes()[..end]);
+        bytes
+    }
+
+    /// Encodes `s` into a `VarcharBytes(max_len)` field, keeping at most `max_len` bytes and
+    /// cutting before a character that would not fit whole.
+    pub fn parse_varchar_bytes(s: &str, max_len: usize) -> Vec<u8> {
         let mut bytes = vec![0u8; max_len + 1];
-        for (i, &c) in s.as_bytes().iter().take(max_len).enumerate() {
-            bytes[i] = c;
+        let mut end = s.len().min(max_len);
+        while !s.is_char_boundary(end) {
+            end -= 1;
         }
+        bytes[..end].copy_from_slice(&s.as_bytes()[..end]);
         bytes
     }
 
@@ -1474,6 +1717,104 @@ impl Serializer {
         Ok(Self::int_to_bytes(int_value))
     }
 
+    /// Parses a literal like `-12.345` into a `Decimal(precision, scale)` field.
+    /// - surplus fractional digits are rounded half away from zero
+    /// - values needing more than `precision` digits fail with `ExceptionDecimalOverflow`
+    pub fn parse_decimal(s: &str, precision: usize, scale: usize) -> Result<Vec<u8>, Status> {
+        let unscaled = Self::parse_decimal_unscaled(s, precision, scale)?;
+        Ok(Self::decimal_to_bytes(unscaled, precision))
+    }
+
+    pub fn parse_decimal_unscaled(s: &str, precision: usize, scale: usize) -> Result<i128, Status> {
+        if precision == 0 || precision > DECIMAL_MAX_PRECISION || scale > precision {
+            return Err(Status::CannotParseDecimal);
+        }
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.as_bytes().first() {
+            Some(b'-') => (true, &trimmed[1..]),
+            Some(b'+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part.bytes().all(|b| b.is_ascii_digit())
+            || !frac_part.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(Status::CannotParseDecimal);
+        }
+
+        let kept_frac = frac_part.bytes().chain(std::iter::repeat(b'0')).take(scale);
+        let mut unscaled: i128 = 0;
+        for digit in int_part.bytes().chain(kept_frac) {
+            unscaled = unscaled
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((digit - b'0') as i128))
+                .ok_or(Status::ExceptionDecimalOverflow)?;
+        }
+        if frac_part.len() > scale && frac_part.as_bytes()[scale] >= b'5' {
+            unscaled += 1;
+        }
+        if unscaled >= 10i128.pow(precision as u32) {
+            return Err(Status::ExceptionDecimalOverflow);
+        }
+        Ok(if negative { -unscaled } else { unscaled })
+    }
+
+    /// The unscaled values at `scale` fractional digits next to a decimal literal of any
+    /// precision, as (floor, ceiling); they are equal if the literal has no further digits.
+    /// Magnitudes are clamped to 10^`DECIMAL_MAX_PRECISION`, which no field reaches.
+    pub fn decimal_literal_bounds(s: &str, scale: usize) -> Result<(i128, i128), Status> {
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.as_bytes().first() {
+            Some(b'-') => (true, &trimmed[1..]),
+            Some(b'+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part.bytes().all(|b| b.is_ascii_digit())
+            || !frac_part.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(Status::CannotParseDecimal);
+        }
+
+        let limit = 10i128.pow(DECIMAL_MAX_PRECISION as u32);
+        let kept_frac = frac_part.bytes().chain(std::iter::repeat(b'0')).take(scale);
+        let mut truncated: i128 = 0;
+        for digit in int_part.bytes().chain(kept_frac) {
+            truncated = truncated
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((digit - b'0') as i128))
+                .map_or(limit, |v| v.min(limit));
+        }
+        let inexact = frac_part.bytes().skip(scale).any(|b| b != b'0') as i128;
+        Ok(if negative {
+            (-truncated - inexact, -truncated)
+        } else {
+            (truncated, truncated + inexact)
+        })
+    }
+
+    /// Parses a hex literal of the form `X'0A1B'` into raw bytes.
+    pub fn parse_blob(s: &str) -> Result<Vec<u8>, Status> {
+        let hex = s
+            .strip_prefix("X'")
+            .or_else(|| s.strip_prefix("x'"))
+            .and_then(|rest| rest.strip_suffix('\''))
+            .ok_or(Status::CannotParseBlob)?;
+        if hex.len() % 2 != 0 {
+            return Err(Status::CannotParseBlob);
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .ok_or(Status::CannotParseBlob)
+            })
+            .collect()
+    }
+
     pub fn parse_date(s: &str) -> Result<[u8; DATE_SIZE], Status> {
         let parts: Vec<&str> = s.split('-').collect();
         if parts.len() < 2 {
@@ -1485,6 +1826,156 @@ impl Serializer {
         Self::date_to_bytes(year, month, day)
     }
 
+    /// Parses `YYYY-MM-DD[( |T)HH:MM[:SS[.ffffff]]]`. With a time zone, an optional
+    /// `Z`, `UTC` or `±HH[:MM]` suffix follows (UTC if omitted); the value is stored as UTC.
+    pub fn parse_timestamp(s: &str, with_time_zone: bool) -> Result<Vec<u8>, Status> {
+        let s = s.trim();
+        let (date_part, rest) = match s.find([' ', 'T', 't']) {
+            Some(idx) => (&s[..idx], s[idx + 1..].trim()),
+            None => (s, ""),
+        };
+        let (time_part, offset_minutes) = Self::split_time_zone(rest)?;
+        if offset_minutes.is_some() && !with_time_zone {
+            return Err(Status::CannotParseTimestamp);
+        }
+
+        let days = Self::parse_civil_date(date_part).ok_or(Status::CannotParseTimestamp)?;
+        let time_of_day = if time_part.is_empty() {
+            0
+        } else {
+            Self::parse_time_of_day(time_part).ok_or(Status::CannotParseTimestamp)?
+        };
+        let offset_minutes = offset_minutes.unwrap_or(0);
+        let micros = days * MICROS_PER_DAY + time_of_day - offset_minutes as i64 * MICROS_PER_MINUTE;
+
+        let mut bytes = Self::timestamp_to_bytes(micros);
+        if with_time_zone {
+            bytes.splice(8..8, offset_minutes.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    /// Parses `HH:MM[:SS[.ffffff]]`.
+    pub fn parse_time(s: &str) -> Result<Vec<u8>, Status> {
+        let micros = Self::parse_time_of_day(s.trim()).ok_or(Status::CannotParseTime)?;
+        let mut bytes = (micros as u64).to_be_bytes().to_vec();
+        bytes.push(0);
+        Ok(bytes)
+    }
+
+    /// Splits a trailing `Z`, `UTC` or `±HH[:MM]` zone off a time string.
+    fn split_time_zone(s: &str) -> Result<(&str, Option<i16>), Status> {
+        if let Some(time) = s.strip_suffix(['Z', 'z']) {
+            return Ok((time.trim(), Some(0)));
+        }
+        if let Some(time) = s.to_ascii_uppercase().strip_suffix("UTC") {
+            return Ok((s[..time.len()].trim(), Some(0)));
+        }
+        let Some(idx) = s.rfind(['+', '-']) else {
+            return Ok((s, None));
+        };
+
+        let zone = &s[idx + 1..];
+        let digits: String = zone.chars().filter(|c| *c != ':').collect();
+        if !(digits.len() == 2 || digits.len() == 4) || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Status::CannotParseTimestamp);
+        }
+        let hours: i16 = digits[..2].parse().map_err(|_| Status::CannotParseTimestamp)?;
+        let minutes: i16 = digits.get(2..).unwrap_or("0").parse().unwrap_or(0);
+        if hours > 18 || minutes > 59 || hours * 60 + minutes > 18 * 60 {
+            return Err(Status::CannotParseTimestamp);
+        }
+        let offset = hours * 60 + minutes;
+        let offset = if s[idx..].starts_with('-') { -offset } else { offset };
+        Ok((s[..idx].trim(), Some(offset)))
+    }
+
+    fn parse_civil_date(s: &str) -> Option<i64> {
+        let mut parts = s.split('-');
+        let year: i64 = parts.next()?.parse().ok()?;
+        let month: u32 = parts.next()?.parse().ok()?;
+        let day: u32 = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || !(1..=9999).contains(&year) || !(1..=12).contains(&month) {
+            return None;
+        }
+        let is_leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if is_leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        if day == 0 || day > days_in_month {
+            return None;
+        }
+        Some(Self::days_from_civil(year, month, day))
+    }
+
+    fn parse_time_of_day(s: &str) -> Option<i64> {
+        let (clock, fraction) = match s.split_once('.') {
+            Some((clock, fraction)) => (clock, Some(fraction)),
+            None => (s, None),
+        };
+        let mut parts = clock.split(':');
+        let hours: i64 = parts.next()?.parse().ok()?;
+        let minutes: i64 = parts.next()?.parse().ok()?;
+        let seconds: i64 = match parts.next() {
+            Some(sec) => sec.parse().ok()?,
+            None if fraction.is_none() => 0,
+            None => return None,
+        };
+        if parts.next().is_some() || hours > 23 || minutes > 59 || seconds > 59 {
+            return None;
+        }
+
+        let micros = match fraction {
+            Some(f) if (1..=6).contains(&f.len()) && f.chars().all(|c| c.is_ascii_digit()) => {
+                f.parse::<i64>().ok()? * 10i64.pow(6 - f.len() as u32)
+            }
+            Some(_) => return None,
+            None => 0,
+        };
+        Some(((hours * 60 + minutes) * 60 + seconds) * 1_000_000 + micros)
+    }
+
+    /// Parses a UUID in the hyphenated `8-4-4-4-12` form or as 32 bare hex digits,
+    /// optionally wrapped in braces. Hex digits may be upper- or lowercase.
+    pub fn parse_uuid(s: &str) -> Result<Vec<u8>, Status> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix('{')
+            .and_then(|inner| inner.strip_suffix('}'))
+            .unwrap_or(s);
+        let hex = if s.len() == 36 {
+            let groups: Vec<&str> = s.split('-').collect();
+            if groups.iter().map(|g| g.len()).collect::<Vec<_>>() != [8, 4, 4, 4, 12] {
+                return Err(Status::CannotParseUuid);
+            }
+            groups.concat()
+        } else {
+            s.to_string()
+        };
+        if hex.len() != 32 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Status::CannotParseUuid);
+        }
+
+        let mut bytes = (0..32)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| Status::CannotParseUuid))
+            .collect::<Result<Vec<u8>, Status>>()?;
+        bytes.push(0);
+        Ok(bytes)
+    }
+
+    /// A random (version 4, RFC 4122 variant) UUID field.
+    pub fn generate_uuid_v4() -> Vec<u8> {
+        let mut bytes = rand::random::<[u8; UUID_SIZE - 1]>().to_vec();
+        bytes[6] = (bytes[6] & 0x0F) | 0x40;
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+        bytes.push(0);
+        bytes
+    }
+
     pub fn parse_bool(s: &str) -> Result<u8, Status> {
         if s.to_ascii_lowercase() == "true" {
             Ok(1)
@@ -1495,28 +1986,33 @@ impl Serializer {
         }
     }
 
-    pub fn bytes_to_ascii(bytes: [u8; STRING_SIZE]) -> String {
-        Self::bytes_to_ascii_slice(&bytes)
+    pub fn bytes_to_utf8(bytes: [u8; STRING_SIZE]) -> String {
+        Self::bytes_to_utf8_slice(&bytes)
     }
 
-    pub fn bytes_to_ascii_slice(bytes: &[u8]) -> String {
-        let payload_len = bytes.len().saturating_sub(1);
-        bytes[..payload_len]
-            .iter()
-            .copied()
-            .take_while(|&b| b != 0)
-            .map(|b| b as char)
-            .collect()
+    pub fn bytes_to_utf8_slice(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(Self::string_content(bytes)).into_owned()
     }
 
-    pub fn ascii_to_bytes(ascii: &str) -> [u8; STRING_SIZE] {
+    /// Encodes `s` into a `String` field. Overlong input is cut at the last
+    /// character boundary that still leaves room for the flag byte.
+    pub fn utf8_to_bytes(s: &str) -> [u8; STRING_SIZE] {
         let mut bytes = [0u8; STRING_SIZE];
-        for (i, &c) in ascii.as_bytes().iter().take(STRING_SIZE - 1).enumerate() {
-            bytes[i] = c;
+        let mut end = s.len().min(STRING_SIZE - 1);
+        while !s.is_char_boundary(end) {
+            end -= 1;
         }
+        bytes[..end].copy_from_slice(&s.as_bytes()[..end]);
         bytes
     }
 
+    /// The content of a NUL-padded string field, without padding and flag byte.
+    fn string_content(bytes: &[u8]) -> &[u8] {
+        let payload = &bytes[..bytes.len().saturating_sub(1)];
+        let end = payload.iter().position(|&b| b == 0).unwrap_or(payload.len());
+        &payload[..end]
+    }
+
     pub fn bytes_to_position(bytes: &[u8; POSITION_SIZE]) -> Position {
         // byte 0, 1 -> page (big-endian)
         // byte 2, 3 -> cell (big-endian)
@@ -1556,6 +2052,107 @@ impl Serializer {
         value
     }
 
+    /// Number of value bytes of a `Decimal(precision, _)` field, excluding the flag byte.
+    pub fn decimal_value_width(precision: usize) -> usize {
+        match precision {
+            0..=2 => 1,
+            3..=4 => 2,
+            5..=9 => 4,
+            10..=18 => 8,
+            _ => 16,
+        }
+    }
+
+    /// big-endian two's complement with the sign bit flipped, so that the
+    /// raw bytes sort in the same order as the numbers they encode
+    pub fn decimal_to_bytes(unscaled: i128, precision: usize) -> Vec<u8> {
+        let width = Self::decimal_value_width(precision);
+        let full = unscaled.to_be_bytes();
+        let mut bytes = full[full.len() - width..].to_vec();
+        bytes[0] ^= 0x80;
+        bytes.push(0);
+        bytes
+    }
+
+    pub fn bytes_to_decimal(bytes: &[u8], precision: usize) -> i128 {
+        let width = Self::decimal_value_width(precision);
+        let is_negative = bytes[0] & 0x80 == 0;
+        let mut full = if is_negative { [0xFFu8; 16] } else { [0u8; 16] };
+        full[16 - width..].copy_from_slice(&bytes[..width]);
+        full[16 - width] ^= 0x80;
+        i128::from_be_bytes(full)
+    }
+
+    /// Builds the in-row descriptor of a payload-backed field. The first
+    /// `INLINE_STRING_PREFIX_LEN` bytes of `content` are kept inline, `pointer` refers to the rest.
+    pub fn payload_field_to_bytes(content: &[u8], pointer: Position) -> Vec<u8> {
+        let mut field = vec![0u8; PAYLOAD_FIELD_SIZE];
+        let inline_len = content.len().min(INLINE_STRING_PREFIX_LEN);
+        field[..inline_len].copy_from_slice(&content[..inline_len]);
+        field[PAYLOAD_FIELD_LEN_OFFSET..PAYLOAD_FIELD_LEN_OFFSET + 4]
+            .copy_from_slice(&(content.len() as u32).to_be_bytes());
+        field[PAYLOAD_FIELD_PTR_OFFSET..PAYLOAD_FIELD_PTR_OFFSET + POSITION_SIZE]
+            .copy_from_slice(&Self::position_to_bytes(pointer));
+        field
+    }
+
+    pub fn get_payload_field_len(field: &[u8]) -> usize {
+        u32::from_be_bytes(
+            field[PAYLOAD_FIELD_LEN_OFFSET..PAYLOAD_FIELD_LEN_OFFSET + 4]
+                .try_into()
+                .expect("slice length checked"),
+        ) as usize
+    }
+
+    pub fn get_payload_field_pointer(field: &[u8]) -> Position {
+        Self::bytes_to_position(
+            field[PAYLOAD_FIELD_PTR_OFFSET..PAYLOAD_FIELD_PTR_OFFSET + POSITION_SIZE]
+                .try_into()
+                .expect("slice length checked"),
+        )
+    }
+
+    pub fn get_payload_field_inline(field: &[u8]) -> &[u8] {
+        &field[..Self::get_payload_field_len(field).min(INLINE_STRING_PREFIX_LEN)]
+    }
+
+    /// Orders two payload-backed fields by their inline prefixes, or `None` if the content
+    /// past them decides. Only byte order can be read off a prefix.
+    pub fn compare_payload_prefixes(
+        a: &[u8],
+        b: &[u8],
+        collation: Collation,
+    ) -> Option<std::cmp::Ordering> {
+        if collation != Collation::Binary {
+            return None;
+        }
+        let (a_len, b_len) = (Self::get_payload_field_len(a), Self::get_payload_field_len(b));
+        let (a_inline, b_inline) =
+            (Self::get_payload_field_inline(a), Self::get_payload_field_inline(b));
+        let common = a_inline.len().min(b_inline.len());
+        match a_inline[..common].cmp(&b_inline[..common]) {
+            // a value that is all inline is a prefix of the other one
+            std::cmp::Ordering::Equal
+                if a_len <= INLINE_STRING_PREFIX_LEN || b_len <= INLINE_STRING_PREFIX_LEN =>
+            {
+                Some(a_len.cmp(&b_len))
+            }
+            std::cmp::Ordering::Equal => None,
+            ordering => Some(ordering),
+        }
+    }
+
+    /// The lower bound a key or index seek for the Text `content` starts at: an all-inline
+    /// field of its first characters, which orders at or before every value starting with
+    /// them. The filter compares the whole value.
+    pub fn payload_seek_bound(content: &[u8]) -> Vec<u8> {
+        let mut end = content.len().min(INLINE_STRING_PREFIX_LEN);
+        while end < content.len() && (content[end] & 0xC0) == 0x80 {
+            end -= 1;
+        }
+        Self::payload_field_to_bytes(&content[..end], Position::make_empty())
+    }
+
     pub fn date_to_bytes(year: i32, month: i32, day: i32) -> Result<[u8; DATE_SIZE], Status> {
         if !(month >= 1 && month <= 12 && day >= 1 && day <= 31 && year > 0) {
             Err(Status::CannotParseIllegalDate)?
@@ -1570,6 +2167,43 @@ impl Serializer {
         Ok(bytes)
     }
 
+    /// Big-endian i64 with the sign bit flipped, so byte order matches time order.
+    pub fn timestamp_to_bytes(micros: i64) -> Vec<u8> {
+        let mut bytes = ((micros as u64) ^ (1 << 63)).to_be_bytes().to_vec();
+        bytes.push(0);
+        bytes
+    }
+
+    pub fn bytes_to_timestamp(bytes: &[u8]) -> i64 {
+        let raw = u64::from_be_bytes(bytes[..8].try_into().expect("wrong len for type Timestamp"));
+        (raw ^ (1 << 63)) as i64
+    }
+
+    // days since 1970-01-01 in the proleptic Gregorian calendar
+    fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_index = (month as i64 + 9) % 12;
+        let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    fn civil_from_days(days: i64) -> (i64, u32, u32) {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+
     pub fn bytes_to_date(bytes: [u8; DATE_SIZE]) -> (i32, i32, i32) {
         let year = ((bytes[0] as i32) << 8) | (bytes[1] as i32);
         let month = bytes[2] as i32;
@@ -1617,9 +2251,17 @@ impl Serializer {
             Type::Null => 0,
             Type::Integer => 1,
             Type::String => 2,
-            Type::Varchar(_) => 5,
+            Type::Varchar(_) | Type::VarcharBytes(_) => 5,
             Type::Date => 3,
             Type::Boolean => 4,
+            Type::Decimal(_, _) => 6,
+            Type::Blob => 7,
+            Type::Text => 11,
+            Type::Timestamp => 8,
+            Type::TimestampTz => 9,
+            Type::Time => 10,
+            Type::Uuid => 12,
+            Type::Json => 13,
         }
     }
 
@@ -1631,6 +2273,14 @@ impl Serializer {
             3 => Some(Type::Date),
             4 => Some(Type::Boolean),
             5 => Some(Type::Varchar(STRING_SIZE - 1)),
+            6 => Some(Type::Decimal(DECIMAL_MAX_PRECISION, 0)),
+            7 => Some(Type::Blob),
+            8 => Some(Type::Timestamp),
+            9 => Some(Type::TimestampTz),
+            10 => Some(Type::Time),
+            11 => Some(Type::Text),
+            12 => Some(Type::Uuid),
+            13 => Some(Type::Json),
             _ => None,
         }
     }
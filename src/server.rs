@@ -1,6 +1,6 @@
 use crate::executor::QueryExecutor;
 use crate::pager::PagerAccessor;
-use crate::pager::{TransactionId, Type};
+use crate::pager::{TransactionId, Type, VARCHAR_BYTES_PER_CHAR};
 use crate::schema::Field;
 use crate::serializer::Serializer;
 use std::io::{self, ErrorKind, Read, Write};
@@ -9,7 +9,7 @@ use std::sync::Arc;
 use std::thread;
 
 const MAGIC: &[u8; 4] = b"RSQL";
-const PROTOCOL_VERSION: u8 = 2;
+const PROTOCOL_VERSION: u8 = 5;
 const DEFAULT_FETCH_N: usize = 256;
 
 struct Request {
@@ -17,10 +17,10 @@ struct Request {
     fetch_n: usize,
 }
 
-/// Wire protocol (v1):
+/// Wire protocol (v5, `PROTOCOL_VERSION`):
 /// request:
 /// - [4] magic: "RSQL"
-/// - [1] version: 2
+/// - [1] version: 5
 /// - [4] big-endian SQL length
 /// - [N] utf8 SQL bytes
 /// - [4] big-endian fetch_n (0 => default)
@@ -35,13 +35,25 @@ struct Request {
 ///   - [2] column name length
 ///   - [name]
 ///   - [1] type tag
-///   - [4] type argument (varchar length or 0)
+///   - [4] type argument (varchar length in characters, decimal precision << 16 | scale, or 0)
 /// - repeated chunks:
 ///   - [4] chunk row count
 ///   - repeated rows:
 ///     - [4] row length
-///     - [row bytes]
+///     - [(column count + 7) / 8] null bitmap, bit i % 8 of byte i / 8 set if cell i is NULL
+///     - [row bytes] (fixed-width cells, blob/text/json cells as [4] length + content)
+///       NULL cells keep their width; NULL blob/text/json cells have length 0
+///       string cells are NUL-padded UTF-8, a varchar(n) cell is 4 * n + 1 bytes wide
 ///   - [1] done flag (0 => more chunks, 1 => done)
+/// - [4] generated key row count, one per inserted row that drew values from sequences
+/// - repeated generated key rows:
+///   - [2] key count
+///   - repeated keys:
+///     - [2] column name length
+///     - [name]
+///     - [4] big-endian value
+///
+/// v5 widened the generated key count to 4 bytes and grouped the keys by row.
 pub fn serve_tcp(bind_addr: &str, db_path: &str, btree_node_width: usize) -> io::Result<()> {
     let shared_pager = {
         let bootstrap = QueryExecutor::init(db_path, btree_node_width);
@@ -160,6 +172,7 @@ fn handle_client(mut stream: TcpStream, mut executor: QueryExecutor) -> io::Resu
         };
 
         let success = result.success;
+        let generated_keys = result.generated_keys;
         let mut data = result.data;
         let message = if success {
             "OK".to_string()
@@ -173,7 +186,14 @@ fn handle_client(mut stream: TcpStream, mut executor: QueryExecutor) -> io::Resu
         } else {
             request.fetch_n
         };
-        write_response(&mut stream, status, &message, &mut data, fetch_n)?;
+        write_response(
+            &mut stream,
+            status,
+            &message,
+            &mut data,
+            fetch_n,
+            &generated_keys,
+        )?;
     }
 }
 
@@ -270,6 +290,7 @@ fn write_response(
     message: &str,
     data: &mut crate::dataframe::DataFrame,
     fetch_n: usize,
+    generated_keys: &[Vec<(String, i32)>],
 ) -> io::Result<()> {
     let columns = data.header.clone();
 
@@ -280,7 +301,7 @@ fn write_response(
     stream.write_all(message.as_bytes())?;
 
     write_u16(stream, columns.len() as u16)?;
-    for field in columns {
+    for field in &columns {
         let name = field.name.as_bytes();
         write_u16(stream, name.len() as u16)?;
         stream.write_all(name)?;
@@ -298,8 +319,9 @@ fn write_response(
 
         write_u32(stream, rows.len() as u32)?;
         for row in &rows {
+            let row = encode_row_for_transfer(data, &columns, row)?;
             write_u32(stream, row.len() as u32)?;
-            stream.write_all(row)?;
+            stream.write_all(&row)?;
         }
 
         if is_done {
@@ -310,17 +332,83 @@ fn write_response(
         }
     }
 
+    let key_rows = u32::try_from(generated_keys.len())
+        .map_err(|_| io::Error::other("too many generated key rows"))?;
+    write_u32(stream, key_rows)?;
+    for keys in generated_keys {
+        let key_count =
+            u16::try_from(keys.len()).map_err(|_| io::Error::other("too many generated keys"))?;
+        write_u16(stream, key_count)?;
+        for (column, value) in keys {
+            write_u16(stream, column.len() as u16)?;
+            stream.write_all(column.as_bytes())?;
+            stream.write_all(&value.to_be_bytes())?;
+        }
+    }
+
     stream.flush()
 }
 
+/// Prefixes the row with its null bitmap. Payload-backed cells are resolved and sent
+/// length-prefixed, all other cells as stored, apart from widening `VarcharBytes` cells.
+fn encode_row_for_transfer(
+    data: &crate::dataframe::DataFrame,
+    columns: &[Field],
+    row: &[u8],
+) -> io::Result<Vec<u8>> {
+    let bitmap_len = columns.len().div_ceil(8);
+    let mut out = vec![0u8; bitmap_len];
+    out.reserve(row.len());
+    let mut position = 0;
+    for (i, field) in columns.iter().enumerate() {
+        let len = Serializer::get_size_of_type(&field.field_type)
+            .map_err(|_| io::Error::other("invalid column type"))?;
+        let cell = row
+            .get(position..position + len)
+            .ok_or_else(|| io::Error::other("row shorter than its columns"))?;
+        let is_null = field.field_type != Type::Null
+            && Serializer::is_null(&cell.to_vec(), &field.field_type)
+                .map_err(|_| io::Error::other("invalid column type"))?;
+        if is_null {
+            out[i / 8] |= 1 << (i % 8);
+        }
+        if field.field_type.is_payload_backed() && is_null {
+            write_u32(&mut out, 0)?;
+        } else if field.field_type.is_payload_backed() {
+            let content = data
+                .read_payload_field(cell)
+                .map_err(|_| io::Error::other("failed to read payload field"))?;
+            write_u32(&mut out, content.len() as u32)?;
+            out.extend_from_slice(&content);
+        } else if let Type::VarcharBytes(max) = field.field_type {
+            // widened to the cell of a Varchar(max), the only varchar width clients know
+            out.extend_from_slice(&cell[..max]);
+            out.resize(out.len() + max * (VARCHAR_BYTES_PER_CHAR - 1), 0);
+            out.push(cell[max]);
+        } else {
+            out.extend_from_slice(cell);
+        }
+        position += len;
+    }
+    Ok(out)
+}
+
 fn map_type(ty: &Type) -> (u8, u32) {
     match ty {
         Type::Null => (0, 0),
         Type::Integer => (1, 0),
         Type::String => (2, 0),
-        Type::Varchar(max) => (3, *max as u32),
+        Type::Varchar(max) | Type::VarcharBytes(max) => (3, *max as u32),
         Type::Date => (4, 0),
         Type::Boolean => (5, 0),
+        Type::Decimal(precision, scale) => (6, ((*precision as u32) << 16) | *scale as u32),
+        Type::Blob => (7, 0),
+        Type::Timestamp => (8, 0),
+        Type::TimestampTz => (9, 0),
+        Type::Time => (10, 0),
+        Type::Text => (11, 0),
+        Type::Uuid => (12, 0),
+        Type::Json => (13, 0),
     }
 }
 
@@ -341,6 +429,7 @@ fn write_u32(stream: &mut impl Write, value: u32) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collation::Collation;
     use crate::dataframe::DataFrame;
     use crate::debug::Status;
     use crate::pager::Type;
@@ -417,6 +506,20 @@ mod tests {
             }
         }
 
+        let mut key_rows_buf = [0u8; 4];
+        cur.read_exact(&mut key_rows_buf).unwrap();
+        for _ in 0..u32::from_be_bytes(key_rows_buf) {
+            let mut key_count_buf = [0u8; 2];
+            cur.read_exact(&mut key_count_buf).unwrap();
+            for _ in 0..u16::from_be_bytes(key_count_buf) {
+                let mut name_len_buf = [0u8; 2];
+                cur.read_exact(&mut name_len_buf).unwrap();
+                let mut key = vec![0u8; u16::from_be_bytes(name_len_buf) as usize + 4];
+                cur.read_exact(&mut key).unwrap();
+            }
+        }
+        assert_eq!(cur.position() as usize, bytes.len());
+
         (status[0], message, cols, chunk_sizes, done_flags)
     }
 
@@ -519,6 +622,30 @@ mod tests {
         assert_eq!(map_type(&Type::Boolean), (5, 0));
     }
 
+    #[test]
+    fn test_map_type_decimal() {
+        assert_eq!(map_type(&Type::Decimal(12, 2)), (6, (12 << 16) | 2));
+    }
+
+    #[test]
+    fn test_map_type_blob() {
+        assert_eq!(map_type(&Type::Blob), (7, 0));
+        assert_eq!(map_type(&Type::Text), (11, 0));
+    }
+
+    #[test]
+    fn test_map_type_timestamp_and_time() {
+        assert_eq!(map_type(&Type::Timestamp), (8, 0));
+        assert_eq!(map_type(&Type::TimestampTz), (9, 0));
+        assert_eq!(map_type(&Type::Time), (10, 0));
+    }
+
+    #[test]
+    fn test_map_type_uuid() {
+        assert_eq!(map_type(&Type::Uuid), (12, 0));
+        assert_eq!(map_type(&Type::Json), (13, 0));
+    }
+
     #[test]
     fn test_write_u16_big_endian() {
         let mut out = Vec::new();
@@ -622,6 +749,7 @@ mod tests {
             field_type: Type::String,
             name: "Message".to_string(),
             table_name: "".to_string(),
+            collation: Collation::Binary,
         }];
         let df = DataFrame::from_memory("x".to_string(), header, vec![vec![1u8]]);
         assert!(decode_message_from_dataframe(&df).is_none());
@@ -631,7 +759,7 @@ mod tests {
     fn test_write_response_basic_shape() {
         let mut df = DataFrame::msg("ok");
         let mut out = Vec::new();
-        write_response(&mut out, 0, "OK", &mut df, 10).unwrap();
+        write_response(&mut out, 0, "OK", &mut df, 10, &[]).unwrap();
 
         let (status, message, cols, chunk_sizes, done_flags) = parse_response_bytes(&out);
         assert_eq!(status, 0);
@@ -642,19 +770,52 @@ mod tests {
         assert_eq!(done_flags, vec![0, 1]);
     }
 
+    #[test]
+    fn test_write_response_appends_generated_keys() {
+        let mut df = DataFrame::msg("ok");
+        let mut out = Vec::new();
+        let keys = vec![vec![("id".to_string(), 7)], vec![("id".to_string(), 8)]];
+        write_response(&mut out, 0, "OK", &mut df, 10, &keys).unwrap();
+
+        let (status, _, _, _, done_flags) = parse_response_bytes(&out);
+        assert_eq!(status, 0);
+        assert_eq!(done_flags, vec![0, 1]);
+        let mut expected = 2u32.to_be_bytes().to_vec();
+        for value in [7i32, 8] {
+            expected.extend_from_slice(&1u16.to_be_bytes());
+            expected.extend_from_slice(&2u16.to_be_bytes());
+            expected.extend_from_slice(b"id");
+            expected.extend_from_slice(&value.to_be_bytes());
+        }
+        assert!(out.ends_with(&expected));
+    }
+
+    #[test]
+    fn test_write_response_counts_generated_key_rows_past_u16() {
+        let mut df = DataFrame::msg("ok");
+        let mut out = Vec::new();
+        let keys = vec![vec![("id".to_string(), 1)]; 70_000];
+        write_response(&mut out, 0, "OK", &mut df, 10, &keys).unwrap();
+
+        let keys_len = 70_000 * (2 + 2 + 2 + 4);
+        let count_at = out.len() - keys_len - 4;
+        assert_eq!(out[count_at..count_at + 4], 70_000u32.to_be_bytes());
+    }
+
     #[test]
     fn test_write_response_chunking_fetch_1() {
         let header = vec![Field {
             field_type: Type::Integer,
             name: "id".to_string(),
             table_name: "t".to_string(),
+            collation: Collation::Binary,
         }];
         let row1 = vec![0, 0, 0, 0, 1];
         let row2 = vec![0, 0, 0, 0, 2];
         let mut df = DataFrame::from_memory("t".to_string(), header, vec![row1, row2]);
 
         let mut out = Vec::new();
-        write_response(&mut out, 0, "OK", &mut df, 1).unwrap();
+        write_response(&mut out, 0, "OK", &mut df, 1, &[]).unwrap();
         let (_, _, _, chunk_sizes, done_flags) = parse_response_bytes(&out);
         assert_eq!(chunk_sizes, vec![1, 1, 0]);
         assert_eq!(done_flags, vec![0, 0, 1]);
@@ -666,11 +827,12 @@ mod tests {
             field_type: Type::Integer,
             name: "id".to_string(),
             table_name: "t".to_string(),
+            collation: Collation::Binary,
         }];
         let mut df = DataFrame::from_memory("t".to_string(), header, vec![]);
 
         let mut out = Vec::new();
-        write_response(&mut out, 1, "ERR", &mut df, 5).unwrap();
+        write_response(&mut out, 1, "ERR", &mut df, 5, &[]).unwrap();
         let (status, message, _cols, chunk_sizes, done_flags) = parse_response_bytes(&out);
         assert_eq!(status, 1);
         assert_eq!(message, "ERR");
@@ -684,17 +846,164 @@ mod tests {
             field_type: Type::Varchar(25),
             name: "name".to_string(),
             table_name: "t".to_string(),
+            collation: Collation::Binary,
         }];
         let row = Serializer::parse_varchar("abc", 25).to_vec();
         let mut df = DataFrame::from_memory("t".to_string(), header, vec![row]);
 
         let mut out = Vec::new();
-        write_response(&mut out, 0, "OK", &mut df, 10).unwrap();
+        write_response(&mut out, 0, "OK", &mut df, 10, &[]).unwrap();
         let (_status, _message, cols, _chunk_sizes, _done_flags) = parse_response_bytes(&out);
         assert_eq!(cols[0].1, 3);
         assert_eq!(cols[0].2, 25);
     }
 
+    #[test]
+    fn test_write_response_varchar_cells_keep_utf8() {
+        let header = vec![Field {
+            field_type: Type::Varchar(6),
+            name: "name".to_string(),
+            table_name: "t".to_string(),
+            collation: Collation::Binary,
+        }];
+        let row = Serializer::parse_varchar("Jürgen 東京", 6);
+        let mut df = DataFrame::from_memory("t".to_string(), header, vec![row]);
+
+        let mut out = Vec::new();
+        write_response(&mut out, 0, "Grüße", &mut df, 10, &[]).unwrap();
+        let (_status, message, cols, chunk_sizes, _done_flags) = parse_response_bytes(&out);
+        assert_eq!(message, "Grüße");
+        assert_eq!(cols[0].2, 6);
+        assert_eq!(chunk_sizes, vec![1, 0]);
+
+        // row length, empty null bitmap, cell
+        let mut framed = (26u32).to_be_bytes().to_vec();
+        framed.push(0);
+        framed.extend_from_slice("Jürgen".as_bytes());
+        framed.extend_from_slice(&[0u8; 25 - 7]);
+        assert!(out.windows(framed.len()).any(|w| w == framed.as_slice()));
+    }
+
+    #[test]
+    fn test_write_response_widens_varchar_cells_of_older_files() {
+        let header = vec![Field {
+            field_type: Type::VarcharBytes(10),
+            name: "name".to_string(),
+            table_name: "t".to_string(),
+            collation: Collation::Binary,
+        }];
+        let row = Serializer::parse_varchar_bytes("Jürgen 東京", 10);
+        let mut df = DataFrame::from_memory("t".to_string(), header, vec![row]);
+
+        let mut out = Vec::new();
+        write_response(&mut out, 0, "", &mut df, 10, &[]).unwrap();
+        let (_status, _message, cols, chunk_sizes, _done_flags) = parse_response_bytes(&out);
+        assert_eq!(cols[0].2, 10);
+        assert_eq!(chunk_sizes, vec![1, 0]);
+
+        // the cell of a Varchar(10): 41 bytes; 東 would end past the tenth byte
+        let mut framed = (42u32).to_be_bytes().to_vec();
+        framed.push(0);
+        framed.extend_from_slice("Jürgen ".as_bytes());
+        framed.extend_from_slice(&[0u8; 41 - 8]);
+        assert!(out.windows(framed.len()).any(|w| w == framed.as_slice()));
+    }
+
+    #[test]
+    fn test_write_response_blob_cells_are_length_prefixed() {
+        let db_path = unique_db_path("rustql_server_test_blob");
+        let mut executor = QueryExecutor::init(&db_path, 3);
+        let content: Vec<u8> = (0..5000u32).map(|i| (i % 256) as u8).collect();
+        let literal: String = content.iter().map(|b| format!("{:02X}", b)).collect();
+        assert!(executor.prepare("CREATE TABLE f (id Integer, data Blob)".to_string()).success);
+        assert!(
+            executor
+                .prepare(format!("INSERT INTO f (id, data) VALUES (7, X'{}')", literal))
+                .success
+        );
+
+        let mut df = executor.prepare("SELECT id, data FROM f".to_string()).data;
+        let mut out = Vec::new();
+        write_response(&mut out, 0, "OK", &mut df, 10, &[]).unwrap();
+        let (_status, _message, cols, chunk_sizes, _done_flags) = parse_response_bytes(&out);
+        assert_eq!(cols[1].1, 7);
+        assert_eq!(chunk_sizes, vec![1, 0]);
+
+        let mut expected_row = vec![0u8];
+        expected_row.extend_from_slice(&Serializer::parse_int("7").unwrap());
+        expected_row.extend_from_slice(&(content.len() as u32).to_be_bytes());
+        expected_row.extend_from_slice(&content);
+        let mut framed = (expected_row.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&expected_row);
+        assert!(out.windows(framed.len()).any(|w| w == framed.as_slice()));
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_write_response_marks_null_cells() {
+        let db_path = unique_db_path("rustql_server_test_null");
+        let mut executor = QueryExecutor::init(&db_path, 3);
+        assert!(
+            executor
+                .prepare("CREATE TABLE n (id Integer, qty Integer, note Text)".to_string())
+                .success
+        );
+        assert!(
+            executor
+                .prepare("INSERT INTO n (id, qty, note) VALUES (1, NULL, NULL)".to_string())
+                .success
+        );
+
+        let mut df = executor.prepare("SELECT id, qty, note FROM n".to_string()).data;
+        let mut out = Vec::new();
+        write_response(&mut out, 0, "OK", &mut df, 10, &[]).unwrap();
+
+        // bits 1 and 2 flag qty and note; the null text cell has length 0
+        let mut expected_row = vec![0b110u8];
+        expected_row.extend_from_slice(&Serializer::parse_int("1").unwrap());
+        expected_row.extend_from_slice(&Serializer::null_field(&Type::Integer).unwrap());
+        expected_row.extend_from_slice(&0u32.to_be_bytes());
+        let mut framed = (expected_row.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&expected_row);
+        assert!(out.windows(framed.len()).any(|w| w == framed.as_slice()));
+
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_write_response_sends_rows_returned_by_delete() {
+        let db_path = unique_db_path("rustql_server_test_returning");
+        let mut executor = QueryExecutor::init(&db_path, 3);
+        let body = "x".repeat(3000);
+        assert!(executor.prepare("CREATE TABLE r (id Integer, body Text)".to_string()).success);
+        assert!(
+            executor
+                .prepare(format!("INSERT INTO r (id, body) VALUES (3, '{}')", body))
+                .success
+        );
+
+        // the payload pages are released by the time the response is written
+        let mut df = executor
+            .prepare("DELETE FROM r WHERE id = 3 RETURNING id, body".to_string())
+            .data;
+        let mut out = Vec::new();
+        write_response(&mut out, 0, "OK", &mut df, 10, &[]).unwrap();
+        let (_status, _message, cols, chunk_sizes, _done_flags) = parse_response_bytes(&out);
+        assert_eq!(cols, vec![("id".to_string(), 1, 0), ("body".to_string(), 11, 0)]);
+        assert_eq!(chunk_sizes, vec![1, 0]);
+
+        let mut expected_row = vec![0u8];
+        expected_row.extend_from_slice(&Serializer::parse_int("3").unwrap());
+        expected_row.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        expected_row.extend_from_slice(body.as_bytes());
+        let mut framed = (expected_row.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&expected_row);
+        assert!(out.windows(framed.len()).any(|w| w == framed.as_slice()));
+
+        let _ = fs::remove_file(db_path);
+    }
+
     #[test]
     fn test_rollback_open_transaction_no_tx_is_noop() {
         let db_path = unique_db_path("rustql_server_test_noop");
@@ -0,0 +1,55 @@
+use crate::executor::{QueryExecutor, QueryResult};
+use crate::pager::Position;
+use crate::pager_proxy::PagerProxy;
+use crate::schema::TableSchema;
+
+impl QueryExecutor {
+    /// Creates a table only this executor sees. It has no row in the master table; its pages
+    /// are scratch pages of the database file, which a crash leaves unreferenced.
+    pub(crate) fn create_temp_table(
+        &mut self,
+        mut table: TableSchema,
+    ) -> Result<QueryResult, QueryResult> {
+        table.btree_order = self.btree_node_width;
+        let root_page = self
+            .outside_transaction(|executor| {
+                PagerProxy::create_empty_node_on_new_page(&table, executor.pager_accessor.clone())
+            })
+            .map_err(QueryResult::err)?
+            .position
+            .page();
+        table.root = Position::new(root_page, 0);
+        let page_capacity = table.max_nodes_per_page().map_err(QueryResult::err)?;
+        table.free_list = vec![(root_page, page_capacity.saturating_sub(1))];
+        self.temp_tables.push(table);
+        self.reload_schema()
+    }
+
+    pub(crate) fn drop_temp_table(&mut self, table_id: usize) -> Result<QueryResult, QueryResult> {
+        let table = self.schema.tables[table_id].clone();
+        self.outside_transaction(|executor| executor.release_table_pages(&table))?;
+        self.temp_tables.retain(|temp_table| temp_table.name != table.name);
+        self.reload_schema()
+    }
+
+    /// Runs `f` with no transaction bound to this thread. Temporary tables are created and
+    /// dropped this way, so a ROLLBACK neither loses a table the executor still lists nor
+    /// brings back one it forgot; their rows follow transactions like any others.
+    fn outside_transaction<T>(&self, f: impl FnOnce(&Self) -> T) -> T {
+        let tx_id = self.pager_accessor.current_transaction_id();
+        let _ = self.pager_accessor.set_current_transaction(None);
+        let result = f(self);
+        let _ = self.pager_accessor.set_current_transaction(tx_id);
+        result
+    }
+}
+
+impl Drop for QueryExecutor {
+    fn drop(&mut self) {
+        for table in std::mem::take(&mut self.temp_tables) {
+            // a drop has no caller to report to; pages it fails to release stay unreferenced,
+            // as they would after a crash
+            let _ = self.outside_transaction(|executor| executor.release_table_pages(&table));
+        }
+    }
+}
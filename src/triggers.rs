@@ -0,0 +1,147 @@
+use crate::debug::Status;
+use crate::executor::{QueryExecutor, QueryResult, MASTER_TABLE_NAME};
+use crate::pager::Row;
+use crate::parser::{Parser, TriggerEvent, TriggerTiming};
+use crate::planner::{CompiledCreateTriggerQuery, CompiledQuery};
+
+/// How deeply trigger statements may fire further triggers, which also stops a trigger
+/// that writes to its own table. Every level takes a few hundred KB of stack in debug builds.
+const MAX_TRIGGER_DEPTH: usize = 4;
+
+impl QueryExecutor {
+    pub(crate) fn create_trigger(
+        &mut self,
+        q: CompiledCreateTriggerQuery,
+        query: String,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+        }
+        if self.name_in_use(&q.trigger_name) {
+            return Err(QueryResult::user_input_wrong(format!(
+                "'{}' already exists",
+                q.trigger_name
+            )));
+        }
+        self.insert_master_row(&q.trigger_name, "trigger", 0, &query, "")?;
+        self.reload_schema()
+    }
+
+    pub(crate) fn drop_trigger(
+        &mut self,
+        trigger_name: &str,
+        allow_modification_to_system_table: bool,
+    ) -> Result<QueryResult, QueryResult> {
+        if !allow_modification_to_system_table {
+            self.lock_table_if_needed(MASTER_TABLE_NAME)?;
+        }
+        self.delete_master_row(trigger_name)?;
+        self.reload_schema()
+    }
+
+    pub(crate) fn has_triggers(&self, table_id: usize) -> bool {
+        let table_name = &self.schema.tables[table_id].name;
+        self.schema
+            .triggers
+            .iter()
+            .any(|trigger| &trigger.table_name == table_name)
+    }
+
+    /// The statements that the triggers for `timing` and `event` on `table_id` run, with
+    /// `OLD` bound to `old_rows` and `NEW` to `new_rows`. One of them is empty unless the
+    /// event is an update. Each row runs the statements of every trigger in turn.
+    pub(crate) fn bind_triggers(
+        &self,
+        table_id: usize,
+        timing: TriggerTiming,
+        event: TriggerEvent,
+        old_rows: &[Row],
+        new_rows: &[Row],
+    ) -> Result<Vec<String>, QueryResult> {
+        let table = &self.schema.tables[table_id];
+        let triggers: Vec<_> = self
+            .schema
+            .triggers
+            .iter()
+            .filter(|trigger| {
+                trigger.table_name == table.name && trigger.timing == timing && trigger.event == event
+            })
+            .collect();
+        let mut statements = Vec::new();
+        if triggers.is_empty() {
+            return Ok(statements);
+        }
+        for row_idx in 0..old_rows.len().max(new_rows.len()) {
+            for trigger in &triggers {
+                for statement in &trigger.statements {
+                    let bound = Parser::bind_row_references(statement, &mut |row, column| {
+                        let values = if row == "NEW" { new_rows } else { old_rows };
+                        let field_idx = table
+                            .fields
+                            .iter()
+                            .position(|field| field.name == column)
+                            .ok_or_else(|| format!("Column '{}.{}' not found", table.name, column))?;
+                        let values = values
+                            .get(row_idx)
+                            .ok_or_else(|| format!("Trigger '{}' has no {} row", trigger.name, row))?;
+                        self.row_literal(table_id, values, field_idx)
+                            .map_err(|status| format!("{:?}", status))
+                    })
+                    .map_err(QueryResult::user_input_wrong)?;
+                    statements.push(bound);
+                }
+            }
+        }
+        Ok(statements)
+    }
+
+    /// Runs bound trigger statements. Writes go to `execute_write` directly, so nested
+    /// triggers only add its frames to the stack.
+    pub(crate) fn run_trigger_statements(
+        &mut self,
+        statements: Vec<String>,
+    ) -> Result<(), QueryResult> {
+        if statements.is_empty() {
+            return Ok(());
+        }
+        if self.trigger_depth >= MAX_TRIGGER_DEPTH {
+            return Err(QueryResult::user_input_wrong(format!(
+                "Triggers are nested more than {} levels deep",
+                MAX_TRIGGER_DEPTH
+            )));
+        }
+        self.trigger_depth += 1;
+        let result = statements.iter().try_for_each(|statement| {
+            match self.compile_query(statement)? {
+                CompiledQuery::Select(q) => {
+                    self.exec_planned_tree(&q.plan)
+                        .and_then(|result| result.fetch())
+                        .map_err(QueryResult::err)?;
+                }
+                CompiledQuery::InsertSelect(q) => {
+                    self.insert_select(q, false)?;
+                }
+                write => {
+                    self.execute_write(write, false)?;
+                }
+            }
+            Ok(())
+        });
+        self.trigger_depth -= 1;
+        result
+    }
+
+    /// A field of a full row as a literal for a trigger statement.
+    fn row_literal(
+        &self,
+        table_id: usize,
+        row: &Row,
+        field_idx: usize,
+    ) -> Result<String, Status> {
+        let Some(text) = self.field_text(&self.schema.tables[table_id], row, field_idx)? else {
+            return Ok("NULL".to_string());
+        };
+        Ok(format!("'{}'", text.replace("'", "''")))
+    }
+}
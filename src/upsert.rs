@@ -0,0 +1,167 @@
+use crate::btree::Btree;
+use crate::cursor::BTreeCursor;
+use crate::debug::Status;
+use crate::executor::{QueryExecutor, QueryResult};
+use crate::pager::Row;
+use crate::parser::{ParsedQuery, ParsedUpdateQuery, ParsedValue};
+use crate::planner::{
+    CompiledConditionExpr, CompiledConflictValue, CompiledInsertQuery, CompiledLogicalOp,
+    CompiledPredicateExpr, CompiledQuery, ConflictTarget, Planner, SqlConditionOpCode,
+    SqlStatementComparisonOperator,
+};
+use crate::returning::ReturnedRows;
+use crate::serializer::Serializer;
+use std::cmp::Ordering;
+
+impl QueryExecutor {
+    /// The stored row that `full_row` would collide with on `target`: a row with the same key,
+    /// or the same non-NULL value in the UNIQUE column. Without a target the key and every
+    /// UNIQUE column are tried.
+    pub(crate) fn find_conflict(
+        &self,
+        table_id: usize,
+        full_row: &Row,
+        target: Option<&ConflictTarget>,
+    ) -> Result<Option<Row>, QueryResult> {
+        let table = self.schema.tables[table_id].clone();
+        let targets = match target {
+            Some(target) => vec![target.clone()],
+            None => std::iter::once(ConflictTarget::Key)
+                .chain(
+                    self.schema
+                        .index_definitions
+                        .iter()
+                        .filter(|idx| idx.unique && idx.base_table == table.name)
+                        .filter_map(|idx| {
+                            table.fields.iter().position(|f| f.name == idx.column_name)
+                        })
+                        .map(ConflictTarget::Column),
+                )
+                .collect(),
+        };
+
+        for target in targets {
+            let existing = match target {
+                ConflictTarget::Key => {
+                    let (key, _) =
+                        Serializer::split_key_and_row(full_row, &table).map_err(QueryResult::err)?;
+                    let mut cursor = BTreeCursor::new(
+                        Btree::init(table.btree_order, self.pager_accessor.clone(), table.clone())
+                            .map_err(QueryResult::err)?,
+                    );
+                    cursor.go_to_greater_than_equal(&key).map_err(QueryResult::err)?;
+                    match cursor.current().map_err(QueryResult::err)? {
+                        Some((found_key, row))
+                            if cursor.btree.compare(&found_key, &key).map_err(QueryResult::err)?
+                                == Ordering::Equal
+                                && !Serializer::is_tomb(&found_key, &table)
+                                    .map_err(QueryResult::err)? =>
+                        {
+                            Some(
+                                Serializer::reconstruct_row(&found_key, &row, &table)
+                                    .map_err(QueryResult::err)?,
+                            )
+                        }
+                        _ => None,
+                    }
+                }
+                ConflictTarget::Column(field_idx) => {
+                    let value = Serializer::get_field_on_row(full_row, field_idx, &table)
+                        .map_err(QueryResult::err)?;
+                    // NULLs never collide
+                    if Serializer::is_null(&value, &table.fields[field_idx].field_type)
+                        .map_err(QueryResult::err)?
+                    {
+                        None
+                    } else {
+                        self.rows_with_value(table_id, field_idx, &value)
+                            .map_err(QueryResult::err)?
+                            .into_iter()
+                            .next()
+                    }
+                }
+            };
+            if existing.is_some() {
+                return Ok(existing);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Handles an INSERT whose row collides with `existing`. The proposed row is dropped, and
+    /// with DO UPDATE the existing row is updated in place like `UPDATE ... WHERE key = ...`,
+    /// so constraints, indexes, foreign keys and UPDATE triggers apply as usual. The updated
+    /// row is what RETURNING reports; a skipped row reports nothing.
+    pub(crate) fn resolve_conflict(
+        &mut self,
+        q: &CompiledInsertQuery,
+        full_row: &Row,
+        existing: &Row,
+        allow_modification_to_system_table: bool,
+        returned: &mut ReturnedRows,
+    ) -> Result<QueryResult, QueryResult> {
+        let table = self.schema.tables[q.table_id].clone();
+        let Some(update) = q.on_conflict.as_ref().and_then(|c| c.update.clone()) else {
+            self.release_conflicting_payloads(q, full_row)?;
+            return Ok(QueryResult::went_fine());
+        };
+
+        // the excluded values are read before the payloads of the proposed row go away
+        let mut assignments = Vec::with_capacity(update.len());
+        for (field_idx, value) in update {
+            let value = match value {
+                CompiledConflictValue::Value(value) => value,
+                CompiledConflictValue::Excluded(source_idx) => self
+                    .field_text(&table, full_row, source_idx)
+                    .map_err(QueryResult::err)?
+                    .map(ParsedValue::Literal),
+            };
+            assignments.push((table.fields[field_idx].name.clone(), value));
+        }
+        self.release_conflicting_payloads(q, full_row)?;
+
+        let update_query = ParsedQuery::Update(ParsedUpdateQuery {
+            table_name: table.name.clone(),
+            assignments,
+            conditions: None,
+            returning: None,
+        });
+        let CompiledQuery::Update(mut update_query) = Planner::plan(&self.schema, update_query)?
+        else {
+            return Err(QueryResult::err(Status::InternalExceptionCompilerError));
+        };
+        let (existing_key, _) =
+            Serializer::split_key_and_row(existing, &table).map_err(QueryResult::err)?;
+        let mut condition = None;
+        for &key_idx in &table.key_positions {
+            let predicate = CompiledConditionExpr::Predicate(CompiledPredicateExpr::Compare {
+                column_idx: key_idx,
+                op: SqlStatementComparisonOperator::Equal,
+                value: Serializer::get_field_on_row(existing, key_idx, &table)
+                    .map_err(QueryResult::err)?,
+                collation: table.fields[key_idx].collation,
+            });
+            condition = Some(match condition {
+                None => predicate,
+                Some(left) => CompiledConditionExpr::Logical {
+                    op: CompiledLogicalOp::And,
+                    left: Box::new(left),
+                    right: Box::new(predicate),
+                },
+            });
+        }
+        update_query.operation = SqlConditionOpCode::SelectKeyUnique;
+        update_query.seek_key = Some(existing_key);
+        update_query.condition = condition;
+        // the INSERT already holds the table lock and runs atomically where it has to
+        self.execute_update(update_query, allow_modification_to_system_table, returned)
+    }
+
+    fn release_conflicting_payloads(
+        &self,
+        q: &CompiledInsertQuery,
+        full_row: &Row,
+    ) -> Result<(), QueryResult> {
+        self.release_inserted_payloads(&q.payloads, full_row)
+    }
+}
@@ -7,25 +7,25 @@ mod tests {
     use rustql::{btree::Btree, pager::Type};
 
     fn make_int_key(k: i32) -> Vec<u8> {
-        let mut v = Serializer::parse_int(&*k.to_string()).expect("").to_vec();
+        let mut v = Serializer::parse_int(&k.to_string()).expect("").to_vec();
         Serializer::set_flag_at_position(&mut v, KeyMeta::Tomb as u8, false, &Type::Integer)
             .expect("");
         v
     }
 
     fn make_row(k: i32) -> Vec<u8> {
-        let mut v = Serializer::parse_int(&*k.to_string()).expect("").to_vec();
+        let mut v = Serializer::parse_int(&k.to_string()).expect("").to_vec();
         Serializer::set_flag_at_position(&mut v, FieldMeta::Null as u8, false, &Type::Integer)
             .expect("");
         v
     }
 
-    fn extract_int_from_key(k: &Vec<u8>) -> i32 {
+    fn extract_int_from_key(k: &[u8]) -> i32 {
         let b = [k[1], k[2], k[3], k[4]];
         i32::from_be_bytes(b)
     }
 
-    fn extract_int_from_row(r: &Vec<u8>) -> i32 {
+    fn extract_int_from_row(r: &[u8]) -> i32 {
         let b = [r[1], r[2], r[3], r[4]];
         i32::from_be_bytes(b)
     }
@@ -79,8 +79,8 @@ mod tests {
     #[test]
     fn test_02_stress_insert() {
         let mut t = make_tree();
-        let mut rng = rand::thread_rng();
-        let random_keys: Vec<i32> = (0..1000).map(|_| rng.gen_range(0..10000)).collect();
+        let mut rng = rand::rng();
+        let random_keys: Vec<i32> = (0..1000).map(|_| rng.random_range(0..10000)).collect();
         let mut unique = random_keys.clone();
         unique.sort();
         unique.dedup();
@@ -97,7 +97,7 @@ mod tests {
     fn test_03_stress_delete() {
         let mut t = make_tree();
         let mut keys: Vec<i32> = (0..500).collect();
-        keys.shuffle(&mut rand::thread_rng());
+        keys.shuffle(&mut rand::rng());
         for k in &keys {
             t.insert(make_int_key(*k), make_row(*k)).unwrap();
         }
@@ -174,7 +174,7 @@ mod tests {
     fn test_07_large_random_zigzag() {
         let mut t = make_tree();
         let mut keys: Vec<i32> = (0..200).collect();
-        keys.shuffle(&mut rand::thread_rng());
+        keys.shuffle(&mut rand::rng());
         for k in &keys {
             t.insert(make_int_key(*k), make_row(*k)).unwrap();
         }
@@ -188,7 +188,7 @@ mod tests {
         let mut idx = 0usize;
         for _ in 0..1000 {
             let dir = if rand::random::<bool>() { 1 } else { -1 };
-            let steps = rand::thread_rng().gen_range(1..=5);
+            let steps = rand::rng().random_range(1..=5);
             if dir == 1 {
                 let possible = 199 - idx;
                 let real = steps.min(possible);
@@ -329,7 +329,7 @@ mod tests {
     fn test_14_stress_go_to_and_traversal() {
         let mut t = make_tree();
         let mut keys: Vec<i32> = (1000..2500).collect();
-        keys.shuffle(&mut rand::thread_rng());
+        keys.shuffle(&mut rand::rng());
         for k in &keys {
             t.insert(make_int_key(*k), make_row(*k)).unwrap();
         }
@@ -337,7 +337,7 @@ mod tests {
         expected.sort_by_key(|x| x.0);
         let mut c = BTreeCursor::new(t.clone());
         for _ in 0..500 {
-            let target = expected[rand::thread_rng().gen_range(0..expected.len())].0;
+            let target = expected[rand::rng().random_range(0..expected.len())].0;
             let idx = expected.iter().position(|p| p.0 == target).unwrap();
             c.go_to(&make_int_key(target)).unwrap();
             assert!(c.is_valid());
@@ -345,7 +345,7 @@ mod tests {
                 extract_int_from_key(&c.current().unwrap().unwrap().0),
                 target
             );
-            let dec = rand::thread_rng().gen_range(1..=5);
+            let dec = rand::rng().random_range(1..=5);
             let real = dec.min(idx);
             for _ in 0..real {
                 c.decrease().unwrap();
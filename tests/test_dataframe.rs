@@ -1,3 +1,4 @@
+use rustql::collation::Collation;
 use rustql::dataframe::DataFrame;
 use rustql::pager::Type;
 use rustql::schema::Field;
@@ -17,6 +18,7 @@ fn single_int_header() -> Vec<Field> {
         field_type: Type::Integer,
         name: "id".to_string(),
         table_name: "t".to_string(),
+        collation: Collation::Binary,
     }]
 }
 
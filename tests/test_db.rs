@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests {
     use rustql::btree::Btree;
-    use rustql::executor::QueryExecutor as RustqlQueryExecutor;
-    use rustql::pager::Position;
-    use rustql::pager_proxy::PagerProxy;
+    use rustql::executor::{QueryExecutor as RustqlQueryExecutor, QueryResult};
+    use rustql::pager::{Position, Type};
+    use rustql::pager_proxy::{PageManager, PagerProxy};
     use rustql::planner::{CompiledQuery, PlanNode, SqlConditionOpCode};
     use rustql::serializer::Serializer;
     use std::collections::HashSet;
@@ -51,6 +51,68 @@ mod tests {
         }
     }
 
+    impl QueryExecutor {
+        /// Closes the database and opens its file again.
+        fn reopen(&self) -> RustqlQueryExecutor {
+            self.exit();
+            RustqlQueryExecutor::init(&self.db_path, BTREE_NODE_SIZE)
+        }
+    }
+
+    fn run(executor: &mut RustqlQueryExecutor, query: &str) -> QueryResult {
+        executor.prepare(query.to_string())
+    }
+
+    /// Runs `query` and fails the test if it does not succeed.
+    fn run_ok(executor: &mut RustqlQueryExecutor, query: &str) -> QueryResult {
+        let result = executor.prepare(query.to_string());
+        assert!(result.success, "{}: {}", query, result);
+        result
+    }
+
+    /// Runs `query`, which has to fail, and returns its message.
+    fn error_message(executor: &mut RustqlQueryExecutor, query: &str) -> String {
+        let result = executor.prepare(query.to_string());
+        assert!(!result.success, "{}", query);
+        result.to_string()
+    }
+
+    fn rows(executor: &mut RustqlQueryExecutor, query: &str) -> Vec<Vec<u8>> {
+        run_ok(executor, query).data.fetch().unwrap()
+    }
+
+    fn count(executor: &mut RustqlQueryExecutor, query: &str) -> usize {
+        rows(executor, query).len()
+    }
+
+    /// The values of the first selected column, formatted as `field_type`.
+    fn values(executor: &mut RustqlQueryExecutor, query: &str, field_type: Type) -> Vec<String> {
+        rows(executor, query)
+            .iter()
+            .map(|row| Serializer::format_field(row, &field_type).unwrap())
+            .collect()
+    }
+
+    fn find_scan(plan: &PlanNode) -> Option<&PlanNode> {
+        match plan {
+            PlanNode::SeqScan { .. } => Some(plan),
+            PlanNode::Project { source, .. } => find_scan(source),
+            PlanNode::Filter { source, .. } => find_scan(source),
+            _ => None,
+        }
+    }
+
+    /// The scan operation the planner picks for the SELECT `query`.
+    fn scan_operation(executor: &RustqlQueryExecutor, query: &str) -> SqlConditionOpCode {
+        match executor.compile_query(query).unwrap() {
+            CompiledQuery::Select(select) => match find_scan(&select.plan) {
+                Some(PlanNode::SeqScan { operation, .. }) => operation.clone(),
+                _ => panic!("expected SeqScan in plan"),
+            },
+            _ => panic!("expected compiled SELECT"),
+        }
+    }
+
     #[test]
     fn test_create_table() {
         let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
@@ -199,7 +261,41 @@ mod tests {
         assert!(result.success);
         let rows = result.data.fetch().unwrap();
         assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0], vec![b'A', b'l', b'i', b'c', b'e', 0]);
+        let mut expected = b"Alice".to_vec();
+        expected.resize(5 * 4 + 1, 0);
+        assert_eq!(rows[0], expected);
+    }
+
+    #[test]
+    fn test_varchar_and_string_keep_utf8_text() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        executor.prepare("CREATE TABLE customers (id Integer, name VARCHAR(5), city String)".to_string());
+
+        // VARCHAR(n) counts characters, not bytes
+        for (id, name, city) in [(1, "Jörg", "Köln"), (2, "山田太郎", "東京"), (3, "Zoë", "Zürich")] {
+            let res = executor.prepare(format!(
+                "INSERT INTO customers (id, name, city) VALUES ({}, '{}', '{}')",
+                id, name, city
+            ));
+            assert!(res.success);
+        }
+        assert!(
+            !executor
+                .prepare("INSERT INTO customers (id, name, city) VALUES (4, 'Müllers', 'Wien')".to_string())
+                .success
+        );
+
+        let result = executor.prepare("SELECT name, city FROM customers".to_string());
+        assert!(result.success);
+        let text = result.to_string();
+        for expected in ["Jörg", "Köln", "山田太郎", "東京", "Zoë", "Zürich"] {
+            assert!(text.contains(expected), "missing {} in {}", expected, text);
+        }
+
+        let found = executor.prepare("SELECT id FROM customers WHERE name = '山田太郎'".to_string());
+        assert_eq!(found.data.fetch().unwrap().len(), 1);
+        let found = executor.prepare("SELECT id FROM customers WHERE city > 'Zürich'".to_string());
+        assert_eq!(found.data.fetch().unwrap().len(), 1);
     }
 
     #[test]
@@ -213,6 +309,49 @@ mod tests {
         assert!(!result.success);
     }
 
+    #[test]
+    fn test_varchar_of_older_files_keeps_its_byte_layout() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        run_ok(&mut executor, "CREATE TABLE names (id Integer, name VARCHAR(5))");
+        run_ok(&mut executor, "CREATE INDEX idx_name ON names (name)");
+        executor.exit();
+        // older files store their tables as 'table', with n + 1 bytes per VARCHAR(n) cell
+        let mut file = fs::read(&executor.db_path).unwrap();
+        for at in 0..file.len() - 8 {
+            if &file[at..at + 8] == b"table_v2" {
+                file[at + 5..at + 8].fill(0);
+            }
+        }
+        fs::write(&executor.db_path, file).unwrap();
+
+        let mut reopened = RustqlQueryExecutor::init(&executor.db_path, BTREE_NODE_SIZE);
+        run_ok(&mut reopened, "INSERT INTO names (id, name) VALUES (1, 'Alice')");
+        run_ok(&mut reopened, "INSERT INTO names (id, name) VALUES (2, 'Jörg')");
+        let mut expected = b"Alice".to_vec();
+        expected.resize(5 + 1, 0);
+        assert_eq!(rows(&mut reopened, "SELECT name FROM names WHERE id = 1"), vec![expected]);
+        let message = error_message(&mut reopened, "INSERT INTO names (id, name) VALUES (3, '東京')");
+        assert!(message.contains("needs 6 bytes of UTF-8"), "{}", message);
+        assert_eq!(count(&mut reopened, "SELECT id FROM names WHERE name = 'Jörg'"), 1);
+
+        // rewriting the table gives it the character layout
+        run_ok(&mut reopened, "ALTER TABLE names ALTER COLUMN name TYPE VARCHAR(5)");
+        reopened.exit();
+        let mut reopened = RustqlQueryExecutor::init(&executor.db_path, BTREE_NODE_SIZE);
+        run_ok(&mut reopened, "INSERT INTO names (id, name) VALUES (3, '東京')");
+        let mut expected = b"Alice".to_vec();
+        expected.resize(5 * 4 + 1, 0);
+        assert_eq!(rows(&mut reopened, "SELECT name FROM names WHERE id = 1"), vec![expected]);
+        assert_eq!(
+            values(&mut reopened, "SELECT name FROM names WHERE name >= 'J'", Type::Varchar(5)),
+            vec!["Jörg", "東京"]
+        );
+        assert_eq!(
+            scan_operation(&reopened, "SELECT name FROM names WHERE name = '東京'"),
+            SqlConditionOpCode::SelectIndexUnique
+        );
+    }
+
     #[test]
     fn test_delete_single_row() {
         let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
@@ -281,6 +420,29 @@ mod tests {
         assert!(executor.check_integrity().is_ok());
     }
 
+    #[test]
+    fn test_insert_rejects_unknown_and_repeated_columns() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE t (id Integer, name String)".to_string())
+                .success
+        );
+        for query in [
+            "INSERT INTO t (id, nmae) VALUES (1, 'x')",
+            "INSERT INTO t (id, id) VALUES (2, 3)",
+            "INSERT INTO t (id, name, id) VALUES (4, 'y', 5)",
+            "INSERT INTO t (id, name) VALUES (6, 'z'), (7, 'w'), (8)",
+            "INSERT INTO t (id, nmae) VALUES (9, 'v'), (10, 'u')",
+        ] {
+            let result = executor.prepare(query.to_string());
+            assert!(!result.success, "{}", query);
+        }
+        let result = executor.prepare("SELECT * FROM t".to_string());
+        assert!(result.success);
+        assert_eq!(result.data.fetch().unwrap().len(), 0);
+    }
+
     #[test]
     fn test_delete_all_rows() {
         let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
@@ -345,7 +507,7 @@ mod tests {
             //println!("{}", result);
             //println!("---");
             assert_eq!(result.data.clone().fetch().unwrap().len(), len / 2);
-            for (_i, _row) in result.data.fetch().unwrap().iter().enumerate() {
+            for _row in result.data.fetch().unwrap().iter() {
                 //assert_eq!(Serializer::bytes_to_int(row[0..5].try_into().unwrap()), expected_id as i32);
             }
 
@@ -362,7 +524,7 @@ mod tests {
 
             let result = executor.prepare("SELECT * FROM test".to_string());
             assert_eq!(result.data.fetch().unwrap().len(), len);
-            if !executor.check_integrity().is_ok() {
+            if executor.check_integrity().is_err() {
                 executor.debug(Some("test"));
             }
             assert!(executor.check_integrity().is_ok())
@@ -595,7 +757,7 @@ mod tests {
 
         // Check the integrity of the data
         for (i, row) in result.data.fetch().unwrap().iter().enumerate() {
-            let expected_id = if i < 5 { i + 6 } else { i + 6 };
+            let expected_id = i + 6;
             let expected_name = if i < 5 {
                 format!("User{}", expected_id)
             } else {
@@ -663,10 +825,8 @@ mod tests {
 
                 for i in 1..=size {
                     let result = executor.prepare(format!(
-                        "INSERT INTO test (id, other, name) VALUES ({}, {}, '{}')",
-                        i,
-                        modulo,
-                        format!("Hallo Welt {}", i)
+                        "INSERT INTO test (id, other, name) VALUES ({}, {}, 'Hallo Welt {}')",
+                        i, modulo, i
                     ));
                     //println!("{}", result);
                     assert!(result.success)
@@ -692,10 +852,8 @@ mod tests {
                 for i in 1..=size {
                     if i % modulo == 0 {
                         let result = executor.prepare(format!(
-                            "INSERT INTO test (id, other, name) VALUES ({}, {}, '{}')",
-                            i,
-                            modulo,
-                            format!("Hallo Welt {}", i)
+                            "INSERT INTO test (id, other, name) VALUES ({}, {}, 'Hallo Welt {}')",
+                            i, modulo, i
                         ));
                         assert!(result.success);
                     }
@@ -909,15 +1067,6 @@ mod tests {
             .unwrap();
         match compiled {
             CompiledQuery::Select(select) => {
-                fn find_scan(plan: &PlanNode) -> Option<&PlanNode> {
-                    match plan {
-                        PlanNode::SeqScan { .. } => Some(plan),
-                        PlanNode::Project { source, .. } => find_scan(source),
-                        PlanNode::Filter { source, .. } => find_scan(source),
-                        _ => None,
-                    }
-                }
-
                 match find_scan(&select.plan) {
                     Some(PlanNode::SeqScan { operation, .. }) => {
                         assert_eq!(*operation, SqlConditionOpCode::SelectFTS);
@@ -950,6 +1099,56 @@ mod tests {
         assert!(!after.success);
     }
 
+    #[test]
+    fn test_drop_table_with_index_keeps_other_tables() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE t (a Integer, b Integer)",
+            "CREATE INDEX t_b ON t (b)",
+            "CREATE TABLE u (a Integer, b Integer)",
+            "CREATE INDEX _u_b ON u (b)",
+            "INSERT INTO u (a, b) VALUES (1, 2)",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+
+        // removes t_b and then t, which empties the root of the master tree
+        assert!(executor.prepare("DROP TABLE t".to_string()).success);
+
+        let result = executor.prepare("SELECT name FROM rustsql_master".to_string());
+        assert!(result.success);
+        let names = result.to_string();
+        assert!(names.contains("_u_b") && names.contains('u'), "{}", names);
+        assert!(!names.contains("t_b"), "{}", names);
+        assert!(executor.prepare("SELECT a FROM u WHERE b = 2".to_string()).success);
+
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("SELECT a FROM u WHERE b = 2".to_string());
+        assert!(result.success);
+        assert!(!reopened.prepare("SELECT * FROM t".to_string()).success);
+    }
+
+    #[test]
+    fn test_drop_tables_keeps_long_master_rows_after_reopen() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE t (a Integer, b Integer)",
+            "CREATE TABLE u (a Integer, b Integer)",
+            "CREATE TABLE v (a Integer)",
+            "CREATE TABLE aw (a Integer)",
+            // rebalances the master tree, moving rows whose SQL is stored outside the node
+            "DROP TABLE v",
+            "DROP TABLE t",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+
+        let mut reopened = executor.reopen();
+        assert!(reopened.prepare("INSERT INTO u (a, b) VALUES (1, 2)".to_string()).success);
+        assert!(reopened.prepare("SELECT * FROM aw".to_string()).success);
+        assert!(!reopened.prepare("SELECT * FROM v".to_string()).success);
+    }
+
     #[test]
     fn test_drop_table_marks_related_pages_deleted() {
         fn collect_pages(node: &rustql::btree::BTreeNode, pages: &mut HashSet<usize>) {
@@ -1040,15 +1239,6 @@ mod tests {
             .unwrap();
         match compiled {
             CompiledQuery::Select(select) => {
-                fn find_scan(plan: &PlanNode) -> Option<&PlanNode> {
-                    match plan {
-                        PlanNode::SeqScan { .. } => Some(plan),
-                        PlanNode::Project { source, .. } => find_scan(source),
-                        PlanNode::Filter { source, .. } => find_scan(source),
-                        _ => None,
-                    }
-                }
-
                 match find_scan(&select.plan) {
                     Some(PlanNode::SeqScan {
                         operation,
@@ -1097,15 +1287,6 @@ mod tests {
 
         match compiled {
             CompiledQuery::Select(select) => {
-                fn find_scan(plan: &PlanNode) -> Option<&PlanNode> {
-                    match plan {
-                        PlanNode::SeqScan { .. } => Some(plan),
-                        PlanNode::Project { source, .. } => find_scan(source),
-                        PlanNode::Filter { source, .. } => find_scan(source),
-                        _ => None,
-                    }
-                }
-
                 match find_scan(&select.plan) {
                     Some(PlanNode::SeqScan {
                         operation,
@@ -1204,15 +1385,6 @@ mod tests {
 
         match compiled {
             CompiledQuery::Select(select) => {
-                fn find_scan(plan: &PlanNode) -> Option<&PlanNode> {
-                    match plan {
-                        PlanNode::SeqScan { .. } => Some(plan),
-                        PlanNode::Project { source, .. } => find_scan(source),
-                        PlanNode::Filter { source, .. } => find_scan(source),
-                        _ => None,
-                    }
-                }
-
                 match find_scan(&select.plan) {
                     Some(PlanNode::SeqScan {
                         operation,
@@ -1261,15 +1433,6 @@ mod tests {
 
         match compiled {
             CompiledQuery::Select(select) => {
-                fn find_scan(plan: &PlanNode) -> Option<&PlanNode> {
-                    match plan {
-                        PlanNode::SeqScan { .. } => Some(plan),
-                        PlanNode::Project { source, .. } => find_scan(source),
-                        PlanNode::Filter { source, .. } => find_scan(source),
-                        _ => None,
-                    }
-                }
-
                 match find_scan(&select.plan) {
                     Some(PlanNode::SeqScan {
                         operation,
@@ -1287,4 +1450,2425 @@ mod tests {
             _ => panic!("expected compiled SELECT"),
         }
     }
+    #[test]
+    fn test_decimal_insert_select_and_range_filter() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE prices (id Integer, amount Decimal(6,2))".to_string())
+                .success
+        );
+        for (id, amount) in [(1, "19.99"), (2, "-0.5"), (3, "100"), (4, "2.345")] {
+            let res = executor.prepare(format!(
+                "INSERT INTO prices (id, amount) VALUES ({}, {})",
+                id, amount
+            ));
+            assert!(res.success);
+        }
+
+        let result = executor.prepare("SELECT amount FROM prices WHERE amount > 2.34".to_string());
+        assert!(result.success);
+        let formatted: Vec<String> = result
+            .data
+            .fetch()
+            .unwrap()
+            .iter()
+            .map(|row| Serializer::format_decimal(row, 6, 2))
+            .collect();
+        assert_eq!(formatted, vec!["19.99", "100.00", "2.35"]);
+
+        let overflow = executor.prepare("INSERT INTO prices (id, amount) VALUES (5, 10000)".to_string());
+        assert!(!overflow.success);
+        assert!(overflow.to_string().contains("ExceptionDecimalOverflow"));
+        let garbage = executor.prepare("INSERT INTO prices (id, amount) VALUES (6, 1.2.3)".to_string());
+        assert!(!garbage.success);
+    }
+
+    #[test]
+    fn test_decimal_filters_compare_at_the_literal_precision() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE prices (id Integer, amount Decimal(6,2))",
+            "INSERT INTO prices VALUES (1, 2.35)",
+            "INSERT INTO prices VALUES (2, -2.35)",
+            "INSERT INTO prices VALUES (3, 9999.99)",
+            "INSERT INTO prices (id) VALUES (4)",
+        ] {
+            run_ok(&mut executor, query);
+        }
+        let ids = |executor: &mut RustqlQueryExecutor, condition: &str| {
+            values(executor, &format!("SELECT id FROM prices WHERE {}", condition), Type::Integer)
+        };
+
+        assert_eq!(ids(&mut executor, "amount > 2.346"), vec!["1", "3"]);
+        assert_eq!(ids(&mut executor, "amount > 2.354"), vec!["3"]);
+        assert_eq!(ids(&mut executor, "amount >= 2.346"), vec!["1", "3"]);
+        assert_eq!(ids(&mut executor, "amount < 2.354"), vec!["1", "2"]);
+        assert_eq!(ids(&mut executor, "amount <= 2.349"), vec!["2"]);
+        assert!(ids(&mut executor, "amount = 2.345").is_empty());
+        assert_eq!(ids(&mut executor, "amount = 2.3500"), vec!["1"]);
+        assert_eq!(ids(&mut executor, "amount < -2.345"), vec!["2"]);
+        assert_eq!(ids(&mut executor, "amount > -2.355"), vec!["1", "2", "3"]);
+
+        // literals beyond the column's range compare like any other number; NULL matches none
+        assert_eq!(ids(&mut executor, "amount < 10000"), vec!["1", "2", "3"]);
+        assert_eq!(ids(&mut executor, "amount <= 10000"), vec!["1", "2", "3"]);
+        assert_eq!(ids(&mut executor, "amount > -10000"), vec!["1", "2", "3"]);
+        assert!(ids(&mut executor, "amount > 9999.991").is_empty());
+        assert!(ids(&mut executor, "amount >= 10000").is_empty());
+        assert!(ids(&mut executor, "amount < -100000000000000000000000000000000000000000").is_empty());
+        assert!(ids(&mut executor, "amount = 10000").is_empty());
+        assert!(!run(&mut executor, "SELECT id FROM prices WHERE amount > 1.2.3").success);
+
+        run_ok(&mut executor, "DELETE FROM prices WHERE amount > 9999.985");
+        assert_eq!(ids(&mut executor, "amount < 10000"), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_decimal_index_range_scan() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        run_ok(&mut executor, "CREATE TABLE prices (id Integer, amount Decimal(8,2))");
+        run_ok(&mut executor, "CREATE INDEX prices_amount ON prices (amount)");
+        for id in 0..40 {
+            let amount = format!("{}.{:02}", (id * 37) % 100 - 50, id);
+            run_ok(&mut executor, &format!("INSERT INTO prices VALUES ({}, {})", id, amount));
+        }
+
+        let query = "SELECT amount FROM prices WHERE amount >= 30.005";
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectIndexRange);
+        let mut expected: Vec<String> = (0..40)
+            .map(|id| format!("{}.{:02}", (id * 37) % 100 - 50, id))
+            .filter(|amount| amount.parse::<f64>().unwrap() >= 30.005)
+            .collect();
+        expected.sort_by(|a, b| a.parse::<f64>().unwrap().total_cmp(&b.parse::<f64>().unwrap()));
+        assert!(!expected.is_empty());
+        assert_eq!(values(&mut executor, query, Type::Decimal(8, 2)), expected);
+
+        let query = "SELECT id FROM prices WHERE amount = -13.01";
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectIndexUnique);
+        assert_eq!(values(&mut executor, query, Type::Integer), vec!["1"]);
+        assert_eq!(count(&mut executor, "SELECT id FROM prices WHERE amount > -1000000"), 40);
+        assert_eq!(count(&mut executor, "SELECT id FROM prices WHERE amount >= 1000000"), 0);
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_decimal_key_ordering() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE ledger (amount Decimal(10,3), note Integer)".to_string())
+                .success
+        );
+        let amounts = ["5", "-12.5", "0.001", "-0.001", "9999999.999", "-3", "42.42", "0"];
+        for (i, amount) in amounts.iter().enumerate() {
+            let res = executor.prepare(format!(
+                "INSERT INTO ledger (amount, note) VALUES ({}, {})",
+                amount, i
+            ));
+            assert!(res.success);
+        }
+
+        let result = executor.prepare("SELECT amount FROM ledger WHERE amount >= -3".to_string());
+        assert!(result.success);
+        let formatted: Vec<String> = result
+            .data
+            .fetch()
+            .unwrap()
+            .iter()
+            .map(|row| Serializer::format_decimal(row, 10, 3))
+            .collect();
+        assert_eq!(
+            formatted,
+            vec!["-3.000", "-0.001", "0.000", "0.001", "5.000", "42.420", "9999999.999"]
+        );
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    fn blob_literal(content: &[u8]) -> String {
+        let hex: String = content.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("X'{}'", hex)
+    }
+
+    #[test]
+    fn test_blob_insert_select_and_filter() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE files (id Integer, data Blob, name String)".to_string())
+                .success
+        );
+
+        let small = vec![0xDE, 0xAD, 0x00, 0xBE, 0xEF];
+        let large: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        for (id, content) in [(1, &small), (2, &large), (3, &Vec::new())] {
+            let res = executor.prepare(format!(
+                "INSERT INTO files (id, data, name) VALUES ({}, {}, 'f{}')",
+                id,
+                blob_literal(content),
+                id
+            ));
+            assert!(res.success);
+        }
+
+        let result = executor.prepare("SELECT data FROM files".to_string());
+        assert!(result.success);
+        assert!(result.to_string().contains("X'DEAD00BEEF'"));
+        let df = result.data;
+        let contents: Vec<Vec<u8>> = df
+            .clone()
+            .fetch()
+            .unwrap()
+            .iter()
+            .map(|row| df.read_payload_field(row).unwrap())
+            .collect();
+        assert_eq!(contents, vec![small.clone(), large.clone(), vec![]]);
+
+        let filtered = executor.prepare(format!(
+            "SELECT id FROM files WHERE data = {}",
+            blob_literal(&large)
+        ));
+        assert!(filtered.success);
+        assert_eq!(filtered.data.fetch().unwrap().len(), 1);
+
+        let garbage = executor.prepare("INSERT INTO files (id, data, name) VALUES (4, X'ABC', 'x')".to_string());
+        assert!(!garbage.success);
+        assert!(garbage.to_string().contains("CannotParseBlob"));
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_blob_update_and_delete_release_payload_pages() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE files (id Integer, data Blob)".to_string())
+                .success
+        );
+        let first: Vec<u8> = (0..9_000u32).map(|i| (i % 7) as u8).collect();
+        let second: Vec<u8> = (0..5_000u32).map(|i| (i % 13) as u8).collect();
+        assert!(
+            executor
+                .prepare(format!("INSERT INTO files (id, data) VALUES (1, {})", blob_literal(&first)))
+                .success
+        );
+
+        let stored = executor.prepare("SELECT data FROM files".to_string()).data;
+        let old_field = stored.fetch().unwrap().remove(0);
+
+        assert!(
+            executor
+                .prepare(format!("UPDATE files SET data = {} WHERE id = 1", blob_literal(&second)))
+                .success
+        );
+        assert!(PageManager::read_payload_field(executor.pager_accessor.clone(), &old_field).is_err());
+
+        let df = executor.prepare("SELECT data FROM files".to_string()).data;
+        let new_field = df.clone().fetch().unwrap().remove(0);
+        assert_eq!(df.read_payload_field(&new_field).unwrap(), second);
+
+        assert!(executor.prepare("DELETE FROM files WHERE id = 1".to_string()).success);
+        assert!(PageManager::read_payload_field(executor.pager_accessor.clone(), &new_field).is_err());
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_blob_cannot_be_key_or_indexed() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(!executor.prepare("CREATE TABLE bad (data Blob, id Integer)".to_string()).success);
+        assert!(executor.prepare("CREATE TABLE files (id Integer, data Blob)".to_string()).success);
+        assert!(!executor.prepare("CREATE INDEX idx_data ON files (data)".to_string()).success);
+    }
+
+    #[test]
+    fn test_timestamp_range_filter_and_key_ordering() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE events (at Timestamp, kind Integer, starts Time)".to_string())
+                .success
+        );
+        let events = [
+            "2026-10-18 12:34:56.789",
+            "2026-10-18 12:34:56.7",
+            "2025-01-01 00:00:00",
+            "2026-10-18 23:00:00",
+            "1999-12-31 23:59:59.999999",
+        ];
+        for (i, at) in events.iter().enumerate() {
+            let res = executor.prepare(format!(
+                "INSERT INTO events (at, kind, starts) VALUES ('{}', {}, '0{}:30')",
+                at, i, i
+            ));
+            assert!(res.success);
+        }
+
+        let result = executor.prepare(
+            "SELECT at FROM events WHERE at >= '2026-01-01' AND at < '2026-10-18 23:00'"
+                .to_string(),
+        );
+        assert!(result.success);
+        let formatted: Vec<String> = result
+            .data
+            .fetch()
+            .unwrap()
+            .iter()
+            .map(|row| Serializer::format_timestamp(row, false))
+            .collect();
+        assert_eq!(
+            formatted,
+            vec!["2026-10-18 12:34:56.7", "2026-10-18 12:34:56.789"]
+        );
+
+        let result = executor.prepare("SELECT kind FROM events WHERE starts > '02:00'".to_string());
+        assert!(result.success);
+        assert_eq!(result.data.fetch().unwrap().len(), 3);
+
+        let bad = executor.prepare(
+            "INSERT INTO events (at, kind, starts) VALUES ('2026-13-01 00:00', 9, '00:00')"
+                .to_string(),
+        );
+        assert!(!bad.success);
+        assert!(bad.to_string().contains("CannotParseTimestamp"));
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_timestamptz_compares_instants() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE logins (id Integer, at TimestampTz)".to_string())
+                .success
+        );
+        for (id, at) in [
+            (1, "2026-10-18 12:00:00+02:00"),
+            (2, "2026-10-18 11:30:00Z"),
+            (3, "2026-10-18 09:00:00-03:00"),
+        ] {
+            assert!(
+                executor
+                    .prepare(format!("INSERT INTO logins (id, at) VALUES ({}, '{}')", id, at))
+                    .success
+            );
+        }
+        assert!(executor.prepare("CREATE INDEX idx_at ON logins (at)".to_string()).success);
+
+        let result =
+            executor.prepare("SELECT id FROM logins WHERE at > '2026-10-18 10:00:00Z'".to_string());
+        assert!(result.success);
+        let ids: Vec<String> = result
+            .data
+            .fetch()
+            .unwrap()
+            .iter()
+            .map(|row| Serializer::format_field(row, &rustql::pager::Type::Integer).unwrap())
+            .collect();
+        assert_eq!(ids, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn test_text_stores_long_values_without_truncation() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE notes (id Integer, body Text, title String)".to_string())
+                .success
+        );
+        let long: String = (0..3000).map(|i| char::from(b'a' + (i % 26) as u8)).collect();
+        for (id, body) in [(1, "short"), (2, long.as_str()), (3, "Grüße aus Köln")] {
+            let res = executor.prepare(format!(
+                "INSERT INTO notes (id, body, title) VALUES ({}, '{}', 't')",
+                id, body
+            ));
+            assert!(res.success);
+        }
+
+        let result = executor.prepare("SELECT body FROM notes".to_string());
+        assert!(result.success);
+        assert!(result.to_string().contains("Grüße aus Köln"));
+        let df = result.data;
+        let bodies: Vec<String> = df
+            .clone()
+            .fetch()
+            .unwrap()
+            .iter()
+            .map(|row| String::from_utf8(df.read_payload_field(row).unwrap()).unwrap())
+            .collect();
+        assert_eq!(bodies, vec!["short".to_string(), long.clone(), "Grüße aus Köln".to_string()]);
+
+        let found = executor.prepare(format!("SELECT id FROM notes WHERE body = '{}'", long));
+        assert_eq!(found.data.fetch().unwrap().len(), 1);
+
+        assert!(
+            executor
+                .prepare("UPDATE notes SET body = 'rewritten' WHERE id = 2".to_string())
+                .success
+        );
+        let found = executor.prepare("SELECT id FROM notes WHERE body = 'rewritten'".to_string());
+        assert_eq!(found.data.fetch().unwrap().len(), 1);
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_text_key_and_index_order_by_content() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let texts = |executor: &mut RustqlQueryExecutor, query: &str| -> Vec<String> {
+            let df = run_ok(executor, query).data;
+            df.clone()
+                .fetch()
+                .unwrap()
+                .iter()
+                .map(|row| String::from_utf8(df.read_payload_field(row).unwrap()).unwrap())
+                .collect()
+        };
+        run_ok(&mut executor, "CREATE TABLE docs (path Text PRIMARY KEY, size Integer, tag Text UNIQUE)");
+        run_ok(&mut executor, "CREATE TABLE notes (id Integer PRIMARY KEY, body Text)");
+        run_ok(&mut executor, "CREATE INDEX notes_body ON notes (body)");
+
+        // the paths share their 12-byte inline prefix, so only the payloads order them
+        let long = format!("/home/alice/{}", "z".repeat(3000));
+        for (path, size, tag) in [
+            ("/home/alice/notes/b.txt", 2, "second file tag"),
+            (long.as_str(), 4, "long"),
+            ("/home/alice/notes/a.txt", 1, "first file tag"),
+            ("/home/alice/", 0, "home"),
+            ("/home/alice/notes", 3, "notes"),
+        ] {
+            run_ok(
+                &mut executor,
+                &format!("INSERT INTO docs (path, size, tag) VALUES ('{}', {}, '{}')", path, size, tag),
+            );
+        }
+        let sorted = vec![
+            "/home/alice/".to_string(),
+            "/home/alice/notes".to_string(),
+            "/home/alice/notes/a.txt".to_string(),
+            "/home/alice/notes/b.txt".to_string(),
+            long.clone(),
+        ];
+        assert_eq!(texts(&mut executor, "SELECT path FROM docs"), sorted);
+
+        let query = "SELECT size FROM docs WHERE path = '/home/alice/notes/a.txt'";
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectKeyUnique);
+        assert_eq!(values(&mut executor, query, Type::Integer), vec!["1"]);
+        let query = format!("SELECT size FROM docs WHERE path = '{}'", long);
+        assert_eq!(values(&mut executor, &query, Type::Integer), vec!["4"]);
+        let query = "SELECT size FROM docs WHERE path > '/home/alice/notes'";
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectKeyRange);
+        assert_eq!(values(&mut executor, query, Type::Integer), vec!["1", "2", "4"]);
+
+        // keys and unique values repeat by content, not by descriptor
+        for query in [
+            "INSERT INTO docs (path, size, tag) VALUES ('/home/alice/notes/a.txt', 9, 'other')",
+            "INSERT INTO docs (path, size, tag) VALUES ('/home/alice/c', 9, 'first file tag')",
+            "INSERT INTO docs (path, size, tag) VALUES ('/x', 8, 'same tag'), ('/y', 9, 'same tag')",
+            "UPDATE docs SET path = '/home/alice/notes' WHERE size = 1",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+        let query = "SELECT size FROM docs WHERE tag = 'second file tag'";
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectIndexUnique);
+        assert_eq!(values(&mut executor, query, Type::Integer), vec!["2"]);
+
+        run_ok(&mut executor, "UPDATE docs SET path = '/home/alice/notes/c.txt' WHERE size = 1");
+        run_ok(&mut executor, "DELETE FROM docs WHERE path = '/home/alice/notes'");
+        let sorted = vec![
+            "/home/alice/".to_string(),
+            "/home/alice/notes/b.txt".to_string(),
+            "/home/alice/notes/c.txt".to_string(),
+            long.clone(),
+        ];
+        assert_eq!(texts(&mut executor, "SELECT path FROM docs"), sorted);
+        assert!(executor.check_integrity().is_ok());
+
+        for (id, body) in [(1, "a note that is long"), (2, "a note that is longer"), (3, "a note")] {
+            run_ok(&mut executor, &format!("INSERT INTO notes (id, body) VALUES ({}, '{}')", id, body));
+        }
+        let query = "SELECT id FROM notes WHERE body = 'a note that is long'";
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectIndexUnique);
+        assert_eq!(values(&mut executor, query, Type::Integer), vec!["1"]);
+
+        // cascades would share the payload of the parent
+        assert!(!run(
+            &mut executor,
+            "CREATE TABLE links (id Integer, doc Text REFERENCES docs (path))"
+        )
+        .success);
+
+        let mut reopened = executor.reopen();
+        assert_eq!(texts(&mut reopened, "SELECT path FROM docs"), sorted);
+        let query = "SELECT size FROM docs WHERE path = '/home/alice/notes/c.txt'";
+        assert_eq!(values(&mut reopened, query, Type::Integer), vec!["1"]);
+        assert!(reopened.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_string_rejects_values_instead_of_truncating() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE notes (id Integer, title String)".to_string())
+                .success
+        );
+        let fits = "x".repeat(255);
+        let too_long = "x".repeat(256);
+        assert!(
+            executor
+                .prepare(format!("INSERT INTO notes (id, title) VALUES (1, '{}')", fits))
+                .success
+        );
+        let res = executor.prepare(format!("INSERT INTO notes (id, title) VALUES (2, '{}')", too_long));
+        assert!(!res.success);
+        assert!(res.to_string().contains("use TEXT"));
+        assert_eq!(
+            executor.prepare("SELECT id FROM notes".to_string()).data.fetch().unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_uuid_keys_and_generated_defaults() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare(
+                    "CREATE TABLE orders (id Uuid DEFAULT gen_random_uuid(), customer Uuid, qty Integer DEFAULT 1)"
+                        .to_string()
+                )
+                .success
+        );
+        assert!(
+            executor
+                .prepare("CREATE INDEX idx_orders_customer ON orders (customer)".to_string())
+                .success
+        );
+
+        let customer = "6f1c0a52-8d4e-4b7f-9a3e-2c5d7e9f0b1a";
+        assert!(
+            executor
+                .prepare(format!("INSERT INTO orders (customer) VALUES ('{}')", customer))
+                .success
+        );
+        for _ in 0..19 {
+            let res = executor.prepare("INSERT INTO orders (customer) VALUES (gen_random_uuid())".to_string());
+            assert!(res.success);
+        }
+        let fixed = "00000000-0000-4000-8000-000000000000";
+        assert!(
+            executor
+                .prepare(format!(
+                    "INSERT INTO orders (id, customer, qty) VALUES ('{}', gen_random_uuid(), 5)",
+                    fixed.to_uppercase()
+                ))
+                .success
+        );
+        assert!(
+            !executor
+                .prepare("INSERT INTO orders (id, customer) VALUES ('not-a-uuid', gen_random_uuid())".to_string())
+                .success
+        );
+
+        let rows = executor
+            .prepare("SELECT id, qty FROM orders".to_string())
+            .data
+            .fetch()
+            .unwrap();
+        assert_eq!(rows.len(), 21);
+        let ids: Vec<String> = rows
+            .iter()
+            .map(|row| Serializer::format_field(&row[..17].to_vec(), &Type::Uuid).unwrap())
+            .collect();
+        assert_eq!(ids[0], fixed);
+        let mut sorted = ids.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, ids);
+
+        let found = executor.prepare(format!("SELECT qty FROM orders WHERE customer = '{}'", customer));
+        assert_eq!(found.data.fetch().unwrap(), vec![Serializer::parse_int("1").unwrap().to_vec()]);
+        let found = executor.prepare(format!("SELECT qty FROM orders WHERE id = '{}'", fixed));
+        assert_eq!(found.data.fetch().unwrap(), vec![Serializer::parse_int("5").unwrap().to_vec()]);
+
+        assert!(
+            !executor
+                .prepare("CREATE TABLE broken (id Uuid DEFAULT 'nope', qty Integer)".to_string())
+                .success
+        );
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_quoted_function_name_is_a_literal() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        run_ok(&mut executor, "CREATE TABLE t (id Integer, name String DEFAULT 'gen_random_uuid()')");
+        run_ok(&mut executor, "INSERT INTO t (id, name) VALUES (1, gen_random_uuid())");
+        run_ok(&mut executor, "INSERT INTO t (id, name) VALUES (2, 'gen_random_uuid()')");
+        run_ok(&mut executor, "INSERT INTO t (id) VALUES (3)");
+
+        let names = values(&mut executor, "SELECT name FROM t", Type::String);
+        assert_eq!(names[0].len(), 36, "{}", names[0]);
+        assert_eq!(names[1..], ["gen_random_uuid()", "gen_random_uuid()"]);
+
+        run_ok(&mut executor, "UPDATE t SET name = 'gen_random_uuid()' WHERE id = 1");
+        assert_eq!(
+            count(&mut executor, "SELECT id FROM t WHERE name = 'gen_random_uuid()'"),
+            3
+        );
+    }
+
+    #[test]
+    fn test_json_validation_and_extraction() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE events (id Integer, meta Json)".to_string())
+                .success
+        );
+        let docs = [
+            r#"{"kind": "click", "user": {"name": "Ann", "age": 31}, "tags": ["a", "b"]}"#,
+            r#"{"kind": "view", "user": {"name": "Bob", "age": 9}}"#,
+            r#"{"kind": "click", "user": {"name": "Cy", "age": 120}, "note": null}"#,
+        ];
+        for (id, doc) in docs.iter().enumerate() {
+            let res = executor.prepare(format!(
+                "INSERT INTO events (id, meta) VALUES ({}, '{}')",
+                id + 1,
+                doc
+            ));
+            assert!(res.success);
+        }
+        assert!(
+            !executor
+                .prepare("INSERT INTO events (id, meta) VALUES (4, '{\"kind\": }')".to_string())
+                .success
+        );
+
+        let result = executor.prepare("SELECT id, meta->>'kind' FROM events".to_string());
+        assert!(result.success);
+        let rows = result.data.fetch().unwrap();
+        assert_eq!(rows.len(), 3);
+        let mut expected = Serializer::parse_int("2").unwrap().to_vec();
+        expected.extend(Serializer::parse_string("view"));
+        assert_eq!(rows[1], expected);
+
+        let result = executor.prepare(
+            "SELECT json_extract(meta, '$.user.name'), json_extract(meta, '$.tags[1]') FROM events"
+                .to_string(),
+        );
+        assert!(result.success);
+        let rows = result.data.fetch().unwrap();
+        assert_eq!(rows[0][..256], Serializer::parse_string("Ann"));
+        assert_eq!(rows[0][256..], Serializer::parse_string("b"));
+        assert!(Serializer::is_null(&rows[1][256..].to_vec(), &Type::String).unwrap());
+
+        let found = executor.prepare("SELECT id FROM events WHERE meta->>'kind' = 'click'".to_string());
+        assert_eq!(found.data.fetch().unwrap().len(), 2);
+        // numbers compare numerically, so 120 > 31 > 9
+        let found = executor
+            .prepare("SELECT id FROM events WHERE json_extract(meta, '$.user.age') > 30".to_string());
+        assert_eq!(found.data.fetch().unwrap().len(), 2);
+        let found = executor.prepare("SELECT id FROM events WHERE meta ->> 'note' = 'null'".to_string());
+        assert_eq!(found.data.fetch().unwrap().len(), 0);
+
+        assert!(
+            !executor
+                .prepare("SELECT id FROM events WHERE id->>'kind' = 'click'".to_string())
+                .success
+        );
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_null_values_and_three_valued_logic() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare("CREATE TABLE people (id Integer, name String, age Integer, note Text)".to_string())
+                .success
+        );
+        assert!(
+            executor
+                .prepare("CREATE INDEX idx_people_age ON people (age)".to_string())
+                .success
+        );
+        for query in [
+            "INSERT INTO people (id, name, age, note) VALUES (1, 'Ann', 30, 'hi')",
+            "INSERT INTO people (id, name, age, note) VALUES (2, NULL, NULL, NULL)",
+            "INSERT INTO people (id, name) VALUES (3, 'NULL')",
+            "INSERT INTO people (id, name, age) VALUES (4, 'Dan', 50)",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert!(
+            !executor
+                .prepare("INSERT INTO people (id, name) VALUES (NULL, 'x')".to_string())
+                .success
+        );
+
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age IS NULL"), 2);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age IS NOT NULL"), 2);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE name IS NULL"), 1);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE note IS NULL"), 3);
+        // comparisons against NULL are unknown, so neither branch keeps the row
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age = 30"), 1);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age < 40"), 1);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age > 0 OR id = 2"), 3);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age > 0 AND id = 2"), 0);
+        assert!(!executor.prepare("SELECT id FROM people WHERE age = NULL".to_string()).success);
+
+        let result = executor.prepare("SELECT name, age FROM people WHERE id = 2".to_string());
+        let rows = result.data.fetch().unwrap();
+        assert!(Serializer::is_null(&rows[0][..256].to_vec(), &Type::String).unwrap());
+        assert!(Serializer::is_null(&rows[0][256..].to_vec(), &Type::Integer).unwrap());
+
+        assert!(
+            executor
+                .prepare("UPDATE people SET age = NULL, note = NULL WHERE id = 1".to_string())
+                .success
+        );
+        assert!(
+            executor
+                .prepare("UPDATE people SET age = 7 WHERE id = 2".to_string())
+                .success
+        );
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age IS NULL"), 2);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE age = 7"), 1);
+        assert_eq!(count(&mut executor, "SELECT id FROM people WHERE note IS NOT NULL"), 0);
+
+        assert!(
+            executor
+                .prepare("CREATE TABLE ages (age Integer, label String)".to_string())
+                .success
+        );
+        assert!(
+            executor
+                .prepare("INSERT INTO ages (age, label) VALUES (7, 'seven')".to_string())
+                .success
+        );
+        assert!(
+            executor
+                .prepare("INSERT INTO ages (age, label) VALUES (50, 'fifty')".to_string())
+                .success
+        );
+        // NULL join keys never match
+        assert_eq!(
+            count(
+                &mut executor,
+                "SELECT people.id, ages.label FROM people INNER JOIN ages ON people.age = ages.age"
+            ),
+            2
+        );
+        assert!(executor.check_integrity().is_ok());
+
+        // a Boolean's flags sit above its value bit
+        for query in [
+            "CREATE TABLE flags (id Integer, active Boolean)",
+            "INSERT INTO flags (id, active) VALUES (1, true), (2, false), (3, NULL)",
+        ] {
+            run_ok(&mut executor, query);
+        }
+        assert_eq!(values(&mut executor, "SELECT id FROM flags WHERE active = true", Type::Integer), vec!["1"]);
+        assert_eq!(values(&mut executor, "SELECT id FROM flags WHERE active = false", Type::Integer), vec!["2"]);
+        assert_eq!(values(&mut executor, "SELECT id FROM flags WHERE active IS NULL", Type::Integer), vec!["3"]);
+    }
+
+    #[test]
+    fn test_nocase_collation_on_keys_indexes_and_filters() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(
+            executor
+                .prepare(
+                    "CREATE TABLE users (login String COLLATE NOCASE, name Varchar(20) COLLATE NOCASE, city String)"
+                        .to_string()
+                )
+                .success
+        );
+        assert!(
+            executor
+                .prepare("CREATE INDEX idx_users_name ON users (name)".to_string())
+                .success
+        );
+        for (i, (name, city)) in [("Alice", "Zurich"), ("bob", "athens"), ("Carol", "Bern"), ("dave", "bern")]
+            .iter()
+            .enumerate()
+        {
+            let q = format!(
+                "INSERT INTO users (login, name, city) VALUES ('User{}', '{}', '{}')",
+                i, name, city
+            );
+            assert!(executor.prepare(q).success);
+        }
+        assert!(
+            !executor
+                .prepare("CREATE TABLE bad (id Integer COLLATE NOCASE)".to_string())
+                .success
+        );
+        assert!(
+            !executor
+                .prepare("SELECT login FROM users WHERE city = 'a' COLLATE GERMAN".to_string())
+                .success
+        );
+
+        // the key and the index are ordered case-insensitively, so seeks stay index-assisted
+        let query = "SELECT city FROM users WHERE login = 'USER2'";
+        assert_eq!(count(&mut executor, query), 1);
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectKeyUnique);
+        let query = "SELECT city FROM users WHERE name = 'ALICE'";
+        let result = executor.prepare(query.to_string());
+        assert_eq!(result.data.fetch().unwrap()[0], Serializer::parse_string("Zurich").to_vec());
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectIndexUnique);
+        assert_eq!(count(&mut executor, "SELECT city FROM users WHERE name < 'C'"), 2);
+
+        // an explicit COLLATE that differs from the column's cannot use the tree order
+        let query = "SELECT city FROM users WHERE name = 'alice' COLLATE BINARY";
+        assert_eq!(count(&mut executor, query), 0);
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectFTS);
+        assert_eq!(count(&mut executor, "SELECT city FROM users WHERE city = 'BERN'"), 0);
+        assert_eq!(
+            count(&mut executor, "SELECT city FROM users WHERE city COLLATE NOCASE = 'BERN'"),
+            2
+        );
+        // byte order puts 'Z' before 'a', NOCASE does not
+        assert_eq!(count(&mut executor, "SELECT city FROM users WHERE city < 'a'"), 2);
+        assert_eq!(
+            count(&mut executor, "SELECT city FROM users WHERE city < 'a' COLLATE NOCASE"),
+            0
+        );
+
+        assert!(
+            executor
+                .prepare("CREATE TABLE cities (city String COLLATE NOCASE, country String)".to_string())
+                .success
+        );
+        assert!(
+            executor
+                .prepare("INSERT INTO cities (city, country) VALUES ('BERN', 'CH')".to_string())
+                .success
+        );
+        let query = "SELECT users.login, cities.country FROM cities INNER JOIN users ON cities.city = users.city";
+        assert_eq!(count(&mut executor, query), 2);
+
+        assert!(
+            executor
+                .prepare("DELETE FROM users WHERE login = 'user1'".to_string())
+                .success
+        );
+        assert_eq!(count(&mut executor, "SELECT login FROM users"), 3);
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_not_null_default_and_check_constraints() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let create = "CREATE TABLE accounts (id Integer, email String NOT NULL, \
+                      status String NOT NULL DEFAULT 'open' CHECK (status = 'open' OR status = 'closed'), \
+                      balance Integer CHECK (balance >= 0), note Text CHECK (note < 'n'))";
+        let result = executor.prepare(create.to_string());
+        assert!(result.success, "{}", result);
+
+        assert!(
+            executor
+                .prepare("INSERT INTO accounts (id, email, balance) VALUES (1, 'a@x', 10)".to_string())
+                .success
+        );
+        // NULL makes a check unknown, which passes
+        assert!(
+            executor
+                .prepare("INSERT INTO accounts (id, email) VALUES (2, 'b@x')".to_string())
+                .success
+        );
+        let message = error_message(&mut executor, "INSERT INTO accounts (id, balance) VALUES (3, 5)");
+        assert!(message.contains("NOT NULL constraint failed for column 'accounts.email'"), "{}", message);
+        let message = error_message(
+            &mut executor,
+            "INSERT INTO accounts (id, email, status) VALUES (3, 'c@x', NULL)",
+        );
+        assert!(message.contains("'accounts.status'"), "{}", message);
+        let message = error_message(
+            &mut executor,
+            "INSERT INTO accounts (id, email, balance) VALUES (3, 'c@x', -1)",
+        );
+        assert!(message.contains("CHECK constraint failed for column 'accounts.balance'"), "{}", message);
+        let message = error_message(
+            &mut executor,
+            "INSERT INTO accounts (id, email, status) VALUES (3, 'c@x', 'frozen')",
+        );
+        assert!(message.contains("'accounts.status'"), "{}", message);
+        let message = error_message(
+            &mut executor,
+            "INSERT INTO accounts (id, email, note) VALUES (3, 'c@x', 'zzz')",
+        );
+        assert!(message.contains("'accounts.note'"), "{}", message);
+
+        let result = executor.prepare("SELECT status FROM accounts WHERE id = 1".to_string());
+        assert_eq!(result.data.fetch().unwrap()[0], Serializer::parse_string("open").to_vec());
+
+        assert!(
+            executor
+                .prepare("UPDATE accounts SET balance = 0, note = 'fine' WHERE id = 1".to_string())
+                .success
+        );
+        let message = error_message(&mut executor, "UPDATE accounts SET balance = -5 WHERE id = 2");
+        assert!(message.contains("'accounts.balance'"), "{}", message);
+        let message = error_message(&mut executor, "UPDATE accounts SET note = 'zz' WHERE id = 1");
+        assert!(message.contains("'accounts.note'"), "{}", message);
+        let message = error_message(&mut executor, "UPDATE accounts SET email = NULL WHERE id = 1");
+        assert!(message.contains("'accounts.email'"), "{}", message);
+        // a failed update leaves the row and its payload untouched
+        let result = executor.prepare("SELECT balance FROM accounts WHERE note = 'fine'".to_string());
+        assert_eq!(result.data.fetch().unwrap()[0], Serializer::parse_int("0").unwrap().to_vec());
+
+        for query in [
+            "CREATE TABLE bad (id Integer, v Integer CHECK (missing > 0))",
+            "CREATE TABLE bad (id Integer, v Integer CHECK (v IN (SELECT id FROM accounts)))",
+            "CREATE TABLE bad (id Integer, v Integer CHECK (v > 'x'))",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_composite_primary_key_orders_and_seeks_by_prefix() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let create = "CREATE TABLE lines (qty Integer, note Text, customer Integer, item Varchar(8), \
+                      PRIMARY KEY (customer, item))";
+        assert!(executor.prepare(create.to_string()).success);
+        assert!(executor.prepare("CREATE INDEX lines_item ON lines (item)".to_string()).success);
+        for customer in [3, 1, 2] {
+            for item in ["b", "a", "c"] {
+                let query = format!(
+                    "INSERT INTO lines (qty, note, customer, item) VALUES ({}, 'note {}{}', {}, '{}')",
+                    customer * 10, customer, item, customer, item
+                );
+                assert!(executor.prepare(query).success);
+            }
+        }
+        let message = executor
+            .prepare("INSERT INTO lines (qty, item) VALUES (1, 'a')".to_string())
+            .to_string();
+        assert!(message.contains("Key column 'customer' cannot be NULL"), "{}", message);
+
+        let varchar = |item: &str| {
+            let mut bytes = item.as_bytes().to_vec();
+            bytes.resize(8 * 4 + 1, 0);
+            bytes
+        };
+        // rows come out in key order: by customer, then by item
+        let keys = rows(&mut executor, "SELECT customer, item FROM lines");
+        let mut expected = Vec::new();
+        for customer in ["1", "2", "3"] {
+            for item in ["a", "b", "c"] {
+                let mut row = Serializer::parse_int(customer).unwrap().to_vec();
+                row.extend_from_slice(&varchar(item));
+                expected.push(row);
+            }
+        }
+        assert_eq!(keys, expected);
+
+        let query = "SELECT qty FROM lines WHERE customer = 2";
+        assert_eq!(rows(&mut executor, query).len(), 3);
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectKeyUnique);
+        let query = "SELECT qty FROM lines WHERE customer = 2 AND item >= 'b'";
+        assert_eq!(rows(&mut executor, query).len(), 2);
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectKeyRange);
+        let query = "SELECT note FROM lines WHERE item = 'c' AND customer = 3";
+        let result = executor.prepare(query.to_string()).to_string();
+        assert!(result.contains("note 3c") && !result.contains("note 3b"), "{}", result);
+        assert_eq!(rows(&mut executor, "SELECT qty FROM lines WHERE customer >= 2").len(), 6);
+        assert_eq!(rows(&mut executor, "SELECT qty FROM lines WHERE customer = 4").len(), 0);
+        // the index on a non-leading key column points back at the full key
+        let query = "SELECT customer FROM lines WHERE item = 'a'";
+        assert_eq!(rows(&mut executor, query).len(), 3);
+        assert_eq!(scan_operation(&executor, query), SqlConditionOpCode::SelectIndexUnique);
+
+        assert!(
+            executor
+                .prepare("UPDATE lines SET item = 'd', qty = 5 WHERE customer = 1 AND item = 'a'".to_string())
+                .success
+        );
+        assert!(executor.prepare("DELETE FROM lines WHERE customer = 3".to_string()).success);
+        let keys = rows(&mut executor, "SELECT item FROM lines WHERE customer = 1");
+        let items: Vec<Vec<u8>> = ["b", "c", "d"].iter().map(|i| varchar(i)).collect();
+        assert_eq!(keys, items);
+        assert_eq!(rows(&mut executor, "SELECT qty FROM lines WHERE item = 'a'").len(), 1);
+        assert_eq!(rows(&mut executor, "SELECT qty FROM lines").len(), 6);
+        assert!(executor.check_integrity().is_ok());
+
+        // the key definition survives reloading the schema
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("SELECT qty FROM lines WHERE customer = 1 AND item = 'd'".to_string());
+        assert_eq!(result.data.fetch().unwrap(), vec![Serializer::parse_int("5").unwrap().to_vec()]);
+
+        for query in [
+            "CREATE TABLE bad (a Integer, b Integer, PRIMARY KEY (c))",
+            "CREATE TABLE bad (a Integer, b Integer, PRIMARY KEY (a, a))",
+            "CREATE TABLE bad (a Integer, b Blob PRIMARY KEY)",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_key_range_seek_starts_between_keys() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        executor.prepare("CREATE TABLE t (v Integer, id Integer PRIMARY KEY)".to_string());
+        for i in (0..40).step_by(2) {
+            executor.prepare(format!("INSERT INTO t (v, id) VALUES ({}, {})", i, i));
+        }
+        // 5 is not a key; the seek has to land on 6
+        assert_eq!(count(&mut executor, "SELECT v FROM t WHERE id >= 5"), 17);
+        assert_eq!(count(&mut executor, "SELECT v FROM t WHERE id > 5"), 17);
+        assert_eq!(count(&mut executor, "SELECT v FROM t WHERE id > 38"), 0);
+        assert_eq!(count(&mut executor, "SELECT v FROM t WHERE id = 5"), 0);
+        assert_eq!(count(&mut executor, "SELECT v FROM t WHERE id >= -1"), 20);
+    }
+
+    #[test]
+    fn test_primary_key_rejects_duplicates() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE single (v Integer, id Integer PRIMARY KEY)",
+            "CREATE TABLE pairs (a Integer, b Varchar(4) COLLATE NOCASE, v Integer, PRIMARY KEY (a, b))",
+            "INSERT INTO single (v, id) VALUES (10, 1), (20, 2)",
+            "INSERT INTO pairs (a, b, v) VALUES (1, 'x', 10), (1, 'y', 20), (2, 'x', 30)",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+
+        for query in [
+            "INSERT INTO single (v, id) VALUES (30, 1)",
+            // the second row repeats a stored key, the third one of the batch
+            "INSERT INTO single (v, id) VALUES (30, 3), (40, 2)",
+            "INSERT INTO single (v, id) VALUES (30, 3), (40, 3)",
+            "UPDATE single SET id = 2 WHERE id = 1",
+            "UPDATE single SET id = 5",
+            "INSERT INTO pairs (a, b, v) VALUES (1, 'X', 40)",
+            "INSERT INTO pairs (a, b, v) VALUES (3, 'x', 40), (3, 'x', 50)",
+            "UPDATE pairs SET b = 'y' WHERE a = 1 AND b = 'x'",
+            "UPDATE pairs SET a = 1 WHERE a = 2",
+        ] {
+            let message = executor.prepare(query.to_string()).to_string();
+            assert!(message.contains("PRIMARY KEY constraint failed"), "{}: {}", query, message);
+        }
+        assert_eq!(count(&mut executor, "SELECT v FROM single"), 2);
+        assert_eq!(count(&mut executor, "SELECT v FROM pairs"), 3);
+
+        // keys that move past each other or stay put do not collide
+        for query in [
+            "UPDATE single SET id = 3 WHERE id = 2",
+            "UPDATE single SET id = 2 WHERE id = 1",
+            "UPDATE single SET v = 0",
+            "UPDATE pairs SET b = 'z' WHERE a = 1 AND b = 'x'",
+            "INSERT INTO pairs (a, b, v) VALUES (1, 'x', 50), (2, 'y', 60)",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert_eq!(count(&mut executor, "SELECT v FROM single"), 2);
+        assert_eq!(count(&mut executor, "SELECT v FROM pairs"), 5);
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_unique_constraints_and_unique_indexes() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let create = "CREATE TABLE users (id Integer, email Varchar(20) UNIQUE COLLATE NOCASE, \
+                      nick Varchar(10), level Integer UNIQUE)";
+        assert!(executor.prepare(create.to_string()).success);
+
+        assert!(run(&mut executor, "INSERT INTO users (id, email, nick) VALUES (1, 'ann@x', 'ann')").success);
+        assert!(run(&mut executor, "INSERT INTO users (id, email, nick) VALUES (2, 'bob@x', 'bob')").success);
+        let message = error_message(
+            &mut executor,
+            "INSERT INTO users (id, email, nick) VALUES (3, 'ANN@x', 'ann2')",
+        );
+        assert!(message.contains("UNIQUE constraint failed for column 'users.email'"), "{}", message);
+        // NULLs never conflict
+        assert!(run(&mut executor, "INSERT INTO users (id, nick) VALUES (3, 'x')").success);
+        assert!(run(&mut executor, "INSERT INTO users (id, nick) VALUES (4, 'y')").success);
+
+        // an update may keep its own value, but not take another row's or give one to many
+        assert!(run(&mut executor, "UPDATE users SET email = 'Ann@X', level = 1 WHERE id = 1").success);
+        let message = error_message(&mut executor, "UPDATE users SET email = 'bob@x' WHERE id = 1");
+        assert!(message.contains("'users.email'"), "{}", message);
+        let message = error_message(&mut executor, "UPDATE users SET level = 2 WHERE id >= 2");
+        assert!(message.contains("'users.level'"), "{}", message);
+        let result = run(&mut executor, "SELECT id FROM users WHERE email = 'bob@x'");
+        assert_eq!(result.data.fetch().unwrap(), vec![Serializer::parse_int("2").unwrap().to_vec()]);
+
+        // a failed insert inside a transaction leaves the earlier ones in place
+        assert!(run(&mut executor, "BEGIN TRANSACTION").success);
+        assert!(run(&mut executor, "INSERT INTO users (id, email) VALUES (5, 'cy@x')").success);
+        assert!(!run(&mut executor, "INSERT INTO users (id, email) VALUES (6, 'CY@x')").success);
+        assert!(run(&mut executor, "ROLLBACK").success);
+        assert!(run(&mut executor, "INSERT INTO users (id, email) VALUES (6, 'CY@x')").success);
+
+        // CREATE UNIQUE INDEX checks the stored rows first
+        assert!(run(&mut executor, "UPDATE users SET nick = 'dup' WHERE id >= 3").success);
+        let message = error_message(&mut executor, "CREATE UNIQUE INDEX users_nick ON users (nick)");
+        assert!(message.contains("'users.nick'"), "{}", message);
+        assert!(!run(&mut executor, "DROP INDEX users_nick").success);
+        assert!(run(&mut executor, "UPDATE users SET nick = 'z' WHERE id = 3").success);
+        assert!(run(&mut executor, "UPDATE users SET nick = 'w' WHERE id = 6").success);
+        assert!(run(&mut executor, "CREATE UNIQUE INDEX users_nick ON users (nick)").success);
+        let message = error_message(&mut executor, "INSERT INTO users (id, nick) VALUES (7, 'ann')");
+        assert!(message.contains("'users.nick'"), "{}", message);
+        assert!(run(&mut executor, "DROP INDEX users_nick").success);
+        assert!(run(&mut executor, "INSERT INTO users (id, nick) VALUES (7, 'ann')").success);
+
+        // a unique key column
+        assert!(run(&mut executor, "CREATE TABLE codes (code Integer UNIQUE, v Integer)").success);
+        assert!(run(&mut executor, "INSERT INTO codes (code, v) VALUES (1, 1)").success);
+        assert!(!run(&mut executor, "INSERT INTO codes (code, v) VALUES (1, 2)").success);
+        assert!(executor.check_integrity().is_ok());
+
+        // dropping a table drops its indexes, including the implicit ones
+        assert!(run(&mut executor, "DROP TABLE users").success);
+        assert!(run(&mut executor, "CREATE TABLE users (id Integer, email String UNIQUE)").success);
+        assert!(run(&mut executor, "INSERT INTO users (id, email) VALUES (1, 'ann@x')").success);
+
+        assert!(!run(&mut executor, "CREATE TABLE bad (id Integer, t Blob UNIQUE)").success);
+    }
+
+    #[test]
+    fn test_foreign_keys() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let ids = |executor: &mut QueryExecutor, query: &str| {
+            let result = executor.prepare(query.to_string());
+            assert!(result.success, "{}", query);
+            result.data.fetch().unwrap()
+        };
+        let int = |v: &str| Serializer::parse_int(v).unwrap().to_vec();
+        for query in [
+            "CREATE TABLE customers (id Integer, name Varchar(10) UNIQUE COLLATE NOCASE)",
+            "CREATE TABLE orders (id Integer, customer Varchar(10) COLLATE NOCASE REFERENCES customers (name) \
+             ON UPDATE CASCADE ON DELETE SET NULL)",
+            "CREATE TABLE lines (order_id Integer REFERENCES orders ON DELETE CASCADE ON UPDATE CASCADE, \
+             pos Integer, PRIMARY KEY (order_id, pos))",
+            "CREATE TABLE notes (id Integer, line_order Integer, FOREIGN KEY (line_order) REFERENCES orders (id))",
+            "INSERT INTO customers (id, name) VALUES (1, 'ann')",
+            "INSERT INTO customers (id, name) VALUES (2, 'bob')",
+            "INSERT INTO orders (id, customer) VALUES (10, 'ANN')",
+            "INSERT INTO orders (id, customer) VALUES (11, 'bob')",
+            "INSERT INTO orders (id) VALUES (12)",
+            "INSERT INTO lines (order_id, pos) VALUES (10, 1)",
+            "INSERT INTO lines (order_id, pos) VALUES (10, 2)",
+            "INSERT INTO lines (order_id, pos) VALUES (11, 1)",
+            "INSERT INTO notes (id, line_order) VALUES (1, 12)",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+
+        // parents must exist, NULLs reference nothing
+        let result = run(&mut executor, "INSERT INTO lines (order_id, pos) VALUES (13, 1)");
+        assert!(!result.success);
+        assert!(
+            result.to_string().contains("FOREIGN KEY constraint failed: 'lines.order_id' references 'orders.id'"),
+            "{}",
+            result
+        );
+        assert!(!run(&mut executor, "INSERT INTO orders (id, customer) VALUES (13, 'cy')").success);
+        assert!(!run(&mut executor, "UPDATE lines SET order_id = 13 WHERE order_id = 11").success);
+
+        // RESTRICT is the default and leaves everything in place
+        assert!(!run(&mut executor, "DELETE FROM orders WHERE id = 12").success);
+        assert!(!run(&mut executor, "UPDATE orders SET id = 14 WHERE id = 12").success);
+        assert_eq!(ids(&mut executor, "SELECT id FROM orders").len(), 3);
+
+        // ON UPDATE CASCADE follows the key into the lines, which key on it as well
+        assert!(run(&mut executor, "UPDATE orders SET id = 20 WHERE id = 10").success);
+        assert_eq!(
+            ids(&mut executor, "SELECT pos FROM lines WHERE order_id = 20"),
+            vec![int("1"), int("2")]
+        );
+        assert!(ids(&mut executor, "SELECT pos FROM lines WHERE order_id = 10").is_empty());
+
+        // a change under the parent's collation is no change
+        assert!(run(&mut executor, "UPDATE customers SET name = 'Ann' WHERE id = 1").success);
+        assert_eq!(ids(&mut executor, "SELECT id FROM orders WHERE customer = 'ANN'"), vec![int("20")]);
+        assert!(run(&mut executor, "UPDATE customers SET name = 'bo' WHERE id = 2").success);
+        assert_eq!(ids(&mut executor, "SELECT id FROM orders WHERE customer = 'bo'"), vec![int("11")]);
+
+        // ON DELETE SET NULL keeps the order, ON DELETE CASCADE removes the lines
+        assert!(run(&mut executor, "DELETE FROM customers WHERE id = 1").success);
+        assert_eq!(ids(&mut executor, "SELECT id FROM orders WHERE customer IS NULL"), vec![int("12"), int("20")]);
+        assert!(run(&mut executor, "DELETE FROM orders WHERE id = 20").success);
+        assert_eq!(ids(&mut executor, "SELECT order_id FROM lines"), vec![int("11")]);
+        assert!(executor.check_integrity().is_ok());
+
+        // the tables and the index the foreign keys rely on cannot go
+        assert!(!run(&mut executor, "DROP TABLE orders").success);
+        assert!(!run(&mut executor, "DROP TABLE customers").success);
+        assert!(!run(&mut executor, "CREATE TABLE bad (a Integer REFERENCES customers (id2))").success);
+        assert!(!run(&mut executor, "CREATE TABLE bad (a Integer REFERENCES orders (customer))").success);
+        assert!(!run(&mut executor, "CREATE TABLE bad (a String REFERENCES orders)").success);
+        assert!(!run(&mut executor, "CREATE TABLE bad (a Integer REFERENCES nowhere)").success);
+
+        // a cascade follows a table referencing itself
+        for query in [
+            "CREATE TABLE staff (id Integer, boss Integer REFERENCES staff ON DELETE CASCADE)",
+            "INSERT INTO staff (id) VALUES (1)",
+            "INSERT INTO staff (id, boss) VALUES (2, 1)",
+            "INSERT INTO staff (id, boss) VALUES (3, 2)",
+            "INSERT INTO staff (id) VALUES (4)",
+            "DELETE FROM staff WHERE id = 1",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+        assert_eq!(ids(&mut executor, "SELECT id FROM staff"), vec![int("4")]);
+
+        // checks are per session and off after the pragma
+        assert!(run(&mut executor, "PRAGMA foreign_keys = OFF").success);
+        assert!(run(&mut executor, "INSERT INTO lines (order_id, pos) VALUES (99, 1)").success);
+        assert!(run(&mut executor, "DELETE FROM orders WHERE id = 12").success);
+        assert!(run(&mut executor, "PRAGMA foreign_keys = ON").success);
+        assert!(!run(&mut executor, "INSERT INTO lines (order_id, pos) VALUES (98, 1)").success);
+
+        // an ALTER TABLE checks only the foreign keys of the column it adds, not older orphans
+        let result = run(&mut executor, "ALTER TABLE lines ADD COLUMN note Varchar(10) DEFAULT 'hi'");
+        assert!(result.success, "{}", result);
+        let result = run(&mut executor, "ALTER TABLE lines ADD COLUMN other Integer DEFAULT 98 REFERENCES orders");
+        assert!(result.to_string().contains("FOREIGN KEY constraint failed"), "{}", result);
+
+        // the foreign keys are part of the stored schema
+        let mut reopened = executor.reopen();
+        assert!(!reopened.prepare("INSERT INTO lines (order_id, pos) VALUES (98, 1)".to_string()).success);
+        assert!(reopened.prepare("DELETE FROM orders WHERE id = 11".to_string()).success);
+        let result = reopened.prepare("SELECT order_id FROM lines".to_string());
+        assert_eq!(result.data.fetch().unwrap(), vec![int("99")]);
+    }
+
+    #[test]
+    fn test_sequences() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let keys = |executor: &mut RustqlQueryExecutor, query: &str| {
+            let result = executor.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+            result.generated_keys
+        };
+        let id = |value: i32| vec![vec![("id".to_string(), value)]];
+        for query in [
+            "CREATE TABLE users (id Integer PRIMARY KEY AUTOINCREMENT, name Varchar(10))",
+            "CREATE TABLE tickets (no Integer GENERATED ALWAYS AS IDENTITY, note String)",
+            "CREATE SEQUENCE evens START WITH 10 INCREMENT BY 2",
+            "CREATE TABLE events (id Integer DEFAULT nextval('evens'), v Integer)",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+
+        // BY DEFAULT columns draw from their sequence unless a value is given, which moves
+        // the sequence past it
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('a')"), id(1));
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('b')"), id(2));
+        assert!(keys(&mut executor, "INSERT INTO users (id, name) VALUES (10, 'c')").is_empty());
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('d')"), id(11));
+        assert!(keys(&mut executor, "INSERT INTO users (id, name) VALUES (5, 'e'), (20, 'f')").is_empty());
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('g')"), id(21));
+        // the keys are grouped by inserted row
+        assert_eq!(
+            keys(&mut executor, "INSERT INTO users (name) VALUES ('h'), ('i')"),
+            [id(22), id(23)].concat()
+        );
+        assert!(keys(&mut executor, "UPDATE users SET id = 30 WHERE name = 'g'").is_empty());
+        assert!(keys(&mut executor, "DELETE FROM users WHERE id >= 20").is_empty());
+        assert!(keys(&mut executor, "SELECT * FROM users").is_empty());
+
+        let result = executor.prepare("INSERT INTO tickets (no, note) VALUES (5, 'x')".to_string());
+        assert!(!result.success);
+        assert!(
+            result.to_string().contains("Column 'no' is GENERATED ALWAYS AS IDENTITY"),
+            "{}",
+            result
+        );
+        assert_eq!(
+            keys(&mut executor, "INSERT INTO tickets (note) VALUES ('x')"),
+            vec![vec![("no".to_string(), 1)]]
+        );
+        let message = error_message(&mut executor, "UPDATE tickets SET no = 2 WHERE note = 'x'");
+        assert!(message.contains("Column 'no' is GENERATED ALWAYS AS IDENTITY and cannot be assigned"), "{}", message);
+        assert_eq!(
+            keys(&mut executor, "INSERT INTO tickets (note) VALUES ('y')"),
+            vec![vec![("no".to_string(), 2)]]
+        );
+        assert_eq!(values(&mut executor, "SELECT no FROM tickets", Type::Integer), vec!["1", "2"]);
+
+        assert_eq!(keys(&mut executor, "INSERT INTO events (v) VALUES (1)"), id(10));
+        assert_eq!(
+            keys(&mut executor, "INSERT INTO events (id, v) VALUES (nextval('evens'), 2)"),
+            id(12)
+        );
+        for query in [
+            "SELECT * FROM events WHERE id = nextval('evens')",
+            "INSERT INTO events (id, v) VALUES (nextval('odds'), 3)",
+            "CREATE TABLE bad (name String DEFAULT nextval('evens'))",
+            "CREATE TABLE bad (id Integer DEFAULT 1 GENERATED BY DEFAULT AS IDENTITY)",
+            "CREATE SEQUENCE evens",
+            "CREATE SEQUENCE never INCREMENT BY 0",
+            "DROP SEQUENCE evens",
+            "DROP SEQUENCE odds",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert!(executor.prepare("CREATE SEQUENCE IF NOT EXISTS evens".to_string()).success);
+
+        // values are not handed out twice, not even after a rollback
+        assert!(executor.prepare("BEGIN TRANSACTION".to_string()).success);
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('h')"), id(31));
+        assert!(executor.prepare("ROLLBACK".to_string()).success);
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('i')"), id(32));
+
+        // connections sharing the pager share the counters
+        let mut other =
+            RustqlQueryExecutor::from_pager_accessor(executor.pager_accessor.clone(), BTREE_NODE_SIZE);
+        assert_eq!(keys(&mut other, "INSERT INTO users (name) VALUES ('j')"), id(33));
+        assert_eq!(keys(&mut executor, "INSERT INTO users (name) VALUES ('k')"), id(34));
+
+        // the identity sequence goes with its table
+        assert!(executor.prepare("DROP TABLE tickets".to_string()).success);
+        assert!(executor.prepare("CREATE SEQUENCE tickets_no_seq".to_string()).success);
+        assert!(executor.prepare("DROP SEQUENCE tickets_no_seq".to_string()).success);
+
+        let mut reopened = executor.reopen();
+        assert_eq!(keys(&mut reopened, "INSERT INTO users (name) VALUES ('l')"), id(35));
+        assert_eq!(keys(&mut reopened, "INSERT INTO events (v) VALUES (3)"), id(14));
+        let result = reopened.prepare("SELECT id FROM users WHERE name = 'l'".to_string());
+        assert_eq!(result.data.fetch().unwrap(), vec![Serializer::parse_int("35").unwrap().to_vec()]);
+    }
+
+    #[test]
+    fn test_quoted_nextval_is_a_literal() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE SEQUENCE s START WITH 5",
+            "CREATE TABLE n (id Integer, k Integer)",
+            "CREATE TABLE t (id Integer, name String DEFAULT 'nextval(''s'')')",
+        ] {
+            run_ok(&mut executor, query);
+        }
+
+        let message = error_message(&mut executor, "INSERT INTO n (id, k) VALUES (1, 'nextval(''s'')')");
+        assert!(message.contains("is not a valid integer"), "{}", message);
+        run_ok(&mut executor, "INSERT INTO t (id, name) VALUES (1, 'nextval(''s'')')");
+        run_ok(&mut executor, "INSERT INTO t (id) VALUES (2)");
+        run_ok(&mut executor, "UPDATE t SET name = 'nextval(''s'')' WHERE id = 2");
+        assert_eq!(
+            values(&mut executor, "SELECT name FROM t", Type::String),
+            vec!["nextval('s')"; 2]
+        );
+
+        // the sequence was not drawn from
+        let result = run_ok(&mut executor, "INSERT INTO n (id, k) VALUES (2, nextval('s'))");
+        assert_eq!(result.generated_keys, vec![vec![("k".to_string(), 5)]]);
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE people (id Integer PRIMARY KEY AUTOINCREMENT, \
+             name Varchar(5) UNIQUE CHECK (name >= 'a'), age Integer)",
+            "CREATE INDEX people_age ON people (age)",
+            "INSERT INTO people (name, age) VALUES ('ann', 30)",
+            "INSERT INTO people (name, age) VALUES ('bob', 40)",
+            "INSERT INTO people (name) VALUES ('cy')",
+        ] {
+            let result = run(&mut executor, query);
+            assert!(result.success, "{}: {}", query, result);
+        }
+
+        // existing rows get the default of an added column, or NULL
+        assert!(run(&mut executor, "ALTER TABLE people ADD COLUMN city Varchar(10) DEFAULT 'Bern' NOT NULL").success);
+        assert!(run(&mut executor, "ALTER TABLE people ADD note Text").success);
+        assert!(run(&mut executor, "ALTER TABLE people ADD COLUMN score Integer DEFAULT 7").success);
+        assert_eq!(values(&mut executor, "SELECT city FROM people", Type::Varchar(10)), vec!["Bern"; 3]);
+        assert_eq!(values(&mut executor, "SELECT id FROM people WHERE note IS NULL", Type::Integer).len(), 3);
+        assert!(run(&mut executor, "INSERT INTO people (name, city, note) VALUES ('dee', 'Basel', 'a long note')").success);
+        let result = run(&mut executor, "SELECT note FROM people WHERE city = 'Basel'");
+        assert!(result.to_string().contains("a long note"), "{}", result);
+        for query in [
+            "ALTER TABLE people ADD COLUMN code Integer NOT NULL",
+            "ALTER TABLE people ADD COLUMN tag Integer UNIQUE DEFAULT 1",
+            "ALTER TABLE people ADD COLUMN city Integer",
+            "ALTER TABLE people ADD COLUMN k Integer PRIMARY KEY",
+            "ALTER TABLE people ADD COLUMN n Integer AUTOINCREMENT",
+            "ALTER TABLE people ADD COLUMN a Integer, b Integer",
+            "ALTER TABLE rustsql_master ADD COLUMN a Integer",
+            "ALTER TABLE _people_name DROP COLUMN idx_value",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+        assert_eq!(values(&mut executor, "SELECT id FROM people", Type::Integer).len(), 4);
+
+        // only widening type changes; the indexes and checks on the column stay in force
+        assert!(!run(&mut executor, "ALTER TABLE people ALTER COLUMN name TYPE Varchar(2)").success);
+        assert!(!run(&mut executor, "ALTER TABLE people ALTER COLUMN age TYPE String").success);
+        assert!(run(&mut executor, "ALTER TABLE people ALTER COLUMN name TYPE Varchar(20)").success);
+        assert!(run(&mut executor, "ALTER TABLE people ALTER COLUMN age TYPE Decimal(12,2)").success);
+        assert_eq!(scan_operation(&executor, "SELECT name FROM people WHERE age >= 35"), SqlConditionOpCode::SelectIndexRange);
+        assert_eq!(values(&mut executor, "SELECT age FROM people WHERE age >= 35", Type::Decimal(12, 2)), vec!["40.00"]);
+        assert!(run(&mut executor, "ALTER TABLE people ALTER COLUMN score SET DATA TYPE Decimal(12,2)").success);
+        assert_eq!(
+            values(&mut executor, "SELECT score FROM people WHERE name = 'bob'", Type::Decimal(12, 2)),
+            vec!["7.00"]
+        );
+        assert!(run(&mut executor, "INSERT INTO people (name) VALUES ('a longer name')").success);
+        assert!(!run(&mut executor, "INSERT INTO people (name) VALUES ('ann')").success);
+        assert!(!run(&mut executor, "INSERT INTO people (name) VALUES ('Zed')").success);
+        assert_eq!(
+            values(&mut executor, "SELECT name FROM people WHERE age = 40", Type::Varchar(20)),
+            vec!["bob"]
+        );
+
+        // a rename reaches the indexes, the checks and the identity sequence
+        assert!(!run(&mut executor, "ALTER TABLE people RENAME COLUMN name TO city").success);
+        assert!(run(&mut executor, "ALTER TABLE people RENAME COLUMN name TO full_name").success);
+        assert!(run(&mut executor, "ALTER TABLE people RENAME id TO pid").success);
+        assert!(!run(&mut executor, "INSERT INTO people (full_name) VALUES ('ann')").success);
+        assert!(!run(&mut executor, "INSERT INTO people (full_name) VALUES ('Zed')").success);
+        let result = run(&mut executor, "INSERT INTO people (full_name) VALUES ('eve')");
+        assert!(result.success, "{}", result);
+        assert_eq!(result.generated_keys[0][0].0, "pid");
+        assert!(result.generated_keys[0][0].1 > 4);
+        assert_eq!(
+            values(&mut executor, "SELECT city FROM people WHERE full_name = 'eve'", Type::Varchar(10)),
+            vec!["Bern"]
+        );
+
+        // dropped columns take their indexes along; key and referenced columns stay
+        assert!(run(&mut executor, "ALTER TABLE people DROP COLUMN note").success);
+        assert!(run(&mut executor, "ALTER TABLE people DROP COLUMN age").success);
+        assert!(!run(&mut executor, "SELECT age FROM people").success);
+        assert!(!run(&mut executor, "DROP INDEX people_age").success);
+        assert!(run(&mut executor, "CREATE TABLE pets (id Integer, owner Integer REFERENCES people (pid))").success);
+        for query in [
+            "ALTER TABLE people DROP COLUMN pid",
+            "ALTER TABLE people RENAME COLUMN pid TO id",
+            "ALTER TABLE people DROP COLUMN missing",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+        assert!(executor.check_integrity().is_ok());
+
+        // the stored schema is the altered one
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("SELECT full_name, city FROM people WHERE full_name = 'bob'".to_string());
+        assert!(result.success, "{}", result);
+        assert_eq!(result.data.fetch().unwrap().len(), 1);
+        assert!(!reopened.prepare("INSERT INTO people (full_name) VALUES ('bob')".to_string()).success);
+        let result = reopened.prepare("INSERT INTO people (full_name) VALUES ('fay')".to_string());
+        assert_eq!(result.generated_keys[0][0].0, "pid");
+    }
+
+    #[test]
+    fn test_rowid() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        assert!(run(&mut executor, "CREATE TABLE log (level Varchar(5), msg Varchar(20)) WITH ROWID").success);
+
+        // repeated first values are ordinary rows, keyed by the rowid in insertion order
+        for (query, rowid) in [
+            ("INSERT INTO log (level, msg) VALUES ('info', 'started')", 1),
+            ("INSERT INTO log (level, msg) VALUES ('info', 'started')", 2),
+            ("INSERT INTO log VALUES ('warn', 'disk')", 3),
+        ] {
+            let result = run(&mut executor, query);
+            assert!(result.success, "{}: {}", query, result);
+            assert_eq!(result.generated_keys, vec![vec![("rowid".to_string(), rowid)]]);
+        }
+        assert_eq!(
+            values(&mut executor, "SELECT rowid FROM log WHERE level = 'info'", Type::Integer),
+            vec!["1", "2"]
+        );
+        assert_eq!(values(&mut executor, "SELECT level FROM log WHERE rowid = 3", Type::Varchar(5)), vec!["warn"]);
+
+        // the rowid stays out of SELECT * and is not handed out twice
+        let result = run(&mut executor, "SELECT * FROM log");
+        let header: Vec<String> = result.data.header.iter().map(|f| f.name.clone()).collect();
+        assert_eq!(header, vec!["level", "msg"]);
+        assert!(run(&mut executor, "DELETE FROM log WHERE rowid = 3").success);
+        assert_eq!(
+            run(&mut executor, "INSERT INTO log VALUES ('warn', 'disk')").generated_keys,
+            vec![vec![("rowid".to_string(), 4)]]
+        );
+
+        // UPDATE cannot move a row out of the sequence either
+        let message = error_message(&mut executor, "UPDATE log SET rowid = 77 WHERE level = 'info'");
+        assert!(message.contains("Column 'rowid' is the rowid and cannot be assigned"), "{}", message);
+        assert!(!run(&mut executor, "UPDATE log SET msg = 'x', rowid = 77").success);
+        assert_eq!(
+            run(&mut executor, "INSERT INTO log VALUES ('info', 'later')").generated_keys,
+            vec![vec![("rowid".to_string(), 5)]]
+        );
+        assert_eq!(values(&mut executor, "SELECT rowid FROM log", Type::Integer), vec!["1", "2", "4", "5"]);
+
+        for query in [
+            "INSERT INTO log (rowid, level, msg) VALUES (9, 'info', 'x')",
+            "CREATE TABLE bad (rowid Integer, a Integer)",
+            "CREATE TABLE bad (a Integer PRIMARY KEY, b Integer) WITH ROWID",
+            "CREATE TABLE bad (a Integer) WITH OIDS",
+            "ALTER TABLE log RENAME COLUMN rowid TO id",
+            "ALTER TABLE log DROP COLUMN rowid",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+
+        // a NATURAL JOIN ignores the rowids
+        for query in [
+            "CREATE TABLE levels (level Varchar(5), rank Integer) WITH ROWID",
+            "INSERT INTO levels VALUES ('warn', 2)",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+        assert_eq!(
+            values(&mut executor, "SELECT msg FROM log NATURAL JOIN levels", Type::Varchar(20)),
+            vec!["disk"]
+        );
+
+        // ALTER TABLE and a reopen keep the table WITH ROWID
+        assert!(run(&mut executor, "ALTER TABLE log ADD COLUMN seen Integer DEFAULT 0").success);
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("INSERT INTO log VALUES ('info', 'reopened', 1)".to_string());
+        assert!(result.success, "{}", result);
+        assert_eq!(result.generated_keys, vec![vec![("rowid".to_string(), 6)]]);
+        let result = reopened.prepare("SELECT rowid FROM log WHERE seen = 0".to_string());
+        assert_eq!(result.data.fetch().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_views() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE orders (id Integer, customer Varchar(10), total Integer)",
+            "INSERT INTO orders VALUES (1, 'ada', 50)",
+            "INSERT INTO orders VALUES (2, 'bob', 120)",
+            "INSERT INTO orders VALUES (3, 'cy', 300)",
+            "CREATE VIEW big_orders AS SELECT id, customer FROM orders WHERE total >= 100",
+            "CREATE VIEW big_customers AS SELECT customer FROM big_orders",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+
+        let result = run(&mut executor, "SELECT * FROM big_orders");
+        let header: Vec<String> = result.data.header.iter().map(|f| f.name.clone()).collect();
+        assert_eq!(header, vec!["id", "customer"]);
+        assert_eq!(
+            values(&mut executor, "SELECT customer FROM big_orders WHERE id = 3", Type::Varchar(10)),
+            vec!["cy"]
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT customer FROM big_customers", Type::Varchar(10)),
+            vec!["bob", "cy"]
+        );
+
+        // the filter moves below the view's projection, which is narrowed to what is selected
+        match executor
+            .compile_query("SELECT customer FROM big_orders WHERE id = 3")
+            .unwrap()
+        {
+            CompiledQuery::Select(select) => {
+                let PlanNode::Project { source, fields, .. } = &select.plan else {
+                    panic!("expected the view's Project on top, got {:?}", select.plan);
+                };
+                assert_eq!(fields.len(), 1);
+                let PlanNode::Filter { source, .. } = source.as_ref() else {
+                    panic!("expected the Filter below the view's Project, got {:?}", source);
+                };
+                match source.as_ref() {
+                    PlanNode::SeqScan { operation, .. } => {
+                        assert_eq!(*operation, SqlConditionOpCode::SelectKeyUnique)
+                    }
+                    other => panic!("expected SeqScan, got {:?}", other),
+                }
+            }
+            _ => panic!("expected compiled SELECT"),
+        }
+
+        // a view joins like a table, its columns qualified by the view's name
+        for query in [
+            "CREATE TABLE customers (name Varchar(10), city Varchar(10))",
+            "INSERT INTO customers VALUES ('ada', 'Aarau')",
+            "INSERT INTO customers VALUES ('cy', 'Chur')",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+        for query in [
+            "SELECT big_orders.id FROM customers INNER JOIN big_orders ON customers.name = big_orders.customer",
+            "SELECT big_orders.id FROM big_orders INNER JOIN customers ON big_orders.customer = customers.name",
+        ] {
+            assert_eq!(values(&mut executor, query, Type::Integer), vec!["3"], "{}", query);
+        }
+        let result = run(&mut executor, "SELECT * FROM customers INNER JOIN big_orders ON name = customer");
+        let header: Vec<String> = result.data.header.iter().map(|f| format!("{}.{}", f.table_name, f.name)).collect();
+        assert_eq!(header, vec!["customers.name", "customers.city", "big_orders.id", "big_orders.customer"]);
+        let result = run(&mut executor, "SELECT * FROM customers INNER JOIN big_orders ON name = big_orders.total");
+        assert!(result.to_string().contains("Column 'big_orders.total' not found in join source"), "{}", result);
+
+        for query in [
+            "CREATE VIEW broken AS SELECT id FROM missing",
+            "CREATE VIEW broken AS SELECT nope FROM orders",
+            "CREATE VIEW orders AS SELECT id FROM orders",
+            "CREATE TABLE big_orders (a Integer)",
+            "INSERT INTO big_orders VALUES (4, 'dan')",
+            "DROP TABLE orders",
+            "DROP VIEW big_orders",
+            "DROP VIEW missing",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+        let result = run(&mut executor, "DROP TABLE orders");
+        assert!(result.to_string().contains("used by view 'big_orders'"), "{}", result);
+
+        // views are stored in the master table and survive a reopen
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("SELECT customer FROM big_customers".to_string());
+        assert!(result.success, "{}", result);
+        assert_eq!(result.data.fetch().unwrap().len(), 2);
+        for query in ["DROP VIEW big_customers", "DROP VIEW big_orders", "DROP TABLE orders"] {
+            assert!(reopened.prepare(query.to_string()).success, "{}", query);
+        }
+        assert!(!reopened.prepare("SELECT * FROM big_orders".to_string()).success);
+    }
+
+    #[test]
+    fn test_alter_table_keeps_views_working() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE orders (id Integer, customer Varchar(10), total Integer, city Varchar(10))",
+            "INSERT INTO orders VALUES (1, 'ada', 50, 'Aarau')",
+            "INSERT INTO orders VALUES (2, 'bob', 120, 'Bern')",
+            "CREATE VIEW big_orders AS SELECT id, customer FROM orders WHERE total >= 100",
+            "CREATE VIEW everything AS SELECT * FROM orders",
+            "CREATE VIEW customers AS SELECT customer FROM everything",
+            "CREATE MATERIALIZED VIEW cities AS SELECT id, city FROM orders",
+        ] {
+            run_ok(&mut executor, query);
+        }
+
+        for (query, view) in [
+            ("ALTER TABLE orders RENAME COLUMN total TO amount", "big_orders"),
+            ("ALTER TABLE orders DROP COLUMN total", "big_orders"),
+            ("ALTER TABLE orders RENAME customer TO buyer", "big_orders"),
+            ("ALTER TABLE orders DROP COLUMN city", "cities"),
+            // REFRESH would no longer produce the stored columns
+            ("ALTER TABLE orders ALTER COLUMN city TYPE Varchar(20)", "cities"),
+        ] {
+            let message = error_message(&mut executor, query);
+            assert!(message.contains(&format!("is used by view '{}'", view)), "{}: {}", query, message);
+        }
+        // also through a view that selects every column
+        run_ok(&mut executor, "DROP VIEW big_orders");
+        let message = error_message(&mut executor, "ALTER TABLE orders RENAME customer TO buyer");
+        assert!(message.contains("Column 'orders.customer' is used by view 'customers'"), "{}", message);
+
+        // changes the views do not notice go through
+        run_ok(&mut executor, "ALTER TABLE orders RENAME COLUMN total TO amount");
+        run_ok(&mut executor, "ALTER TABLE orders ADD note Integer");
+        assert_eq!(
+            values(&mut executor, "SELECT customer FROM customers", Type::Varchar(10)),
+            vec!["ada", "bob"]
+        );
+        run_ok(&mut executor, "REFRESH MATERIALIZED VIEW cities");
+        assert_eq!(count(&mut executor, "SELECT city FROM cities"), 2);
+    }
+
+    #[test]
+    fn test_commit_persists_pages_created_in_the_transaction() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        // the statement is long enough to put its tail on a payload page of its own
+        for query in [
+            "BEGIN TRANSACTION",
+            "CREATE TABLE a_rather_long_table_name (first_column Integer, second_column Varchar(10))",
+            "INSERT INTO a_rather_long_table_name VALUES (1, 'one')",
+            "COMMIT",
+        ] {
+            let result = executor.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("SELECT second_column FROM a_rather_long_table_name".to_string());
+        assert!(result.success, "{}", result);
+        assert_eq!(result.data.fetch().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rollback_keeps_pages_created_in_the_transaction_readable() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        // writing a payload looks through every page for one to reuse, including those
+        // the rolled-back transaction created
+        for query in [
+            "BEGIN TRANSACTION",
+            "CREATE TABLE a_rather_long_table_name (first_column Integer, second_column Varchar(10))",
+            "ROLLBACK",
+            "CREATE TABLE another_rather_long_table_name (first_column Integer, second_column Varchar(10))",
+            "INSERT INTO another_rather_long_table_name VALUES (1, 'one')",
+        ] {
+            let result = executor.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        assert!(executor.check_integrity().is_ok());
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("CREATE TABLE a_rather_long_table_name (first_column Integer)".to_string());
+        assert!(result.success, "{}", result);
+    }
+
+    #[test]
+    fn test_materialized_views() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let long_note = "shipped in several parcels ".repeat(10);
+        for query in [
+            "CREATE TABLE customers (name Varchar(10), city Varchar(10))".to_string(),
+            "CREATE TABLE orders (id Integer, customer Varchar(10), total Integer, note Text)".to_string(),
+            "INSERT INTO customers VALUES ('ada', 'Paris')".to_string(),
+            "INSERT INTO customers VALUES ('bob', 'Rome')".to_string(),
+            format!("INSERT INTO orders VALUES (1, 'ada', 50, '{}')", long_note),
+            "INSERT INTO orders VALUES (2, 'ada', 120, NULL)".to_string(),
+            "INSERT INTO orders VALUES (3, 'bob', 300, 'express')".to_string(),
+            "CREATE MATERIALIZED VIEW order_cities AS SELECT orders.id, customers.city, orders.note FROM orders INNER JOIN customers ON orders.customer = customers.name".to_string(),
+            "CREATE MATERIALIZED VIEW people (who) AS SELECT customer FROM orders UNION SELECT name FROM customers".to_string(),
+        ] {
+            let result = run(&mut executor, &query);
+            assert!(result.success, "{}: {}", query, result);
+        }
+
+        let result = run(&mut executor, "SELECT * FROM order_cities");
+        let header: Vec<String> = result.data.header.iter().map(|f| f.name.clone()).collect();
+        assert_eq!(header, vec!["id", "city", "note"]);
+        assert_eq!(
+            values(&mut executor, "SELECT city FROM order_cities WHERE id = 3", Type::Varchar(10)),
+            vec!["Rome"]
+        );
+        let query = format!("SELECT id FROM order_cities WHERE note = '{}'", long_note);
+        assert_eq!(values(&mut executor, &query, Type::Integer), vec!["1"]);
+        assert_eq!(values(&mut executor, "SELECT who FROM people", Type::Varchar(10)), vec!["ada", "bob"]);
+
+        // the stored result only changes on REFRESH, indexes on the view included
+        for query in [
+            "CREATE INDEX idx_order_cities_city ON order_cities (city)",
+            "INSERT INTO customers VALUES ('cy', 'Oslo')",
+            "INSERT INTO orders VALUES (4, 'cy', 80, 'late')",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+        assert!(values(&mut executor, "SELECT id FROM order_cities WHERE city = 'Oslo'", Type::Integer).is_empty());
+        assert!(run(&mut executor, "REFRESH MATERIALIZED VIEW order_cities").success);
+        assert_eq!(
+            values(&mut executor, "SELECT id FROM order_cities WHERE city = 'Oslo'", Type::Integer),
+            vec!["4"]
+        );
+        assert_eq!(values(&mut executor, "SELECT id FROM order_cities", Type::Integer).len(), 4);
+        assert!(executor.check_integrity().is_ok());
+
+        for query in [
+            "INSERT INTO order_cities VALUES (5, 'Rome', NULL)",
+            "UPDATE order_cities SET city = 'Rome'",
+            "DELETE FROM order_cities WHERE id = 1",
+            "ALTER TABLE order_cities ADD COLUMN x Integer",
+            "DROP TABLE order_cities",
+            "DROP VIEW order_cities",
+            "DROP TABLE orders",
+            "REFRESH MATERIALIZED VIEW orders",
+            "CREATE MATERIALIZED VIEW order_cities AS SELECT id FROM orders",
+            "CREATE MATERIALIZED VIEW bad (a, b) AS SELECT customer FROM orders",
+            "CREATE MATERIALIZED VIEW bad (a Integer) AS SELECT customer FROM orders",
+            "CREATE MATERIALIZED VIEW bad AS SELECT customer, customer FROM orders",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+
+        // the view and its statement survive a reopen, and REFRESH still works
+        let mut reopened = executor.reopen();
+        for query in [
+            "INSERT INTO customers VALUES ('dan', 'Lima')",
+            "REFRESH MATERIALIZED VIEW people",
+        ] {
+            let result = reopened.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        let result = reopened.prepare("SELECT who FROM people".to_string());
+        assert_eq!(result.data.fetch().unwrap().len(), 4);
+        let result = reopened.prepare(query);
+        assert_eq!(result.data.fetch().unwrap().len(), 1);
+        for query in [
+            "DROP MATERIALIZED VIEW order_cities",
+            "DROP MATERIALIZED VIEW people",
+            "DROP TABLE orders",
+        ] {
+            let result = reopened.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+    }
+
+    #[test]
+    fn test_triggers() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE accounts (id Integer, owner Varchar(10), balance Integer, note Text)",
+            "CREATE TABLE audit (account Integer, action Varchar(10), amount Integer, note Text) WITH ROWID",
+            "CREATE TABLE blocked (owner Varchar(10) UNIQUE)",
+            "INSERT INTO blocked VALUES ('mallory')",
+            "CREATE TRIGGER log_insert AFTER INSERT ON accounts FOR EACH ROW BEGIN INSERT INTO audit VALUES (NEW.id, 'insert', NEW.balance, NEW.note); END",
+            "CREATE TRIGGER log_update AFTER UPDATE ON accounts FOR EACH ROW BEGIN INSERT INTO audit VALUES (OLD.id, 'old', OLD.balance, NULL); INSERT INTO audit VALUES (NEW.id, 'new', NEW.balance, NULL); END",
+            "CREATE TRIGGER check_owner BEFORE INSERT ON accounts FOR EACH ROW BEGIN INSERT INTO blocked VALUES (NEW.owner); DELETE FROM blocked WHERE owner = NEW.owner; END",
+            "CREATE TRIGGER forget BEFORE DELETE ON accounts FOR EACH ROW BEGIN DELETE FROM audit WHERE account = OLD.id; END",
+        ] {
+            let result = run(&mut executor, query);
+            assert!(result.success, "{}: {}", query, result);
+        }
+
+        // NEW carries the whole row, quotes and payloads included
+        let note = "it's the first account; ".repeat(5);
+        let query = format!("INSERT INTO accounts VALUES (1, 'ada', 50, '{}')", note.replace("'", "''"));
+        assert!(run(&mut executor, &query).success);
+        assert!(run(&mut executor, "INSERT INTO accounts VALUES (2, 'bob', 20, NULL)").success);
+        let query = format!("SELECT account FROM audit WHERE note = '{}'", note.replace("'", "''"));
+        assert_eq!(values(&mut executor, &query, Type::Integer), vec!["1"]);
+        assert_eq!(
+            values(&mut executor, "SELECT amount FROM audit WHERE action = 'insert'", Type::Integer),
+            vec!["50", "20"]
+        );
+
+        // a failing BEFORE trigger leaves neither the row nor what the other triggers wrote
+        let result = run(&mut executor, "INSERT INTO accounts VALUES (3, 'mallory', 10, NULL)");
+        assert!(!result.success);
+        assert!(values(&mut executor, "SELECT id FROM accounts WHERE id = 3", Type::Integer).is_empty());
+        assert!(values(&mut executor, "SELECT account FROM audit WHERE account = 3", Type::Integer).is_empty());
+        assert_eq!(values(&mut executor, "SELECT owner FROM blocked", Type::Varchar(10)), vec!["mallory"]);
+
+        // every updated row fires with its OLD and NEW values
+        assert!(run(&mut executor, "UPDATE accounts SET balance = 70 WHERE id = 1").success);
+        assert_eq!(
+            values(&mut executor, "SELECT amount FROM audit WHERE action = 'old'", Type::Integer),
+            vec!["50"]
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT amount FROM audit WHERE action = 'new'", Type::Integer),
+            vec!["70"]
+        );
+
+        assert!(run(&mut executor, "DELETE FROM accounts WHERE id = 1").success);
+        assert!(values(&mut executor, "SELECT account FROM audit WHERE account = 1", Type::Integer).is_empty());
+        assert_eq!(values(&mut executor, "SELECT account FROM audit", Type::Integer), vec!["2"]);
+        assert!(executor.check_integrity().is_ok());
+
+        // a trigger that writes to its own table stops at the nesting limit
+        for query in [
+            "CREATE TABLE counter (n Integer)",
+            "CREATE TRIGGER again AFTER INSERT ON counter FOR EACH ROW BEGIN INSERT INTO counter VALUES (NEW.n); END",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+        assert!(!run(&mut executor, "INSERT INTO counter VALUES (1)").success);
+        assert!(values(&mut executor, "SELECT n FROM counter", Type::Integer).is_empty());
+
+        for query in [
+            "CREATE TRIGGER log_insert AFTER INSERT ON audit FOR EACH ROW BEGIN SELECT * FROM audit; END",
+            "CREATE TRIGGER bad AFTER DELETE ON accounts FOR EACH ROW BEGIN INSERT INTO audit VALUES (NEW.id, 'x', 0, NULL); END",
+            "CREATE TRIGGER bad BEFORE INSERT ON accounts FOR EACH ROW BEGIN INSERT INTO audit VALUES (OLD.id, 'x', 0, NULL); END",
+            "CREATE TRIGGER bad BEFORE INSERT ON accounts FOR EACH ROW BEGIN INSERT INTO audit VALUES (NEW.nope, 'x', 0, NULL); END",
+            "CREATE TRIGGER bad BEFORE INSERT ON missing FOR EACH ROW BEGIN SELECT * FROM audit; END",
+            "CREATE TRIGGER bad BEFORE INSERT ON rustsql_master FOR EACH ROW BEGIN SELECT * FROM audit; END",
+            "CREATE TRIGGER bad BEFORE INSERT ON accounts FOR EACH ROW BEGIN DROP TABLE audit; END",
+            "CREATE TRIGGER bad BEFORE INSERT ON accounts FOR EACH ROW BEGIN SELECT * FROM audit END",
+            "CREATE TABLE log_update (a Integer)",
+            "ALTER TABLE accounts DROP COLUMN note",
+            "ALTER TABLE accounts RENAME COLUMN balance TO amount",
+            "DROP TRIGGER missing",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+
+        // triggers survive a reopen and go with DROP TRIGGER or their table
+        let mut reopened = executor.reopen();
+        for query in [
+            "INSERT INTO accounts VALUES (4, 'cy', 5, NULL)",
+            "DROP TRIGGER log_insert",
+            "INSERT INTO accounts VALUES (5, 'dan', 6, NULL)",
+        ] {
+            let result = reopened.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        let result = reopened.prepare("SELECT account FROM audit WHERE action = 'insert'".to_string());
+        assert_eq!(result.data.fetch().unwrap().len(), 2);
+        assert!(reopened.prepare("DROP TABLE accounts".to_string()).success);
+        let names: Vec<&str> = reopened.schema.triggers.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["again"]);
+        assert!(!reopened.prepare("DROP TRIGGER log_update".to_string()).success);
+    }
+
+    #[test]
+    fn test_definitions_longer_than_the_master_sql_column() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let columns = "id, first_name, last_name, street_and_house_number, postal_code, city, \
+                       country_of_residence, date_of_birth, preferred_language, note";
+        let view = format!(
+            "CREATE VIEW wide AS SELECT {} FROM people WHERE city = 'Bern' OR city = 'Thun' OR postal_code = '3000' \
+             OR preferred_language = 'de' OR country_of_residence = 'Switzerland' OR id > 100",
+            columns
+        );
+        let trigger = format!(
+            "CREATE TRIGGER track AFTER INSERT ON people FOR EACH ROW BEGIN {} END",
+            (0..4)
+                .map(|i| format!("INSERT INTO audit VALUES (NEW.id, 'step {}', NEW.city);", i))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        assert!(view.len() > 300 && trigger.len() > 255);
+        for query in [
+            "CREATE TABLE people (id Integer CHECK (id > 0), first_name Varchar(20), last_name Varchar(20), \
+             street_and_house_number Varchar(40), postal_code Varchar(10), city Varchar(20), \
+             country_of_residence Varchar(20), date_of_birth Date, preferred_language Varchar(10), note Text)",
+            "CREATE TABLE audit (person Integer, step Varchar(10), city Varchar(20)) WITH ROWID",
+            &view,
+            &trigger,
+            "INSERT INTO people (id, city) VALUES (1, 'Bern')",
+        ] {
+            let result = run(&mut executor, query);
+            assert!(result.success, "{}: {}", query, result);
+        }
+        assert_eq!(values(&mut executor, "SELECT city FROM wide", Type::Varchar(20)), vec!["Bern"]);
+        assert_eq!(values(&mut executor, "SELECT person FROM audit", Type::Integer), vec!["1"; 4]);
+
+        // the definitions are read back on reopen and follow ALTER TABLE and DROP
+        let mut reopened = executor.reopen();
+        for query in [
+            "ALTER TABLE people ADD COLUMN zip Varchar(10) DEFAULT '3000'",
+            "INSERT INTO people (id, city) VALUES (2, 'Thun')",
+        ] {
+            let result = reopened.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        let result = reopened.prepare("SELECT city FROM wide".to_string());
+        assert_eq!(result.data.fetch().unwrap().len(), 2);
+        let result = reopened.prepare("SELECT person FROM audit".to_string());
+        assert_eq!(result.data.fetch().unwrap().len(), 8);
+        for query in ["DROP VIEW wide", "DROP TRIGGER track", "DROP TABLE people"] {
+            let result = reopened.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        assert!(reopened.schema.views.is_empty() && reopened.schema.triggers.is_empty());
+        assert!(reopened.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_generated_columns() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE users (id Integer, email String, born Date, data Json, \
+             email_lc String GENERATED ALWAYS AS (lower(email)) STORED, \
+             born_year Integer GENERATED ALWAYS AS (year(born)) STORED, \
+             tag String GENERATED ALWAYS AS (upper(data->>'role') || id) STORED)",
+            "CREATE INDEX users_email ON users (email_lc)",
+            "INSERT INTO users VALUES (1, 'Ada@Example.com', '1990-05-17', '{\"role\": \"admin\"}')",
+            "INSERT INTO users (id, email) VALUES (2, 'BOB@example.com')",
+        ] {
+            let result = run(&mut executor, query);
+            assert!(result.success, "{}: {}", query, result);
+        }
+        assert_eq!(
+            values(&mut executor, "SELECT id FROM users WHERE email_lc = 'bob@example.com'", Type::Integer),
+            vec!["2"]
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT born_year FROM users WHERE id = 1", Type::Integer),
+            vec!["1990"]
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT tag FROM users WHERE id = 1", Type::String),
+            vec!["ADMIN1"]
+        );
+        // a NULL operand makes the value NULL
+        let result = run(&mut executor, "SELECT id FROM users WHERE born_year IS NULL AND tag IS NULL");
+        assert_eq!(result.data.fetch().unwrap().len(), 1);
+
+        // an update recomputes the values, and the index follows them
+        assert!(run(&mut executor, "UPDATE users SET email = 'Ada@Lovelace.org', born = '1815-12-10' WHERE id = 1").success);
+        assert_eq!(
+            values(&mut executor, "SELECT id FROM users WHERE email_lc = 'ada@lovelace.org'", Type::Integer),
+            vec!["1"]
+        );
+        assert!(values(&mut executor, "SELECT id FROM users WHERE email_lc = 'ada@example.com'", Type::Integer).is_empty());
+        assert_eq!(
+            values(&mut executor, "SELECT born_year FROM users WHERE id = 1", Type::Integer),
+            vec!["1815"]
+        );
+        assert!(executor.check_integrity().is_ok());
+
+        for query in [
+            "INSERT INTO users (id, email, email_lc) VALUES (3, 'a', 'a')",
+            "INSERT INTO users VALUES (3, 'a', NULL, NULL, 'a', NULL, NULL)",
+            "UPDATE users SET born_year = 2000 WHERE id = 1",
+            "ALTER TABLE users DROP COLUMN email",
+            "ALTER TABLE users ALTER COLUMN id TYPE Integer",
+            "ALTER TABLE users ADD COLUMN twice Integer GENERATED ALWAYS AS (id * 2) STORED",
+            "CREATE TABLE bad (a Integer, b Integer GENERATED ALWAYS AS (nope + 1) STORED)",
+            "CREATE TABLE bad (a Integer, b Integer GENERATED ALWAYS AS (a + 1) STORED, c Integer GENERATED ALWAYS AS (b + 1) STORED)",
+            "CREATE TABLE bad (a Integer, b Integer GENERATED ALWAYS AS (a + 1) VIRTUAL)",
+            "CREATE TABLE bad (a Integer, b Text GENERATED ALWAYS AS (a || 'x') STORED)",
+            "CREATE TABLE bad (a Integer, b Integer PRIMARY KEY GENERATED ALWAYS AS (a + 1) STORED)",
+            "CREATE TABLE bad (a Integer, b Integer DEFAULT 1 GENERATED ALWAYS AS (a + 1) STORED)",
+            "CREATE TABLE bad (a Integer, b Integer GENERATED ALWAYS AS (sqrt(a)) STORED)",
+            "CREATE TABLE bad (a Integer, b Integer GENERATED ALWAYS AS (a->>'x') STORED)",
+        ] {
+            assert!(!run(&mut executor, query).success, "{}", query);
+        }
+
+        // values that cannot be computed reject the row
+        for query in [
+            "CREATE TABLE ratios (a Integer, b Integer, r Integer NOT NULL GENERATED ALWAYS AS (a / b) STORED)",
+            "INSERT INTO ratios VALUES (7, 2)",
+        ] {
+            assert!(run(&mut executor, query).success, "{}", query);
+        }
+        assert!(!run(&mut executor, "INSERT INTO ratios VALUES (7, 0)").success);
+        assert!(!run(&mut executor, "INSERT INTO ratios VALUES (7, NULL)").success);
+        assert!(!run(&mut executor, "UPDATE ratios SET b = 0").success);
+        assert_eq!(values(&mut executor, "SELECT r FROM ratios", Type::Integer), vec!["3"]);
+
+        // a renamed source column is renamed in the expression, which survives a reopen
+        assert!(run(&mut executor, "ALTER TABLE users RENAME COLUMN email TO mail").success);
+        let mut reopened = executor.reopen();
+        let result = reopened.prepare("INSERT INTO users (id, mail) VALUES (4, 'Cy@Example.com')".to_string());
+        assert!(result.success, "{}", result);
+        let result = reopened.prepare("SELECT id FROM users WHERE email_lc = 'cy@example.com'".to_string());
+        assert_eq!(result.data.fetch().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_temp_tables() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TEMP TABLE stage (id Integer, name String)",
+            "INSERT INTO stage VALUES (1, 'a')",
+            "INSERT INTO stage VALUES (2, 'b')",
+        ] {
+            let result = executor.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        assert_eq!(count(&mut executor, "SELECT * FROM stage"), 2);
+        assert_eq!(count(&mut executor, "SELECT name FROM rustsql_master WHERE name = 'stage'"), 0);
+
+        // rows follow transactions, the table itself stays
+        for query in ["BEGIN TRANSACTION", "INSERT INTO stage VALUES (3, 'c')", "ROLLBACK"] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert_eq!(count(&mut executor, "SELECT * FROM stage"), 2);
+
+        // another executor on the same pager neither sees the table nor clashes with its name
+        let mut other =
+            RustqlQueryExecutor::from_pager_accessor(executor.pager_accessor.clone(), BTREE_NODE_SIZE);
+        assert!(!other.prepare("SELECT * FROM stage".to_string()).success);
+        for query in [
+            "CREATE TEMPORARY TABLE stage (id Integer)",
+            "INSERT INTO stage VALUES (10)",
+            "CREATE TEMP TABLE later (id Integer, code String)",
+        ] {
+            let result = other.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+        }
+        assert_eq!(count(&mut other, "SELECT * FROM stage"), 1);
+        assert_eq!(count(&mut executor, "SELECT * FROM stage"), 2);
+
+        // a permanent table created meanwhile is hidden by the temporary one, and its index
+        // is left alone
+        for query in [
+            "CREATE TABLE later (id Integer, code String UNIQUE)",
+            "INSERT INTO later VALUES (1, 'x')",
+        ] {
+            assert!(executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert!(other.reload_schema().is_ok());
+        for query in ["INSERT INTO later VALUES (1, 'x')", "INSERT INTO later VALUES (2, 'x')"] {
+            assert!(other.prepare(query.to_string()).success, "{}", query);
+        }
+        assert_eq!(count(&mut other, "SELECT * FROM later"), 2);
+        assert_eq!(count(&mut executor, "SELECT * FROM later"), 1);
+        assert!(!executor.prepare("INSERT INTO later VALUES (2, 'x')".to_string()).success);
+
+        for query in [
+            "CREATE TEMP TABLE stage (id Integer)",
+            "CREATE TEMP TABLE t (id Integer, code String UNIQUE)",
+            "CREATE TEMP TABLE t (id Integer) WITH ROWID",
+            "CREATE TEMP TABLE t (id Integer, p Integer REFERENCES later (id))",
+            "CREATE TABLE t (id Integer, p Integer REFERENCES stage (id))",
+            "CREATE INDEX stage_name ON stage (name)",
+            "CREATE VIEW staged AS SELECT id FROM stage",
+            "CREATE MATERIALIZED VIEW staged AS SELECT id FROM stage",
+            "CREATE TRIGGER t AFTER INSERT ON stage FOR EACH ROW BEGIN SELECT * FROM later; END",
+            "ALTER TABLE stage ADD COLUMN extra Integer",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+
+        // dropping the executor releases its tables
+        drop(other);
+        assert!(executor.prepare("DROP TABLE stage".to_string()).success);
+        assert!(!executor.prepare("SELECT * FROM stage".to_string()).success);
+        assert!(executor.prepare("CREATE TEMP TABLE stage (id Integer)".to_string()).success);
+        assert!(executor.check_integrity().is_ok());
+        let mut reopened = executor.reopen();
+        assert!(!reopened.prepare("SELECT * FROM stage".to_string()).success);
+        assert_eq!(count(&mut reopened, "SELECT * FROM later"), 1);
+    }
+
+    #[test]
+    fn test_create_table_as_and_insert_select() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        let long_note = "shipped in several parcels ".repeat(10);
+        for query in [
+            "CREATE TABLE orders (id Integer, customer Varchar(10), total Integer, note Text)".to_string(),
+            format!("INSERT INTO orders VALUES (1, 'ada', 50, '{}')", long_note),
+            "INSERT INTO orders VALUES (2, 'ada', 120, NULL)".to_string(),
+            "INSERT INTO orders VALUES (3, 'bob', 300, 'express')".to_string(),
+        ] {
+            run_ok(&mut executor, &query);
+        }
+
+        // the new table takes the result's names and types and is keyed by a rowid
+        run_ok(&mut executor, "CREATE TABLE ada_orders AS SELECT customer, id, note FROM orders WHERE customer = 'ada'");
+        let result = run_ok(&mut executor, "SELECT * FROM ada_orders");
+        let header: Vec<String> = result.data.header.iter().map(|f| f.name.clone()).collect();
+        assert_eq!(header, vec!["customer", "id", "note"]);
+        assert_eq!(result.data.header[0].field_type, Type::Varchar(10));
+        assert_eq!(values(&mut executor, "SELECT id FROM ada_orders", Type::Integer), vec!["1", "2"]);
+        let query = format!("SELECT id FROM ada_orders WHERE note = '{}'", long_note);
+        assert_eq!(values(&mut executor, &query, Type::Integer), vec!["1"]);
+
+        // rows go through the ordinary insert: defaults, unique indexes and triggers apply
+        for query in [
+            "CREATE TABLE totals (customer Varchar(10) UNIQUE, total Integer, source Varchar(10) DEFAULT 'orders')",
+            "CREATE TABLE audit (msg Varchar(10)) WITH ROWID",
+            "CREATE TRIGGER totals_audit AFTER INSERT ON totals FOR EACH ROW BEGIN INSERT INTO audit VALUES (NEW.customer); END",
+            "INSERT INTO totals (customer, total) SELECT customer, total FROM orders WHERE id > 1",
+        ] {
+            run_ok(&mut executor, query);
+        }
+        assert_eq!(
+            values(&mut executor, "SELECT total FROM totals WHERE customer = 'bob'", Type::Integer),
+            vec!["300"]
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT source FROM totals", Type::Varchar(10)),
+            vec!["orders", "orders"]
+        );
+        assert_eq!(values(&mut executor, "SELECT msg FROM audit", Type::Varchar(10)), vec!["ada", "bob"]);
+
+        // a failing row undoes the whole statement
+        run_ok(&mut executor, "INSERT INTO orders VALUES (4, 'cy', 10, NULL)");
+        run_ok(&mut executor, "INSERT INTO orders VALUES (5, 'cy', 20, NULL)");
+        let result = executor.prepare(
+            "INSERT INTO totals (customer, total) SELECT customer, total FROM orders WHERE id > 3".to_string(),
+        );
+        assert!(!result.success);
+        assert!(result.to_string().contains("UNIQUE"), "{}", result);
+        assert_eq!(values(&mut executor, "SELECT total FROM totals", Type::Integer).len(), 2);
+        assert_eq!(values(&mut executor, "SELECT msg FROM audit", Type::Varchar(10)).len(), 2);
+
+        // the query reads the rows from before the statement
+        run_ok(&mut executor, "INSERT INTO audit SELECT msg FROM audit");
+        run_ok(&mut executor, "INSERT INTO audit SELECT msg FROM audit");
+        assert_eq!(values(&mut executor, "SELECT msg FROM audit", Type::Varchar(10)).len(), 8);
+
+        // results beyond a batch stream in several; cells of another type are converted, and a
+        // row failing in a late batch undoes the earlier ones
+        let numbers = (1..=600)
+            .map(|n| format!("({}, 'n{}')", n, n))
+            .collect::<Vec<String>>()
+            .join(", ");
+        for query in [
+            "CREATE TABLE numbers (n Integer, label Varchar(8))".to_string(),
+            format!("INSERT INTO numbers VALUES {}", numbers),
+            "CREATE TABLE copies (n Decimal(12,2), label Text)".to_string(),
+            "INSERT INTO copies SELECT n, label FROM numbers".to_string(),
+            "CREATE TABLE capped (n Integer CHECK (n < 590), label Varchar(8))".to_string(),
+        ] {
+            run_ok(&mut executor, &query);
+        }
+        assert_eq!(values(&mut executor, "SELECT n FROM copies", Type::Decimal(12, 2)).len(), 600);
+        assert_eq!(
+            values(&mut executor, "SELECT n FROM copies WHERE n >= 599.5", Type::Decimal(12, 2)),
+            vec!["600.00"]
+        );
+        let result = run_ok(&mut executor, "SELECT label FROM copies WHERE n = 600");
+        assert!(result.to_string().contains("n600"), "{}", result);
+        let result = executor.prepare("INSERT INTO capped SELECT n, label FROM numbers".to_string());
+        assert!(!result.success);
+        assert!(values(&mut executor, "SELECT n FROM capped", Type::Integer).is_empty());
+
+        run_ok(&mut executor, "BEGIN TRANSACTION");
+        run_ok(&mut executor, "CREATE TABLE bob_orders AS SELECT id FROM orders WHERE customer = 'bob'");
+        assert_eq!(values(&mut executor, "SELECT id FROM bob_orders", Type::Integer), vec!["3"]);
+        run_ok(&mut executor, "ROLLBACK");
+        assert!(!executor.prepare("SELECT * FROM bob_orders".to_string()).success);
+
+        for query in [
+            "INSERT INTO totals SELECT customer FROM orders",
+            "INSERT INTO totals (customer, missing) SELECT customer, total FROM orders",
+            "INSERT INTO audit (rowid, msg) SELECT id, customer FROM orders",
+            "CREATE TABLE ada_orders AS SELECT id FROM orders",
+            "CREATE TABLE pairs AS SELECT orders.id, totals.customer, orders.customer FROM orders INNER JOIN totals ON orders.customer = totals.customer",
+            "CREATE TEMP TABLE staged AS SELECT id FROM orders",
+            "CREATE TABLE missing_source AS SELECT id FROM nowhere",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+
+        assert!(executor.check_integrity().is_ok());
+        let mut reopened = executor.reopen();
+        assert_eq!(values(&mut reopened, "SELECT id FROM ada_orders", Type::Integer), vec!["1", "2"]);
+        run_ok(&mut reopened, "INSERT INTO ada_orders VALUES ('cy', 4, NULL)");
+        assert_eq!(values(&mut reopened, "SELECT id FROM ada_orders", Type::Integer).len(), 3);
+    }
+
+    #[test]
+    fn test_multi_row_insert() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        run_ok(
+            &mut executor,
+            "CREATE TABLE items (id Integer PRIMARY KEY AUTOINCREMENT, code Varchar(10) UNIQUE, qty Integer NOT NULL, note Text DEFAULT 'none')",
+        );
+        let long_note = "packed in several boxes ".repeat(10);
+        let result = run_ok(
+            &mut executor,
+            &format!(
+                "INSERT INTO items (code, qty, note) VALUES ('a', 1, NULL), ('b', 2, '{}'), ('c', 3, 'x')",
+                long_note
+            ),
+        );
+        assert_eq!(result.generated_keys.len(), 3);
+        run_ok(&mut executor, "INSERT INTO items (code, qty) VALUES ('d', 4), ('e', 5)");
+        assert_eq!(
+            values(&mut executor, "SELECT qty FROM items WHERE code = 'b'", Type::Integer),
+            vec!["2"]
+        );
+        let query = format!("SELECT code FROM items WHERE note = '{}'", long_note);
+        assert_eq!(values(&mut executor, &query, Type::Varchar(10)), vec!["b"]);
+        assert_eq!(
+            values(&mut executor, "SELECT code FROM items WHERE note = 'none'", Type::Varchar(10)),
+            vec!["d", "e"]
+        );
+
+        // one bad row rejects the whole statement, duplicates within the batch included
+        for query in [
+            "INSERT INTO items (code, qty) VALUES ('f', 6), ('f', 7)",
+            "INSERT INTO items (code, qty) VALUES ('f', 6), ('a', 7)",
+            "INSERT INTO items (code, qty) VALUES ('f', 6), ('g', NULL)",
+            "INSERT INTO items (code, qty) VALUES ('f', 6), ('g')",
+            "INSERT INTO items (code, qty) VALUES ('f', 6), ('g', 'many')",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert_eq!(values(&mut executor, "SELECT qty FROM items", Type::Integer).len(), 5);
+        assert!(values(&mut executor, "SELECT qty FROM items WHERE code = 'f'", Type::Integer).is_empty());
+
+        // enough rows to split the table and its index
+        let tuples: Vec<String> = (0..300).map(|n| format!("('n{}', {})", n, n)).collect();
+        run_ok(
+            &mut executor,
+            &format!("INSERT INTO items (code, qty) VALUES {}", tuples.join(", ")),
+        );
+        assert_eq!(values(&mut executor, "SELECT qty FROM items", Type::Integer).len(), 305);
+        assert_eq!(
+            values(&mut executor, "SELECT qty FROM items WHERE code = 'n217'", Type::Integer),
+            vec!["217"]
+        );
+
+        // with triggers the rows are inserted one by one, still in one statement
+        for query in [
+            "CREATE TABLE log (msg Varchar(10)) WITH ROWID",
+            "CREATE TRIGGER items_log AFTER INSERT ON items FOR EACH ROW BEGIN INSERT INTO log VALUES (NEW.code), ('again'); END",
+            "INSERT INTO items (code, qty) VALUES ('h', 8), ('i', 9)",
+        ] {
+            run_ok(&mut executor, query);
+        }
+        assert_eq!(values(&mut executor, "SELECT msg FROM log", Type::Varchar(10)).len(), 4);
+        assert!(!executor.prepare("INSERT INTO items (code, qty) VALUES ('j', 10), ('h', 11)".to_string()).success);
+        assert_eq!(values(&mut executor, "SELECT msg FROM log", Type::Varchar(10)).len(), 4);
+
+        run_ok(&mut executor, "BEGIN TRANSACTION");
+        run_ok(&mut executor, "INSERT INTO items (code, qty) VALUES ('k', 12), ('l', 13)");
+        run_ok(&mut executor, "ROLLBACK");
+        assert!(values(&mut executor, "SELECT qty FROM items WHERE code = 'k'", Type::Integer).is_empty());
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_insert_on_conflict() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        for query in [
+            "CREATE TABLE stock (id Integer PRIMARY KEY, code Varchar(10) UNIQUE, qty Integer, note Text)",
+            "CREATE INDEX stock_qty ON stock (qty)",
+            "INSERT INTO stock VALUES (1, 'a', 10, 'first'), (2, 'b', 20, 'second')",
+        ] {
+            run_ok(&mut executor, query);
+        }
+
+        // DO NOTHING keeps the stored row, on the key and on a UNIQUE column
+        run_ok(&mut executor, "INSERT INTO stock VALUES (1, 'z', 99, 'lost') ON CONFLICT (id) DO NOTHING");
+        run_ok(&mut executor, "INSERT INTO stock VALUES (7, 'b', 99, 'lost') ON CONFLICT (code) DO NOTHING");
+        run_ok(&mut executor, "INSERT INTO stock VALUES (2, 'y', 99, 'lost') ON CONFLICT DO NOTHING");
+        assert_eq!(values(&mut executor, "SELECT qty FROM stock", Type::Integer), vec!["10", "20"]);
+        run_ok(&mut executor, "INSERT INTO stock VALUES (3, 'c', 30, NULL) ON CONFLICT DO NOTHING");
+        assert_eq!(values(&mut executor, "SELECT qty FROM stock", Type::Integer).len(), 3);
+
+        // DO UPDATE rewrites the stored row with the proposed and literal values
+        let long_note = "restocked from the north warehouse ".repeat(8);
+        run_ok(
+            &mut executor,
+            &format!(
+                "INSERT INTO stock VALUES (1, 'z', 15, '{}') ON CONFLICT (id) DO UPDATE SET qty = excluded.qty, note = excluded.note",
+                long_note
+            ),
+        );
+        run_ok(
+            &mut executor,
+            "INSERT INTO stock VALUES (9, 'b', 25, NULL) ON CONFLICT (code) DO UPDATE SET qty = excluded.qty, note = 'counted'",
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT code FROM stock WHERE id = 1", Type::Varchar(10)),
+            vec!["a"]
+        );
+        let query = format!("SELECT qty FROM stock WHERE note = '{}'", long_note);
+        assert_eq!(values(&mut executor, &query, Type::Integer), vec!["15"]);
+        assert_eq!(
+            values(&mut executor, "SELECT id FROM stock WHERE note = 'counted'", Type::Integer),
+            vec!["2"]
+        );
+        // the secondary index follows the update
+        assert_eq!(
+            values(&mut executor, "SELECT id FROM stock WHERE qty = 25", Type::Integer),
+            vec!["2"]
+        );
+        assert!(values(&mut executor, "SELECT id FROM stock WHERE qty = 20", Type::Integer).is_empty());
+
+        // each row of a VALUES list sees the rows before it
+        run_ok(
+            &mut executor,
+            "INSERT INTO stock VALUES (4, 'd', 40, NULL), (4, 'd', 41, NULL), (5, 'e', 50, NULL) ON CONFLICT (id) DO UPDATE SET qty = excluded.qty",
+        );
+        assert_eq!(
+            values(&mut executor, "SELECT qty FROM stock WHERE id > 3", Type::Integer),
+            vec!["41", "50"]
+        );
+
+        // an update that breaks a constraint fails the statement like any UPDATE
+        assert!(
+            !executor
+                .prepare("INSERT INTO stock VALUES (1, 'q', 1, NULL) ON CONFLICT (id) DO UPDATE SET code = 'b'".to_string())
+                .success
+        );
+        for query in [
+            "INSERT INTO stock VALUES (1, 'q', 1, NULL) ON CONFLICT (qty) DO NOTHING",
+            "INSERT INTO stock VALUES (1, 'q', 1, NULL) ON CONFLICT (missing) DO NOTHING",
+            "INSERT INTO stock VALUES (1, 'q', 1, NULL) ON CONFLICT DO UPDATE SET qty = 1",
+            "INSERT INTO stock VALUES (1, 'q', 1, NULL) ON CONFLICT (id) DO UPDATE SET qty = 'many'",
+            "INSERT INTO stock VALUES (1, 'q', 1, NULL) ON CONFLICT (id) DO UPDATE SET qty = excluded.missing",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+        assert_eq!(
+            values(&mut executor, "SELECT code FROM stock WHERE id = 1", Type::Varchar(10)),
+            vec!["a"]
+        );
+        assert!(executor.check_integrity().is_ok());
+    }
+
+    #[test]
+    fn test_returning() {
+        let mut executor = QueryExecutor::init("./default.db.bin", BTREE_NODE_SIZE);
+        // the rows of a result as tab-separated text, payload-backed cells resolved
+        let returned = |executor: &mut RustqlQueryExecutor, query: &str| {
+            let result = executor.prepare(query.to_string());
+            assert!(result.success, "{}: {}", query, result);
+            let header: Vec<String> = result.data.header.iter().map(|f| f.name.clone()).collect();
+            let rows: Vec<String> = result
+                .data
+                .to_string()
+                .lines()
+                .skip(1)
+                .map(|line| line.trim_end_matches('\t').to_string())
+                .collect();
+            (header, rows, result.generated_keys)
+        };
+        assert!(
+            executor
+                .prepare("CREATE TABLE notes (id Integer PRIMARY KEY AUTOINCREMENT, title Varchar(20), body Text, size Integer GENERATED ALWAYS AS (length(body)) STORED)".to_string())
+                .success
+        );
+
+        let long_body = "a note that spills into payload pages ".repeat(12);
+        let (header, rows, keys) = returned(
+            &mut executor,
+            &format!(
+                "INSERT INTO notes (title, body) VALUES ('first', '{}') RETURNING id, body, size",
+                long_body
+            ),
+        );
+        assert_eq!(header, vec!["id", "body", "size"]);
+        assert_eq!(rows, vec![format!("1\t{}\t{}", long_body, long_body.len())]);
+        assert_eq!(keys, vec![vec![("id".to_string(), 1)]]);
+
+        let (header, rows, keys) = returned(
+            &mut executor,
+            "INSERT INTO notes (title, body) VALUES ('second', 'b'), ('third', NULL) RETURNING *",
+        );
+        assert_eq!(header, vec!["id", "title", "body", "size"]);
+        assert_eq!(rows, vec!["2\tsecond\tb\t1", "3\tthird\tNULL\tNULL"]);
+        assert_eq!(keys.len(), 2);
+
+        let (_, rows, _) = returned(
+            &mut executor,
+            "UPDATE notes SET title = 'renamed' WHERE id > 1 RETURNING id, title",
+        );
+        assert_eq!(rows, vec!["2\trenamed", "3\trenamed"]);
+        let (_, rows, _) =
+            returned(&mut executor, "UPDATE notes SET title = 'none' WHERE id = 9 RETURNING id");
+        assert!(rows.is_empty());
+
+        // a deleted row's text is reported although its payload pages are released
+        let (_, rows, _) =
+            returned(&mut executor, "DELETE FROM notes WHERE id = 1 RETURNING title, body");
+        assert_eq!(rows, vec![format!("first\t{}", long_body)]);
+        let (_, rows, _) = returned(
+            &mut executor,
+            &format!("INSERT INTO notes (title, body) VALUES ('reuse', '{}') RETURNING id", long_body),
+        );
+        assert_eq!(rows, vec!["4"]);
+
+        // an upserted row reports the row it updated, a skipped one nothing
+        let (_, rows, keys) = returned(
+            &mut executor,
+            "INSERT INTO notes (id, title, body) VALUES (2, 'again', 'bb') ON CONFLICT (id) DO UPDATE SET body = excluded.body RETURNING id, title, size",
+        );
+        assert_eq!(rows, vec!["2\trenamed\t2"]);
+        assert!(keys.is_empty());
+        let (_, rows, _) = returned(
+            &mut executor,
+            "INSERT INTO notes (id, title) VALUES (2, 'again') ON CONFLICT DO NOTHING RETURNING id",
+        );
+        assert!(rows.is_empty());
+
+        // without RETURNING a write still answers with a message
+        let (header, _, _) = returned(&mut executor, "DELETE FROM notes WHERE id = 3");
+        assert_eq!(header, vec!["Message"]);
+
+        // RETURNING * leaves the rowid out, like SELECT *
+        assert!(executor.prepare("CREATE TABLE log (msg Varchar(10)) WITH ROWID".to_string()).success);
+        let (header, rows, _) =
+            returned(&mut executor, "INSERT INTO log VALUES ('x'), ('y') RETURNING *");
+        assert_eq!(header, vec!["msg"]);
+        assert_eq!(rows, vec!["x", "y"]);
+        let (_, rows, _) = returned(&mut executor, "DELETE FROM log WHERE msg = 'y' RETURNING rowid");
+        assert_eq!(rows, vec!["2"]);
+
+        for query in [
+            "INSERT INTO notes (title) VALUES ('lost') RETURNING missing",
+            "UPDATE notes SET title = 'lost' RETURNING id, missing",
+            "DELETE FROM notes RETURNING missing",
+        ] {
+            assert!(!executor.prepare(query.to_string()).success, "{}", query);
+        }
+        let (_, rows, _) = returned(&mut executor, "SELECT title FROM notes");
+        assert_eq!(rows, vec!["renamed", "reuse"]);
+        assert!(executor.check_integrity().is_ok());
+    }
 }
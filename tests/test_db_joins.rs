@@ -449,7 +449,7 @@ mod tests {
         );
 
         let query = "SELECT users.id FROM users INNER JOIN orders ON users.name = orders.user_name";
-        let result = exec.prepare(query.into());
+        let _result = exec.prepare(query.into());
         //assert_row_count(result, 3);
 
         let compiled = exec.compile_query(query).unwrap();
@@ -687,7 +687,7 @@ mod tests {
             union_query
         );
 
-        let result = exec.prepare(full_query.into());
+        let result = exec.prepare(full_query);
 
         assert_row_count(result, 3);
     }
@@ -720,7 +720,7 @@ mod tests {
 
         let full_query = format!("({}) INTERSECT ({})", query_a, query_b);
 
-        let result = exec.prepare(full_query.into());
+        let result = exec.prepare(full_query);
         assert_row_count(result, 1);
     }
 
@@ -746,7 +746,7 @@ mod tests {
             inner_query
         );
 
-        let result = exec.prepare(full_query.into());
+        let result = exec.prepare(full_query);
         println!("{}", result);
         // ID=1 (100, 90) -> Fails inner filter (score > 90)
         // ID=2 (200, 95) -> Passes inner filter (score > 90). Passes outer filter (value > 150).
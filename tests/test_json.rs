@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use rustql::debug::Status;
+    use rustql::json::{JsonPath, JsonValue, compare_text};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_parse_and_compact_round_trip() {
+        let doc = JsonValue::parse(
+            r#" { "a" : [1, -2.5e3, true, null], "b": {"c": "x\"yé"}, "a2": {} } "#,
+        )
+        .unwrap();
+        let compact = doc.to_string();
+        assert_eq!(compact, r#"{"a":[1,-2.5e3,true,null],"b":{"c":"x\"yé"},"a2":{}}"#);
+        assert_eq!(JsonValue::parse(&compact).unwrap(), doc);
+
+        let nested = r#"{"kind":"click","user":{"name":"Ann","age":31},"tags":["a","b"]}"#;
+        assert_eq!(JsonValue::parse(nested).unwrap().to_string(), nested);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_documents() {
+        for input in ["", "{", "{\"a\" 1}", "[1,]", "01", "tru", "\"open", "{\"a\":1} x"] {
+            assert_eq!(JsonValue::parse(input), Err(Status::CannotParseJson), "{}", input);
+        }
+        let deep = "[".repeat(1000) + &"]".repeat(1000);
+        assert_eq!(JsonValue::parse(&deep), Err(Status::CannotParseJson));
+    }
+
+    #[test]
+    fn test_path_lookup() {
+        let doc = JsonValue::parse(r#"{"user": {"tags": ["x", {"k": 2}]}, "n": null}"#).unwrap();
+        let text = |path: &str| doc.get(&JsonPath::parse(path).unwrap()).and_then(JsonValue::to_text);
+        assert_eq!(text("$.user.tags[0]"), Some("x".to_string()));
+        assert_eq!(text("$[\"user\"].tags[1].k"), Some("2".to_string()));
+        assert_eq!(text("$.user.tags"), Some(r#"["x",{"k":2}]"#.to_string()));
+        assert_eq!(text("$.n"), None);
+        assert_eq!(text("$.missing"), None);
+        assert_eq!(text("$.user.tags[5]"), None);
+        assert_eq!(JsonPath::parse("user.tags"), Err(Status::CannotParseJsonPath));
+        assert_eq!(JsonPath::parse("$.a["), Err(Status::CannotParseJsonPath));
+    }
+
+    #[test]
+    fn test_compare_text() {
+        assert_eq!(compare_text("120", "31"), Ordering::Greater);
+        assert_eq!(compare_text("2.50", "2.5"), Ordering::Equal);
+        assert_eq!(compare_text("apple", "banana"), Ordering::Less);
+        assert_eq!(compare_text("10", "abc"), Ordering::Less);
+    }
+}
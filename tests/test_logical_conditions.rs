@@ -435,7 +435,7 @@ mod tests {
             let v = i % 120;
             let flag = (i * 13) % 120;
             let cond = (v < 10) ^ (v > 110);
-            let cond2 = flag > 90 || flag < 5;
+            let cond2 = !(5..=90).contains(&flag);
             if cond && cond2 {
                 deleted += 1;
             }
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use rustql::btree::BTreeNode;
+    use rustql::collation::Collation;
     use rustql::debug::Status;
     use rustql::pager::{Key, PAGE_SIZE, PagerAccessor, PagerCore, Position, Row, Type};
     use rustql::pager_proxy::{PageManager, PagerProxy};
@@ -14,19 +15,26 @@ mod tests {
             next_position: Position::new(0, 0),
             root: Position::new(0, 0),
             has_key: true,
-            key_position: 0,
+            key_positions: vec![0],
             fields: vec![
                 Field {
                     name: "Id".to_string(),
                     field_type: Type::Integer,
                     table_name: "".to_string(),
+                    collation: Collation::Binary,
                 },
                 Field {
                     name: "Name".to_string(),
                     field_type: Type::String,
                     table_name: "".to_string(),
+                    collation: Collation::Binary,
                 },
             ],
+            column_defaults: vec![],
+            not_null: vec![],
+            checks: vec![],
+            identity: vec![],
+            generated: vec![],
             table_type: 0,
             entry_count: 0,
             name: "".to_string(),
@@ -1,10 +1,19 @@
 #[cfg(test)]
 mod tests {
     use rustql::parser::{
-        JoinType, ParsedConditionExpr, ParsedLogicalOp, ParsedPredicateExpr, ParsedQuery,
-        ParsedQueryTreeNode, ParsedSource, ParsedValueExpr, Parser,
+        ForeignKeyAction, Identity, JoinType, ParsedAlterTableAction, ParsedConditionExpr, ParsedConflictValue, ParsedCreateIndexQuery, ParsedLogicalOp,
+        ParsedPragmaStatement, ParsedPredicateExpr, ParsedQuery, ParsedQueryTreeNode, ParsedScalarExpr,
+        ParsedSource, ParsedValue, ParsedValueExpr, ParsedViewColumn, Parser, TriggerEvent, TriggerTiming,
     };
 
+    fn literal(value: &str) -> Option<ParsedValue> {
+        Some(ParsedValue::Literal(value.to_string()))
+    }
+
+    fn values(values: &[&str]) -> Vec<Option<ParsedValue>> {
+        values.iter().map(|v| literal(v)).collect()
+    }
+
     #[test]
     fn test_create_table_valid() {
         let query = "CREATE TABLE users (id Integer, name String, age Integer)";
@@ -38,6 +47,748 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_table_with_decimal_type() {
+        let query = "CREATE TABLE prices (id Integer, amount DECIMAL(12, 2))";
+        let mut parser = Parser::new(query.to_string());
+        let result = parser.parse_query();
+        assert!(result.is_ok());
+
+        if let ParsedQuery::CreateTable(create_query) = result.unwrap() {
+            assert_eq!(create_query.table_types, vec!["Integer", "DECIMAL(12,2)"]);
+        } else {
+            panic!("Expected CreateTable query");
+        }
+
+        let mut parser = Parser::new("CREATE TABLE prices (amount DECIMAL(12,))".to_string());
+        assert!(parser.parse_query().is_err());
+    }
+
+    #[test]
+    fn test_create_table_with_defaults() {
+        let query = "CREATE TABLE orders (id UUID DEFAULT gen_random_uuid(), qty Integer DEFAULT 1, note String)";
+        let mut parser = Parser::new(query.to_string());
+        let result = parser.parse_query();
+        assert!(result.is_ok());
+
+        if let ParsedQuery::CreateTable(create_query) = result.unwrap() {
+            assert_eq!(create_query.table_types, vec!["UUID", "Integer", "String"]);
+            assert_eq!(
+                create_query.table_defaults,
+                vec![Some(ParsedValue::Call("gen_random_uuid".to_string())), literal("1"), None]
+            );
+        } else {
+            panic!("Expected CreateTable query");
+        }
+
+        let mut parser = Parser::new("CREATE TABLE t (id UUID DEFAULT now())".to_string());
+        assert!(parser.parse_query().is_err());
+        let mut parser = Parser::new("CREATE TABLE t (id UUID DEFAULT gen_random_uuid(1))".to_string());
+        assert!(parser.parse_query().is_err());
+    }
+
+    #[test]
+    fn test_parse_column_constraints() {
+        let query = "CREATE TABLE t (id Integer NOT NULL, s String CHECK (s = 'it''s' OR s IS NULL) DEFAULT 'a', n Integer NOT NULL CHECK (n > 0))";
+        let mut parser = Parser::new(query.to_string());
+        if let Ok(ParsedQuery::CreateTable(create_query)) = parser.parse_query() {
+            assert_eq!(create_query.table_not_null, vec![true, false, true]);
+            assert_eq!(create_query.table_defaults[1], literal("a"));
+            assert!(create_query.table_checks[0].is_none());
+            match &create_query.table_checks[1] {
+                Some(ParsedConditionExpr::Logical { op, left, .. }) => {
+                    assert_eq!(*op, ParsedLogicalOp::Or);
+                    assert!(matches!(
+                        left.as_ref(),
+                        ParsedConditionExpr::Predicate(ParsedPredicateExpr::Compare {
+                            right: ParsedValueExpr::Token(t),
+                            ..
+                        }) if t == "it's"
+                    ));
+                }
+                _ => panic!("Expected OR condition in CHECK"),
+            }
+            assert!(create_query.table_checks[2].is_some());
+        } else {
+            panic!("Expected CreateTable query");
+        }
+
+        for query in [
+            "CREATE TABLE t (id Integer NOT)",
+            "CREATE TABLE t (id Integer CHECK id > 0)",
+            "CREATE TABLE t (id Integer CHECK (id > 0) CHECK (id < 9))",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_primary_key() {
+        let query = "CREATE TABLE t (a Integer, b String, c Integer, PRIMARY KEY (c, a))";
+        let mut parser = Parser::new(query.to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => {
+                assert_eq!(create_query.table_fields, vec!["a", "b", "c"]);
+                assert_eq!(create_query.primary_key, vec!["c", "a"]);
+            }
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        let query = "CREATE TABLE t (a Integer, b String NOT NULL PRIMARY KEY)";
+        let mut parser = Parser::new(query.to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => {
+                assert_eq!(create_query.primary_key, vec!["b"]);
+                assert_eq!(create_query.table_not_null, vec![false, true]);
+            }
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        for query in [
+            "CREATE TABLE t (a Integer PRIMARY KEY, b Integer, PRIMARY KEY (b))",
+            "CREATE TABLE t (a Integer, PRIMARY KEY ())",
+            "CREATE TABLE t (a Integer, PRIMARY KEY (a)",
+            "CREATE TABLE t (a Integer PRIMARY)",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_unique() {
+        let query = "CREATE TABLE t (a Integer UNIQUE NOT NULL, b String)";
+        let mut parser = Parser::new(query.to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => {
+                assert_eq!(create_query.table_unique, vec![true, false]);
+                assert_eq!(create_query.table_not_null, vec![true, false]);
+            }
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        let mut parser = Parser::new("CREATE UNIQUE INDEX t_b ON t (b)".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateIndex(create_index)) => {
+                assert!(create_index.unique);
+                assert_eq!(create_index.columns, vec!["b"]);
+            }
+            _ => panic!("Expected CreateIndex query"),
+        }
+        let mut parser = Parser::new("CREATE INDEX t_b ON t (b)".to_string());
+        assert!(matches!(
+            parser.parse_query(),
+            Ok(ParsedQuery::CreateIndex(ParsedCreateIndexQuery { unique: false, .. }))
+        ));
+        let mut parser = Parser::new("CREATE UNIQUE TABLE t (a Integer)".to_string());
+        assert!(parser.parse_query().is_err());
+    }
+
+    #[test]
+    fn test_parse_foreign_keys() {
+        let query = "CREATE TABLE lines (id Integer, order_id Integer REFERENCES orders ON DELETE CASCADE, \
+                     sku String, FOREIGN KEY (sku) REFERENCES items (code) ON UPDATE SET NULL ON DELETE RESTRICT)";
+        let mut parser = Parser::new(query.to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => {
+                assert_eq!(create_query.table_fields, vec!["id", "order_id", "sku"]);
+                let [order, item] = create_query.foreign_keys.as_slice() else {
+                    panic!("Expected two foreign keys");
+                };
+                assert_eq!(order.column, "order_id");
+                assert_eq!(order.parent_table, "orders");
+                assert_eq!(order.parent_column, None);
+                assert_eq!(order.on_delete, ForeignKeyAction::Cascade);
+                assert_eq!(order.on_update, ForeignKeyAction::Restrict);
+                assert_eq!(item.column, "sku");
+                assert_eq!(item.parent_column.as_deref(), Some("code"));
+                assert_eq!(item.on_delete, ForeignKeyAction::Restrict);
+                assert_eq!(item.on_update, ForeignKeyAction::SetNull);
+            }
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        for (query, enabled) in [("PRAGMA foreign_keys = OFF", false), ("PRAGMA foreign_keys = 1", true)] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(matches!(
+                parser.parse_query(),
+                Ok(ParsedQuery::Pragma(ParsedPragmaStatement::ForeignKeys(e))) if e == enabled
+            ));
+        }
+        for query in [
+            "CREATE TABLE t (a Integer, b Integer, FOREIGN KEY (a, b) REFERENCES p (x, y))",
+            "CREATE TABLE t (a Integer REFERENCES p ON DELETE SET DEFAULT)",
+            "PRAGMA journal_mode = OFF",
+            "PRAGMA foreign_keys = MAYBE",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_with_rowid() {
+        for (query, with_rowid) in [
+            ("CREATE TABLE log (level String, msg String) WITH ROWID", true),
+            ("CREATE TABLE log (level String, msg String) with rowid", true),
+            ("CREATE TABLE log (level String, msg String)", false),
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            match parser.parse_query() {
+                Ok(ParsedQuery::CreateTable(create)) => {
+                    assert_eq!(create.with_rowid, with_rowid, "{}", query);
+                    assert_eq!(create.table_fields, vec!["level", "msg"]);
+                }
+                _ => panic!("Expected CreateTable query for {}", query),
+            }
+        }
+        let mut parser = Parser::new("CREATE TABLE log (level String) WITH".to_string());
+        assert!(parser.parse_query().is_err());
+    }
+
+    #[test]
+    fn test_parse_temp_tables() {
+        for (query, temporary) in [
+            ("CREATE TEMP TABLE stage (id Integer)", true),
+            ("CREATE temporary TABLE stage (id Integer)", true),
+            ("CREATE TABLE stage (id Integer)", false),
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            match parser.parse_query() {
+                Ok(ParsedQuery::CreateTable(create)) => {
+                    assert_eq!(create.temporary, temporary, "{}", query);
+                    assert_eq!(create.table_name, "stage");
+                }
+                _ => panic!("Expected CreateTable query for {}", query),
+            }
+        }
+        let mut parser = Parser::new("CREATE TEMP VIEW v AS SELECT * FROM t".to_string());
+        assert!(parser.parse_query().is_err());
+    }
+
+    #[test]
+    fn test_parse_multi_row_insert() {
+        let mut parser =
+            Parser::new("INSERT INTO users (id, name) VALUES (1, 'a'), (2, NULL),(3,'c, d')".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Insert(insert)) => {
+                assert_eq!(insert.values, values(&["1", "a"]));
+                assert_eq!(
+                    insert.additional_rows,
+                    vec![vec![literal("2"), None], values(&["3", "c, d"])]
+                );
+            }
+            _ => panic!("Expected Insert query"),
+        }
+        let mut parser = Parser::new("INSERT INTO users VALUES (1, 'a')".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Insert(insert)) => assert!(insert.additional_rows.is_empty()),
+            _ => panic!("Expected Insert query"),
+        }
+        for query in [
+            "INSERT INTO users (id, name) VALUES (1, 'a'), (2)",
+            "INSERT INTO users VALUES (1, 'a'),",
+            "INSERT INTO users VALUES (1, 'a'), 2",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_insert_on_conflict() {
+        let mut parser = Parser::new(
+            "INSERT INTO stock VALUES (1, 'a'), (2, 'b') ON CONFLICT (id) DO UPDATE SET code = excluded.code, note = 'excluded.code', qty = NULL"
+                .to_string(),
+        );
+        match parser.parse_query() {
+            Ok(ParsedQuery::Insert(insert)) => {
+                assert_eq!(insert.additional_rows.len(), 1);
+                let on_conflict = insert.on_conflict.expect("Expected ON CONFLICT");
+                assert_eq!(on_conflict.columns, vec!["id"]);
+                assert_eq!(
+                    on_conflict.update,
+                    Some(vec![
+                        ("code".to_string(), ParsedConflictValue::Excluded("code".to_string())),
+                        ("note".to_string(), ParsedConflictValue::Value(literal("excluded.code"))),
+                        ("qty".to_string(), ParsedConflictValue::Value(None)),
+                    ])
+                );
+            }
+            _ => panic!("Expected Insert query"),
+        }
+        let mut parser = Parser::new("INSERT INTO stock VALUES (1, 'a') ON CONFLICT DO NOTHING".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Insert(insert)) => {
+                let on_conflict = insert.on_conflict.expect("Expected ON CONFLICT");
+                assert!(on_conflict.columns.is_empty());
+                assert!(on_conflict.update.is_none());
+            }
+            _ => panic!("Expected Insert query"),
+        }
+        for query in [
+            "INSERT INTO stock VALUES (1, 'a') ON CONFLICT",
+            "INSERT INTO stock VALUES (1, 'a') ON CONFLICT () DO NOTHING",
+            "INSERT INTO stock VALUES (1, 'a') ON CONFLICT (id) DO REPLACE",
+            "INSERT INTO stock VALUES (1, 'a') ON CONFLICT (id) DO UPDATE SET",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_returning() {
+        let mut parser = Parser::new(
+            "INSERT INTO notes (title) VALUES ('a') ON CONFLICT DO NOTHING RETURNING id, title".to_string(),
+        );
+        match parser.parse_query() {
+            Ok(ParsedQuery::Insert(insert)) => {
+                assert!(insert.on_conflict.is_some());
+                assert_eq!(insert.returning, Some(vec!["id".to_string(), "title".to_string()]));
+            }
+            _ => panic!("Expected Insert query"),
+        }
+        let mut parser =
+            Parser::new("UPDATE notes SET title = 'b' WHERE id = 1 AND size > 2 RETURNING *".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Update(update)) => {
+                assert!(update.conditions.is_some());
+                assert_eq!(update.returning, Some(vec!["*".to_string()]));
+            }
+            _ => panic!("Expected Update query"),
+        }
+        let mut parser = Parser::new("DELETE FROM notes returning body".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Delete(delete)) => {
+                assert!(delete.conditions.is_none());
+                assert_eq!(delete.returning, Some(vec!["body".to_string()]));
+            }
+            _ => panic!("Expected Delete query"),
+        }
+        let mut parser = Parser::new("DELETE FROM notes WHERE id = 1".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Delete(delete)) => assert!(delete.returning.is_none()),
+            _ => panic!("Expected Delete query"),
+        }
+        for query in [
+            "DELETE FROM notes RETURNING",
+            "DELETE FROM notes RETURNING id,",
+            "UPDATE notes SET title = 'b' RETURNING , id",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_insert_select_and_create_table_as() {
+        let mut parser =
+            Parser::new("INSERT INTO totals (customer, total) SELECT name, sum FROM orders".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::InsertSelect(insert)) => {
+                assert_eq!(insert.table_name, "totals");
+                assert_eq!(insert.fields, vec!["customer", "total"]);
+                assert!(matches!(insert.query, ParsedQueryTreeNode::SingleQuery(_)));
+            }
+            _ => panic!("Expected InsertSelect query"),
+        }
+        let mut parser = Parser::new("INSERT INTO totals SELECT * FROM a UNION SELECT * FROM b".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::InsertSelect(insert)) => {
+                assert!(insert.fields.is_empty());
+                assert!(matches!(insert.query, ParsedQueryTreeNode::SetOperation(_)));
+            }
+            _ => panic!("Expected InsertSelect query"),
+        }
+
+        let mut parser = Parser::new("CREATE TABLE archive AS SELECT id FROM orders WHERE id > 3".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTableAs(create)) => {
+                assert_eq!(create.table_name, "archive");
+                assert!(!create.temporary);
+            }
+            _ => panic!("Expected CreateTableAs query"),
+        }
+        for query in [
+            "CREATE TABLE archive AS",
+            "CREATE TABLE archive AS orders",
+            "INSERT INTO totals SELECT",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_views() {
+        let mut parser =
+            Parser::new("CREATE VIEW adults AS SELECT name FROM users WHERE age >= 18".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateView(view)) => {
+                assert_eq!(view.view_name, "adults");
+                match view.query {
+                    ParsedQueryTreeNode::SingleQuery(select) => {
+                        assert_eq!(select.result, vec!["name"]);
+                        assert!(select.conditions.is_some());
+                    }
+                    _ => panic!("Expected a single query"),
+                }
+            }
+            _ => panic!("Expected CreateView query"),
+        }
+        let mut parser = Parser::new("DROP VIEW adults".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::DropView(drop)) => assert_eq!(drop.view_name, "adults"),
+            _ => panic!("Expected DropView query"),
+        }
+        let mut parser = Parser::new(
+            "CREATE MATERIALIZED VIEW ages (who, years Integer) AS SELECT name, age FROM users".to_string(),
+        );
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateView(view)) => {
+                assert!(view.materialized);
+                assert_eq!(
+                    view.columns,
+                    vec![
+                        ParsedViewColumn {
+                            name: "who".to_string(),
+                            column_type: None,
+                            collation: None,
+                        },
+                        ParsedViewColumn {
+                            name: "years".to_string(),
+                            column_type: Some("Integer".to_string()),
+                            collation: None,
+                        },
+                    ]
+                );
+                assert_eq!(view.query_sql, "SELECT name, age FROM users");
+            }
+            _ => panic!("Expected CreateView query"),
+        }
+        let mut parser = Parser::new("REFRESH MATERIALIZED VIEW ages".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::RefreshMaterializedView(refresh)) => assert_eq!(refresh.view_name, "ages"),
+            _ => panic!("Expected RefreshMaterializedView query"),
+        }
+        let mut parser = Parser::new("DROP MATERIALIZED VIEW ages".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::DropView(drop)) => assert!(drop.materialized),
+            _ => panic!("Expected DropView query"),
+        }
+        for query in [
+            "CREATE VIEW adults SELECT name FROM users",
+            "CREATE VIEW adults AS users",
+            "CREATE VIEW adults (who) AS SELECT name FROM users",
+            "REFRESH VIEW adults",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_triggers() {
+        let mut parser = Parser::new(
+            "CREATE TRIGGER audit_pay AFTER UPDATE ON accounts FOR EACH ROW BEGIN INSERT INTO log VALUES (NEW.id, 'a;b'); DELETE FROM log WHERE id = OLD.id; END".to_string(),
+        );
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTrigger(trigger)) => {
+                assert_eq!(trigger.trigger_name, "audit_pay");
+                assert_eq!(trigger.timing, TriggerTiming::After);
+                assert_eq!(trigger.event, TriggerEvent::Update);
+                assert_eq!(trigger.table_name, "accounts");
+                assert_eq!(
+                    trigger.statements,
+                    vec![
+                        "INSERT INTO log VALUES (NEW.id, 'a;b')",
+                        "DELETE FROM log WHERE id = OLD.id",
+                    ]
+                );
+            }
+            _ => panic!("Expected CreateTrigger query"),
+        }
+        let bound = Parser::bind_row_references(
+            "INSERT INTO log VALUES (NEW.id, 'NEW.id', old.name)",
+            &mut |row, column| Ok(format!("{}_{}", row, column)),
+        );
+        assert_eq!(bound.unwrap(), "INSERT INTO log VALUES (NEW_id, 'NEW.id', OLD_name)");
+        let mut parser = Parser::new("DROP TRIGGER audit_pay".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::DropTrigger(drop)) => assert_eq!(drop.trigger_name, "audit_pay"),
+            _ => panic!("Expected DropTrigger query"),
+        }
+        for query in [
+            "CREATE TRIGGER t DURING INSERT ON a FOR EACH ROW BEGIN SELECT * FROM a; END",
+            "CREATE TRIGGER t AFTER UPSERT ON a FOR EACH ROW BEGIN SELECT * FROM a; END",
+            "CREATE TRIGGER t AFTER INSERT ON a BEGIN SELECT * FROM a; END",
+            "CREATE TRIGGER t AFTER INSERT ON a FOR EACH ROW BEGIN END",
+            "CREATE TRIGGER t AFTER INSERT ON a FOR EACH ROW BEGIN SELECT * FROM a;",
+            "CREATE TRIGGER t AFTER INSERT ON a FOR EACH ROW BEGIN CREATE TABLE b (x Integer); END",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_alter_table() {
+        let add = |column: &str, definition: &str| ParsedAlterTableAction::AddColumn {
+            column: column.to_string(),
+            definition: definition.to_string(),
+        };
+        let rename = ParsedAlterTableAction::RenameColumn {
+            from: "a".to_string(),
+            to: "b".to_string(),
+        };
+        let retype = ParsedAlterTableAction::AlterColumnType {
+            column: "a".to_string(),
+            column_type: "Varchar(20)".to_string(),
+        };
+        for (query, action) in [
+            ("ALTER TABLE t ADD COLUMN c Integer DEFAULT 1 NOT NULL", add("c", "c Integer DEFAULT 1 NOT NULL")),
+            ("ALTER TABLE t ADD c Varchar(5) CHECK (c > 'a')", add("c", "c Varchar(5) CHECK (c > 'a')")),
+            ("ALTER TABLE t DROP COLUMN a", ParsedAlterTableAction::DropColumn("a".to_string())),
+            ("ALTER TABLE t DROP a", ParsedAlterTableAction::DropColumn("a".to_string())),
+            ("ALTER TABLE t RENAME COLUMN a TO b", rename.clone()),
+            ("ALTER TABLE t RENAME a TO b", rename),
+            ("ALTER TABLE t ALTER COLUMN a TYPE Varchar(20)", retype.clone()),
+            ("ALTER TABLE t ALTER a SET DATA TYPE Varchar( 20 )", retype),
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            match parser.parse_query() {
+                Ok(ParsedQuery::AlterTable(alter)) => {
+                    assert_eq!(alter.table_name, "t");
+                    assert_eq!(alter.action, action, "{}", query);
+                }
+                other => panic!("Expected AlterTable query for {}: {:?}", query, other),
+            }
+        }
+
+        for query in [
+            "ALTER TABLE t ADD COLUMN c Integer, d Integer",
+            "ALTER TABLE t ADD PRIMARY KEY (a)",
+            "ALTER TABLE t RENAME TO u",
+            "ALTER TABLE t ALTER COLUMN a Varchar(20)",
+            "ALTER TABLE t TRUNCATE",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+
+        // CREATE TABLE keeps the definitions as written, which ALTER TABLE rebuilds from
+        let query = "CREATE TABLE t (a Integer PRIMARY KEY, b Varchar(5) CHECK (b > 'a') \
+                     REFERENCES u (a), c Integer, FOREIGN KEY (c) REFERENCES t (a))";
+        let mut parser = Parser::new(query.to_string());
+        let Ok(ParsedQuery::CreateTable(create)) = parser.parse_query() else {
+            panic!("Expected CreateTable query");
+        };
+        assert_eq!(
+            create.column_definitions,
+            vec![
+                "a Integer PRIMARY KEY",
+                "b Varchar(5) CHECK (b > 'a') REFERENCES u (a)",
+                "c Integer"
+            ]
+        );
+        assert_eq!(create.table_constraints, vec!["FOREIGN KEY (c) REFERENCES t (a)"]);
+        let renamed: Vec<String> = create
+            .column_definitions
+            .iter()
+            .chain(create.table_constraints.iter())
+            .map(|text| Parser::rename_column_in(text, "t", "a", "id"))
+            .collect();
+        assert_eq!(
+            renamed,
+            vec![
+                "id Integer PRIMARY KEY",
+                "b Varchar(5) CHECK (b > 'a') REFERENCES u (a)",
+                "c Integer",
+                "FOREIGN KEY (c) REFERENCES t (id)"
+            ]
+        );
+        assert_eq!(
+            Parser::with_column_type("b Varchar(5) CHECK (b > 'a')", "Text"),
+            Ok("b Text CHECK (b > 'a')".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        let query = "CREATE TABLE users (id Integer PRIMARY KEY AUTOINCREMENT, \
+                     n Integer GENERATED ALWAYS AS IDENTITY, m Integer GENERATED BY DEFAULT AS IDENTITY, name String)";
+        let mut parser = Parser::new(query.to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => assert_eq!(
+                create_query.table_identity,
+                vec![
+                    Some(Identity::ByDefault),
+                    Some(Identity::Always),
+                    Some(Identity::ByDefault),
+                    None
+                ]
+            ),
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        for (query, start, increment, if_not_exists) in [
+            ("CREATE SEQUENCE ids", 1, 1, false),
+            ("CREATE SEQUENCE IF NOT EXISTS ids START WITH 100 INCREMENT BY -5", 100, -5, true),
+            ("CREATE SEQUENCE ids INCREMENT 10 START 0", 0, 10, false),
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            match parser.parse_query() {
+                Ok(ParsedQuery::CreateSequence(sequence)) => {
+                    assert_eq!(sequence.sequence_name, "ids");
+                    assert_eq!(sequence.start, start, "{}", query);
+                    assert_eq!(sequence.increment, increment, "{}", query);
+                    assert_eq!(sequence.if_not_exists, if_not_exists, "{}", query);
+                }
+                _ => panic!("Expected CreateSequence query for {}", query),
+            }
+        }
+
+        let mut parser = Parser::new("DROP SEQUENCE ids".to_string());
+        assert!(matches!(
+            parser.parse_query(),
+            Ok(ParsedQuery::DropSequence(drop)) if drop.sequence_name == "ids"
+        ));
+
+        let mut parser =
+            Parser::new("INSERT INTO users (id, name) VALUES (nextval('ids'), 'a')".to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::Insert(insert)) => {
+                assert_eq!(insert.values, vec![Some(ParsedValue::NextVal("ids".to_string())), literal("a")])
+            }
+            _ => panic!("Expected Insert query"),
+        }
+
+        for query in [
+            "CREATE SEQUENCE ids START WITH",
+            "CREATE SEQUENCE ids INCREMENT BY x",
+            "CREATE TABLE t (id Integer GENERATED AS IDENTITY)",
+            "CREATE TABLE t (id Integer GENERATED ALWAYS)",
+            "INSERT INTO t (id) VALUES (nextval(ids))",
+            "INSERT INTO t (id) VALUES (nextval('ids')",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_generated_columns() {
+        let column = |name: &str| ParsedScalarExpr::Column(ParsedValueExpr::Token(name.to_string()));
+        let query = "CREATE TABLE t (a Integer, b Integer, s String, \
+                     c Integer GENERATED ALWAYS AS (a + b * 2) STORED, \
+                     d String GENERATED ALWAYS AS (lower(trim(s)) || '!') STORED)";
+        let mut parser = Parser::new(query.to_string());
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => {
+                assert_eq!(create_query.table_identity, vec![None; 5]);
+                assert_eq!(
+                    create_query.table_generated,
+                    vec![
+                        None,
+                        None,
+                        None,
+                        Some(ParsedScalarExpr::Binary {
+                            operator: "+".to_string(),
+                            left: Box::new(column("a")),
+                            right: Box::new(ParsedScalarExpr::Binary {
+                                operator: "*".to_string(),
+                                left: Box::new(column("b")),
+                                right: Box::new(ParsedScalarExpr::Literal("2".to_string())),
+                            }),
+                        }),
+                        Some(ParsedScalarExpr::Binary {
+                            operator: "||".to_string(),
+                            left: Box::new(ParsedScalarExpr::Function {
+                                name: "lower".to_string(),
+                                args: vec![ParsedScalarExpr::Function {
+                                    name: "trim".to_string(),
+                                    args: vec![column("s")],
+                                }],
+                            }),
+                            right: Box::new(ParsedScalarExpr::Literal("!".to_string())),
+                        }),
+                    ]
+                );
+            }
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        let mut parser = Parser::new(
+            "CREATE TABLE t (j Json, k String GENERATED ALWAYS AS ((j->>'k')) STORED)".to_string(),
+        );
+        match parser.parse_query() {
+            Ok(ParsedQuery::CreateTable(create_query)) => assert_eq!(
+                create_query.table_generated[1],
+                Some(ParsedScalarExpr::Column(ParsedValueExpr::JsonExtract {
+                    column: "j".to_string(),
+                    path: "$[\"k\"]".to_string(),
+                }))
+            ),
+            _ => panic!("Expected CreateTable query"),
+        }
+
+        for query in [
+            "CREATE TABLE t (a Integer, c Integer GENERATED ALWAYS AS (a + 1))",
+            "CREATE TABLE t (a Integer, c Integer GENERATED ALWAYS AS (a + 1) VIRTUAL)",
+            "CREATE TABLE t (a Integer, c Integer GENERATED BY DEFAULT AS (a + 1) STORED)",
+            "CREATE TABLE t (a Integer, c Integer GENERATED ALWAYS AS (a +) STORED)",
+            "CREATE TABLE t (a Integer, c Integer GENERATED ALWAYS AS (lower(a) STORED)",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_collations() {
+        let query = "CREATE TABLE users (name String COLLATE NOCASE, city Varchar(20) DEFAULT 'x' COLLATE binary, age Integer)";
+        let mut parser = Parser::new(query.to_string());
+        if let Ok(ParsedQuery::CreateTable(create_query)) = parser.parse_query() {
+            assert_eq!(
+                create_query.table_collations,
+                vec![Some("NOCASE".to_string()), Some("binary".to_string()), None]
+            );
+            assert_eq!(create_query.table_defaults[1], literal("x"));
+        } else {
+            panic!("Expected CreateTable query");
+        }
+
+        for (query, expected) in [
+            ("SELECT name FROM users WHERE name = 'alice' COLLATE NOCASE", "NOCASE"),
+            ("SELECT name FROM users WHERE name COLLATE BINARY > 'a'", "BINARY"),
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            match parser.parse_query() {
+                Ok(ParsedQuery::Select(ParsedQueryTreeNode::SingleQuery(select_query))) => {
+                    assert!(matches!(
+                        select_query.conditions,
+                        Some(ParsedConditionExpr::Predicate(ParsedPredicateExpr::Compare {
+                            collation: Some(ref c),
+                            ..
+                        })) if c == expected
+                    ));
+                }
+                _ => panic!("Expected SelectQuery"),
+            }
+        }
+
+        for query in [
+            "SELECT name FROM users WHERE name COLLATE NOCASE = 'a' COLLATE BINARY",
+            "SELECT name FROM users WHERE name COLLATE NOCASE IS NULL",
+            "SELECT name FROM users WHERE name = 'a' COLLATE",
+        ] {
+            let mut parser = Parser::new(query.to_string());
+            assert!(parser.parse_query().is_err(), "{}", query);
+        }
+    }
+
     #[test]
     fn test_create_index_valid() {
         let query = "CREATE INDEX idx_users_name ON users (name)";
@@ -173,6 +924,7 @@ mod tests {
                                     left,
                                     operator,
                                     right,
+                                    collation: None,
                                 }) => {
                                     assert_eq!(operator, "=");
                                     assert!(
@@ -190,6 +942,7 @@ mod tests {
                                     left,
                                     operator,
                                     right,
+                                    collation: None,
                                 }) => {
                                     assert_eq!(operator, "=");
                                     assert!(
@@ -234,6 +987,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_select_json_extraction() {
+        let query = "SELECT id, meta->>'kind', json_extract(meta, '$.user.name') FROM events \
+                     WHERE meta ->> 'kind' = 'click'";
+        let mut parser = Parser::new(query.to_string());
+        let result = parser.parse_query();
+        assert!(result.is_ok());
+
+        if let ParsedQuery::Select(ParsedQueryTreeNode::SingleQuery(select_query)) = result.unwrap() {
+            assert_eq!(
+                select_query.result,
+                vec!["id", "meta->>'kind'", "json_extract(meta, '$.user.name')"]
+            );
+            assert_eq!(
+                select_query.result_exprs[1],
+                ParsedValueExpr::JsonExtract {
+                    column: "meta".to_string(),
+                    path: "$[\"kind\"]".to_string(),
+                }
+            );
+            assert_eq!(
+                select_query.result_exprs[2],
+                ParsedValueExpr::JsonExtract {
+                    column: "meta".to_string(),
+                    path: "$.user.name".to_string(),
+                }
+            );
+            match select_query.conditions {
+                Some(ParsedConditionExpr::Predicate(ParsedPredicateExpr::Compare { left, .. })) => {
+                    assert!(
+                        matches!(left, ParsedValueExpr::JsonExtract { ref column, .. } if column == "meta")
+                    );
+                }
+                _ => panic!("Expected compare predicate"),
+            }
+        } else {
+            panic!("Expected Select query");
+        }
+    }
+
     #[test]
     fn test_select_with_invalid_condition() {
         let query = "SELECT id, name FROM users WHERE id =";
@@ -457,7 +1250,46 @@ mod tests {
         if let ParsedQuery::Insert(insert_query) = result.unwrap() {
             assert_eq!(insert_query.table_name, "users");
             assert_eq!(insert_query.fields, vec!["id", "name", "age"]);
-            assert_eq!(insert_query.values, vec!["1", "John Doe", "30"]);
+            assert_eq!(insert_query.values, values(&["1", "John Doe", "30"]));
+        } else {
+            panic!("Expected InsertQuery");
+        }
+    }
+
+    #[test]
+    fn test_parse_insert_hex_literal_and_unicode() {
+        let query = "INSERT INTO files (id, data, name) VALUES (1, X'DEADbeef', 'Grüße')".to_string();
+        let mut parser = Parser::new(query);
+        let result = parser.parse_query();
+
+        assert!(result.is_ok());
+        if let ParsedQuery::Insert(insert_query) = result.unwrap() {
+            assert_eq!(insert_query.values, values(&["1", "X'DEADbeef'", "Grüße"]));
+        } else {
+            panic!("Expected InsertQuery");
+        }
+    }
+
+    #[test]
+    fn test_parse_insert_function_value() {
+        let query = "INSERT INTO orders (id, qty) VALUES (GEN_RANDOM_UUID(), 3)".to_string();
+        let mut parser = Parser::new(query);
+        let result = parser.parse_query();
+
+        assert!(result.is_ok());
+        if let ParsedQuery::Insert(insert_query) = result.unwrap() {
+            assert_eq!(
+                insert_query.values,
+                vec![Some(ParsedValue::Call("gen_random_uuid".to_string())), literal("3")]
+            );
+        } else {
+            panic!("Expected InsertQuery");
+        }
+
+        let mut parser =
+            Parser::new("INSERT INTO orders (id, qty) VALUES ('gen_random_uuid()', 3)".to_string());
+        if let Ok(ParsedQuery::Insert(insert_query)) = parser.parse_query() {
+            assert_eq!(insert_query.values, values(&["gen_random_uuid()", "3"]));
         } else {
             panic!("Expected InsertQuery");
         }
@@ -474,7 +1306,7 @@ mod tests {
         if let ParsedQuery::Insert(insert_query) = result.unwrap() {
             assert_eq!(insert_query.table_name, "products");
             assert_eq!(insert_query.fields, vec!["id", "name", "price"]);
-            assert_eq!(insert_query.values, vec!["101", "Laptop", "999.99"]);
+            assert_eq!(insert_query.values, values(&["101", "Laptop", "999.99"]));
         } else {
             panic!("Expected InsertQuery");
         }
@@ -567,7 +1399,7 @@ mod tests {
         if let ParsedQuery::Insert(insert_query) = result.unwrap() {
             assert_eq!(insert_query.table_name, "users");
             assert_eq!(insert_query.fields, vec!["id", "name", "age"]);
-            assert_eq!(insert_query.values, vec!["1", "John Doe", "30"]);
+            assert_eq!(insert_query.values, values(&["1", "John Doe", "30"]));
         } else {
             panic!("Expected InsertQuery");
         }
@@ -595,8 +1427,8 @@ mod tests {
             assert_eq!(
                 update_query.assignments,
                 vec![
-                    ("name".to_string(), "Jane".to_string()),
-                    ("age".to_string(), "31".to_string())
+                    ("name".to_string(), literal("Jane")),
+                    ("age".to_string(), literal("31"))
                 ]
             );
             match update_query.conditions {
@@ -604,6 +1436,7 @@ mod tests {
                     left,
                     operator,
                     right,
+                    collation: None,
                 })) => {
                     assert_eq!(operator, "=");
                     assert!(matches!(left, ParsedValueExpr::Token(ref t) if t == "id"));
@@ -627,11 +1460,44 @@ mod tests {
             assert_eq!(update_query.table_name, "users");
             assert_eq!(
                 update_query.assignments,
-                vec![("active".to_string(), "true".to_string())]
+                vec![("active".to_string(), literal("true"))]
             );
             assert!(update_query.conditions.is_none());
         } else {
             panic!("Expected UpdateQuery");
         }
     }
+
+    #[test]
+    fn test_parse_null_literals_and_predicates() {
+        let mut parser =
+            Parser::new("INSERT INTO t (a, b, c) VALUES (null, 'NULL', 1)".to_string());
+        if let Ok(ParsedQuery::Insert(insert_query)) = parser.parse_query() {
+            assert_eq!(
+                insert_query.values,
+                vec![None, literal("NULL"), literal("1")]
+            );
+        } else {
+            panic!("Expected InsertQuery");
+        }
+
+        let mut parser = Parser::new("UPDATE t SET a = NULL WHERE b IS NOT NULL".to_string());
+        if let Ok(ParsedQuery::Update(update_query)) = parser.parse_query() {
+            assert_eq!(update_query.assignments, vec![("a".to_string(), None)]);
+            assert!(matches!(
+                update_query.conditions,
+                Some(ParsedConditionExpr::Predicate(ParsedPredicateExpr::IsNull {
+                    negated: true,
+                    ..
+                }))
+            ));
+        } else {
+            panic!("Expected UpdateQuery");
+        }
+
+        let mut parser = Parser::new("SELECT a FROM t WHERE a = NULL".to_string());
+        assert!(parser.parse_query().is_err());
+        let mut parser = Parser::new("SELECT a FROM t WHERE a IS 1".to_string());
+        assert!(parser.parse_query().is_err());
+    }
 }
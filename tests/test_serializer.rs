@@ -2,6 +2,8 @@
 
 #[cfg(test)]
 mod tests {
+    use rustql::collation::Collation;
+    use rustql::debug::Status;
     use rustql::pager::*;
     use rustql::schema::{Field, TableSchema};
     use rustql::serializer::Serializer;
@@ -11,19 +13,26 @@ mod tests {
             next_position: Position::make_empty(),
             root: Position::make_empty(),
             has_key: true,
-            key_position: 0,
+            key_positions: vec![0],
             fields: vec![
                 Field {
                     name: "Id".to_string(),
                     field_type: Type::Integer,
                     table_name: "".to_string(),
+                    collation: Collation::Binary,
                 },
                 Field {
                     name: "Num".to_string(),
                     field_type: Type::Integer,
                     table_name: "".to_string(),
+                    collation: Collation::Binary,
                 },
             ],
+            column_defaults: vec![],
+            not_null: vec![],
+            checks: vec![],
+            identity: vec![],
+            generated: vec![],
             table_type: 0,
             entry_count: 0,
             name: "".to_string(),
@@ -40,7 +49,7 @@ mod tests {
         let children: Vec<Position> = vec![Position::new(2, 2); num_keys + 1];
         let rows: Vec<Row> = (0..num_keys)
             .map(|i| {
-                let mut row = vec![(i * 2) as u8; schema.get_row_length().unwrap()];
+                let row = vec![(i * 2) as u8; schema.get_row_length().unwrap()];
                 row
             })
             .collect();
@@ -66,7 +75,7 @@ mod tests {
             let children: Vec<Position> = vec![Position::new(2, 2); num_keys_lambda(j) + 1];
             let rows: Vec<Row> = (0..num_keys_lambda(j))
                 .map(|i| {
-                    let mut row = vec![(i * 2) as u8; schema.get_row_length().unwrap()];
+                    let row = vec![(i * 2) as u8; schema.get_row_length().unwrap()];
                     row
                 })
                 .collect();
@@ -89,7 +98,7 @@ mod tests {
         let mut page = create_mock_page_data(3);
         let pos_a = Position::new(0, 0);
         let pos_b = Position::new(0, 1);
-        let src = page.clone();
+        let src = page;
         Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page, &src).unwrap();
 
         let keys_a = Serializer::read_keys_as_vec(&page, &pos_a, &schema).unwrap();
@@ -100,12 +109,12 @@ mod tests {
     #[test]
     fn test_copy_node_different_pages() {
         let schema = get_schema();
-        let mut page_a = create_mock_page_data_multiple_nodes(2, |i| 3, |i, j| j * 4);
-        let mut page_b = create_mock_page_data_multiple_nodes(3, |i| 3, |i, j| i);
+        let page_a = create_mock_page_data_multiple_nodes(2, |_i| 3, |_i, j| j * 4);
+        let mut page_b = create_mock_page_data_multiple_nodes(3, |_i| 3, |i, _j| i);
         let pos_a = Position::new(0, 0);
         let pos_b = Position::new(1, 4);
 
-        Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page_b, &mut page_a).unwrap();
+        Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page_b, &page_a).unwrap();
 
         let keys_a = Serializer::read_keys_as_vec(&page_a, &pos_a, &schema).unwrap();
         let keys_b = Serializer::read_keys_as_vec(&page_b, &pos_b, &schema).unwrap();
@@ -115,12 +124,12 @@ mod tests {
     #[test]
     fn test_copy_node_with_data() {
         let schema = get_schema();
-        let mut page_a = create_mock_page_data(2);
+        let page_a = create_mock_page_data(2);
         let mut page_b = create_mock_page_data(3);
         let pos_a = Position::new(0, 0);
         let pos_b = Position::new(1, 0);
 
-        Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page_b, &mut page_a).unwrap();
+        Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page_b, &page_a).unwrap();
 
         let data_a = Serializer::read_data_as_vec(&page_a, &pos_a, &schema).unwrap();
         let data_b = Serializer::read_data_as_vec(&page_b, &pos_b, &schema).unwrap();
@@ -130,12 +139,12 @@ mod tests {
     #[test]
     fn test_copy_node_v2() {
         let schema = get_schema();
-        let mut page_a = create_mock_page_data_multiple_nodes(6, |i| 3, |i, j| j * j * j);
-        let mut page_b = create_mock_page_data_multiple_nodes(5, |i| 3, |i, j| i);
+        let page_a = create_mock_page_data_multiple_nodes(6, |_i| 3, |_i, j| j * j * j);
+        let mut page_b = create_mock_page_data_multiple_nodes(5, |_i| 3, |i, _j| i);
         let pos_a = Position::new(0, 2);
         let pos_b = Position::new(1, 4);
 
-        Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page_b, &mut page_a).unwrap();
+        Serializer::copy_node(&schema, &pos_b, &pos_a, &mut page_b, &page_a).unwrap();
 
         let children_a = Serializer::read_children_as_vec(&page_a, &pos_a, &schema).unwrap();
         let children_b = Serializer::read_children_as_vec(&page_b, &pos_b, &schema).unwrap();
@@ -145,7 +154,7 @@ mod tests {
     #[test]
     fn test_switch_nodes_same_page() {
         let schema = get_schema();
-        let mut page = create_mock_page_data_multiple_nodes(4, |i| 3, |i, j| j * 4);
+        let mut page = create_mock_page_data_multiple_nodes(4, |_i| 3, |_i, j| j * 4);
         let pos_a = Position::new(0, 1);
         let pos_b = Position::new(0, 2);
 
@@ -173,7 +182,7 @@ mod tests {
     fn test_switch_nodes_different_pages() {
         let schema = get_schema();
         let mut page_a = create_mock_page_data(1);
-        let mut page_b = create_mock_page_data_multiple_nodes(2, |i| 3, |i, j| j * 4);
+        let mut page_b = create_mock_page_data_multiple_nodes(2, |_i| 3, |_i, j| j * 4);
         let pos_a = Position::new(0, 0);
         let pos_b = Position::new(1, 1);
         Serializer::switch_nodes(&schema, &pos_a, &pos_b, &mut page_a, Some(&mut page_b)).unwrap();
@@ -192,7 +201,7 @@ mod tests {
     #[test]
     fn test_switch_nodes_large_page() {
         let schema = get_schema();
-        let mut page = create_mock_page_data_multiple_nodes(5, |i| 5, |i, j| j * 3 + i * 2);
+        let mut page = create_mock_page_data_multiple_nodes(5, |_i| 5, |i, j| j * 3 + i * 2);
         let pos_a = Position::new(0, 0);
         let pos_b = Position::new(0, 4);
 
@@ -366,7 +375,7 @@ mod tests {
 
     #[test]
     fn test_is_leaf() {
-        let mut page = create_mock_page_data(1);
+        let _page = create_mock_page_data(1);
         //assert!(Serializer::is_leaf(&page).unwrap());
 
         //Serializer::set_is_leaf(&mut page, false).unwrap();
@@ -442,7 +451,7 @@ mod tests {
         let schema = get_schema();
         let page = create_mock_page_data(2);
         let position = Position::make_empty();
-        let row = Serializer::read_data_by_index(0, &page, &position, &schema).unwrap();
+        let _row = Serializer::read_data_by_index(0, &page, &position, &schema).unwrap();
 
         //TODO something
         assert_eq!(page.len(), PAGE_SIZE);
@@ -590,19 +599,75 @@ mod tests {
     }
 
     #[test]
-    fn test_bytes_to_ascii() {
+    fn test_bytes_to_utf8() {
         let mut input: [u8; STRING_SIZE] = [0; STRING_SIZE];
         input[..5].copy_from_slice(b"hello");
         let expected = "hello".to_string();
-        assert_eq!(Serializer::bytes_to_ascii(input), expected);
+        assert_eq!(Serializer::bytes_to_utf8(input), expected);
+
+        let mut input: [u8; STRING_SIZE] = [0; STRING_SIZE];
+        let name = "Müller 東京".as_bytes();
+        input[..name.len()].copy_from_slice(name);
+        assert_eq!(Serializer::bytes_to_utf8(input), "Müller 東京");
     }
 
     #[test]
-    fn test_ascii_to_bytes() {
+    fn test_utf8_to_bytes() {
         let mut expected: [u8; STRING_SIZE] = [0; STRING_SIZE];
         expected[..5].copy_from_slice(b"hello");
         let input = "hello".to_string();
-        assert_eq!(Serializer::ascii_to_bytes(&*input), expected);
+        assert_eq!(Serializer::utf8_to_bytes(&input), expected);
+
+        // 254 ASCII bytes leave one free byte, which cannot hold the two-byte 'ü'
+        let input = "a".repeat(254) + "ü";
+        let bytes = Serializer::utf8_to_bytes(&input);
+        assert_eq!(bytes[253], b'a');
+        assert_eq!(bytes[254], 0);
+        assert_eq!(Serializer::bytes_to_utf8(bytes), "a".repeat(254));
+    }
+
+    #[test]
+    fn test_parse_varchar_counts_characters() {
+        let field = Serializer::parse_varchar("Grüße, 世界!", 5);
+        assert_eq!(field.len(), 5 * 4 + 1);
+        assert_eq!(Serializer::format_field(&field, &Type::Varchar(5)).unwrap(), "Grüße");
+
+        let field = Serializer::parse_varchar("世界", 2);
+        assert_eq!(Serializer::format_field(&field, &Type::Varchar(2)).unwrap(), "世界");
+    }
+
+    #[test]
+    fn test_parse_varchar_bytes_keeps_whole_characters() {
+        // 'ß' would end past the fifth byte
+        let field = Serializer::parse_varchar_bytes("Grüße, 世界!", 5);
+        assert_eq!(field.len(), 5 + 1);
+        assert_eq!(Serializer::format_field(&field, &Type::VarcharBytes(5)).unwrap(), "Grü");
+
+        let field = Serializer::parse_varchar_bytes("世界", 5);
+        assert_eq!(Serializer::format_field(&field, &Type::VarcharBytes(5)).unwrap(), "世");
+    }
+
+    #[test]
+    fn test_compare_multibyte_strings() {
+        let cmp = |a: &str, b: &str| {
+            Serializer::compare_with_type(
+                &Serializer::parse_varchar(a, 8),
+                &Serializer::parse_varchar(b, 8),
+                &Type::Varchar(8),
+            )
+            .unwrap()
+        };
+        assert_eq!(cmp("Zoe", "Ärger"), std::cmp::Ordering::Less);
+        assert_eq!(cmp("Ärger", "東京"), std::cmp::Ordering::Less);
+        assert_eq!(cmp("東", "東京"), std::cmp::Ordering::Less);
+        assert_eq!(cmp("東京", "東京"), std::cmp::Ordering::Equal);
+        assert_eq!(
+            Serializer::compare_strings(
+                Serializer::parse_string("Jörg"),
+                Serializer::parse_string("Jorg")
+            ),
+            std::cmp::Ordering::Greater
+        );
     }
 
     #[test]
@@ -651,20 +716,20 @@ mod tests {
 
     #[test]
     fn test_byte_to_bool() {
-        assert_eq!(Serializer::byte_to_bool(0), false);
-        assert_eq!(Serializer::byte_to_bool(1), true);
-        assert_eq!(Serializer::byte_to_bool(2), false);
-        assert_eq!(Serializer::byte_to_bool(3), true);
+        assert!(!Serializer::byte_to_bool(0));
+        assert!(Serializer::byte_to_bool(1));
+        assert!(!Serializer::byte_to_bool(2));
+        assert!(Serializer::byte_to_bool(3));
     }
 
     #[test]
     fn test_byte_to_bool_at_position() {
-        assert_eq!(Serializer::byte_to_bool_at_position(0b00000001, 0), true);
-        assert_eq!(Serializer::byte_to_bool_at_position(0b00000010, 1), true);
-        assert_eq!(Serializer::byte_to_bool_at_position(0b00000100, 2), true);
-        assert_eq!(Serializer::byte_to_bool_at_position(0b00001000, 3), true);
-        assert_eq!(Serializer::byte_to_bool_at_position(0b11111111, 7), true);
-        assert_eq!(Serializer::byte_to_bool_at_position(0b00000000, 0), false);
+        assert!(Serializer::byte_to_bool_at_position(0b00000001, 0));
+        assert!(Serializer::byte_to_bool_at_position(0b00000010, 1));
+        assert!(Serializer::byte_to_bool_at_position(0b00000100, 2));
+        assert!(Serializer::byte_to_bool_at_position(0b00001000, 3));
+        assert!(Serializer::byte_to_bool_at_position(0b11111111, 7));
+        assert!(!Serializer::byte_to_bool_at_position(0b00000000, 0));
     }
 
     #[test]
@@ -677,6 +742,81 @@ mod tests {
         assert_eq!(Serializer::bytes_to_bits(&input), expected);
     }
 
+    #[test]
+    fn test_decimal_parse_and_format() {
+        let bytes = Serializer::parse_decimal("-12.5", 6, 2).unwrap();
+        assert_eq!(bytes.len(), 5);
+        assert_eq!(Serializer::format_decimal(&bytes, 6, 2), "-12.50");
+
+        let bytes = Serializer::parse_decimal("0.05", 3, 2).unwrap();
+        assert_eq!(Serializer::format_decimal(&bytes, 3, 2), "0.05");
+
+        let bytes = Serializer::parse_decimal("42", 38, 0).unwrap();
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(Serializer::format_decimal(&bytes, 38, 0), "42");
+    }
+
+    #[test]
+    fn test_decimal_rounds_half_away_from_zero() {
+        assert_eq!(Serializer::parse_decimal_unscaled("1.005", 5, 2), Ok(101));
+        assert_eq!(Serializer::parse_decimal_unscaled("1.004", 5, 2), Ok(100));
+        assert_eq!(Serializer::parse_decimal_unscaled("-1.005", 5, 2), Ok(-101));
+    }
+
+    #[test]
+    fn test_decimal_overflow_and_garbage() {
+        assert_eq!(
+            Serializer::parse_decimal("1000.00", 5, 2),
+            Err(Status::ExceptionDecimalOverflow)
+        );
+        assert_eq!(
+            Serializer::parse_decimal("999.995", 5, 2),
+            Err(Status::ExceptionDecimalOverflow)
+        );
+        assert_eq!(
+            Serializer::parse_decimal(&"9".repeat(60), 38, 0),
+            Err(Status::ExceptionDecimalOverflow)
+        );
+        assert_eq!(
+            Serializer::parse_decimal("12a.5", 5, 2),
+            Err(Status::CannotParseDecimal)
+        );
+        assert_eq!(Serializer::parse_decimal(".", 5, 2), Err(Status::CannotParseDecimal));
+    }
+
+    #[test]
+    fn test_decimal_literal_bounds() {
+        assert_eq!(Serializer::decimal_literal_bounds("2.346", 2), Ok((234, 235)));
+        assert_eq!(Serializer::decimal_literal_bounds("2.3400", 2), Ok((234, 234)));
+        assert_eq!(Serializer::decimal_literal_bounds("-2.345", 2), Ok((-235, -234)));
+        assert_eq!(Serializer::decimal_literal_bounds("10000", 2), Ok((1000000, 1000000)));
+        let limit = 10i128.pow(38);
+        assert_eq!(Serializer::decimal_literal_bounds(&"9".repeat(60), 0), Ok((limit, limit)));
+        assert_eq!(
+            Serializer::decimal_literal_bounds("1.2.3", 2),
+            Err(Status::CannotParseDecimal)
+        );
+    }
+
+    #[test]
+    fn test_decimal_byte_order_matches_numeric_order() {
+        let ty = Type::Decimal(10, 3);
+        let values = ["-1000.5", "-0.001", "0", "0.001", "7.25", "1000000"];
+        let encoded: Vec<Vec<u8>> = values
+            .iter()
+            .map(|v| Serializer::parse_decimal(v, 10, 3).unwrap())
+            .collect();
+        for pair in encoded.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert_eq!(
+                Serializer::compare_with_type(&pair[0], &pair[1], &ty).unwrap(),
+                std::cmp::Ordering::Less
+            );
+        }
+        assert!(Serializer::negative_infinity(&ty) < encoded[0]);
+        assert!(Serializer::infinity(&ty) > encoded[encoded.len() - 1]);
+    }
+
     #[test]
     fn test_parse_type() {
         assert_eq!(Serializer::byte_to_type(0), Some(Type::Null));
@@ -684,6 +824,294 @@ mod tests {
         assert_eq!(Serializer::byte_to_type(2), Some(Type::String));
         assert_eq!(Serializer::byte_to_type(3), Some(Type::Date));
         assert_eq!(Serializer::byte_to_type(4), Some(Type::Boolean));
+        assert_eq!(Serializer::byte_to_type(6), Some(Type::Decimal(38, 0)));
+        assert_eq!(Serializer::byte_to_type(7), Some(Type::Blob));
+        assert_eq!(Serializer::byte_to_type(8), Some(Type::Timestamp));
+        assert_eq!(Serializer::byte_to_type(9), Some(Type::TimestampTz));
+        assert_eq!(Serializer::byte_to_type(10), Some(Type::Time));
+        assert_eq!(Serializer::byte_to_type(11), Some(Type::Text));
+        assert_eq!(Serializer::byte_to_type(12), Some(Type::Uuid));
         assert_eq!(Serializer::byte_to_type(255), None);
     }
+
+    #[test]
+    fn test_blob_literal_parse_and_format() {
+        assert_eq!(
+            Serializer::parse_blob("X'00ff7A'").unwrap(),
+            vec![0x00, 0xFF, 0x7A]
+        );
+        assert_eq!(Serializer::parse_blob("x''").unwrap(), Vec::<u8>::new());
+        assert_eq!(Serializer::format_blob(&[0x00, 0xFF, 0x7A]), "X'00FF7A'");
+
+        for bad in ["00ff", "X'0'", "X'zz'", "X'00", "'00'"] {
+            assert_eq!(Serializer::parse_blob(bad), Err(Status::CannotParseBlob));
+        }
+    }
+
+    #[test]
+    fn test_payload_field_descriptor() {
+        let content: Vec<u8> = (0..40).collect();
+        let field = Serializer::payload_field_to_bytes(&content, Position::new(9, 0));
+        assert_eq!(field.len(), rustql::constants::PAYLOAD_FIELD_SIZE);
+        assert_eq!(Serializer::get_payload_field_len(&field), 40);
+        assert_eq!(Serializer::get_payload_field_pointer(&field), Position::new(9, 0));
+        assert_eq!(Serializer::get_payload_field_inline(&field), &content[..12]);
+        assert_eq!(
+            Serializer::format_field(&field, &Type::Blob).unwrap(),
+            "X'000102030405060708090A0B... (40 bytes)"
+        );
+
+        let small = Serializer::payload_field_to_bytes(&[0xAB], Position::make_empty());
+        assert_eq!(Serializer::format_field(&small, &Type::Blob).unwrap(), "X'AB'");
+
+        let text = Serializer::payload_field_to_bytes(b"hello, variable world", Position::new(3, 0));
+        assert_eq!(
+            Serializer::format_field(&text, &Type::Text).unwrap(),
+            "hello, varia... (21 bytes)"
+        );
+        let short = Serializer::payload_field_to_bytes(b"hi", Position::make_empty());
+        assert_eq!(Serializer::format_field(&short, &Type::Text).unwrap(), "hi");
+    }
+
+    #[test]
+    fn test_boolean_bytes_of_older_files_read_unchanged() {
+        // before NULLs a Boolean byte held just its value
+        let (old_true, old_false) = (vec![1u8], vec![0u8]);
+        for (field, text) in [(&old_true, "true"), (&old_false, "false")] {
+            assert!(!Serializer::is_null(field, &Type::Boolean).unwrap());
+            assert!(!Serializer::is_external(field, &Type::Boolean).unwrap());
+            assert_eq!(Serializer::format_field(field, &Type::Boolean).unwrap(), text);
+        }
+        assert_eq!(Serializer::parse_bool("true").unwrap(), old_true[0]);
+        assert_eq!(Serializer::parse_bool("false").unwrap(), old_false[0]);
+        assert_eq!(
+            Serializer::compare_with_type(&old_false, &old_true, &Type::Boolean).unwrap(),
+            std::cmp::Ordering::Less
+        );
+
+        let null = Serializer::null_field(&Type::Boolean).unwrap();
+        assert_eq!(null, vec![0b10]);
+        assert_eq!(Serializer::format_field(&null, &Type::Boolean).unwrap(), "NULL");
+    }
+
+    #[test]
+    fn test_payload_prefixes_decide_where_they_can() {
+        use std::cmp::Ordering;
+        let field = |content: &[u8]| Serializer::payload_field_to_bytes(content, Position::new(3, 0));
+        let compare = |a: &[u8], b: &[u8], collation| {
+            Serializer::compare_payload_prefixes(&field(a), &field(b), collation)
+        };
+        assert_eq!(compare(b"apple", b"banana", Collation::Binary), Some(Ordering::Less));
+        assert_eq!(compare(b"apple", b"apple pie", Collation::Binary), Some(Ordering::Less));
+        assert_eq!(
+            compare(b"a long text that differs late", b"a long text.", Collation::Binary),
+            Some(Ordering::Less)
+        );
+        // equal inline prefixes of two long values leave the order to the payloads
+        assert_eq!(compare(b"same prefix, then a", b"same prefix, then b", Collation::Binary), None);
+        assert_eq!(compare(b"apple", b"banana", Collation::NoCase), None);
+
+        // the seek bound keeps whole characters and orders before the value
+        let bound = Serializer::payload_seek_bound("aaaaaaaaaaaüber".as_bytes());
+        assert_eq!(Serializer::get_payload_field_inline(&bound), b"aaaaaaaaaaa");
+        assert_eq!(Serializer::get_payload_field_len(&bound), 11);
+        let value = field("aaaaaaaaaaaüber".as_bytes());
+        assert_eq!(
+            Serializer::compare_payload_prefixes(&bound, &value, Collation::Binary),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn test_timestamp_parse_and_format() {
+        let ts = Serializer::parse_timestamp("2026-10-18 12:34:56.789", false).unwrap();
+        assert_eq!(ts.len(), TIMESTAMP_SIZE);
+        assert_eq!(
+            Serializer::format_field(&ts, &Type::Timestamp).unwrap(),
+            "2026-10-18 12:34:56.789"
+        );
+
+        let midnight = Serializer::parse_timestamp("1969-12-31", false).unwrap();
+        assert_eq!(Serializer::format_timestamp(&midnight, false), "1969-12-31 00:00:00");
+        assert_eq!(Serializer::bytes_to_timestamp(&midnight), -86_400_000_000);
+
+        let leap = Serializer::parse_timestamp("2024-02-29T23:59:59.000001", false).unwrap();
+        assert_eq!(Serializer::format_timestamp(&leap, false), "2024-02-29 23:59:59.000001");
+
+        for bad in [
+            "2026-02-29 10:00:00",
+            "2026-10-18 24:00:00",
+            "2026-10-18 12:00:00.1234567",
+            "2026-10-18 12:00:00+02:00",
+            "yesterday",
+        ] {
+            assert_eq!(
+                Serializer::parse_timestamp(bad, false),
+                Err(Status::CannotParseTimestamp)
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamptz_offsets_are_normalized_to_utc() {
+        let berlin = Serializer::parse_timestamp("2026-10-18 12:00:00+02:00", true).unwrap();
+        let utc = Serializer::parse_timestamp("2026-10-18T10:00:00Z", true).unwrap();
+        let no_zone = Serializer::parse_timestamp("2026-10-18 10:00:00", true).unwrap();
+        assert_eq!(berlin.len(), TIMESTAMPTZ_SIZE);
+        assert_eq!(
+            Serializer::bytes_to_timestamp(&berlin),
+            Serializer::bytes_to_timestamp(&utc)
+        );
+        assert_eq!(utc, no_zone);
+        assert_eq!(
+            Serializer::format_field(&berlin, &Type::TimestampTz).unwrap(),
+            "2026-10-18 12:00:00+02:00"
+        );
+        assert_eq!(
+            Serializer::format_timestamp(
+                &Serializer::parse_timestamp("2026-10-18 01:30:00 -0530", true).unwrap(),
+                true
+            ),
+            "2026-10-18 01:30:00-05:30"
+        );
+        assert_eq!(
+            Serializer::format_timestamp(
+                &Serializer::parse_timestamp("2026-10-18 08:00 UTC", true).unwrap(),
+                true
+            ),
+            "2026-10-18 08:00:00+00:00"
+        );
+        assert!(Serializer::parse_timestamp("2026-10-18 08:00+19:00", true).is_err());
+    }
+
+    #[test]
+    fn test_time_parse_format_and_order() {
+        let t = Serializer::parse_time("07:05:03.25").unwrap();
+        assert_eq!(t.len(), TIME_SIZE);
+        assert_eq!(Serializer::format_field(&t, &Type::Time).unwrap(), "07:05:03.25");
+        assert_eq!(
+            Serializer::format_time(&Serializer::parse_time("23:59").unwrap()),
+            "23:59:00"
+        );
+        assert_eq!(Serializer::parse_time("7"), Err(Status::CannotParseTime));
+        assert_eq!(Serializer::parse_time("12:60:00"), Err(Status::CannotParseTime));
+
+        let earlier = Serializer::parse_time("09:00:00").unwrap();
+        assert_eq!(
+            Serializer::compare_with_type(&earlier, &t, &Type::Time).unwrap(),
+            std::cmp::Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_timestamp_byte_order_matches_time_order() {
+        let values = [
+            "0001-01-01 00:00:00",
+            "1900-06-30 23:59:59.999999",
+            "1969-12-31 23:59:59.5",
+            "1970-01-01 00:00:00",
+            "2026-10-18 12:34:56.789",
+            "9999-12-31 23:59:59.999999",
+        ];
+        let encoded: Vec<Vec<u8>> = values
+            .iter()
+            .map(|v| Serializer::parse_timestamp(v, false).unwrap())
+            .collect();
+        for pair in encoded.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert_eq!(
+                Serializer::compare_with_type(&pair[0], &pair[1], &Type::Timestamp).unwrap(),
+                std::cmp::Ordering::Less
+            );
+        }
+        for (value, bytes) in values.iter().zip(&encoded) {
+            assert_eq!(&Serializer::format_timestamp(bytes, false), value);
+        }
+    }
+
+    #[test]
+    fn test_uuid_parse_and_format() {
+        let canonical = "550e8400-e29b-41d4-a716-446655440000";
+        let bytes = Serializer::parse_uuid(canonical).unwrap();
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(bytes[0], 0x55);
+        assert_eq!(bytes[15], 0x00);
+        assert_eq!(Serializer::format_field(&bytes, &Type::Uuid).unwrap(), canonical);
+
+        for variant in [
+            "550E8400-E29B-41D4-A716-446655440000",
+            "550e8400e29b41d4a716446655440000",
+            "{550e8400-e29b-41d4-a716-446655440000}",
+        ] {
+            assert_eq!(Serializer::parse_uuid(variant).unwrap(), bytes);
+        }
+
+        for invalid in [
+            "",
+            "550e8400-e29b-41d4-a716-44665544000",
+            "550e8400-e29b41d4-a716-4466-55440000",
+            "550e8400-e29b-41d4-a716-44665544000g",
+            "550e8400-e29b-41d4-a716-4466554400ü",
+        ] {
+            assert_eq!(Serializer::parse_uuid(invalid), Err(Status::CannotParseUuid));
+        }
+    }
+
+    #[test]
+    fn test_uuid_generation_and_order() {
+        let a = Serializer::generate_uuid_v4();
+        let b = Serializer::generate_uuid_v4();
+        assert_ne!(a, b);
+        assert_eq!(a[6] >> 4, 4);
+        assert_eq!(a[8] >> 6, 0b10);
+        let text = Serializer::format_uuid(&a);
+        assert_eq!(Serializer::parse_uuid(&text).unwrap(), a);
+
+        let low = Serializer::parse_uuid("00000000-0000-4000-8000-000000000001").unwrap();
+        let high = Serializer::parse_uuid("ff000000-0000-4000-8000-000000000000").unwrap();
+        assert_eq!(
+            Serializer::compare_with_type(&low, &high, &Type::Uuid).unwrap(),
+            std::cmp::Ordering::Less
+        );
+        assert_eq!(
+            Serializer::compare_with_type(&high, &Serializer::infinity(&Type::Uuid), &Type::Uuid)
+                .unwrap(),
+            std::cmp::Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_compare_with_collation() {
+        let alice = Serializer::parse_string("Alice").to_vec();
+        let lower = Serializer::parse_string("alice").to_vec();
+        let zed = Serializer::parse_string("Zed").to_vec();
+        let string = Type::String;
+        assert_eq!(
+            Serializer::compare_with_collation(&alice, &lower, &string, Collation::NoCase),
+            Ok(std::cmp::Ordering::Equal)
+        );
+        assert_eq!(
+            Serializer::compare_with_collation(&alice, &lower, &string, Collation::Binary),
+            Ok(std::cmp::Ordering::Less)
+        );
+        assert_eq!(
+            Serializer::compare_with_collation(&lower, &zed, &string, Collation::NoCase),
+            Ok(std::cmp::Ordering::Less)
+        );
+        assert_eq!(
+            Serializer::compare_with_collation(&lower, &zed, &string, Collation::Binary),
+            Ok(std::cmp::Ordering::Greater)
+        );
+        // non-ASCII letters fold too
+        assert_eq!(
+            Collation::NoCase.compare("ÄRGER".as_bytes(), "ärger".as_bytes()),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(
+            Serializer::collation_key(&alice, &string, Collation::NoCase),
+            Serializer::collation_key(&lower, &string, Collation::NoCase)
+        );
+        assert_eq!(Collation::from_name("nocase"), Ok(Collation::NoCase));
+        assert!(Collation::from_name("de_DE").is_err());
+    }
 }
@@ -10,7 +10,7 @@ mod tests {
     use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
     const MAGIC: &[u8; 4] = b"RSQL";
-    const VERSION: u8 = 2;
+    const VERSION: u8 = 5;
 
     static NAME_COUNTER: AtomicUsize = AtomicUsize::new(0);
     static TEST_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -24,6 +24,7 @@ mod tests {
         rows: Vec<Vec<u8>>,
         chunk_counts: Vec<u32>,
         done_flags: Vec<u8>,
+        generated_keys: Vec<Vec<(String, i32)>>,
     }
 
     struct Client {
@@ -155,6 +156,25 @@ mod tests {
             }
         }
 
+        let mut key_rows_buf = [0u8; 4];
+        stream.read_exact(&mut key_rows_buf).unwrap();
+        let mut generated_keys = Vec::new();
+        for _ in 0..u32::from_be_bytes(key_rows_buf) {
+            let mut key_count_buf = [0u8; 2];
+            stream.read_exact(&mut key_count_buf).unwrap();
+            let mut keys = Vec::new();
+            for _ in 0..u16::from_be_bytes(key_count_buf) {
+                let mut name_len_buf = [0u8; 2];
+                stream.read_exact(&mut name_len_buf).unwrap();
+                let mut name = vec![0u8; u16::from_be_bytes(name_len_buf) as usize];
+                stream.read_exact(&mut name).unwrap();
+                let mut value = [0u8; 4];
+                stream.read_exact(&mut value).unwrap();
+                keys.push((String::from_utf8(name).unwrap(), i32::from_be_bytes(value)));
+            }
+            generated_keys.push(keys);
+        }
+
         Response {
             status,
             message,
@@ -162,6 +182,7 @@ mod tests {
             rows,
             chunk_counts,
             done_flags,
+            generated_keys,
         }
     }
 
@@ -779,4 +800,136 @@ mod tests {
         let r = verify.send(&format!("SELECT * FROM {}", t1), 256);
         assert_eq!(r.status, 0);
     }
+
+    #[test]
+    fn integration_30_sequences_are_shared_across_connections() {
+        let _g = acquire_test_lock();
+        let seq = unique_name("it_seq");
+        let auto = unique_name("it_auto");
+        let mut setup = Client::connect();
+        assert_eq!(setup.send(&format!("CREATE SEQUENCE {}", seq), 256).status, 0);
+        assert_eq!(
+            setup
+                .send(
+                    &format!(
+                        "CREATE TABLE {} (id Integer PRIMARY KEY AUTOINCREMENT, v Integer)",
+                        auto
+                    ),
+                    256
+                )
+                .status,
+            0
+        );
+
+        let r = setup.send(&format!("INSERT INTO {} (v) VALUES (7)", auto), 256);
+        assert_eq!(r.status, 0);
+        assert_eq!(r.generated_keys, vec![vec![("id".to_string(), 1)]]);
+        let r = setup.send(&format!("INSERT INTO {} (v) VALUES (8), (9)", auto), 256);
+        assert_eq!(r.status, 0);
+        assert_eq!(
+            r.generated_keys,
+            vec![vec![("id".to_string(), 2)], vec![("id".to_string(), 3)]]
+        );
+        let r = setup.send(&format!("SELECT * FROM {}", auto), 256);
+        assert!(r.generated_keys.is_empty());
+
+        let workers = 4;
+        let per_worker = 10;
+        let barrier = Arc::new(Barrier::new(workers));
+        let mut handles = Vec::new();
+        for _ in 0..workers {
+            let table = unique_name("it_seq_t");
+            let r = setup.send(&format!("CREATE TABLE {} (id Integer, v Integer)", table), 256);
+            assert_eq!(r.status, 0, "{}", r.message);
+            let seq = seq.clone();
+            let barrier = barrier.clone();
+            handles.push(thread::spawn(move || {
+                let mut c = Client::connect();
+                barrier.wait();
+                let mut keys = Vec::new();
+                for i in 0..per_worker {
+                    let r = c.send(
+                        &format!(
+                            "INSERT INTO {} (id, v) VALUES (nextval('{}'), {})",
+                            table, seq, i
+                        ),
+                        256,
+                    );
+                    assert_eq!(r.status, 0, "{}", r.message);
+                    assert_eq!(r.generated_keys.len(), 1);
+                    assert_eq!(r.generated_keys[0][0].0, "id");
+                    keys.push(r.generated_keys[0][0].1);
+                }
+                keys
+            }));
+        }
+
+        let mut all: Vec<i32> = handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("worker panicked"))
+            .collect();
+        all.sort();
+        let expected: Vec<i32> = (1..=(workers * per_worker) as i32).collect();
+        assert_eq!(all, expected);
+    }
+
+    #[test]
+    fn integration_31_temp_tables_are_private_to_their_connection() {
+        let _g = acquire_test_lock();
+        let t = unique_name("it_temp");
+        let mut owner = Client::connect();
+        let mut other = Client::connect();
+
+        let r = owner.send(&format!("CREATE TEMP TABLE {} (id Integer, v Integer)", t), 256);
+        assert_eq!(r.status, 0, "{}", r.message);
+        assert_eq!(owner.send(&format!("INSERT INTO {} VALUES (1, 1)", t), 256).status, 0);
+        assert_eq!(other.send(&format!("SELECT * FROM {}", t), 256).status, 1);
+
+        let r = other.send(&format!("CREATE TEMP TABLE {} (id Integer)", t), 256);
+        assert_eq!(r.status, 0, "{}", r.message);
+        assert_eq!(other.send(&format!("SELECT * FROM {}", t), 256).rows.len(), 0);
+        assert_eq!(owner.send(&format!("SELECT * FROM {}", t), 256).rows.len(), 1);
+
+        drop(owner);
+        drop(other);
+        let mut c = Client::connect();
+        assert_eq!(c.send(&format!("SELECT * FROM {}", t), 256).status, 1);
+        let r = c.send(
+            &format!("SELECT name FROM rustsql_master WHERE name = '{}'", t),
+            256,
+        );
+        assert_eq!(r.status, 0);
+        assert!(r.rows.is_empty());
+    }
+
+    #[test]
+    fn integration_32_returning_sends_written_rows() {
+        let _g = acquire_test_lock();
+        let t = unique_name("it_returning");
+        let mut c = Client::connect();
+        let r = c.send(
+            &format!(
+                "CREATE TABLE {} (id Integer PRIMARY KEY AUTOINCREMENT, v Integer)",
+                t
+            ),
+            256,
+        );
+        assert_eq!(r.status, 0, "{}", r.message);
+
+        let r = c.send(
+            &format!("INSERT INTO {} (v) VALUES (10), (20), (30) RETURNING id, v", t),
+            2,
+        );
+        assert_eq!(r.status, 0, "{}", r.message);
+        assert_eq!(r.columns, 2);
+        assert_eq!(r.rows.len(), 3);
+        assert_eq!(r.chunk_counts, vec![2, 1, 0]);
+        assert_eq!(r.generated_keys.len(), 3);
+
+        let r = c.send(&format!("DELETE FROM {} WHERE v > 15 RETURNING id", t), 256);
+        assert_eq!(r.status, 0, "{}", r.message);
+        assert_eq!(r.columns, 1);
+        assert_eq!(r.rows.len(), 2);
+        assert_eq!(c.send(&format!("SELECT * FROM {}", t), 256).rows.len(), 1);
+    }
 }